@@ -39,6 +39,7 @@ impl Expr {
             | ExprKind::Call(..)
             | ExprKind::Struct(..)
             | ExprKind::Field(..)
+            | ExprKind::Panic(..)
             | ExprKind::Err
             | ExprKind::Break
             | ExprKind::Continue => false,
@@ -83,6 +84,8 @@ pub enum ExprKind {
     Struct(Path, Vec<Field>),
     Field(P<Expr>, Ident),
     Match(P<Expr>, Vec<Arm>),
+    /// panic!("msg")
+    Panic(Symbol),
     Break,
     Continue,
     Err,
@@ -118,6 +121,7 @@ impl Display for ExprKind {
                 None => write!(fmt, "if {} {}", c, l),
             },
             Self::Match(_, _) => todo!(),
+            Self::Panic(msg) => write!(fmt, "panic!({:?})", msg.as_str()),
             Self::Err => write!(fmt, "<expr-err>"),
             Self::Continue => write!(fmt, "continue"),
             Self::Break => write!(fmt, "break"),
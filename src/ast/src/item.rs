@@ -9,9 +9,28 @@ pub struct Item<K = ItemKind> {
     pub id: NodeId,
     pub vis: Visibility,
     pub ident: Ident,
+    pub attrs: Vec<Attribute>,
     pub kind: K,
 }
 
+/// #[<attr>]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Attribute {
+    pub span: Span,
+    pub kind: AttrKind,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum AttrKind {
+    /// #[derive(<ident>, ...)]
+    Derive(Vec<Ident>),
+    /// #[inline]
+    Inline,
+    /// #[lang]
+    /// marks a trait as the lang item of the same name
+    Lang,
+}
+
 impl Item {
     pub fn generics(&self) -> Option<&Generics> {
         match &self.kind {
@@ -21,7 +40,11 @@ impl Item {
             | ItemKind::TypeAlias(generics, _)
             | ItemKind::Trait { generics, .. }
             | ItemKind::Enum(generics, _) => Some(generics),
-            ItemKind::Mod(..) | ItemKind::Use(..) | ItemKind::Extern(..) => None,
+            ItemKind::Mod(..)
+            | ItemKind::Use(..)
+            | ItemKind::Extern(..)
+            | ItemKind::Const(..)
+            | ItemKind::Static(..) => None,
         }
     }
 }
@@ -38,6 +61,10 @@ pub enum ItemKind {
     Extern(Abi, Vec<P<ForeignItem>>),
     /// type T = S;
     TypeAlias(Generics, P<Ty>),
+    /// const N: T = <expr>;
+    Const(P<Ty>, P<Expr>),
+    /// static S: T = <expr>;
+    Static(P<Ty>, P<Expr>),
     /// mod foo;
     Mod(Module),
     /// use some::path;
@@ -76,6 +103,8 @@ impl ItemKind {
             ItemKind::Impl { .. } => "impl block",
             ItemKind::Extern(..) => "extern block",
             ItemKind::TypeAlias(..) => "type alias",
+            ItemKind::Const(..) => "constant",
+            ItemKind::Static(..) => "static",
             ItemKind::Use(..) => "use import",
             ItemKind::Mod(..) => "module",
             ItemKind::Trait { .. } => "trait",
@@ -101,7 +130,9 @@ impl TryFrom<ItemKind> for AssocItemKind {
             | ItemKind::Enum(..)
             | ItemKind::Struct(..)
             | ItemKind::Trait { .. }
-            | ItemKind::Impl { .. } => Err(kind),
+            | ItemKind::Impl { .. }
+            | ItemKind::Const(..)
+            | ItemKind::Static(..) => Err(kind),
         }
     }
 }
@@ -143,6 +174,10 @@ impl Display for Item {
             ),
             ItemKind::TypeAlias(generics, ty) =>
                 write!(f, "{} type {}<{}> = {}", self.vis.node, self.ident, generics, ty),
+            ItemKind::Const(ty, expr) =>
+                write!(f, "{} const {}: {} = {}", self.vis.node, self.ident, ty, expr),
+            ItemKind::Static(ty, expr) =>
+                write!(f, "{} static {}: {} = {}", self.vis.node, self.ident, ty, expr),
             ItemKind::Enum(_generics, _variants) => todo!(),
             ItemKind::Struct(_generics, _variant_kind) => todo!(),
             ItemKind::Extern(..) => todo!(),
@@ -52,6 +52,27 @@ pub struct Variant {
     pub span: Span,
     pub ident: Ident,
     pub kind: VariantKind,
+    /// = <expr>
+    pub discr: Option<AnonConst>,
+}
+
+/// an expression that is evaluated at compile time where a constant is required
+/// e.g. the length of an array type or the discriminant of a variant
+#[derive(Debug, PartialEq, Clone)]
+pub struct AnonConst {
+    pub id: NodeId,
+    pub expr: P<Expr>,
+}
+
+// types are required to be hashable and contain these
+// the id alone identifies the constant
+impl Eq for AnonConst {
+}
+
+impl Hash for AnonConst {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state)
+    }
 }
 
 /// access of field `p.x`
@@ -200,6 +221,16 @@ pub struct TyParam {
     pub id: NodeId,
     pub ident: Ident,
     pub default: Option<P<Ty>>,
+    pub bounds: Vec<GenericBound>,
+}
+
+/// `Trait` or `Fn(A, B) -> C`
+#[derive(Debug, PartialEq, Clone)]
+pub struct GenericBound {
+    pub span: Span,
+    pub path: Path,
+    /// the parenthesized signature of a `Fn*` bound represented as a function type
+    pub sig: Option<P<Ty>>,
 }
 
 impl Display for TyParam {
@@ -210,13 +241,30 @@ impl Display for TyParam {
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct FnSig {
+    pub constness: Constness,
     pub params: Vec<Param>,
     pub ret_ty: Option<P<Ty>>,
 }
 
 impl Display for FnSig {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "fn {} -> {:?}", lutil::join(&self.params, ", "), self.ret_ty)
+        write!(f, "{}fn {} -> {:?}", self.constness, lutil::join(&self.params, ", "), self.ret_ty)
+    }
+}
+
+/// whether a function may be called during constant evaluation
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Hash, Serialize, Deserialize)]
+pub enum Constness {
+    Const,
+    NotConst,
+}
+
+impl Display for Constness {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Const => write!(f, "const "),
+            Self::NotConst => write!(f, ""),
+        }
     }
 }
 
@@ -370,6 +418,8 @@ impl From<Tok> for BinOp {
             TokenType::Slash => Self::Div,
             TokenType::Gt => Self::Gt,
             TokenType::Lt => Self::Lt,
+            TokenType::Deq => Self::Eq,
+            TokenType::Neq => Self::Neq,
             k => panic!("invalid binary operator `{:?}`", k),
         }
     }
@@ -1,4 +1,4 @@
-use crate::Module;
+use crate::{Module, NodeId};
 use std::fmt::{self, Display, Formatter};
 
 /// top level ast representation that stores entire contents of the program being compiled
@@ -6,6 +6,9 @@ use std::fmt::{self, Display, Formatter};
 pub struct Ast {
     /// implicit top level module
     pub module: Module,
+    /// the first `NodeId` that has not been assigned to a node
+    /// passes that create nodes after parsing allocate their ids from here
+    pub next_node_id: NodeId,
 }
 
 impl Display for Ast {
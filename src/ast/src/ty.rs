@@ -1,4 +1,4 @@
-use super::{NodeId, Path, P};
+use super::{AnonConst, NodeId, Path, P};
 use span::Span;
 use std::fmt::{self, Display, Formatter};
 
@@ -11,11 +11,14 @@ pub struct Ty {
 
 #[derive(Debug, PartialEq, Clone, Eq, Hash)]
 pub enum TyKind {
-    Array(P<Ty>),
+    /// [<ty>; <expr>]
+    Array(P<Ty>, AnonConst),
     Tuple(Vec<P<Ty>>),
     /// (<ty>)
     Paren(P<Ty>),
     Path(Path),
+    /// dyn <path>
+    Dyn(Path),
     /// &<ty>
     Box(P<Ty>),
     /// fn(<ty>...) (-> <ty>)?
@@ -116,6 +116,10 @@ pub trait Visitor<'ast>: Sized {
     fn visit_assoc_item(&mut self, item: &'ast AssocItem) {
         walk_assoc_item(self, item)
     }
+
+    fn visit_anon_const(&mut self, anon_const: &'ast AnonConst) {
+        walk_anon_const(self, anon_const)
+    }
 }
 
 pub fn walk_ast<'ast>(visitor: &mut impl Visitor<'ast>, ast: &'ast Ast) {
@@ -208,7 +212,11 @@ pub fn walk_arm<'ast>(visitor: &mut impl Visitor<'ast>, arm: &'ast Arm) {
 pub fn walk_expr<'ast>(visitor: &mut impl Visitor<'ast>, expr: &'ast Expr) {
     visitor.visit_id(expr.id);
     match &expr.kind {
-        ExprKind::Err | ExprKind::Lit(..) | ExprKind::Continue | ExprKind::Break => {}
+        ExprKind::Err
+        | ExprKind::Lit(..)
+        | ExprKind::Panic(..)
+        | ExprKind::Continue
+        | ExprKind::Break => {}
         ExprKind::Ret(expr) => expr.iter().for_each(|expr| visitor.visit_expr(expr)),
         ExprKind::Unary(_, expr) => visitor.visit_expr(expr),
         ExprKind::Paren(expr) => visitor.visit_expr(expr),
@@ -254,6 +262,11 @@ pub fn walk_expr<'ast>(visitor: &mut impl Visitor<'ast>, expr: &'ast Expr) {
     }
 }
 
+pub fn walk_anon_const<'ast>(visitor: &mut impl Visitor<'ast>, anon_const: &'ast AnonConst) {
+    visitor.visit_id(anon_const.id);
+    visitor.visit_expr(&anon_const.expr);
+}
+
 pub fn walk_generics<'ast>(visitor: &mut impl Visitor<'ast>, generics: &'ast Generics) {
     generics.params.iter().for_each(|p| visitor.visit_ty_param(p));
 }
@@ -298,10 +311,13 @@ pub fn walk_pat<'ast>(visitor: &mut impl Visitor<'ast>, pat: &'ast Pattern) {
 pub fn walk_ty<'ast>(visitor: &mut impl Visitor<'ast>, ty: &'ast Ty) {
     visitor.visit_id(ty.id);
     match &ty.kind {
-        TyKind::Box(ty) | TyKind::Array(ty) | TyKind::Ptr(ty) | TyKind::Paren(ty) =>
-            visitor.visit_ty(ty),
+        TyKind::Box(ty) | TyKind::Ptr(ty) | TyKind::Paren(ty) => visitor.visit_ty(ty),
+        TyKind::Array(ty, len) => {
+            visitor.visit_ty(ty);
+            visitor.visit_anon_const(len);
+        }
         TyKind::Tuple(tys) => tys.iter().for_each(|ty| visitor.visit_ty(ty)),
-        TyKind::Path(path) => visitor.visit_path(path),
+        TyKind::Path(path) | TyKind::Dyn(path) => visitor.visit_path(path),
         TyKind::Fn(params, ret) => {
             params.iter().for_each(|ty| visitor.visit_ty(ty));
             ret.iter().for_each(|ty| visitor.visit_ty(ty));
@@ -327,6 +343,7 @@ pub fn walk_variant<'ast>(visitor: &mut impl Visitor<'ast>, variant: &'ast Varia
     visitor.visit_id(variant.id);
     visitor.visit_ident(variant.ident);
     visitor.visit_variant_kind(&variant.kind);
+    variant.discr.iter().for_each(|discr| visitor.visit_anon_const(discr));
 }
 
 pub fn walk_variant_kind<'ast>(visitor: &mut impl Visitor<'ast>, kind: &'ast VariantKind) {
@@ -351,6 +368,10 @@ pub fn walk_item<'ast>(visitor: &mut impl Visitor<'ast>, item: &'ast Item) {
             visitor.visit_generics(generics);
             visitor.visit_ty(ty);
         }
+        ItemKind::Const(ty, expr) | ItemKind::Static(ty, expr) => {
+            visitor.visit_ty(ty);
+            visitor.visit_expr(expr);
+        }
         ItemKind::Enum(generics, variants) => {
             visitor.visit_generics(generics);
             variants.iter().for_each(|variant| visitor.visit_variant(variant));
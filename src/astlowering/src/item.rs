@@ -12,7 +12,7 @@ impl<'a, 'ir> AstLoweringCtx<'a, 'ir> {
 
     pub fn lower_item(&mut self, item: &Item) {
         self.with_def_id(item.id, |lctx| {
-            let &Item { span, id, vis, ref kind, ident } = item;
+            let &Item { span, id, vis, ref kind, ident, ref attrs } = item;
             let id = lctx.lower_node_id(id);
             let kind = match &kind {
                 ItemKind::Fn(sig, generics, expr) => {
@@ -28,6 +28,10 @@ impl<'a, 'ir> AstLoweringCtx<'a, 'ir> {
                     let body = lctx.lower_body(sig, expr.as_ref().unwrap());
                     ir::ItemKind::Fn(lowered_sig, generics, body)
                 }
+                ItemKind::Const(ty, expr) =>
+                    ir::ItemKind::Const(lctx.lower_ty(ty), lctx.lower_const_body(expr)),
+                ItemKind::Static(ty, expr) =>
+                    ir::ItemKind::Static(lctx.lower_ty(ty), lctx.lower_const_body(expr)),
                 ItemKind::Enum(generics, variants) => {
                     let generics = lctx.lower_generics(generics);
                     let variants = lctx.lower_variants(variants);
@@ -55,7 +59,8 @@ impl<'a, 'ir> AstLoweringCtx<'a, 'ir> {
                         .alloc_from_iter(items.iter().map(|item| lctx.lower_trait_item_ref(item))),
                 },
             };
-            let item = lctx.alloc(ir::Item { span, id, vis, ident, kind });
+            let attrs = lctx.lower_attrs(attrs);
+            let item = lctx.alloc(ir::Item { span, id, vis, ident, attrs, kind });
             lctx.mk_def_node(id.def, item);
             lctx.items.insert(id.def, item);
         });
@@ -130,7 +135,7 @@ impl<'a, 'ir> AstLoweringCtx<'a, 'ir> {
     }
 
     fn lower_trait_item(&mut self, trait_item: &TraitItem) -> &'ir ir::TraitItem<'ir> {
-        let &TraitItem { id, ident, vis, span, ref kind } = trait_item;
+        let &TraitItem { id, ident, vis, span, ref attrs, ref kind } = trait_item;
         let id = self.lower_node_id(id);
         let (generics, kind) = match kind {
             AssocItemKind::Fn(sig, generics, body) => (
@@ -142,7 +147,10 @@ impl<'a, 'ir> AstLoweringCtx<'a, 'ir> {
             ),
         };
         let generics = self.lower_generics(generics);
-        let trait_item = self.alloc(ir::TraitItem { id, ident, vis, span, generics, kind });
+        let trait_def_id = self.parent_def_id(id);
+        let attrs = self.lower_attrs(attrs);
+        let trait_item =
+            self.alloc(ir::TraitItem { id, trait_def_id, ident, vis, span, attrs, generics, kind });
         self.mk_def_node(id.def, trait_item);
         trait_item
     }
@@ -156,8 +164,12 @@ impl<'a, 'ir> AstLoweringCtx<'a, 'ir> {
         })
     }
 
+    fn lower_attrs(&self, attrs: &[Attribute]) -> &'ir [Attribute] {
+        self.arena.alloc_from_iter(attrs.iter().cloned())
+    }
+
     fn lower_impl_item(&mut self, impl_item: &AssocItem) -> &'ir ir::ImplItem<'ir> {
-        let &AssocItem { span, id, vis, ident, ref kind } = impl_item;
+        let &AssocItem { span, id, vis, ident, ref attrs, ref kind } = impl_item;
         let id = self.lower_node_id(id);
         let (generics, kind) = match kind {
             AssocItemKind::Fn(sig, generics, body) => {
@@ -169,8 +181,9 @@ impl<'a, 'ir> AstLoweringCtx<'a, 'ir> {
         };
 
         let impl_def_id = self.parent_def_id(id);
+        let attrs = self.lower_attrs(attrs);
         let impl_item =
-            self.alloc(ir::ImplItem { id, impl_def_id, ident, span, vis, generics, kind });
+            self.alloc(ir::ImplItem { id, impl_def_id, ident, span, vis, attrs, generics, kind });
         self.mk_def_node(id.def, impl_item);
         impl_item
     }
@@ -188,9 +201,11 @@ impl<'a, 'ir> AstLoweringCtx<'a, 'ir> {
         self.with_def_id(variant.id, |lctx| {
             let id = lctx.lower_node_id(variant.id);
             let kind = lctx.lower_variant_kind(&variant.kind);
+            let discr = variant.discr.as_ref().map(|discr| lctx.lower_anon_const(discr));
             ir::Variant {
                 id,
                 kind,
+                discr,
                 adt_def_id,
                 ident: variant.ident,
                 span: variant.span,
@@ -227,6 +242,6 @@ impl<'a, 'ir> AstLoweringCtx<'a, 'ir> {
         let inputs =
             self.arena.alloc_from_iter(sig.params.iter().map(|p| self.lower_ty_inner(&p.ty)));
         let output = sig.ret_ty.as_ref().map(|ty| self.lower_ty(ty));
-        self.arena.alloc(ir::FnSig { inputs, output })
+        self.arena.alloc(ir::FnSig { constness: sig.constness, inputs, output })
     }
 }
@@ -113,17 +113,27 @@ impl<'a, 'ir> AstLoweringCtx<'a, 'ir> {
     fn lower_ty_param(&mut self, param: &TyParam) -> ir::TyParam<'ir> {
         // `TyParam`s have their own `DefId`
         self.with_def_id(param.id, |lctx| {
-            let &TyParam { span, id, ident, ref default } = param;
+            let &TyParam { span, id, ident, ref default, ref bounds } = param;
             ir::TyParam {
                 span,
                 id: lctx.lower_node_id(id),
                 index: lctx.resolver.idx_of_ty_param(id),
                 ident,
                 default: default.as_ref().map(|ty| lctx.lower_ty(ty)),
+                bounds: lctx.arena.alloc_from_iter(bounds.iter().map(|b| lctx.lower_bound(b))),
             }
         })
     }
 
+    fn lower_bound(&mut self, bound: &GenericBound) -> ir::GenericBound<'ir> {
+        let &GenericBound { span, ref path, ref sig } = bound;
+        ir::GenericBound {
+            span,
+            path: self.lower_path(path),
+            sig: sig.as_ref().map(|ty| self.lower_ty(ty)),
+        }
+    }
+
     fn with_def_id<T>(&mut self, owner: NodeId, f: impl FnOnce(&mut Self) -> T) -> T {
         let def_id = self.resolver.def_id(owner);
         self.owner_stack.push((def_id, 0));
@@ -172,6 +182,22 @@ impl<'a, 'ir> AstLoweringCtx<'a, 'ir> {
         self.alloc(ir::Body { params, expr })
     }
 
+    /// the body of a constant is a body without any parameters
+    fn lower_const_body(&mut self, expr: &Expr) -> &'ir ir::Body<'ir> {
+        let expr = self.lower_expr(expr);
+        self.alloc(ir::Body { params: &[], expr })
+    }
+
+    fn lower_anon_const(&mut self, anon_const: &AnonConst) -> &'ir ir::AnonConst<'ir> {
+        self.with_def_id(anon_const.id, |lctx| {
+            let id = lctx.lower_node_id(anon_const.id);
+            let body = lctx.lower_const_body(&anon_const.expr);
+            let anon_const = lctx.alloc(ir::AnonConst { id, span: body.expr.span, body });
+            lctx.mk_def_node(id.def, anon_const);
+            anon_const
+        })
+    }
+
     fn lower_params(&mut self, params: &[Param]) -> &'ir [ir::Param<'ir>] {
         self.arena.alloc_from_iter(params.iter().map(|p| self.lower_param(p)))
     }
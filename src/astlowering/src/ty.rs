@@ -18,9 +18,11 @@ impl<'ir> AstLoweringCtx<'_, 'ir> {
                 ir::TyKind::Fn(self.lower_tys(params), ret.as_ref().map(|ty| self.lower_ty(ty))),
             TyKind::Box(ty) => ir::TyKind::Box(self.lower_ty(ty)),
             TyKind::Paren(ty) => return self.lower_ty_inner(ty),
-            TyKind::Array(ty) => ir::TyKind::Array(self.lower_ty(ty)),
+            TyKind::Array(ty, len) =>
+                ir::TyKind::Array(self.lower_ty(ty), self.lower_anon_const(len)),
             TyKind::Tuple(tys) => ir::TyKind::Tuple(self.lower_tys(tys)),
             TyKind::Path(path) => ir::TyKind::Path(self.lower_qpath(path)),
+            TyKind::Dyn(path) => ir::TyKind::Dyn(self.lower_path(path)),
             TyKind::Ptr(ty) => ir::TyKind::Ptr(self.lower_ty(ty)),
             TyKind::ImplicitSelf => {
                 let res = self.resolver.full_res(ty.id);
@@ -49,6 +49,10 @@ impl<'ir> Visitor<'ir> for Validator<'ir> {
         self.with_def_id(decl.id.def, |this| ir::walk_field_decl(this, decl));
     }
 
+    fn visit_anon_const(&mut self, anon_const: &'ir ir::AnonConst<'ir>) {
+        self.with_def_id(anon_const.id.def, |this| ir::walk_anon_const(this, anon_const));
+    }
+
     fn visit_id(&mut self, ir: ir::Id) {
         assert_eq!(self.curr_def_id.unwrap(), ir.def);
     }
@@ -20,6 +20,7 @@ enum SubCommand {
     Jit(CompilerOptions),
     Build(CompilerOptions),
     Run(CompilerOptions),
+    Interp(CompilerOptions),
     Check(CompilerOptions),
     New(NewCmd),
     Test(TestCmd),
@@ -43,7 +44,16 @@ pub fn main() -> io::Result<()> {
             Ok(())
         }
         SubCommand::Run(rcfg) => {
-            let _ = ldriver::run_compiler(rcfg, |compiler| compiler.run());
+            // forward the exit code of the program (e.g. so panics are visible to the caller)
+            if let Ok(Some(code)) = ldriver::run_compiler(rcfg, |compiler| compiler.run()) {
+                std::process::exit(code)
+            }
+            Ok(())
+        }
+        SubCommand::Interp(rcfg) => {
+            if let Ok(code) = ldriver::run_compiler(rcfg, |compiler| compiler.interp()) {
+                std::process::exit(code)
+            }
             Ok(())
         }
         SubCommand::Build(bcfg) => {
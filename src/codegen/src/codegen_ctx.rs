@@ -1,13 +1,18 @@
 use super::*;
 use context::Context;
 use error::{ErrorReported, LResult};
-use inkwell::passes::PassManager;
+use inkwell::passes::{PassManager, PassManagerBuilder};
+use inkwell::targets::TargetMachine;
 use inkwell::types::*;
 use inkwell::values::*;
 use inkwell::*;
 use inkwell::{builder::Builder, module::Module};
+use ir::DefId;
+use itertools::Itertools;
+use lcore::mir::{VarId, RET_VAR};
 use lcore::ty::*;
 use rustc_hash::FxHashMap;
+use session::OptLevel;
 use span::{sym, Span};
 use std::cell::RefCell;
 use std::ops::Deref;
@@ -26,6 +31,11 @@ pub struct CodegenCtx<'tcx> {
     pub intrinsics: RefCell<FxHashMap<Instance<'tcx>, FunctionValue<'tcx>>>,
     pub instances: RefCell<FxHashMap<Instance<'tcx>, FunctionValue<'tcx>>>,
     pub lltypes: RefCell<FxHashMap<Ty<'tcx>, BasicTypeEnum<'tcx>>>,
+    pub vtables: RefCell<FxHashMap<(Ty<'tcx>, DefId), PointerValue<'tcx>>>,
+    pub reify_shims: RefCell<FxHashMap<Instance<'tcx>, FunctionValue<'tcx>>>,
+    /// only present when debug info is enabled
+    pub dcx: Option<DebugCtx<'tcx>>,
+    pub target_machine: TargetMachine,
 }
 
 pub struct CommonValues<'tcx> {
@@ -41,6 +51,8 @@ pub struct CommonValues<'tcx> {
 pub struct CommonTypes<'tcx> {
     pub i32: IntType<'tcx>,
     pub i64: IntType<'tcx>,
+    /// an integer with the width of a pointer on the target
+    pub isize: IntType<'tcx>,
     pub unit: StructType<'tcx>,
     pub byte: IntType<'tcx>,
     pub float: FloatType<'tcx>,
@@ -48,13 +60,23 @@ pub struct CommonTypes<'tcx> {
     pub i8ptr: PointerType<'tcx>,
     pub i32ptr: PointerType<'tcx>,
     pub i64ptr: PointerType<'tcx>,
+    /// `{ i8*, i8** }`: a pointer to the data and a pointer to the vtable
+    pub trait_object: StructType<'tcx>,
+    /// `{ i8*, i8* }`: a pointer to the function and a pointer to its environment
+    pub fn_ptr: StructType<'tcx>,
     // using a fix sized discriminant for ease for now
     pub discr: IntType<'tcx>,
 }
 
 impl<'tcx> CodegenCtx<'tcx> {
-    pub fn new(tcx: TyCtx<'tcx>, llctx: &'tcx Context) -> Self {
+    pub fn new(tcx: TyCtx<'tcx>, llctx: &'tcx Context) -> LResult<Self> {
+        let target_machine = target::create_target_machine(&tcx.sess.opts)
+            .map_err(|err| tcx.sess.emit_error(Span::default(), LLVMError::Target(err)))?;
+        let target_data = target_machine.get_target_data();
         let module = llctx.create_module("main");
+        // this must be set before anything queries the size of a type
+        module.set_triple(&target_machine.get_triple());
+        module.set_data_layout(&target_data.get_data_layout());
         let fpm = PassManager::create(&module);
         fpm.add_instruction_combining_pass();
         fpm.add_reassociate_pass();
@@ -66,17 +88,22 @@ impl<'tcx> CodegenCtx<'tcx> {
         fpm.add_reassociate_pass();
         fpm.initialize();
 
+        let i8ptr = llctx.i8_type().ptr_type(AddressSpace::Generic);
         let types = CommonTypes {
             unit: llctx.struct_type(&[], false),
             i64: llctx.i64_type(),
+            isize: llctx.ptr_sized_int_type(&target_data, None),
             i32: llctx.i32_type(),
             float: llctx.f64_type(),
             byte: llctx.i8_type(),
             bool: llctx.bool_type(),
-            i8ptr: llctx.i8_type().ptr_type(AddressSpace::Generic),
+            i8ptr,
             i32ptr: llctx.i32_type().ptr_type(AddressSpace::Generic),
             i64ptr: llctx.i64_type().ptr_type(AddressSpace::Generic),
             discr: llctx.i16_type(),
+            trait_object: llctx
+                .struct_type(&[i8ptr.into(), i8ptr.ptr_type(AddressSpace::Generic).into()], false),
+            fn_ptr: llctx.struct_type(&[i8ptr.into(), i8ptr.into()], false),
         };
 
         let vals = CommonValues {
@@ -89,11 +116,18 @@ impl<'tcx> CodegenCtx<'tcx> {
             unit: types.unit.get_undef(),
         };
 
-        let native_functions = NativeFunctionsBuilder::new(llctx, &module).build();
+        let native_functions = NativeFunctionsBuilder::new(llctx, &module, types.isize).build();
         let llvm_intrinsics = LLVMIntrinsics::new(llctx, &module);
-        let gc = GCFunctions::new(llctx, &module);
+        let gc = GCFunctions::new(llctx, &module, types.isize);
+        let opts = &tcx.sess.opts;
+        let dcx = if opts.debuginfo() {
+            let is_optimized = opts.opt_level() != OptLevel::O0;
+            Some(DebugCtx::new(&module, span::ROOT_FILE_IDX, is_optimized))
+        } else {
+            None
+        };
 
-        Self {
+        Ok(Self {
             tcx,
             llctx,
             module,
@@ -107,7 +141,11 @@ impl<'tcx> CodegenCtx<'tcx> {
             intrinsics: Default::default(),
             instances: Default::default(),
             lltypes: Default::default(),
-        }
+            vtables: Default::default(),
+            reify_shims: Default::default(),
+            dcx,
+            target_machine,
+        })
     }
 
     pub fn declare_instances<I>(&self, instances: &I)
@@ -141,26 +179,116 @@ impl<'tcx> CodegenCtx<'tcx> {
                 };
                 let llty = self.llvm_fn_ty_from_ty(ty.subst(self.tcx, substs));
                 let llfn = self.module.add_function(&name, llty, None);
-                self.instances
-                    .borrow_mut()
-                    .insert(Instance::resolve(self.tcx, def_id, substs), llfn);
+                self.instances.borrow_mut().insert(self.collected_instance(def_id, substs), llfn);
+            }
+            InstanceKind::Closure(id) => {
+                let Instance { def_id, substs, .. } = instance;
+                // the lifted body takes its environment as an implicit first argument
+                // which is already included in the arguments of its mir
+                let mir = self.tcx.instance_mir(instance);
+                let ty_of = |var: VarId| self.llvm_ty(mir.vars[var].ty.subst(self.tcx, substs));
+                let params = mir.arg_iter().map(ty_of).collect_vec();
+                let llty = ty_of(RET_VAR).fn_type(&params, false);
+                let ident = self.tcx.defs().ident(def_id);
+                let name = format!("{}<{}>::closure<{:?}>", ident, substs, id.local);
+                let llfn = self.module.add_function(&name, llty, None);
+                self.instances.borrow_mut().insert(instance, llfn);
             }
             InstanceKind::Intrinsic => self.codegen_intrinsic(instance),
+            InstanceKind::Virtual(..) => unreachable!("virtual instances are not collected"),
+        }
+    }
+
+    /// resolves an instance that monomorphization has already resolved
+    /// codegen only starts if every instance it collected resolved without errors
+    pub fn collected_instance(&self, def_id: DefId, substs: SubstsRef<'tcx>) -> Instance<'tcx> {
+        Instance::resolve(self.tcx, def_id, substs).expect("instance failed to resolve")
+    }
+
+    /// returns the llvm function of an instance that can be called directly
+    pub fn instance_llfn(&self, instance: Instance<'tcx>) -> FunctionValue<'tcx> {
+        match instance.kind {
+            InstanceKind::Item | InstanceKind::Closure(..) => self.instances.borrow()[&instance],
+            InstanceKind::Intrinsic => self.intrinsics.borrow()[&instance],
+            InstanceKind::Virtual(..) => unreachable!("virtual instances have no llvm function"),
         }
     }
 
+    /// returns a shim that allows `instance` to be called through a function pointer value
+    /// the shim ignores the environment argument and forwards the remaining arguments
+    pub fn get_reify_shim(&self, instance: Instance<'tcx>) -> FunctionValue<'tcx> {
+        if let Some(&shim) = self.reify_shims.borrow().get(&instance) {
+            return shim;
+        }
+        let llfn = self.instance_llfn(instance);
+        let llty = llfn.get_type();
+        let params = std::iter::once(self.types.i8ptr.into())
+            .chain(llty.get_param_types())
+            .collect_vec();
+        let shim_ty = llty.get_return_type().unwrap().fn_type(&params, false);
+        let name = format!("{}::shim", llfn.get_name().to_str().unwrap());
+        let shim = self.module.add_function(&name, shim_ty, None);
+
+        // the shim may be requested while in the middle of generating another function
+        let prev_block = self.get_insert_block();
+        // the shim has no debug info of its own so it mustn't inherit the current location
+        let prev_loc = self.dcx.as_ref().map(|_| self.get_debug_location());
+        if prev_loc.is_some() {
+            self.set_debug_location(std::ptr::null_mut());
+        }
+        self.position_at_end(self.llctx.append_basic_block(shim, "shim_entry"));
+        let args = shim.get_param_iter().skip(1).collect_vec();
+        let ret = self.build_call(llfn, &args, "shim_call").try_as_basic_value().left().unwrap();
+        self.build_return(Some(&ret));
+        if let Some(block) = prev_block {
+            self.position_at_end(block);
+        }
+        if let Some(loc) = prev_loc {
+            self.set_debug_location(loc);
+        }
+
+        self.reify_shims.borrow_mut().insert(instance, shim);
+        shim
+    }
+
     pub fn codegen_instances(&self) {
         self.instances.borrow().keys().for_each(|&instance| self.codegen_instance(instance));
     }
 
     pub fn codegen_instance(&self, instance: Instance<'tcx>) {
         match instance.kind {
-            InstanceKind::Item => FnCtx::new(self, instance).codegen(),
+            InstanceKind::Item | InstanceKind::Closure(..) => FnCtx::new(self, instance).codegen(),
             // codegenned during declaration
             InstanceKind::Intrinsic => {}
+            InstanceKind::Virtual(..) => unreachable!("virtual instances are not collected"),
         }
     }
 
+    /// returns a pointer to the vtable of `ty` as a trait object of the given trait
+    /// the vtable is a constant global array of (type erased) function pointers
+    pub fn get_vtable(&self, ty: Ty<'tcx>, trait_def_id: DefId) -> PointerValue<'tcx> {
+        if let Some(&vtable) = self.vtables.borrow().get(&(ty, trait_def_id)) {
+            return vtable;
+        }
+        let entries = self
+            .tcx
+            .vtable_entries((ty, trait_def_id))
+            .expect("vtable entries failed to resolve")
+            .iter()
+            .map(|instance| self.instances.borrow()[instance].as_llvm_ptr())
+            .map(|llfn| llfn.const_cast(self.types.i8ptr))
+            .collect_vec();
+        let llty = self.types.i8ptr.array_type(entries.len() as u32);
+        let name = format!("vtable<{}, {}>", ty, self.tcx.defs().ident(trait_def_id));
+        let global = self.module.add_global(llty, None, &name);
+        global.set_initializer(&self.types.i8ptr.const_array(&entries));
+        global.set_constant(true);
+        let vtable =
+            global.as_pointer_value().const_cast(self.types.i8ptr.ptr_type(AddressSpace::Generic));
+        self.vtables.borrow_mut().insert((ty, trait_def_id), vtable);
+        vtable
+    }
+
     /// returns the main function
     pub fn codegen(&self) -> LResult<()> {
         let instances = self.tcx.monomorphization_instances(());
@@ -169,12 +297,44 @@ impl<'tcx> CodegenCtx<'tcx> {
         }
         self.declare_instances(instances);
         self.codegen_instances();
+        if let Some(dcx) = &self.dcx {
+            dcx.finalize();
+        }
         self.module.verify().unwrap();
         if self.module.get_function(sym::main.as_str()).is_none() {
             self.tcx.sess.build_error(Span::default(), LLVMError::MissingMain).emit();
         }
+        self.optimize();
         Ok(())
     }
+
+    /// runs the llvm pass pipeline of the selected optimization level over the module
+    fn optimize(&self) {
+        let opt_level = self.tcx.sess.opts.opt_level();
+        if opt_level == OptLevel::O0 {
+            return;
+        }
+        let pmb = PassManagerBuilder::create();
+        pmb.set_optimization_level(llvm_opt_level(opt_level));
+        // the same inlining thresholds that clang uses
+        match opt_level {
+            OptLevel::O2 => pmb.set_inliner_with_threshold(225),
+            OptLevel::O3 => pmb.set_inliner_with_threshold(250),
+            OptLevel::O0 | OptLevel::O1 => {}
+        }
+        let mpm = PassManager::create(());
+        pmb.populate_module_pass_manager(&mpm);
+        mpm.run_on(&self.module);
+    }
+}
+
+pub fn llvm_opt_level(opt_level: OptLevel) -> OptimizationLevel {
+    match opt_level {
+        OptLevel::O0 => OptimizationLevel::None,
+        OptLevel::O1 => OptimizationLevel::Less,
+        OptLevel::O2 => OptimizationLevel::Default,
+        OptLevel::O3 => OptimizationLevel::Aggressive,
+    }
 }
 
 impl<'tcx> Deref for CodegenCtx<'tcx> {
@@ -0,0 +1,510 @@
+//! dwarf debug info (enabled with `-g`)
+//! like `unwind`, this is built through llvm-sys directly
+
+use crate::CodegenCtx;
+use inkwell::basic_block::BasicBlock;
+use inkwell::module::Module;
+use inkwell::types::{AsTypeRef, BasicTypeEnum};
+use inkwell::values::{AsValueRef, FunctionValue, PointerValue};
+use lcore::mir::SpanInfo;
+use lcore::ty::*;
+use llvm_sys::core::*;
+use llvm_sys::debuginfo::*;
+use llvm_sys::prelude::*;
+use llvm_sys::target::*;
+use llvm_sys::LLVMModuleFlagBehavior;
+use rustc_hash::FxHashMap;
+use span::{FileIdx, Span};
+use std::cell::RefCell;
+use std::ptr;
+
+// https://dwarfstd.org/doc/DWARF4.pdf (section 7.8)
+const DW_ATE_BOOLEAN: LLVMDWARFTypeEncoding = 0x02;
+const DW_ATE_FLOAT: LLVMDWARFTypeEncoding = 0x04;
+const DW_ATE_SIGNED: LLVMDWARFTypeEncoding = 0x05;
+const DW_ATE_UNSIGNED_CHAR: LLVMDWARFTypeEncoding = 0x08;
+const DW_TAG_STRUCTURE_TYPE: u32 = 0x13;
+
+pub struct DebugCtx<'tcx> {
+    llcx: LLVMContextRef,
+    builder: LLVMDIBuilderRef,
+    compile_unit: LLVMMetadataRef,
+    files: RefCell<FxHashMap<FileIdx, LLVMMetadataRef>>,
+    ditypes: RefCell<FxHashMap<Ty<'tcx>, LLVMMetadataRef>>,
+}
+
+impl<'tcx> DebugCtx<'tcx> {
+    pub fn new(module: &Module<'tcx>, root_file: FileIdx, is_optimized: bool) -> Self {
+        let llmod = module.get_module_ref();
+        let version = i32_metadata(llmod, unsafe { LLVMDebugMetadataVersion() });
+        add_module_flag(llmod, "Debug Info Version", version);
+        add_module_flag(llmod, "Dwarf Version", i32_metadata(llmod, 4));
+
+        let mut dcx = unsafe {
+            Self {
+                llcx: LLVMGetModuleContext(llmod),
+                builder: LLVMCreateDIBuilder(llmod),
+                compile_unit: ptr::null_mut(),
+                files: Default::default(),
+                ditypes: Default::default(),
+            }
+        };
+        let file = dcx.file(root_file);
+        let producer = "lc";
+        dcx.compile_unit = unsafe {
+            LLVMDIBuilderCreateCompileUnit(
+                dcx.builder,
+                // debuggers don't know about L, and C gives the most sensible output for locals
+                LLVMDWARFSourceLanguage::LLVMDWARFSourceLanguageC,
+                file,
+                producer.as_ptr() as *const _,
+                producer.len(),
+                is_optimized as LLVMBool,
+                ptr::null(),
+                0,
+                0,
+                ptr::null(),
+                0,
+                LLVMDWARFEmissionKind::LLVMDWARFEmissionKindFull,
+                0,
+                0,
+                0,
+            )
+        };
+        dcx
+    }
+
+    /// resolves all temporary metadata, must be called before the module is verified
+    pub fn finalize(&self) {
+        unsafe { LLVMDIBuilderFinalize(self.builder) }
+    }
+
+    fn file(&self, file: FileIdx) -> LLVMMetadataRef {
+        if let Some(&difile) = self.files.borrow().get(&file) {
+            return difile;
+        }
+        let path = span::with_source_map(|map| map.path_of(file).to_path_buf());
+        let name = path.file_name().unwrap().to_str().unwrap();
+        let dir = path.parent().unwrap().to_str().unwrap();
+        let difile = unsafe {
+            LLVMDIBuilderCreateFile(
+                self.builder,
+                name.as_ptr() as *const _,
+                name.len(),
+                dir.as_ptr() as *const _,
+                dir.len(),
+            )
+        };
+        self.files.borrow_mut().insert(file, difile);
+        difile
+    }
+
+    /// attaches a subprogram to `llfn` which is used as the scope of everything inside it
+    pub fn create_subprogram(
+        &self,
+        llfn: FunctionValue<'tcx>,
+        name: &str,
+        span: Span,
+        ditys: &mut [LLVMMetadataRef],
+    ) -> LLVMMetadataRef {
+        let file = self.file(span.file);
+        let (line, _) = span::with_source_map(|map| map.span_to_line_col(span));
+        let linkage_name = llfn.get_name().to_str().unwrap();
+        unsafe {
+            let ty = LLVMDIBuilderCreateSubroutineType(
+                self.builder,
+                file,
+                ditys.as_mut_ptr(),
+                ditys.len() as u32,
+                LLVMDIFlagZero,
+            );
+            let subprogram = LLVMDIBuilderCreateFunction(
+                self.builder,
+                file,
+                name.as_ptr() as *const _,
+                name.len(),
+                linkage_name.as_ptr() as *const _,
+                linkage_name.len(),
+                file,
+                line as u32,
+                ty,
+                0,
+                1,
+                line as u32,
+                LLVMDIFlagZero,
+                0,
+            );
+            LLVMSetSubprogram(llfn.as_value_ref(), subprogram);
+            subprogram
+        }
+    }
+
+    pub fn location(&self, scope: LLVMMetadataRef, info: SpanInfo) -> LLVMMetadataRef {
+        let (line, col) = span::with_source_map(|map| map.span_to_line_col(info.span));
+        unsafe {
+            LLVMDIBuilderCreateDebugLocation(
+                self.llcx,
+                line as u32,
+                col as u32,
+                scope,
+                ptr::null_mut(),
+            )
+        }
+    }
+
+    /// describes the variable stored at `ptr` to the debugger
+    /// `arg_no` is the (1-indexed) position of the variable in the parameters, if it is one
+    pub fn declare_var(
+        &self,
+        scope: LLVMMetadataRef,
+        block: BasicBlock<'tcx>,
+        ptr: PointerValue<'tcx>,
+        name: &str,
+        info: SpanInfo,
+        dity: LLVMMetadataRef,
+        arg_no: Option<u32>,
+    ) {
+        let file = self.file(info.span.file);
+        let (line, _) = span::with_source_map(|map| map.span_to_line_col(info.span));
+        let name_ptr = name.as_ptr() as *const _;
+        unsafe {
+            let var = match arg_no {
+                Some(arg_no) => LLVMDIBuilderCreateParameterVariable(
+                    self.builder,
+                    scope,
+                    name_ptr,
+                    name.len(),
+                    arg_no,
+                    file,
+                    line as u32,
+                    dity,
+                    1,
+                    LLVMDIFlagZero,
+                ),
+                None => LLVMDIBuilderCreateAutoVariable(
+                    self.builder,
+                    scope,
+                    name_ptr,
+                    name.len(),
+                    file,
+                    line as u32,
+                    dity,
+                    1,
+                    LLVMDIFlagZero,
+                    0,
+                ),
+            };
+            let expr = LLVMDIBuilderCreateExpression(self.builder, ptr::null_mut(), 0);
+            LLVMDIBuilderInsertDeclareAtEnd(
+                self.builder,
+                ptr.as_value_ref(),
+                var,
+                expr,
+                self.location(scope, info),
+                block.as_mut_ptr(),
+            );
+        }
+    }
+}
+
+impl<'tcx> Drop for DebugCtx<'tcx> {
+    fn drop(&mut self) {
+        unsafe { LLVMDisposeDIBuilder(self.builder) }
+    }
+}
+
+impl<'tcx> CodegenCtx<'tcx> {
+    /// the debug info type of `ty`
+    /// types without a more precise description are described as a struct of the right size
+    pub fn dity(&self, ty: Ty<'tcx>) -> LLVMMetadataRef {
+        let dcx = self.dcx.as_ref().unwrap();
+        if let Some(&dity) = dcx.ditypes.borrow().get(ty) {
+            return dity;
+        }
+        let name = ty.to_string();
+        let size = 8 * self.sizeof_ty(ty);
+        let dity = match ty.kind {
+            TyKind::Bool => self.basic_dity(&name, size, DW_ATE_BOOLEAN),
+            TyKind::Char => self.basic_dity(&name, size, DW_ATE_UNSIGNED_CHAR),
+            TyKind::Float => self.basic_dity(&name, size, DW_ATE_FLOAT),
+            TyKind::Int | TyKind::Discr => self.basic_dity(&name, size, DW_ATE_SIGNED),
+            TyKind::Box(pointee) | TyKind::Ptr(pointee)
+                if !matches!(pointee.kind, TyKind::Dyn(..)) =>
+            {
+                let pointee = self.dity(pointee);
+                unsafe {
+                    LLVMDIBuilderCreatePointerType(
+                        dcx.builder,
+                        pointee,
+                        size,
+                        0,
+                        0,
+                        name.as_ptr() as *const _,
+                        name.len(),
+                    )
+                }
+            }
+            TyKind::Tuple(tys) => {
+                let fields = tys.iter().enumerate().map(|(i, ty)| (i.to_string(), ty));
+                self.struct_dity(ty, &name, fields.collect())
+            }
+            TyKind::Adt(adt, substs) => match adt.kind {
+                AdtKind::Struct =>
+                    self.struct_dity(ty, &name, self.variant_fields(adt.single_variant(), substs)),
+                AdtKind::Enum => self.enum_dity(ty, &name, adt, substs),
+            },
+            _ => self.struct_dity(ty, &name, vec![]),
+        };
+        dcx.ditypes.borrow_mut().insert(ty, dity);
+        dity
+    }
+
+    fn basic_dity(
+        &self,
+        name: &str,
+        size: u64,
+        encoding: LLVMDWARFTypeEncoding,
+    ) -> LLVMMetadataRef {
+        let dcx = self.dcx.as_ref().unwrap();
+        unsafe {
+            LLVMDIBuilderCreateBasicType(
+                dcx.builder,
+                name.as_ptr() as *const _,
+                name.len(),
+                size,
+                encoding,
+                LLVMDIFlagZero,
+            )
+        }
+    }
+
+    fn variant_fields(
+        &self,
+        variant: &VariantTy,
+        substs: SubstsRef<'tcx>,
+    ) -> Vec<(String, Ty<'tcx>)> {
+        variant.fields.iter().map(|f| (f.ident.to_string(), f.ty(self.tcx, substs))).collect()
+    }
+
+    fn struct_dity(
+        &self,
+        ty: Ty<'tcx>,
+        name: &str,
+        fields: Vec<(String, Ty<'tcx>)>,
+    ) -> LLVMMetadataRef {
+        let llty = self.llvm_ty(ty);
+        let fwd = self.declare_composite_dity(ty, name, llty);
+        let mut members = self.member_ditys(llty, fields);
+        self.define_composite_dity(fwd, name, llty, &mut members, false)
+    }
+
+    /// enums are described as their discriminant followed by a union of their variants,
+    /// where each variant is a struct of its fields
+    fn enum_dity(
+        &self,
+        ty: Ty<'tcx>,
+        name: &str,
+        adt: &AdtTy,
+        substs: SubstsRef<'tcx>,
+    ) -> LLVMMetadataRef {
+        let llty = self.llvm_ty(ty);
+        let fwd = self.declare_composite_dity(ty, name, llty);
+        let mut variants = adt
+            .variants
+            .iter()
+            .map(|variant| {
+                let variant_llty: BasicTypeEnum<'tcx> =
+                    self.variant_ty_to_llvm_ty(variant, substs).into();
+                let mut fields =
+                    self.member_ditys(variant_llty, self.variant_fields(variant, substs));
+                let variant_name = variant.ident.to_string();
+                let dity = self.define_composite_dity(
+                    ptr::null_mut(),
+                    &variant_name,
+                    variant_llty,
+                    &mut fields,
+                    false,
+                );
+                self.member_dity(&variant_name, variant_llty, 0, dity)
+            })
+            .collect::<Vec<_>>();
+
+        let td = unsafe { LLVMGetModuleDataLayout(self.module.get_module_ref()) };
+        let content_llty = llty.into_struct_type().get_field_type_at_index(1).unwrap();
+        let content_offset = unsafe { LLVMOffsetOfElement(td, llty.as_type_ref(), 1) };
+        let payload_name = format!("{}::payload", name);
+        let payload = self.define_composite_dity(
+            ptr::null_mut(),
+            &payload_name,
+            content_llty,
+            &mut variants,
+            true,
+        );
+        let discr: BasicTypeEnum<'tcx> = self.types.discr.into();
+        let mut members = vec![
+            self.member_dity("discriminant", discr, 0, self.dity(self.tcx.types.discr)),
+            self.member_dity("payload", content_llty, content_offset, payload),
+        ];
+        self.define_composite_dity(fwd, name, llty, &mut members, false)
+    }
+
+    /// recursive types refer to themselves through a temporary forward declaration
+    fn declare_composite_dity(
+        &self,
+        ty: Ty<'tcx>,
+        name: &str,
+        llty: BasicTypeEnum<'tcx>,
+    ) -> LLVMMetadataRef {
+        let dcx = self.dcx.as_ref().unwrap();
+        let file = dcx.file(span::ROOT_FILE_IDX);
+        let fwd = unsafe {
+            LLVMDIBuilderCreateReplaceableCompositeType(
+                dcx.builder,
+                DW_TAG_STRUCTURE_TYPE,
+                name.as_ptr() as *const _,
+                name.len(),
+                dcx.compile_unit,
+                file,
+                0,
+                0,
+                8 * self.sizeof(llty),
+                8 * self.alignof(llty),
+                LLVMDIFlagZero,
+                ptr::null(),
+                0,
+            )
+        };
+        dcx.ditypes.borrow_mut().insert(ty, fwd);
+        fwd
+    }
+
+    /// the members of the struct `llty`, named by `fields`
+    fn member_ditys(
+        &self,
+        llty: BasicTypeEnum<'tcx>,
+        fields: Vec<(String, Ty<'tcx>)>,
+    ) -> Vec<LLVMMetadataRef> {
+        let td = unsafe { LLVMGetModuleDataLayout(self.module.get_module_ref()) };
+        fields
+            .into_iter()
+            .enumerate()
+            .map(|(i, (field_name, field_ty))| {
+                let offset = unsafe { LLVMOffsetOfElement(td, llty.as_type_ref(), i as u32) };
+                let field_llty = self.llvm_ty(field_ty);
+                self.member_dity(&field_name, field_llty, offset, self.dity(field_ty))
+            })
+            .collect()
+    }
+
+    /// `offset` is in bytes
+    fn member_dity(
+        &self,
+        name: &str,
+        llty: BasicTypeEnum<'tcx>,
+        offset: u64,
+        dity: LLVMMetadataRef,
+    ) -> LLVMMetadataRef {
+        let dcx = self.dcx.as_ref().unwrap();
+        unsafe {
+            LLVMDIBuilderCreateMemberType(
+                dcx.builder,
+                dcx.compile_unit,
+                name.as_ptr() as *const _,
+                name.len(),
+                dcx.file(span::ROOT_FILE_IDX),
+                0,
+                8 * self.sizeof(llty),
+                8 * self.alignof(llty),
+                8 * offset,
+                LLVMDIFlagZero,
+                dity,
+            )
+        }
+    }
+
+    /// creates a struct (or union) of `members`, replacing the forward declaration `fwd`
+    /// if there is one
+    fn define_composite_dity(
+        &self,
+        fwd: LLVMMetadataRef,
+        name: &str,
+        llty: BasicTypeEnum<'tcx>,
+        members: &mut [LLVMMetadataRef],
+        is_union: bool,
+    ) -> LLVMMetadataRef {
+        let dcx = self.dcx.as_ref().unwrap();
+        let file = dcx.file(span::ROOT_FILE_IDX);
+        let name_ptr = name.as_ptr() as *const _;
+        let size = 8 * self.sizeof(llty);
+        let align = 8 * self.alignof(llty);
+        unsafe {
+            let dity = if is_union {
+                LLVMDIBuilderCreateUnionType(
+                    dcx.builder,
+                    dcx.compile_unit,
+                    name_ptr,
+                    name.len(),
+                    file,
+                    0,
+                    size,
+                    align,
+                    LLVMDIFlagZero,
+                    members.as_mut_ptr(),
+                    members.len() as u32,
+                    0,
+                    ptr::null(),
+                    0,
+                )
+            } else {
+                LLVMDIBuilderCreateStructType(
+                    dcx.builder,
+                    dcx.compile_unit,
+                    name_ptr,
+                    name.len(),
+                    file,
+                    0,
+                    size,
+                    align,
+                    LLVMDIFlagZero,
+                    ptr::null_mut(),
+                    members.as_mut_ptr(),
+                    members.len() as u32,
+                    0,
+                    ptr::null_mut(),
+                    ptr::null(),
+                    0,
+                )
+            };
+            if !fwd.is_null() {
+                LLVMMetadataReplaceAllUsesWith(fwd, dity);
+            }
+            dity
+        }
+    }
+
+    /// sets the source location of the instructions built from now on
+    /// a null `loc` clears the location
+    pub fn set_debug_location(&self, loc: LLVMMetadataRef) {
+        unsafe { LLVMSetCurrentDebugLocation2(self.raw_builder(), loc) }
+    }
+
+    pub fn get_debug_location(&self) -> LLVMMetadataRef {
+        unsafe { LLVMGetCurrentDebugLocation2(self.raw_builder()) }
+    }
+
+    fn raw_builder(&self) -> LLVMBuilderRef {
+        self.builder.as_mut_ptr()
+    }
+}
+
+fn i32_metadata(llmod: LLVMModuleRef, i: u32) -> LLVMMetadataRef {
+    unsafe {
+        let i32_type = LLVMInt32TypeInContext(LLVMGetModuleContext(llmod));
+        LLVMValueAsMetadata(LLVMConstInt(i32_type, i as u64, 0))
+    }
+}
+
+fn add_module_flag(llmod: LLVMModuleRef, key: &str, val: LLVMMetadataRef) {
+    let behavior = LLVMModuleFlagBehavior::LLVMModuleFlagBehaviorWarning;
+    unsafe { LLVMAddModuleFlag(llmod, behavior, key.as_ptr() as *const _, key.len(), val) }
+}
@@ -0,0 +1,87 @@
+//! writes the generated module to disk and links it into an executable
+
+use crate::{CodegenCtx, LLVMError};
+use error::{ErrorReported, LResult};
+use inkwell::targets::FileType;
+use session::EmitKind;
+use span::Span;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// the linker driver, it knows where to find the c runtime and startup objects
+pub const LINKER: &str = "cc";
+
+/// all outputs are named `l` with the extension determined by their kind
+pub fn output_path(out_dir: &Path, kind: EmitKind) -> PathBuf {
+    out_dir.join("l").with_extension(kind.extension())
+}
+
+impl<'tcx> CodegenCtx<'tcx> {
+    /// writes each of the requested outputs into `out_dir`
+    pub fn emit(&self, out_dir: &Path, kinds: &[EmitKind]) -> LResult<()> {
+        let write_err = |path: &Path, err: String| {
+            let err = LLVMError::WriteFailed(path.to_path_buf(), err);
+            self.tcx.sess.emit_error(Span::default(), err)
+        };
+        std::fs::create_dir_all(out_dir).map_err(|err| write_err(out_dir, err.to_string()))?;
+
+        let target_machine = &self.target_machine;
+        for &kind in kinds {
+            let path = output_path(out_dir, kind);
+            match kind {
+                EmitKind::LlvmIr => self
+                    .module
+                    .print_to_file(&path)
+                    .map_err(|err| write_err(&path, err.to_string()))?,
+                EmitKind::LlvmBc =>
+                    if !self.module.write_bitcode_to_path(&path) {
+                        return Err(write_err(&path, "failed to write bitcode".to_owned()));
+                    },
+                EmitKind::Asm | EmitKind::Obj => {
+                    let file_type =
+                        if kind == EmitKind::Asm { FileType::Assembly } else { FileType::Object };
+                    target_machine
+                        .write_to_file(&self.module, file_type, &path)
+                        .map_err(|err| write_err(&path, err.to_string()))?
+                }
+                EmitKind::Link => {
+                    // linking requires an object file, which is removed afterwards unless requested
+                    let obj_path = output_path(out_dir, EmitKind::Obj);
+                    target_machine
+                        .write_to_file(&self.module, FileType::Object, &obj_path)
+                        .map_err(|err| write_err(&obj_path, err.to_string()))?;
+                    let res = self.link(&obj_path, &path);
+                    if !kinds.contains(&EmitKind::Obj) {
+                        let _ = std::fs::remove_file(&obj_path);
+                    }
+                    res?
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn link(&self, obj_path: &Path, out_path: &Path) -> LResult<()> {
+        let output = Command::new(LINKER)
+            .arg(obj_path)
+            .arg("-o")
+            .arg(out_path)
+            .arg("-lgc")
+            // panics unwind using the c++ runtime
+            .arg("-lstdc++")
+            .output()
+            .map_err(|err| {
+                self.tcx.sess.emit_error(Span::default(), LLVMError::LinkerNotFound(LINKER, err))
+            })?;
+        if output.status.success() {
+            return Ok(());
+        }
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        self.tcx
+            .sess
+            .build_error(Span::default(), LLVMError::LinkFailed(LINKER, output.status))
+            .note(stderr.trim())
+            .emit();
+        Err(ErrorReported)
+    }
+}
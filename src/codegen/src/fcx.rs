@@ -9,6 +9,7 @@ use inkwell::{AddressSpace, FloatPredicate, IntPredicate};
 use itertools::Itertools;
 use lcore::mir::{self, BlockId, VarId};
 use lcore::ty::*;
+use llvm_sys::prelude::LLVMMetadataRef;
 use rustc_hash::FxHashSet;
 use std::ops::Deref;
 
@@ -20,6 +21,8 @@ pub struct FnCtx<'a, 'tcx> {
     vars: IndexVec<mir::VarId, LvalueRef<'tcx>>,
     /// map from mir block to llvm block
     blocks: IndexVec<BlockId, BasicBlock<'tcx>>,
+    /// the debug info subprogram of `llfn` (only present when debug info is enabled)
+    scope: Option<LLVMMetadataRef>,
     #[cfg(debug_assertions)]
     mallocs: FxHashSet<LvalueRef<'tcx>>,
 }
@@ -43,11 +46,15 @@ impl<'a, 'tcx> FnCtx<'a, 'tcx> {
             blocks,
             instance,
             vars: Default::default(),
+            scope: None,
             #[cfg(debug_assertions)]
             mallocs: Default::default(),
         };
         ctx.set_block(BlockId::new(0));
         ctx.vars = ctx.alloc_vars();
+        if ctx.dcx.is_some() {
+            ctx.declare_debuginfo();
+        }
         ctx
     }
 
@@ -73,11 +80,43 @@ impl<'a, 'tcx> FnCtx<'a, 'tcx> {
         std::iter::once(retvar).chain(args).chain(vars).collect()
     }
 
+    /// creates the subprogram of `llfn` and describes its named variables
+    fn declare_debuginfo(&mut self) {
+        let dcx = self.cctx.dcx.as_ref().unwrap();
+        let def_id = self.instance.def_id;
+        let ty_of = |var: VarId| self.mir.vars[var].ty.subst(self.tcx, self.instance.substs);
+        let mut ditys = std::iter::once(mir::RET_VAR)
+            .chain(self.mir.arg_iter())
+            .map(|var| self.dity(ty_of(var)))
+            .collect_vec();
+        let name = self.tcx.defs().ident(def_id).to_string();
+        let span = self.tcx.defs().span(def_id);
+        let scope = dcx.create_subprogram(self.llfn, &name, span, &mut ditys);
+
+        let args = self.mir.arg_iter().enumerate().map(|(i, var)| (var, Some(1 + i as u32)));
+        let locals = self.mir.var_iter().map(|var| (var, None));
+        for (var, arg_no) in args.chain(locals) {
+            let mir_var = &self.mir.vars[var];
+            let name = match debuginfo_name(mir_var) {
+                Some(name) => name,
+                None => continue,
+            };
+            let dity = self.dity(ty_of(var));
+            let (entry, ptr) = (self.blocks[BlockId::new(0)], self.vars[var].ptr);
+            dcx.declare_var(scope, entry, ptr, &name, mir_var.info, dity, arg_no);
+        }
+        self.scope = Some(scope);
+    }
+
     /// entry point of `FnCtx` code generation
     pub fn codegen(&mut self) {
         for block in self.mir.indices() {
             self.codegen_basic_block(block);
         }
+        // the location must not leak into whatever is built next
+        if self.scope.is_some() {
+            self.set_debug_location(std::ptr::null_mut());
+        }
         // self.fpm.run_on(&self.llfn);
     }
 
@@ -99,19 +138,38 @@ impl<'a, 'tcx> FnCtx<'a, 'tcx> {
         // let string = self.build_global_string_ptr("string\n", "somestr").as_pointer_value();
         // let printf = self.native_functions.printf;
         // self.build_call(printf, &[string.into()], "printfcall");
-        block.stmts.iter().for_each(|stmt| self.codegen_stmt(stmt));
-        self.codegen_terminator(block.terminator());
+        for stmt in &block.stmts {
+            self.set_debug_info(stmt.info);
+            self.codegen_stmt(stmt);
+        }
+        let terminator = block.terminator();
+        self.set_debug_info(terminator.info);
+        self.codegen_terminator(terminator);
         self.blocks[id]
     }
 
+    /// attributes the instructions built from now on to `info`
+    fn set_debug_info(&self, info: mir::SpanInfo) {
+        if let Some(scope) = self.scope {
+            let loc = self.dcx.as_ref().unwrap().location(scope, info);
+            self.set_debug_location(loc);
+        }
+    }
+
     fn codegen_stmt(&mut self, stmt: &'tcx mir::Stmt<'tcx>) {
         match stmt.kind {
-            mir::StmtKind::Assign(lvalue, ref rvalue) => self.codegen_assignment(lvalue, rvalue),
-            mir::StmtKind::Nop => {}
+            mir::StmtKind::Assign(lvalue, ref rvalue) =>
+                self.codegen_assignment(stmt.info, lvalue, rvalue),
+            mir::StmtKind::StorageLive(_) | mir::StmtKind::Nop => {}
         }
     }
 
-    fn codegen_assignment(&mut self, lvalue: mir::Lvalue<'tcx>, rvalue: &'tcx mir::Rvalue<'tcx>) {
+    fn codegen_assignment(
+        &mut self,
+        info: mir::SpanInfo,
+        lvalue: mir::Lvalue<'tcx>,
+        rvalue: &'tcx mir::Rvalue<'tcx>,
+    ) {
         let lvalue_ref = self.codegen_lvalue(lvalue);
         // certain aggregate rvalues require special treatment as
         // llvm doesn't like recursively building these values (with temporaries)
@@ -133,10 +191,10 @@ impl<'a, 'tcx> FnCtx<'a, 'tcx> {
                     AdtKind::Enum => {
                         let (adt_ty, substs) = (lvalue_ref.ty, self.instance.substs);
                         debug_assert!(!adt_ty.has_ty_params());
-                        let idx = variant_idx.index() as u64;
+                        let discr = adt.variants[*variant_idx].discr as u64;
                         let discr_ptr =
                             self.build_struct_gep(lvalue_ref.ptr, 0, "discr_gep").unwrap();
-                        self.build_store(discr_ptr, self.types.discr.const_int(idx, false));
+                        self.build_store(discr_ptr, self.types.discr.const_int(discr, false));
                         let content_ptr =
                             self.build_struct_gep(lvalue_ref.ptr, 1, "enum_gep").unwrap();
                         let variant_ty =
@@ -157,7 +215,7 @@ impl<'a, 'tcx> FnCtx<'a, 'tcx> {
                 }
             }
             _ => {
-                let value = self.codegen_rvalue(rvalue);
+                let value = self.codegen_rvalue(info, rvalue);
                 self.build_store(lvalue_ref.ptr, value.val);
             }
         }
@@ -199,28 +257,26 @@ impl<'a, 'tcx> FnCtx<'a, 'tcx> {
         }
     }
 
-    fn codegen_rvalue(&mut self, rvalue: &'tcx mir::Rvalue<'tcx>) -> ValueRef<'tcx> {
+    fn codegen_rvalue(
+        &mut self,
+        info: mir::SpanInfo,
+        rvalue: &'tcx mir::Rvalue<'tcx>,
+    ) -> ValueRef<'tcx> {
         match rvalue {
-            mir::Rvalue::Closure(..) => {
-                todo!();
-                // let name = "<closure>";
-                // let f = self.cctx.module.add_function(name, self.llvm_fn_ty_from_ty(ty), None);
-                // self.with_new_insertion_point(|ctx| ctx.codegen_body(name, body));
-                // let val = f.as_llvm_ptr().into();
-                // ValueRef { val, ty }
+            mir::Rvalue::Closure(ty, id, env) => {
+                let instance = Instance::closure(*id, self.instance.substs);
+                let llfn = self.instance_llfn(instance);
+                let env = self.codegen_operand(env).val.into_pointer_value();
+                self.codegen_fn_ptr(llfn, env, self.monomorphize(*ty))
             }
             mir::Rvalue::Operand(operand) => self.codegen_operand(operand),
             mir::Rvalue::Box(operand) => {
                 let operand_ty = operand.ty(self.tcx, self.mir);
-                let llty = self.llvm_ty(operand_ty);
                 let operand = self.codegen_operand(operand);
 
+                let size = self.types.isize.const_int(self.sizeof_ty(operand_ty), false);
                 let gc_ptr = self
-                    .build_call(
-                        self.gc_functions.gc_malloc,
-                        &[llty.size_of().expect("allocating unsized type").into()],
-                        "gc_malloc",
-                    )
+                    .build_call(self.gc_functions.gc_malloc, &[size.into()], "gc_malloc")
                     .as_any_value_enum()
                     .into_pointer_value();
 
@@ -244,7 +300,7 @@ impl<'a, 'tcx> FnCtx<'a, 'tcx> {
                     (BasicValueEnum::FloatValue(_), BasicValueEnum::FloatValue(_)) =>
                         self.codegen_float_op(*op, lhs, rhs),
                     (BasicValueEnum::IntValue(_), BasicValueEnum::IntValue(_)) =>
-                        self.codegen_int_op(*op, lhs, rhs),
+                        self.codegen_int_op(info, *op, lhs, rhs),
                     _ => unreachable!(),
                 }
             }
@@ -254,12 +310,63 @@ impl<'a, 'tcx> FnCtx<'a, 'tcx> {
                 let val = self.build_load(discr_ptr, "load_discr");
                 ValueRef { val, ty: self.tcx.types.int }
             }
-            mir::Rvalue::Unary(_, _) => todo!(),
+            mir::Rvalue::Cast(cast, operand, ty) => {
+                let ty = self.monomorphize(*ty);
+                let operand = self.codegen_operand(operand);
+                match cast {
+                    // closures and function pointers share the same runtime representation
+                    PointerCast::ReifyFn | PointerCast::ClosureFnPtr =>
+                        ValueRef { val: operand.val, ty },
+                    PointerCast::Unsize => self.codegen_unsize(operand, ty),
+                }
+            }
+            mir::Rvalue::Unary(op, operand) => {
+                let operand = self.codegen_operand(operand);
+                self.codegen_unary_op(info, *op, operand)
+            }
             // handle these cases in `codegen_assignment`
             mir::Rvalue::Adt { .. } => unreachable!(),
         }
     }
 
+    /// converts a pointer to a concrete type into a trait object
+    /// by pairing the (type erased) pointer with the vtable of the pointee
+    fn codegen_unsize(&mut self, operand: ValueRef<'tcx>, ty: Ty<'tcx>) -> ValueRef<'tcx> {
+        let vtable = self.get_vtable(operand.ty.deref_ty(), ty.deref_ty().expect_dyn());
+        let data_ptr = self.build_pointer_cast(
+            operand.val.into_pointer_value(),
+            self.types.i8ptr,
+            "data_ptr_cast",
+        );
+        let trait_object = self.types.trait_object.get_undef();
+        let trait_object = self
+            .build_insert_value(trait_object, data_ptr, 0, "insert_data_ptr")
+            .unwrap()
+            .into_struct_value();
+        let trait_object = self
+            .build_insert_value(trait_object, vtable, 1, "insert_vtable")
+            .unwrap()
+            .into_struct_value();
+        ValueRef { val: trait_object.into(), ty }
+    }
+
+    /// pairs the (type erased) function with its environment to form a function pointer value
+    fn codegen_fn_ptr(
+        &mut self,
+        llfn: FunctionValue<'tcx>,
+        env: PointerValue<'tcx>,
+        ty: Ty<'tcx>,
+    ) -> ValueRef<'tcx> {
+        let f = llfn.as_llvm_ptr().const_cast(self.types.i8ptr);
+        let env = self.build_pointer_cast(env, self.types.i8ptr, "env_ptr_cast");
+        let fn_ptr = self.types.fn_ptr.get_undef();
+        let fn_ptr =
+            self.build_insert_value(fn_ptr, f, 0, "insert_fn").unwrap().into_struct_value();
+        let fn_ptr =
+            self.build_insert_value(fn_ptr, env, 1, "insert_env").unwrap().into_struct_value();
+        ValueRef { val: fn_ptr.into(), ty }
+    }
+
     fn codegen_operand(&mut self, operand: &mir::Operand<'tcx>) -> ValueRef<'tcx> {
         match *operand {
             mir::Operand::Const(c) => match c.kind {
@@ -286,30 +393,69 @@ impl<'a, 'tcx> FnCtx<'a, 'tcx> {
                 let val = self.build_load(var.ptr, "load").into();
                 ValueRef { val, ty: var.ty }
             }
+            // items used as values are called through a shim that discards the environment
+            // direct calls to items are handled by `codegen_callee`
             mir::Operand::Item(def_id, substs) => {
                 let substs = self.monomorphize(substs);
-                let instance = Instance::resolve(self.tcx, def_id, substs);
-                let llfn = match instance.kind {
-                    InstanceKind::Item => self.instances.borrow()[&instance],
-                    InstanceKind::Intrinsic => self.intrinsics.borrow()[&instance],
-                };
-                let val = llfn.as_llvm_ptr().into();
-                ValueRef { val, ty: instance.ty(self.tcx) }
+                let instance = self.collected_instance(def_id, substs);
+                let shim = self.get_reify_shim(instance);
+                self.codegen_fn_ptr(shim, self.types.i8ptr.const_null(), instance.ty(self.tcx))
             }
         }
     }
 
+    fn codegen_unary_op(
+        &mut self,
+        info: mir::SpanInfo,
+        op: mir::UnaryOp,
+        operand: ValueRef<'tcx>,
+    ) -> ValueRef<'tcx> {
+        let val = match (op, operand.val) {
+            // `-x` is `0 - x` which only overflows for the minimum integer
+            (mir::UnaryOp::Neg, BasicValueEnum::IntValue(i))
+                if self.tcx.sess.opts.debug_assertions() =>
+            {
+                let (val, overflowed) = self.build_overflowing_op(BinOp::Sub, self.vals.zero, i);
+                self.codegen_panic_if(info, overflowed, mir::PanicKind::NegOverflow);
+                val.into()
+            }
+            (mir::UnaryOp::Neg, BasicValueEnum::IntValue(i)) =>
+                self.build_int_neg(i, "ineg").into(),
+            (mir::UnaryOp::Neg, BasicValueEnum::FloatValue(f)) =>
+                self.build_float_neg(f, "fneg").into(),
+            // booleans are `i1`s so this is also logical negation
+            (mir::UnaryOp::Not, BasicValueEnum::IntValue(i)) => self.build_not(i, "not").into(),
+            _ => unreachable!(),
+        };
+        ValueRef { val, ty: operand.ty }
+    }
+
     fn codegen_int_op(
         &mut self,
+        info: mir::SpanInfo,
         op: BinOp,
         lhs: ValueRef<'tcx>,
         rhs: ValueRef<'tcx>,
     ) -> ValueRef<'tcx> {
         let l = lhs.val.into_int_value();
         let r = rhs.val.into_int_value();
+        let checked = self.tcx.sess.opts.debug_assertions();
         let val = match op {
+            BinOp::Add | BinOp::Sub | BinOp::Mul if checked => {
+                let (val, overflowed) = self.build_overflowing_op(op, l, r);
+                self.codegen_panic_if(info, overflowed, mir::PanicKind::Overflow(op));
+                val.into()
+            }
+            // division by zero and `int::MIN / -1` are undefined in llvm so are always checked
+            BinOp::Div => {
+                let zero = self.vals.zero;
+                let is_zero = self.build_int_compare(IntPredicate::EQ, r, zero, "is_zero");
+                self.codegen_panic_if(info, is_zero, mir::PanicKind::DivisionByZero);
+                let overflowed = self.build_div_overflowed(l, r);
+                self.codegen_panic_if(info, overflowed, mir::PanicKind::Overflow(op));
+                self.build_int_signed_div(l, r, "idiv").into()
+            }
             BinOp::Mul => self.build_int_mul(l, r, "imul").into(),
-            BinOp::Div => self.build_int_signed_div(l, r, "idiv").into(),
             BinOp::Add => self.build_int_add(l, r, "iadd").into(),
             BinOp::Sub => self.build_int_sub(l, r, "isub").into(),
             BinOp::And => self.build_and(l, r, "and").into(),
@@ -399,20 +545,18 @@ impl<'a, 'tcx> FnCtx<'a, 'tcx> {
                 let dyn_val = &val as &dyn BasicValue;
                 self.build_return(Some(dyn_val));
             }
-            mir::TerminatorKind::Abort => {
-                // self.build_call(self.native_functions.abort, &[], "abort");
-                self.build_call(self.native_functions.exit, &[self.vals.one32.into()], "exit");
-                self.builder.build_unreachable();
-            }
+            mir::TerminatorKind::Panic(kind) => self.codegen_panic(terminator.info, *kind),
             mir::TerminatorKind::Unreachable => {
                 self.builder.build_unreachable();
             }
             mir::TerminatorKind::Branch(block) => {
                 self.build_unconditional_branch(self.blocks[*block]);
             }
+            // panics only unwind as far as the innermost `catch_unwind`, so calls never need
+            // a landing pad of their own
             mir::TerminatorKind::Call { f, args, lvalue, target, unwind: _ } => {
-                let f = self.codegen_operand(f).val.into_pointer_value();
-                let args = args.iter().map(|arg| self.codegen_operand(arg).val).collect_vec();
+                let mut args = args.iter().map(|arg| self.codegen_operand(arg).val).collect_vec();
+                let f = self.codegen_callee(f, &mut args);
                 let value = self.build_call(f, &args, "fcall").try_as_basic_value().left().unwrap();
                 let lvalue_ref = self.codegen_lvalue(*lvalue);
                 self.build_store(lvalue_ref.ptr, value);
@@ -431,6 +575,84 @@ impl<'a, 'tcx> FnCtx<'a, 'tcx> {
         }
     }
 
+    /// panics if `cond` is true, otherwise continues in a new block
+    fn codegen_panic_if(
+        &mut self,
+        info: mir::SpanInfo,
+        cond: IntValue<'tcx>,
+        kind: mir::PanicKind,
+    ) {
+        let panic_block = self.llctx.append_basic_block(self.llfn, "panic");
+        let cont_block = self.llctx.append_basic_block(self.llfn, "cont");
+        self.build_conditional_branch(cond, panic_block, cont_block);
+        self.position_at_end(panic_block);
+        self.codegen_panic(info, kind);
+        self.position_at_end(cont_block);
+    }
+
+    /// calls into the runtime to report the panic with its source location
+    fn codegen_panic(&mut self, info: mir::SpanInfo, kind: mir::PanicKind) {
+        let location = span::with_source_map(|map| map.span_to_location(info.span));
+        let msg = self.build_global_string_ptr(&kind.to_string(), "panic_msg").as_pointer_value();
+        let location = self.build_global_string_ptr(&location, "panic_location").as_pointer_value();
+        self.build_call(self.native_functions.panic, &[msg.into(), location.into()], "panic");
+        self.builder.build_unreachable();
+    }
+
+    /// returns the function pointer to call
+    /// for calls through a trait object, the trait object argument is replaced by its data pointer
+    /// for calls through a function pointer value, its environment is passed as the first argument
+    fn codegen_callee(
+        &mut self,
+        f: &mir::Operand<'tcx>,
+        args: &mut Vec<BasicValueEnum<'tcx>>,
+    ) -> PointerValue<'tcx> {
+        if let &mir::Operand::Item(def_id, substs) = f {
+            let instance = self.collected_instance(def_id, self.monomorphize(substs));
+            return match instance.kind {
+                InstanceKind::Virtual(idx) => self.codegen_virtual_fn(instance, idx, args),
+                _ => self.instance_llfn(instance).as_llvm_ptr(),
+            };
+        }
+        let fn_ptr = self.codegen_operand(f);
+        let sig = self.monomorphize(fn_ptr.ty).expect_callable();
+        let fn_ptr = fn_ptr.val.into_struct_value();
+        let f = self.build_extract_value(fn_ptr, 0, "fn_ptr_fn").unwrap().into_pointer_value();
+        let env = self.build_extract_value(fn_ptr, 1, "fn_ptr_env").unwrap();
+        args.insert(0, env);
+
+        let params = std::iter::once(self.types.i8ptr.into())
+            .chain(sig.params.iter().map(|ty| self.llvm_ty(ty)))
+            .collect_vec();
+        let llty = self.llvm_ty(sig.ret).fn_type(&params, false);
+        self.build_pointer_cast(f, llty.ptr_type(AddressSpace::Generic), "fn_ptr_cast")
+    }
+
+    /// loads the function pointer at index `idx` of the vtable of the trait object `args[0]`
+    fn codegen_virtual_fn(
+        &mut self,
+        instance: Instance<'tcx>,
+        idx: usize,
+        args: &mut [BasicValueEnum<'tcx>],
+    ) -> PointerValue<'tcx> {
+        let trait_object = args[0].into_struct_value();
+        let data_ptr = self.build_extract_value(trait_object, 0, "data_ptr").unwrap();
+        let vtable = self.build_extract_value(trait_object, 1, "vtable").unwrap();
+        let idx = self.types.i64.const_int(idx as u64, false);
+        let entry_ptr =
+            unsafe { self.build_in_bounds_gep(vtable.into_pointer_value(), &[idx], "vtable_gep") };
+        let f = self.build_load(entry_ptr, "load_vtable_entry").into_pointer_value();
+        args[0] = data_ptr;
+
+        // the receiver of the implementation is passed as an opaque pointer
+        let sig = instance.ty(self.tcx).expect_fn_ptr();
+        let params = std::iter::once(self.types.i8ptr.into())
+            .chain(sig.params.iter().skip(1).map(|ty| self.llvm_ty(ty)))
+            .collect_vec();
+        let llty = self.llvm_ty(sig.ret).fn_type(&params, false);
+        self.build_pointer_cast(f, llty.ptr_type(AddressSpace::Generic), "virtual_fn_cast")
+    }
+
     fn codegen_switch(
         &mut self,
         discr: &mir::Operand<'tcx>,
@@ -466,3 +688,13 @@ impl<'a, 'tcx> Deref for FnCtx<'a, 'tcx> {
         &self.cctx
     }
 }
+
+/// the name of a user declared variable, if it has one
+/// (arguments that are destructured and temporaries don't)
+fn debuginfo_name(var: &mir::Var<'_>) -> Option<String> {
+    match var.kind {
+        mir::VarKind::Arg | mir::VarKind::Local => {}
+        mir::VarKind::Tmp | mir::VarKind::Ret | mir::VarKind::Upvar => return None,
+    }
+    var.ident.map(|ident| ident.to_string())
+}
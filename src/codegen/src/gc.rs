@@ -1,7 +1,8 @@
-use crate::llvm_ty;
 use inkwell::context::Context;
 use inkwell::module::Module;
+use inkwell::types::IntType;
 use inkwell::values::FunctionValue;
+use inkwell::AddressSpace;
 
 // it is important to only each of these intrinsics exactly once
 // each new invocation will create a new copy with a suffix integer
@@ -11,10 +12,12 @@ pub struct GCFunctions<'tcx> {
 }
 
 impl<'tcx> GCFunctions<'tcx> {
-    pub fn new(llctx: &'tcx Context, module: &Module<'tcx>) -> Self {
-        let gc_malloc = module.add_function("GC_malloc", llvm_ty!(llctx, fn(i64) -> *i8), None);
-        let gc_malloc_atomic =
-            module.add_function("GC_malloc_atomic", llvm_ty!(llctx, fn(i64) -> *i8), None);
+    /// `isize` is the type of the size argument (`size_t`)
+    pub fn new(llctx: &'tcx Context, module: &Module<'tcx>, isize: IntType<'tcx>) -> Self {
+        let i8ptr = llctx.i8_type().ptr_type(AddressSpace::Generic);
+        let malloc_ty = i8ptr.fn_type(&[isize.into()], false);
+        let gc_malloc = module.add_function("GC_malloc", malloc_ty, None);
+        let gc_malloc_atomic = module.add_function("GC_malloc_atomic", malloc_ty, None);
 
         Self { gc_malloc, gc_malloc_atomic }
     }
@@ -1,7 +1,8 @@
 use crate::CodegenCtx;
+use ast::BinOp;
 use inkwell::types::BasicType;
-use inkwell::values::FunctionValue;
-use inkwell::AddressSpace;
+use inkwell::values::{FunctionValue, IntValue};
+use inkwell::{AddressSpace, IntPredicate};
 use lcore::ty::Instance;
 use span::sym;
 
@@ -13,7 +14,14 @@ impl<'tcx> CodegenCtx<'tcx> {
         let ident = self.tcx.defs().ident(instance.def_id);
         let llfn = match ident.symbol {
             sym::addr => self.codegen_addr_intrinsic(instance),
+            sym::catch_unwind => self.native_functions.catch_unwind,
+            sym::ptr_cast => self.codegen_ptr_cast_intrinsic(instance),
             sym::print => self.native_functions.print,
+            sym::checked_add | sym::checked_sub | sym::checked_mul | sym::checked_div =>
+                self.codegen_checked_intrinsic(instance),
+            sym::wrapping_add | sym::wrapping_sub | sym::wrapping_mul =>
+                self.codegen_wrapping_intrinsic(instance),
+            sym::float_to_bits | sym::char_to_int => self.codegen_to_int_intrinsic(instance),
             _ => panic!("unknown intrinsic `{}`", ident),
         };
         self.intrinsics.borrow_mut().insert(instance, llfn);
@@ -37,4 +45,125 @@ impl<'tcx> CodegenCtx<'tcx> {
         self.build_return(Some(&int));
         llfn
     }
+
+    /// `ptr_cast<T, U>: fn(*T) -> *U`
+    fn codegen_ptr_cast_intrinsic(&self, instance: Instance<'tcx>) -> FunctionValue<'tcx> {
+        let ident = self.tcx.defs().ident(instance.def_id);
+        let name = format!("{}<{}>", ident, instance.substs);
+        let llty = self.llvm_fn_ty_from_ty(instance.ty(self.tcx));
+        let llfn = self.module.add_function(&name, llty, None);
+        self.position_at_end(self.llctx.append_basic_block(llfn, "ptr_cast_entry"));
+        let ptr = llfn.get_first_param().unwrap().into_pointer_value();
+        let ret_ty = llty.get_return_type().unwrap().into_pointer_type();
+        let cast = self.build_pointer_cast(ptr, ret_ty, "ptr_cast");
+        self.build_return(Some(&cast));
+        llfn
+    }
+
+    /// `checked_<op>: fn(int, int) -> (int, bool)`
+    /// returns the wrapped result paired with whether the operation overflowed
+    /// a division by zero also counts as overflow, in which case the dividend is returned
+    fn codegen_checked_intrinsic(&self, instance: Instance<'tcx>) -> FunctionValue<'tcx> {
+        let ident = self.tcx.defs().ident(instance.def_id);
+        let llty = self.llvm_fn_ty_from_ty(instance.ty(self.tcx));
+        let llfn = self.module.add_function(&ident.to_string(), llty, None);
+        self.position_at_end(self.llctx.append_basic_block(llfn, "checked_entry"));
+        let l = llfn.get_nth_param(0).unwrap().into_int_value();
+        let r = llfn.get_nth_param(1).unwrap().into_int_value();
+
+        let (val, overflowed) = match ident.symbol {
+            sym::checked_add => self.build_overflowing_op(BinOp::Add, l, r),
+            sym::checked_sub => self.build_overflowing_op(BinOp::Sub, l, r),
+            sym::checked_mul => self.build_overflowing_op(BinOp::Mul, l, r),
+            sym::checked_div => {
+                let zero = self.vals.zero;
+                let is_zero = self.build_int_compare(IntPredicate::EQ, r, zero, "is_zero");
+                let overflowed = self.build_div_overflowed(l, r);
+                let overflowed = self.build_or(is_zero, overflowed, "overflowed");
+                // divide by one instead so that the division itself is always well defined
+                let divisor = self.build_select(overflowed, self.vals.one, r, "divisor");
+                let val = self.build_int_signed_div(l, divisor.into_int_value(), "idiv");
+                (val, overflowed)
+            }
+            _ => unreachable!(),
+        };
+
+        let ret_ty = llty.get_return_type().unwrap().into_struct_type();
+        let ret = self
+            .build_insert_value(ret_ty.get_undef(), val, 0, "insert_val")
+            .unwrap()
+            .into_struct_value();
+        let ret = self
+            .build_insert_value(ret, overflowed, 1, "insert_overflowed")
+            .unwrap()
+            .into_struct_value();
+        self.build_return(Some(&ret));
+        llfn
+    }
+
+    /// `wrapping_<op>: fn(int, int) -> int`
+    fn codegen_wrapping_intrinsic(&self, instance: Instance<'tcx>) -> FunctionValue<'tcx> {
+        let ident = self.tcx.defs().ident(instance.def_id);
+        let llty = self.llvm_fn_ty_from_ty(instance.ty(self.tcx));
+        let llfn = self.module.add_function(&ident.to_string(), llty, None);
+        self.position_at_end(self.llctx.append_basic_block(llfn, "wrapping_entry"));
+        let l = llfn.get_nth_param(0).unwrap().into_int_value();
+        let r = llfn.get_nth_param(1).unwrap().into_int_value();
+        let val = match ident.symbol {
+            sym::wrapping_add => self.build_int_add(l, r, "iadd"),
+            sym::wrapping_sub => self.build_int_sub(l, r, "isub"),
+            sym::wrapping_mul => self.build_int_mul(l, r, "imul"),
+            _ => unreachable!(),
+        };
+        self.build_return(Some(&val));
+        llfn
+    }
+
+    /// `float_to_bits: fn(float) -> int` and `char_to_int: fn(char) -> int`
+    fn codegen_to_int_intrinsic(&self, instance: Instance<'tcx>) -> FunctionValue<'tcx> {
+        let ident = self.tcx.defs().ident(instance.def_id);
+        let llty = self.llvm_fn_ty_from_ty(instance.ty(self.tcx));
+        let llfn = self.module.add_function(&ident.to_string(), llty, None);
+        self.position_at_end(self.llctx.append_basic_block(llfn, "to_int_entry"));
+        let arg = llfn.get_first_param().unwrap();
+        let val = match ident.symbol {
+            sym::float_to_bits =>
+                self.build_bitcast(arg, self.types.i64, "float_to_bits").into_int_value(),
+            sym::char_to_int =>
+                self.build_int_z_extend(arg.into_int_value(), self.types.i64, "char_to_int"),
+            _ => unreachable!(),
+        };
+        self.build_return(Some(&val));
+        llfn
+    }
+
+    /// computes `op` using the llvm overflow intrinsics
+    /// returns the wrapped result and whether the operation overflowed
+    pub fn build_overflowing_op(
+        &self,
+        op: BinOp,
+        l: IntValue<'tcx>,
+        r: IntValue<'tcx>,
+    ) -> (IntValue<'tcx>, IntValue<'tcx>) {
+        let intrinsic = match op {
+            BinOp::Add => self.llvm_intrinsics.sadd_with_overflow,
+            BinOp::Sub => self.llvm_intrinsics.ssub_with_overflow,
+            BinOp::Mul => self.llvm_intrinsics.smul_with_overflow,
+            _ => unreachable!("`{}` has no overflow intrinsic", op),
+        };
+        let result = self.build_call(intrinsic, &[l.into(), r.into()], "with_overflow");
+        let result = result.try_as_basic_value().left().unwrap().into_struct_value();
+        let val = self.build_extract_value(result, 0, "val").unwrap().into_int_value();
+        let overflowed = self.build_extract_value(result, 1, "overflowed").unwrap();
+        (val, overflowed.into_int_value())
+    }
+
+    /// `int::MIN / -1` is the only quotient that doesn't fit in an `int`
+    pub fn build_div_overflowed(&self, l: IntValue<'tcx>, r: IntValue<'tcx>) -> IntValue<'tcx> {
+        let min = self.types.i64.const_int(i64::MIN as u64, true);
+        let is_min = self.build_int_compare(IntPredicate::EQ, l, min, "is_min");
+        let neg_one = self.vals.neg_one;
+        let is_neg_one = self.build_int_compare(IntPredicate::EQ, r, neg_one, "is_neg_one");
+        self.build_and(is_min, is_neg_one, "div_overflowed")
+    }
 }
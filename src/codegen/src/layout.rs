@@ -1,6 +1,6 @@
 use crate::CodegenCtx;
 use inkwell::types::BasicType;
-use lcore::ty::{SubstsRef, Ty, VariantTy};
+use lcore::ty::{layout, Ty};
 use llvm_sys::target::*;
 
 impl<'tcx> CodegenCtx<'tcx> {
@@ -10,13 +10,17 @@ impl<'tcx> CodegenCtx<'tcx> {
         unsafe { LLVMABISizeOfType(opaque_target_data, type_ref) }
     }
 
+    pub fn alignof(&self, llty: impl BasicType<'tcx>) -> u32 {
+        let type_ref = llty.as_type_ref();
+        let opaque_target_data = unsafe { LLVMGetModuleDataLayout(self.module.get_module_ref()) };
+        unsafe { LLVMABIAlignmentOfType(opaque_target_data, type_ref) }
+    }
+
+    /// the size of `ty` according to its layout, which its llvm type agrees with
     pub fn sizeof_ty(&self, ty: Ty<'tcx>) -> u64 {
-        let size = self.sizeof(self.llvm_ty(ty));
+        let size = layout::layout_of(self.tcx, ty).size;
         debug!("sizeof {} {}", ty, size);
+        debug_assert_eq!(size, self.sizeof(self.llvm_ty(ty)), "llvm layout of `{}` differs", ty);
         size
     }
-
-    pub fn variant_size(&self, variant_ty: &'tcx VariantTy, substs: SubstsRef<'tcx>) -> u64 {
-        variant_ty.fields.iter().map(|f| f.ty(self.tcx, substs)).map(|ty| self.sizeof_ty(ty)).sum()
-    }
 }
@@ -5,6 +5,8 @@
 extern crate log;
 
 mod codegen_ctx;
+mod debuginfo;
+mod emit;
 mod fcx;
 mod gc;
 mod intrinsics;
@@ -14,11 +16,15 @@ mod llvm_error;
 mod llvm_intrinsics;
 mod monomorphize;
 mod native;
+mod target;
+mod unwind;
 
 #[cfg(test)]
 mod tests;
 
-pub use codegen_ctx::CodegenCtx;
+pub use codegen_ctx::{llvm_opt_level, CodegenCtx};
+pub use debuginfo::DebugCtx;
+pub use emit::{output_path, LINKER};
 pub use fcx::FnCtx;
 
 use gc::GCFunctions;
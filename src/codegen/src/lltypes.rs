@@ -83,10 +83,12 @@ impl<'tcx> CodegenCtx<'tcx> {
             TyKind::Int => self.types.i64.into(),
             TyKind::Discr => self.types.discr.into(),
             TyKind::Float => self.types.float.into(),
-            TyKind::Char => todo!(),
+            TyKind::Char => self.types.i32.into(),
             TyKind::Tuple(xs) if xs.is_empty() => self.types.unit.into(),
-            TyKind::Array(_ty, _n) => todo!(),
-            TyKind::FnPtr(sig) => self.llvm_fn_ty(sig).ptr_type(AddressSpace::Generic).into(),
+            TyKind::Array(ty, n) => self.llvm_ty(ty).array_type(n as u32).into(),
+            // function pointers are fat pointers consisting of the function and its environment
+            // closures share the representation of function pointers
+            TyKind::FnPtr(..) | TyKind::Opaque(..) => self.types.fn_ptr.into(),
             TyKind::Tuple(tys) => {
                 // tuples are represented as anonymous structs
                 let lltys = tys.iter().map(|ty| self.llvm_ty(ty)).collect_vec();
@@ -109,24 +111,27 @@ impl<'tcx> CodegenCtx<'tcx> {
                         opaque_ty.set_body(&tys, false);
                     }
                     AdtKind::Enum => {
-                        // it is fine to unwrap here as if the enum has no variants it is not
-                        // constructable and this will never be called
-                        let largest_variant = adt.variants.iter().max_by(|s, t| {
-                            self.variant_size(s, substs).cmp(&self.variant_size(t, substs))
-                        });
-                        let llvariant =
-                            self.variant_ty_to_llvm_ty(largest_variant.unwrap(), substs).into();
+                        // the content is an array of integers with the size and alignment of
+                        // the content of the enum's layout, it is accessed by casting a pointer
+                        // to it to the type of a variant
+                        let content = layout::enum_content_layout(self.tcx, adt, substs);
+                        let llelem = self.llctx.custom_width_int_type(8 * content.align as u32);
+                        let llcontent = llelem.array_type((content.size / content.align) as u32);
                         assert!(adt.variants.len() < 256, "too many variants");
-                        opaque_ty.set_body(&[self.types.discr.into(), llvariant], false);
+                        opaque_ty.set_body(&[self.types.discr.into(), llcontent.into()], false);
                     }
                 }
                 return opaque_ty.into();
             }
+            // pointers to trait objects are fat pointers consisting of a data pointer and a vtable
+            TyKind::Box(ty) | TyKind::Ptr(ty) if matches!(ty.kind, TyKind::Dyn(..)) =>
+                self.types.trait_object.into(),
             // boxes and pointers have the same runtime type
             // however, boxes will have a refcount implicitly stored after the content
             TyKind::Box(ty) | TyKind::Ptr(ty) =>
                 self.llvm_ty(ty).ptr_type(AddressSpace::Generic).into(),
-            TyKind::Opaque(..) => todo!(),
+            // trait objects are unsized and only exist behind a pointer
+            TyKind::Dyn(..) => unreachable!("{}", ty),
             TyKind::Param(..) | TyKind::Infer(..) | TyKind::Never | TyKind::Error =>
                 unreachable!("{}", ty),
         };
@@ -1,4 +1,6 @@
 use lcore::ty::Ty;
+use std::path::PathBuf;
+use std::process::ExitStatus;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -9,4 +11,12 @@ pub enum LLVMError<'tcx> {
     InvalidMainType(Ty<'tcx>),
     #[error("function `main` defined twice")]
     DuplicateMain,
+    #[error("failed to write `{}`: {}", .0.display(), .1)]
+    WriteFailed(PathBuf, String),
+    #[error("{0}")]
+    Target(String),
+    #[error("could not run linker `{0}`: {1}")]
+    LinkerNotFound(&'static str, std::io::Error),
+    #[error("linking with `{0}` failed: {1}")]
+    LinkFailed(&'static str, ExitStatus),
 }
@@ -8,6 +8,9 @@ use inkwell::values::FunctionValue;
 pub struct LLVMIntrinsics<'tcx> {
     pub stackmap: FunctionValue<'tcx>,
     pub gcalloc: FunctionValue<'tcx>,
+    pub sadd_with_overflow: FunctionValue<'tcx>,
+    pub ssub_with_overflow: FunctionValue<'tcx>,
+    pub smul_with_overflow: FunctionValue<'tcx>,
 }
 
 impl<'tcx> LLVMIntrinsics<'tcx> {
@@ -21,6 +24,14 @@ impl<'tcx> LLVMIntrinsics<'tcx> {
         // let gcalloc = module.add_function("GC_malloc", llvm_ty!(llctx, fn(i64) -> *i8), None);
         let gcalloc = module.add_function("GC_gcollect", llvm_ty!(llctx, fn()), None);
 
-        Self { stackmap, gcalloc }
+        // `fn(i64, i64) -> {i64, i1}` where the `i1` is set if the operation overflowed
+        let with_overflow = |name| {
+            module.add_function(name, llvm_ty!(llctx, fn(i64, i64) -> {i64, bool}), None)
+        };
+        let sadd_with_overflow = with_overflow("llvm.sadd.with.overflow.i64");
+        let ssub_with_overflow = with_overflow("llvm.ssub.with.overflow.i64");
+        let smul_with_overflow = with_overflow("llvm.smul.with.overflow.i64");
+
+        Self { stackmap, gcalloc, sadd_with_overflow, ssub_with_overflow, smul_with_overflow }
     }
 }
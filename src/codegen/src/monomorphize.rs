@@ -1,7 +1,7 @@
 use ir::{DefId, FnVisitor, ItemVisitor};
-use lcore::mir::{MirVisitor, Operand, SpanInfo};
+use lcore::mir::{Mir, MirTy, MirVisitor, Operand, Rvalue, SpanInfo};
 use lcore::queries::Queries;
-use lcore::ty::{HasTyFlags, Instance, InstanceKind, Subst, TyCtx, TypeFoldable};
+use lcore::ty::{HasTyFlags, Instance, InstanceKind, PointerCast, Subst, TyCtx, TypeFoldable};
 use rustc_hash::FxHashSet;
 use std::cell::RefCell;
 use std::ops::Deref;
@@ -48,14 +48,20 @@ impl<'a, 'tcx> MonomorphizationCollector<'a, 'tcx> {
     }
 
     fn collect_instance(&self, instance: Instance<'tcx>) {
+        // virtual instances are resolved at runtime, the actual implementations are
+        // collected when the trait object is created (see `InstanceCollector::visit_rvalue`)
+        if let InstanceKind::Virtual(..) = instance.kind {
+            return;
+        }
         self.mono_instances.borrow_mut().insert(instance);
         match instance.kind {
-            InstanceKind::Item => {
-                let mir = self.tcx.mir_of(instance.def_id);
-                InstanceCollector { collector: self, instance }.visit_mir(mir);
+            InstanceKind::Item | InstanceKind::Closure(..) => {
+                let mir = self.tcx.instance_mir(instance);
+                InstanceCollector { collector: self, instance, mir }.visit_mir(mir);
             }
             // no need to recurse on intrinsics as they do not have associated mir
             InstanceKind::Intrinsic => {}
+            InstanceKind::Virtual(..) => unreachable!(),
         }
     }
 }
@@ -95,6 +101,7 @@ impl<'tcx> FnVisitor<'tcx> for RootCollector<'tcx> {
 struct InstanceCollector<'a, 'tcx> {
     collector: &'a MonomorphizationCollector<'a, 'tcx>,
     instance: Instance<'tcx>,
+    mir: &'tcx Mir<'tcx>,
 }
 
 impl<'a, 'tcx> Monomorphize<'tcx> for InstanceCollector<'a, 'tcx> {
@@ -107,6 +114,29 @@ impl<'a, 'tcx> Monomorphize<'tcx> for InstanceCollector<'a, 'tcx> {
 }
 
 impl<'a, 'tcx> MirVisitor<'tcx> for InstanceCollector<'a, 'tcx> {
+    fn visit_rvalue(&mut self, info: SpanInfo, rvalue: &Rvalue<'tcx>) {
+        // creating a trait object requires every entry of its vtable
+        if let &Rvalue::Cast(PointerCast::Unsize, ref operand, ty) = rvalue {
+            let pointee = self.monomorphize(operand.ty(self.tcx, self.mir)).deref_ty();
+            let trait_def_id = self.monomorphize(ty).deref_ty().expect_dyn();
+            // the errors of entries that fail to resolve are checked for after collection
+            let entries = self.tcx.vtable_entries((pointee, trait_def_id)).unwrap_or_default();
+            for &instance in entries {
+                if !self.mono_instances.borrow().contains(&instance) {
+                    self.collector.collect_instance(instance);
+                }
+            }
+        }
+        // the lifted body of a closure is instantiated with the substitutions of its parent
+        if let &Rvalue::Closure(_, id, _) = rvalue {
+            let instance = Instance::closure(id, self.instance.substs);
+            if !self.mono_instances.borrow().contains(&instance) {
+                self.collector.collect_instance(instance);
+            }
+        }
+        self.walk_rvalue(info, rvalue);
+    }
+
     fn visit_operand(&mut self, _info: SpanInfo, operand: &Operand<'tcx>) {
         // `Operand::Item` is currently the only way to reference a generic item
         if let &Operand::Item(def_id, substs) = operand {
@@ -118,7 +148,11 @@ impl<'a, 'tcx> MirVisitor<'tcx> for InstanceCollector<'a, 'tcx> {
             // just composing substitutions
             let substs = self.monomorphize(substs);
             debug_assert!(!substs.has_ty_params());
-            let instance = Instance::resolve(self.tcx, def_id, substs);
+            // the error has been reported, and codegen stops once collection is complete
+            let instance = match Instance::resolve(self.tcx, def_id, substs) {
+                Ok(instance) => instance,
+                Err(..) => return,
+            };
 
             if !self.mono_instances.borrow().contains(&instance) {
                 // recursively collect all its neighbours
@@ -1,13 +1,22 @@
+use crate::unwind::{self, UnwindBuilder};
+use inkwell::builder::Builder;
 use inkwell::context::Context;
 use inkwell::module::Linkage;
 use inkwell::module::Module;
+use inkwell::types::IntType;
 use inkwell::values::*;
-use inkwell::AddressSpace;
+use inkwell::{AddressSpace, IntPredicate};
 use std::ops::Deref;
 
+/// the exit code of a program that panicked
+pub const PANIC_EXIT_CODE: u64 = 101;
+
 pub struct NativeFunctions<'tcx> {
     pub abort: FunctionValue<'tcx>,
+    pub catch_unwind: FunctionValue<'tcx>,
     pub exit: FunctionValue<'tcx>,
+    pub panic: FunctionValue<'tcx>,
+    pub personality: FunctionValue<'tcx>,
     pub print: FunctionValue<'tcx>,
     pub printf: FunctionValue<'tcx>,
     pub print_addr: FunctionValue<'tcx>,
@@ -16,11 +25,13 @@ pub struct NativeFunctions<'tcx> {
 pub struct NativeFunctionsBuilder<'a, 'tcx> {
     llcx: &'tcx Context,
     module: &'a Module<'tcx>,
+    /// the type of `size_t`
+    isize: IntType<'tcx>,
 }
 
 impl<'a, 'tcx> NativeFunctionsBuilder<'a, 'tcx> {
-    pub fn new(llcx: &'tcx Context, module: &'a Module<'tcx>) -> Self {
-        Self { llcx, module }
+    pub fn new(llcx: &'tcx Context, module: &'a Module<'tcx>, isize: IntType<'tcx>) -> Self {
+        Self { llcx, module, isize }
     }
 
     pub fn build(&self) -> NativeFunctions<'tcx> {
@@ -29,7 +40,134 @@ impl<'a, 'tcx> NativeFunctionsBuilder<'a, 'tcx> {
         let print_addr = self.build_print_addr();
         let abort = self.build_abort();
         let exit = self.build_exit();
-        NativeFunctions { abort, print, exit, print_addr, printf }
+        self.build_dprintf();
+        self.build_cxa_functions();
+        let personality = self.build_personality();
+        let catch_depth = self.build_catch_depth();
+        let panic = self.build_panic(catch_depth);
+        let catch_unwind = self.build_catch_unwind(catch_depth, personality);
+        NativeFunctions { abort, catch_unwind, print, exit, panic, personality, print_addr, printf }
+    }
+
+    /// the number of `l_catch_unwind` calls currently on the stack
+    fn build_catch_depth(&self) -> GlobalValue<'tcx> {
+        let catch_depth = self.module.add_global(self.i64_type(), None, "l_catch_depth");
+        catch_depth.set_initializer(&self.i64_type().const_zero());
+        catch_depth
+    }
+
+    fn build_add_catch_depth(
+        &self,
+        builder: &Builder<'tcx>,
+        catch_depth: GlobalValue<'tcx>,
+        n: i64,
+    ) {
+        let ptr = catch_depth.as_pointer_value();
+        let depth = builder.build_load(ptr, "load_catch_depth").into_int_value();
+        let n = self.i64_type().const_int(n as u64, true);
+        builder.build_store(ptr, builder.build_int_add(depth, n, "catch_depth"));
+    }
+
+    /// `l_catch_unwind(f) -> bool`
+    /// calls the closure `f` and returns whether it panicked
+    fn build_catch_unwind(
+        &self,
+        catch_depth: GlobalValue<'tcx>,
+        personality: FunctionValue<'tcx>,
+    ) -> FunctionValue<'tcx> {
+        let i8ptr = self.i8_type().ptr_type(AddressSpace::Generic);
+        let fn_ptr = self.struct_type(&[i8ptr.into(), i8ptr.into()], false);
+        let catchfn = self.module.add_function(
+            "l_catch_unwind",
+            self.bool_type().fn_type(&[fn_ptr.into()], false),
+            None,
+        );
+        unwind::set_personality_fn(catchfn, personality);
+        let entry = self.append_basic_block(catchfn, "catch_unwind");
+        let returned = self.append_basic_block(catchfn, "returned");
+        let landing_pad = self.append_basic_block(catchfn, "landing_pad");
+        let builder = self.create_builder();
+        let unwind_builder = UnwindBuilder::new(self.module);
+
+        builder.position_at_end(entry);
+        let exn_ty = self.struct_type(&[i8ptr.into(), self.i32_type().into()], false);
+        let exn_slot = builder.build_alloca(exn_ty, "exn_slot");
+        self.build_add_catch_depth(&builder, catch_depth, 1);
+        let f = catchfn.get_first_param().unwrap().into_struct_value();
+        let fptr = builder.build_extract_value(f, 0, "fn_ptr_fn").unwrap().into_pointer_value();
+        let env = builder.build_extract_value(f, 1, "fn_ptr_env").unwrap();
+        // the closure takes only its environment and returns unit
+        let llty = self.struct_type(&[], false).fn_type(&[i8ptr.into()], false);
+        let fptr =
+            builder.build_pointer_cast(fptr, llty.ptr_type(AddressSpace::Generic), "fn_ptr_cast");
+        unwind_builder.position_at_end(entry);
+        unwind_builder.build_invoke(fptr, &[env], returned, landing_pad, "call");
+
+        builder.position_at_end(returned);
+        self.build_add_catch_depth(&builder, catch_depth, -1);
+        builder.build_return(Some(&self.bool_type().const_zero()));
+
+        unwind_builder.position_at_end(landing_pad);
+        unwind_builder.build_landing_pad(personality, exn_slot);
+        builder.position_at_end(landing_pad);
+        let exn = builder.build_load(exn_slot, "load_exn").into_struct_value();
+        let exn = builder.build_extract_value(exn, 0, "exn").unwrap();
+        let begin_catch = self.module.get_function("__cxa_begin_catch").unwrap();
+        let end_catch = self.module.get_function("__cxa_end_catch").unwrap();
+        builder.build_call(begin_catch, &[exn], "begin_catch");
+        builder.build_call(end_catch, &[], "end_catch");
+        self.build_add_catch_depth(&builder, catch_depth, -1);
+        builder.build_return(Some(&self.bool_type().const_all_ones()));
+        catchfn
+    }
+
+    /// `l_panic(msg, location)`
+    /// reports the panic on stderr and unwinds to the innermost `l_catch_unwind`
+    /// if nothing will catch the panic, exits with `PANIC_EXIT_CODE` instead
+    fn build_panic(&self, catch_depth: GlobalValue<'tcx>) -> FunctionValue<'tcx> {
+        let i8ptr = self.i8_type().ptr_type(AddressSpace::Generic);
+        let panicfn = self.module.add_function(
+            "l_panic",
+            self.void_type().fn_type(&[i8ptr.into(), i8ptr.into()], false),
+            None,
+        );
+        let bb = self.append_basic_block(panicfn, "panic");
+        let builder = self.create_builder();
+        builder.position_at_end(bb);
+
+        let fmt = builder.build_global_string_ptr("panicked at '%s', %s\n", "panic_fmt");
+        let stderr = self.i32_type().const_int(2, false);
+        let mut args: Vec<BasicValueEnum<'tcx>> = panicfn.get_params();
+        args.insert(0, fmt.as_pointer_value().into());
+        args.insert(0, stderr.into());
+        let dprintf = self.module.get_function("dprintf").unwrap();
+        builder.build_call(dprintf, &args, "dprintf");
+
+        let unwind = self.append_basic_block(panicfn, "unwind");
+        let exit = self.append_basic_block(panicfn, "exit");
+        let depth = builder.build_load(catch_depth.as_pointer_value(), "load_catch_depth");
+        let zero = self.i64_type().const_zero();
+        let is_caught =
+            builder.build_int_compare(IntPredicate::NE, depth.into_int_value(), zero, "is_caught");
+        builder.build_conditional_branch(is_caught, unwind, exit);
+
+        // the thrown value itself is irrelevant, it just needs a type that can be caught
+        builder.position_at_end(unwind);
+        let allocate = self.module.get_function("__cxa_allocate_exception").unwrap();
+        let size = self.isize.const_int(8, false);
+        let exn = builder.build_call(allocate, &[size.into()], "exn");
+        let exn = exn.try_as_basic_value().left().unwrap();
+        let typeinfo = self.module.get_global("_ZTIl").unwrap().as_pointer_value();
+        let throw = self.module.get_function("__cxa_throw").unwrap();
+        builder.build_call(throw, &[exn, typeinfo.into(), i8ptr.const_null().into()], "throw");
+        builder.build_unreachable();
+
+        builder.position_at_end(exit);
+        let exit = self.module.get_function("exit").unwrap();
+        let code = self.i32_type().const_int(PANIC_EXIT_CODE, false);
+        builder.build_call(exit, &[code.into()], "exit");
+        builder.build_unreachable();
+        panicfn
     }
 
     fn build_print_addr(&self) -> FunctionValue<'tcx> {
@@ -89,6 +227,39 @@ impl<'a, 'tcx> NativeFunctionsBuilder<'a, 'tcx> {
         )
     }
 
+    fn build_dprintf(&self) -> FunctionValue<'tcx> {
+        let i8ptr = self.i8_type().ptr_type(AddressSpace::Generic);
+        self.module.add_function(
+            "dprintf",
+            self.i32_type().fn_type(&[self.i32_type().into(), i8ptr.into()], true),
+            Some(Linkage::External),
+        )
+    }
+
+    fn build_personality(&self) -> FunctionValue<'tcx> {
+        self.module.add_function(
+            unwind::PERSONALITY_FN,
+            self.i32_type().fn_type(&[], true),
+            Some(Linkage::External),
+        )
+    }
+
+    /// the parts of the c++ runtime used to throw and catch panics
+    fn build_cxa_functions(&self) {
+        let i8ptr = self.i8_type().ptr_type(AddressSpace::Generic);
+        let external = Some(Linkage::External);
+        let allocate_ty = i8ptr.fn_type(&[self.isize.into()], false);
+        self.module.add_function("__cxa_allocate_exception", allocate_ty, external);
+        let throw_ty = self.void_type().fn_type(&[i8ptr.into(), i8ptr.into(), i8ptr.into()], false);
+        self.module.add_function("__cxa_throw", throw_ty, external);
+        let begin_catch_ty = i8ptr.fn_type(&[i8ptr.into()], false);
+        self.module.add_function("__cxa_begin_catch", begin_catch_ty, external);
+        self.module.add_function("__cxa_end_catch", self.void_type().fn_type(&[], false), external);
+        // the typeinfo of `long`, which is the type that panics are thrown as
+        let typeinfo = self.module.add_global(self.i8_type(), None, "_ZTIl");
+        typeinfo.set_linkage(Linkage::External);
+    }
+
     fn build_exit(&self) -> FunctionValue<'tcx> {
         self.module.add_function(
             "exit",
@@ -0,0 +1,34 @@
+//! the target machine that code is generated for
+
+use crate::llvm_opt_level;
+use inkwell::targets::*;
+use session::CompilerOptions;
+
+/// creates a target machine for the triple given by `--target`, or the host if there is none
+crate fn create_target_machine(opts: &CompilerOptions) -> Result<TargetMachine, String> {
+    let config = InitializationConfig::default();
+    let triple = match &opts.target {
+        Some(triple) => {
+            Target::initialize_all(&config);
+            TargetTriple::create(triple)
+        }
+        None => {
+            Target::initialize_native(&config)?;
+            TargetMachine::get_default_triple()
+        }
+    };
+    let target = Target::from_triple(&triple).map_err(|err| err.to_string())?;
+    target
+        .create_target_machine(
+            &triple,
+            "generic",
+            "",
+            llvm_opt_level(opts.opt_level()),
+            RelocMode::PIC,
+            CodeModel::Default,
+        )
+        .ok_or_else(|| {
+            let name = opts.target.as_deref().unwrap_or("the host");
+            format!("failed to create a target machine for `{}`", name)
+        })
+}
@@ -1,6 +1,6 @@
 use super::*;
 
-// #[test]
+#[test]
 fn llvm_simple_closure() {
     let src = r#"
     fn main() -> int {
@@ -9,5 +9,242 @@ fn llvm_simple_closure() {
         return f();
     }"#;
 
-    llvm_jit!(src);
+    assert_eq!(llvm_exec!(src), 5);
+}
+
+#[test]
+fn llvm_closure_with_params() {
+    let src = r#"
+    fn main() -> int {
+        let k = 3;
+        let f = fn(x, y) => x * y + k;
+        f(4, 5)
+    }"#;
+
+    assert_eq!(llvm_exec!(src), 23);
+}
+
+#[test]
+fn llvm_closure_captures_mutable_by_ref() {
+    let src = r#"
+    fn main() -> int {
+        let mut x = 5;
+        let f = fn() => x;
+        x = 10;
+        f()
+    }"#;
+
+    assert_eq!(llvm_exec!(src), 10);
+}
+
+#[test]
+fn llvm_closure_mutates_captured_var() {
+    let src = r#"
+    fn main() -> int {
+        let mut x = 5;
+        let inc = fn() => { x = x + 1; };
+        inc();
+        inc();
+        x
+    }"#;
+
+    assert_eq!(llvm_exec!(src), 7);
+}
+
+#[test]
+fn llvm_closure_outlives_captured_mutable_var() {
+    let src = r#"
+    fn clobber(a: int, b: int, c: int) -> int {
+        let d = a + b + c;
+        d * d
+    }
+
+    fn main() -> int {
+        let counter = fn(start) => {
+            let mut n = start;
+            fn() => { n = n + 1; n }
+        };
+        let c = counter(0);
+        c();
+        clobber(1, 2, 3);
+        c();
+        let d = counter(5);
+        clobber(4, 5, 6);
+        c() * 10 + d()
+    }"#;
+
+    assert_eq!(llvm_exec!(src), 36);
+}
+
+#[test]
+fn llvm_closure_captures_mutable_param() {
+    let src = r#"
+    fn main() -> int {
+        let adder = fn(mut total) => fn(x) => {
+            total = total + x;
+            total
+        };
+        let add = adder(1);
+        add(2);
+        add(3)
+    }"#;
+
+    assert_eq!(llvm_exec!(src), 6);
+}
+
+#[test]
+fn llvm_closure_passed_to_fn() {
+    let src = r#"
+    #[lang]
+    trait Fn {}
+
+    fn apply<F: Fn(int) -> int>(f: F, x: int) -> int {
+        f(x)
+    }
+
+    fn main() -> int {
+        let k = 8;
+        apply(fn(x) => x + k, 3)
+    }"#;
+
+    assert_eq!(llvm_exec!(src), 11);
+}
+
+#[test]
+fn llvm_fn_item_as_fn_ptr() {
+    let src = r#"
+    fn apply(f: fn(int) -> int, x: int) -> int {
+        f(x)
+    }
+
+    fn double(x: int) -> int {
+        x * 2
+    }
+
+    fn main() -> int {
+        apply(double, 4)
+    }"#;
+
+    assert_eq!(llvm_exec!(src), 8);
+}
+
+#[test]
+fn llvm_non_capturing_closure_as_fn_ptr() {
+    let src = r#"
+    fn apply(f: fn(int) -> int, x: int) -> int {
+        f(x)
+    }
+
+    fn main() -> int {
+        apply(fn(x) => x * 3, 4)
+    }"#;
+
+    assert_eq!(llvm_exec!(src), 12);
+}
+
+#[test]
+fn llvm_generic_fn_monomorphized_per_closure() {
+    let src = r#"
+    #[lang]
+    trait Fn {}
+
+    fn map<F: Fn(int) -> int>(f: F, x: int) -> int {
+        f(x)
+    }
+
+    fn main() -> int {
+        let k = 10;
+        let add = fn(x) => x + k;
+        let mul = fn(x) => x * k;
+        map(add, 1) + map(mul, 2)
+    }"#;
+
+    assert_eq!(llvm_exec!(src), 31);
+}
+
+#[test]
+fn llvm_fn_item_satisfies_fn_bound() {
+    let src = r#"
+    #[lang]
+    trait Fn {}
+
+    fn map<F: Fn(int) -> int>(f: F, x: int) -> int {
+        f(x)
+    }
+
+    fn double(x: int) -> int {
+        x * 2
+    }
+
+    fn main() -> int {
+        map(double, 4)
+    }"#;
+
+    assert_eq!(llvm_exec!(src), 8);
+}
+
+#[test]
+fn llvm_fn_bound_forwarded_through_param() {
+    let src = r#"
+    #[lang]
+    trait Fn {}
+
+    fn map<F: Fn(int) -> int>(f: F, x: int) -> int {
+        f(x)
+    }
+
+    fn twice<G: Fn(int) -> int>(g: G, x: int) -> int {
+        map(g, map(g, x))
+    }
+
+    fn main() -> int {
+        let k = 3;
+        twice(fn(x) => x + k, 1)
+    }"#;
+
+    assert_eq!(llvm_exec!(src), 7);
+}
+
+#[test]
+fn llvm_nested_closures() {
+    let src = r#"
+    fn main() -> int {
+        let x = 1;
+        let f = fn(y) => {
+            let g = fn(z) => x + y + z;
+            g(3)
+        };
+        f(2)
+    }"#;
+
+    assert_eq!(llvm_exec!(src), 6);
+}
+
+#[test]
+fn llvm_recursive_named_closure() {
+    let src = r#"
+    fn main() -> int {
+        let k = 1;
+        (fn fact(n: int) -> int {
+            if n < 1 { k } else { n * fact(n - 1) }
+        })(5)
+    }"#;
+
+    assert_eq!(llvm_exec!(src), 120);
+}
+
+#[test]
+fn llvm_closure_in_generic_fn() {
+    let src = r#"
+    fn call<T>(x: T) -> T {
+        let f = fn() => x;
+        f()
+    }
+
+    fn main() -> int {
+        call(false);
+        call(9)
+    }"#;
+
+    assert_eq!(llvm_exec!(src), 9);
 }
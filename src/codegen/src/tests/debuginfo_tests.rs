@@ -0,0 +1,70 @@
+use ldriver::Driver;
+
+macro compile_with_debuginfo($src:expr) {{
+    let driver = Driver::from_src_with_opts($src, |opts| opts.debuginfo = true);
+    let cctx = driver.llvm_compile().unwrap();
+    cctx.module.print_to_string().to_string()
+}}
+
+#[test]
+fn debuginfo_describes_functions_and_locals() {
+    let src = r#"
+    fn add(a: int, b: int) -> int { a + b }
+
+    fn main() -> int {
+        let x = 5;
+        add(x, 2)
+    }"#;
+    let ir = compile_with_debuginfo!(src);
+    assert!(ir.contains("DISubprogram(name: \"add\""));
+    assert!(ir.contains("DISubprogram(name: \"main\""));
+    assert!(ir.contains("DILocalVariable(name: \"x\""));
+    assert!(ir.contains("DILocalVariable(name: \"a\", arg: 1"));
+    assert!(ir.contains("DILocalVariable(name: \"b\", arg: 2"));
+}
+
+#[test]
+fn debuginfo_names_adt_members() {
+    let src = r#"
+    struct S { x: int, y: bool }
+
+    enum Option<T> {
+        Some(T),
+        None,
+    }
+
+    fn main() -> int {
+        let s = S { x: 5, y: true };
+        match Option::Some(s) {
+            Option::Some(t) => t.x,
+            Option::None => 0,
+        }
+    }"#;
+    let ir = compile_with_debuginfo!(src);
+    assert!(ir.contains("DIDerivedType(tag: DW_TAG_member, name: \"x\""));
+    assert!(ir.contains("DIDerivedType(tag: DW_TAG_member, name: \"y\""));
+    assert!(ir.contains("DIDerivedType(tag: DW_TAG_member, name: \"discriminant\""));
+    assert!(ir.contains("DIDerivedType(tag: DW_TAG_member, name: \"Some\""));
+    assert!(ir.contains("DIDerivedType(tag: DW_TAG_member, name: \"None\""));
+    assert!(ir.contains("DICompositeType(tag: DW_TAG_union_type"));
+}
+
+#[test]
+fn debuginfo_does_not_change_behaviour() {
+    let src = r#"
+    struct S { x: int, y: bool }
+
+    fn main() -> int {
+        let s = S { x: 5, y: true };
+        let t = (s, box s);
+        match t.1.y {
+            true => t.0.x,
+            false => 0,
+        }
+    }"#;
+    let code = Driver::from_src_with_opts(src, |opts| opts.debuginfo = true)
+        .run()
+        .expect("process was interrupted before terminating")
+        .unwrap();
+    assert_eq!(code, 5);
+}
@@ -0,0 +1,173 @@
+use super::*;
+
+#[test]
+fn derived_eq_for_struct() {
+    let src = r#"
+    #[lang]
+    trait Eq {
+        fn eq(self, other: Self) -> bool;
+    }
+
+    #[derive(Eq)]
+    struct S { x: int, y: bool }
+
+    fn main() -> int {
+        let a = if S { x: 1, y: true } == S { x: 1, y: true } { 1 } else { 0 };
+        let b = if S { x: 1, y: true } == S { x: 1, y: false } { 10 } else { 0 };
+        let c = if S { x: 2, y: true } == S { x: 1, y: true } { 100 } else { 0 };
+        a + b + c
+    }
+    "#;
+
+    assert_eq!(llvm_jit!(src), 1);
+}
+
+#[test]
+fn derived_eq_for_enum() {
+    let src = r#"
+    #[lang]
+    trait Eq {
+        fn eq(self, other: Self) -> bool;
+    }
+
+    #[derive(Eq)]
+    struct T(int);
+
+    #[derive(Eq)]
+    enum E { A(int, T), B, C(bool) }
+
+    fn main() -> int {
+        let a = if E::A(1, T(2)) == E::A(1, T(2)) { 1 } else { 0 };
+        let b = if E::A(1, T(2)) == E::A(1, T(3)) { 10 } else { 0 };
+        let c = if E::B == E::B { 100 } else { 0 };
+        let d = if E::B == E::C(true) { 1000 } else { 0 };
+        a + b + c + d
+    }
+    "#;
+
+    assert_eq!(llvm_jit!(src), 101);
+}
+
+#[test]
+fn derived_clone() {
+    let src = r#"
+    #[lang]
+    trait Clone {
+        fn clone(self) -> Self;
+    }
+
+    #[derive(Clone)]
+    struct T(int, bool);
+
+    #[derive(Clone)]
+    enum E { A(T), B }
+
+    fn main() -> int {
+        let e = Clone::clone(E::A(T(5, true)));
+        match e {
+            E::A(T(x, b)) => if b { x } else { 0 },
+            E::B => 0,
+        }
+    }
+    "#;
+
+    assert_eq!(llvm_jit!(src), 5);
+}
+
+#[test]
+fn derived_hash() {
+    let src = r#"
+    #[lang]
+    trait Hash {
+        fn hash(self) -> int;
+    }
+
+    #[derive(Hash)]
+    struct T(int, bool);
+
+    #[derive(Hash)]
+    enum E { A(int), B(T) }
+
+    fn main() -> int {
+        // (1 * 31 + ((0 * 31 + 2) * 31 + 1))
+        Hash::hash(E::B(T(2, true)))
+    }
+    "#;
+
+    assert_eq!(llvm_jit!(src), 94);
+}
+
+#[test]
+fn derived_hash_wraps_on_overflow() {
+    let src = r#"
+    #[lang]
+    trait Hash {
+        fn hash(self) -> int;
+    }
+
+    #[derive(Hash)]
+    struct T(int, bool);
+
+    #[derive(Hash)]
+    enum E { A(int), B(T) }
+
+    fn main() -> int {
+        // 2^62 * 31 wraps around to -2^62
+        let h = Hash::hash(E::B(T(4611686018427387904, true)));
+        if h == 32 - 4611686018427387904 { 0 } else { 1 }
+    }
+    "#;
+
+    assert_eq!(llvm_exec!(src), 0);
+}
+
+#[test]
+fn derived_hash_ignores_user_definitions() {
+    // the generated code doesn't refer to whatever `wrapping_add` or `Hash` are in scope
+    let src = r#"
+    mod m {
+        #[lang]
+        trait Hash {
+            fn hash(self) -> int;
+        }
+    }
+
+    trait Hash {
+        fn hash(self) -> int;
+    }
+
+    fn wrapping_add(x: int, y: int) -> int { 0 }
+
+    #[derive(Hash)]
+    struct T(int, bool);
+
+    fn main() -> int {
+        m::Hash::hash(T(2, true))
+    }
+    "#;
+
+    assert_eq!(llvm_jit!(src), 63);
+}
+
+#[test]
+fn derived_hash_of_float_field() {
+    let src = r#"
+    #[lang]
+    trait Hash {
+        fn hash(self) -> int;
+    }
+
+    #[derive(Hash)]
+    struct T(float);
+
+    fn main() -> int {
+        let a = if Hash::hash(T(1.5)) == Hash::hash(T(1.5)) { 1 } else { 0 };
+        let b = if Hash::hash(T(1.5)) == Hash::hash(T(2.5)) { 10 } else { 0 };
+        // the hash of 1.5 is its bit pattern, 0x3ff8000000000000
+        let c = if Hash::hash(T(1.5)) == 4609434218613702656 { 100 } else { 0 };
+        a + b + c
+    }
+    "#;
+
+    assert_eq!(llvm_jit!(src), 101);
+}
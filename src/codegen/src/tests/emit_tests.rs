@@ -0,0 +1,67 @@
+use crate::output_path;
+use ldriver::{Driver, EmitKind};
+use std::path::PathBuf;
+
+#[test]
+fn emit_writes_requested_outputs() {
+    let src = "fn main() -> int { 0 }";
+    let kinds = vec![EmitKind::LlvmIr, EmitKind::LlvmBc, EmitKind::Asm, EmitKind::Obj];
+    let driver = Driver::from_src_with_opts(src, |opts| opts.emit = kinds.clone());
+    driver.build().unwrap();
+    let out_dir = driver.out_dir();
+    for kind in kinds {
+        assert!(output_path(&out_dir, kind).exists());
+    }
+    assert!(!output_path(&out_dir, EmitKind::Link).exists());
+}
+
+#[test]
+fn emit_link_removes_intermediate_object() {
+    let src = "fn main() -> int { 0 }";
+    let out_dir = std::env::temp_dir().join("l-emit-link-test");
+    let _ = std::fs::remove_dir_all(&out_dir);
+    let driver = Driver::from_src_with_opts(src, |opts| opts.out_dir = Some(out_dir.clone()));
+    driver.build().unwrap();
+    assert!(output_path(&out_dir, EmitKind::Link).exists());
+    assert!(!output_path(&out_dir, EmitKind::Obj).exists());
+}
+
+/// emits an object file of `src` for the given target triple
+fn cross_compile(src: &str, target: &str) -> PathBuf {
+    let driver = Driver::from_src_with_opts(src, |opts| {
+        opts.emit = vec![EmitKind::Obj];
+        opts.target = Some(target.to_owned());
+    });
+    driver.build().unwrap();
+    output_path(&driver.out_dir(), EmitKind::Obj)
+}
+
+/// the `e_machine` field of the elf header
+fn elf_machine(path: PathBuf) -> u16 {
+    let bytes = std::fs::read(path).unwrap();
+    assert_eq!(&bytes[..4], b"\x7fELF");
+    u16::from_le_bytes([bytes[18], bytes[19]])
+}
+
+const EM_AARCH64: u16 = 183;
+const EM_RISCV: u16 = 243;
+
+#[test]
+fn cross_compile_aarch64() {
+    let src = r#"
+    fn main() -> int {
+        let x = box (1, 2);
+        x.1
+    }"#;
+    assert_eq!(elf_machine(cross_compile(src, "aarch64-unknown-linux-gnu")), EM_AARCH64);
+}
+
+#[test]
+fn cross_compile_riscv64() {
+    let src = r#"
+    fn main() -> int {
+        let x = box (1, 2);
+        x.1
+    }"#;
+    assert_eq!(elf_machine(cross_compile(src, "riscv64-unknown-linux-gnu")), EM_RISCV);
+}
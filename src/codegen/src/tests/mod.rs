@@ -1,14 +1,19 @@
 mod closure_tests;
 mod control_flow_tests;
+mod debuginfo_tests;
+mod derive_tests;
+mod emit_tests;
 mod enum_tests;
 mod impl_tests;
 mod lltype_tests;
 mod match_tests;
 mod monomorphization_tests;
+mod operator_tests;
 mod output_tests;
 mod pattern_tests;
 mod ptr_tests;
 mod struct_tests;
+mod trait_tests;
 
 pub macro llvm_jit_inner($src:expr) {
     ldriver::Driver::from_src($src).llvm_jit()
@@ -31,6 +36,14 @@ pub macro llvm_exec($src:expr) {
     llvm_exec_inner!($src).unwrap()
 }
 
+/// builds using the release profile
+pub macro llvm_exec_release($src:expr) {
+    ldriver::Driver::from_src_with_opts($src, |opts| opts.release = true)
+        .run()
+        .expect("process was interrupted before terminating")
+        .unwrap()
+}
+
 pub macro llvm_exec_expect_error($src:expr) {
     llvm_exec_inner!($src).unwrap_err()
 }
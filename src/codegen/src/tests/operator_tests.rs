@@ -0,0 +1,193 @@
+use super::*;
+
+#[test]
+fn overloaded_add() {
+    let src = r#"
+    struct V { x: int, y: int }
+
+    #[lang]
+    trait Add {
+        fn add(self, other: Self) -> Self;
+    }
+
+    impl Add for V {
+        fn add(self, other: Self) -> Self {
+            V { x: self.x + other.x, y: self.y + other.y }
+        }
+    }
+
+    fn main() -> int {
+        let v = V { x: 1, y: 2 } + V { x: 3, y: 4 };
+        v.x * v.y
+    }
+    "#;
+
+    assert_eq!(llvm_jit!(src), 24);
+}
+
+#[test]
+fn overloaded_eq_and_ne() {
+    let src = r#"
+    struct S { x: int }
+
+    #[lang]
+    trait Eq {
+        fn eq(self, other: Self) -> bool;
+        fn ne(self, other: Self) -> bool {
+            if Self::eq(self, other) { false } else { true }
+        }
+    }
+
+    impl Eq for S {
+        fn eq(self, other: Self) -> bool { self.x == other.x }
+    }
+
+    fn main() -> int {
+        let p = S { x: 1 };
+        let q = S { x: 1 };
+        let r = S { x: 2 };
+        let a = if p == q { 10 } else { 20 };
+        let b = if p != r { 3 } else { 4 };
+        a + b
+    }
+    "#;
+
+    assert_eq!(llvm_jit!(src), 13);
+}
+
+#[test]
+fn overloaded_ord() {
+    let src = r#"
+    struct S { x: int }
+
+    #[lang]
+    trait Ord {
+        fn lt(self, other: Self) -> bool;
+        fn gt(self, other: Self) -> bool { Self::lt(other, self) }
+    }
+
+    impl Ord for S {
+        fn lt(self, other: Self) -> bool { self.x < other.x }
+    }
+
+    fn main() -> int {
+        let p = S { x: 2 };
+        let q = S { x: 1 };
+        if p > q { 5 } else { 6 }
+    }
+    "#;
+
+    assert_eq!(llvm_jit!(src), 5);
+}
+
+#[test]
+fn add_overflow_panics() {
+    let src = r#"
+    fn double(x: int) -> int { x + x }
+
+    fn main() -> int {
+        double(4611686018427387904)
+    }
+    "#;
+
+    assert_eq!(llvm_exec!(src), crate::native::PANIC_EXIT_CODE as i32);
+}
+
+#[test]
+fn neg_overflow_panics() {
+    let src = r#"
+    fn neg(x: int) -> int { -x }
+    fn main() -> int {
+        neg(-9223372036854775807 - 1)
+    }
+    "#;
+
+    assert_eq!(llvm_exec!(src), crate::native::PANIC_EXIT_CODE as i32);
+}
+
+#[test]
+fn add_overflow_wraps_in_release() {
+    let src = r#"
+    fn main() -> int {
+        let x = 4611686018427387904;
+        x + x + 7
+    }
+    "#;
+
+    assert_eq!(llvm_exec_release!(src), 7);
+}
+
+#[test]
+fn div_by_zero_panics() {
+    let src = r#"
+    fn div(x: int, y: int) -> int { x / y }
+
+    fn main() -> int {
+        div(1, 0)
+    }
+    "#;
+
+    assert_eq!(llvm_exec!(src), crate::native::PANIC_EXIT_CODE as i32);
+}
+
+#[test]
+fn div_by_zero_panics_in_release() {
+    let src = r#"
+    fn div(x: int, y: int) -> int { x / y }
+
+    fn main() -> int {
+        div(1, 0)
+    }
+    "#;
+
+    assert_eq!(llvm_exec_release!(src), crate::native::PANIC_EXIT_CODE as i32);
+}
+
+#[test]
+fn div_overflow_panics_in_release() {
+    let src = r#"
+    fn div(x: int, y: int) -> int { x / y }
+
+    fn main() -> int {
+        div(-9223372036854775807 - 1, -1)
+    }
+    "#;
+
+    assert_eq!(llvm_exec_release!(src), crate::native::PANIC_EXIT_CODE as i32);
+}
+
+#[test]
+fn wrapping_intrinsics() {
+    let src = r#"
+    extern "l-intrinsic" {
+        fn wrapping_add(x: int, y: int) -> int;
+        fn wrapping_mul(x: int, y: int) -> int;
+    }
+
+    fn main() -> int {
+        let x = 4611686018427387904;
+        wrapping_mul(x, 4) + wrapping_add(wrapping_add(x, x), wrapping_add(x, x)) + 5
+    }
+    "#;
+
+    assert_eq!(llvm_exec!(src), 5);
+}
+
+#[test]
+fn checked_intrinsics() {
+    let src = r#"
+    extern "l-intrinsic" {
+        fn checked_add(x: int, y: int) -> (int, bool);
+        fn checked_div(x: int, y: int) -> (int, bool);
+    }
+
+    fn main() -> int {
+        let (_, overflowed) = checked_add(4611686018427387904, 4611686018427387904);
+        let (q, div_by_zero) = checked_div(7, 0);
+        let (r, failed) = checked_div(12, 4);
+        if overflowed { if div_by_zero { if failed { 0 } else { q + r } } else { 0 } } else { 0 }
+    }
+    "#;
+
+    assert_eq!(llvm_exec!(src), 10);
+}
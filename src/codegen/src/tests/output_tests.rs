@@ -117,7 +117,7 @@ fn llvm_multiple_returns() {
     assert_eq!(llvm_jit!(src), 5);
 }
 
-// #[test]
+#[test]
 fn llvm_non_escaping_closure() {
     let src = r#"
     fn main() -> int {
@@ -125,10 +125,10 @@ fn llvm_non_escaping_closure() {
         (fn () => x + 4)()
     }
     "#;
-    assert_eq!(llvm_jit!(src), 9);
+    assert_eq!(llvm_exec!(src), 9);
 }
 
-// #[test]
+#[test]
 fn llvm_lambda_no_capture() {
     let src = r#"
     fn main() -> int {
@@ -136,7 +136,7 @@ fn llvm_lambda_no_capture() {
         2 + f()
     }
     "#;
-    assert_eq!(llvm_jit!(src), 7)
+    assert_eq!(llvm_exec!(src), 7)
 }
 
 #[test]
@@ -74,3 +74,40 @@ fn llvm_unpack_tuple_in_parameter() {
 
     assert_eq!(llvm_jit!(src), 185)
 }
+
+#[test]
+fn llvm_unpack_single_variant_enum() {
+    let src = r#"
+    enum Wrapper<T> {
+        Wrap(T, int),
+    }
+
+    fn main() -> int {
+        let Wrapper::Wrap((x, _), y) = Wrapper::Wrap((5, false), 8);
+        x + unwrap(Wrapper::Wrap(y, 1))
+    }
+
+    fn unwrap(Wrapper::Wrap(x, _): Wrapper<int>) -> int {
+        x
+    }
+    "#;
+
+    assert_eq!(llvm_jit!(src), 13)
+}
+
+#[test]
+fn llvm_declare_single_variant_enum_pattern() {
+    let src = r#"
+    enum Wrapper {
+        Wrap(int),
+    }
+
+    fn main() -> int {
+        let Wrapper::Wrap(x);
+        x = 4;
+        x
+    }
+    "#;
+
+    assert_eq!(llvm_jit!(src), 4)
+}
@@ -0,0 +1,124 @@
+use super::*;
+
+#[test]
+fn inherit_default_trait_method() {
+    let src = r#"
+    struct S;
+
+    trait Five {
+        fn five() -> int { 5 }
+    }
+
+    impl Five for S {}
+
+    fn main() -> int {
+        S::five()
+    }
+    "#;
+
+    assert_eq!(llvm_jit!(src), 5);
+}
+
+#[test]
+fn override_default_trait_method() {
+    let src = r#"
+    struct S;
+
+    trait Five {
+        fn five() -> int { 5 }
+    }
+
+    impl Five for S {
+        fn five() -> int { 6 }
+    }
+
+    fn main() -> int {
+        S::five()
+    }
+    "#;
+
+    assert_eq!(llvm_jit!(src), 6);
+}
+
+#[test]
+fn default_trait_method_calls_required_method() {
+    let src = r#"
+    struct S { x: int }
+    struct T;
+
+    trait Double {
+        fn get(self) -> int;
+        fn double(self) -> int { Self::get(self) + Self::get(self) }
+    }
+
+    impl Double for S {
+        fn get(self) -> int { self.x }
+    }
+
+    impl Double for T {
+        fn get(self) -> int { 1 }
+    }
+
+    fn main() -> int {
+        S::double(S { x: 4 }) + T::double(T)
+    }
+    "#;
+
+    assert_eq!(llvm_jit!(src), 10);
+}
+
+#[test]
+fn dynamic_dispatch_through_trait_object() {
+    let src = r#"
+    struct S { x: int }
+    struct T;
+
+    trait Get {
+        fn get(&self) -> int;
+    }
+
+    impl Get for S {
+        fn get(&self) -> int { self.x }
+    }
+
+    impl Get for T {
+        fn get(&self) -> int { 1 }
+    }
+
+    fn call(x: &dyn Get) -> int {
+        Get::get(x)
+    }
+
+    fn main() -> int {
+        call(box S { x: 5 }) + call(box T)
+    }
+    "#;
+
+    assert_eq!(llvm_exec!(src), 6);
+}
+
+#[test]
+fn trait_object_uses_default_method() {
+    let src = r#"
+    struct S { x: int }
+
+    trait Double {
+        fn get(&self) -> int;
+        fn double(&self) -> int { Self::get(self) * 2 }
+    }
+
+    impl Double for S {
+        fn get(&self) -> int { self.x }
+    }
+
+    fn call(x: &dyn Double) -> int {
+        Double::double(x)
+    }
+
+    fn main() -> int {
+        call(box S { x: 4 })
+    }
+    "#;
+
+    assert_eq!(llvm_exec!(src), 8);
+}
@@ -0,0 +1,95 @@
+//! inkwell doesn't expose llvm's exception handling instructions (`invoke` and `landingpad`)
+//! so they are built through llvm-sys directly
+//! the only landing pad is the one in `l_catch_unwind`, a panic unwinds every frame in between
+
+use inkwell::basic_block::BasicBlock;
+use inkwell::module::Module;
+use inkwell::values::{AsValueRef, BasicValueEnum, FunctionValue, PointerValue};
+use llvm_sys::core::*;
+use llvm_sys::prelude::*;
+use std::ffi::CString;
+use std::marker::PhantomData;
+
+/// the c++ personality routine
+/// panics are thrown as c++ exceptions so we can use the unwinder of the c++ runtime
+pub const PERSONALITY_FN: &str = "__gxx_personality_v0";
+
+crate struct UnwindBuilder<'tcx> {
+    llcx: LLVMContextRef,
+    builder: LLVMBuilderRef,
+    pd: PhantomData<&'tcx ()>,
+}
+
+impl<'tcx> UnwindBuilder<'tcx> {
+    crate fn new(module: &Module<'tcx>) -> Self {
+        unsafe {
+            let llcx = LLVMGetModuleContext(module.get_module_ref());
+            Self { llcx, builder: LLVMCreateBuilderInContext(llcx), pd: PhantomData }
+        }
+    }
+
+    crate fn position_at_end(&self, block: BasicBlock<'tcx>) {
+        unsafe { LLVMPositionBuilderAtEnd(self.builder, block.as_mut_ptr()) }
+    }
+
+    /// `invoke f(args) to label then unwind label unwind`
+    /// returns the raw return value of the call which is only available in `then`
+    crate fn build_invoke(
+        &self,
+        f: PointerValue<'tcx>,
+        args: &[BasicValueEnum<'tcx>],
+        then: BasicBlock<'tcx>,
+        unwind: BasicBlock<'tcx>,
+        name: &str,
+    ) -> LLVMValueRef {
+        let mut args = args.iter().map(|arg| arg.as_value_ref()).collect::<Vec<_>>();
+        let name = CString::new(name).unwrap();
+        unsafe {
+            LLVMBuildInvoke(
+                self.builder,
+                f.as_value_ref(),
+                args.as_mut_ptr(),
+                args.len() as u32,
+                then.as_mut_ptr(),
+                unwind.as_mut_ptr(),
+                name.as_ptr(),
+            )
+        }
+    }
+
+    /// builds a landing pad that catches every exception and stores it into `exn_slot`
+    crate fn build_landing_pad(
+        &self,
+        personality: FunctionValue<'tcx>,
+        exn_slot: PointerValue<'tcx>,
+    ) {
+        let name = CString::new("landing_pad").unwrap();
+        unsafe {
+            let ty = exn_ty(self.llcx);
+            let lp =
+                LLVMBuildLandingPad(self.builder, ty, personality.as_value_ref(), 1, name.as_ptr());
+            let i8ptr = LLVMPointerType(LLVMInt8TypeInContext(self.llcx), 0);
+            LLVMAddClause(lp, LLVMConstNull(i8ptr));
+            LLVMBuildStore(self.builder, lp, exn_slot.as_value_ref());
+        }
+    }
+}
+
+impl<'tcx> Drop for UnwindBuilder<'tcx> {
+    fn drop(&mut self) {
+        unsafe { LLVMDisposeBuilder(self.builder) }
+    }
+}
+
+crate fn set_personality_fn<'tcx>(llfn: FunctionValue<'tcx>, personality: FunctionValue<'tcx>) {
+    unsafe { LLVMSetPersonalityFn(llfn.as_value_ref(), personality.as_value_ref()) }
+}
+
+/// `{ i8*, i32 }`: the exception pointer and the selector produced by a landing pad
+fn exn_ty(llcx: LLVMContextRef) -> LLVMTypeRef {
+    unsafe {
+        let i8ptr = LLVMPointerType(LLVMInt8TypeInContext(llcx), 0);
+        let mut fields = [i8ptr, LLVMInt32TypeInContext(llcx)];
+        LLVMStructTypeInContext(llcx, fields.as_mut_ptr(), fields.len() as u32, 0)
+    }
+}
@@ -7,7 +7,7 @@ const WORD_BYTES: usize = std::mem::size_of::<Word>();
 const WORD_BITS: usize = WORD_BYTES * 8;
 
 // we make it generic over T so the iterator knows what type to return
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Bitset<T: Idx> {
     words: Vec<Word>,
     /// the size the set was constructed with
@@ -46,6 +46,43 @@ impl<T: Idx> Bitset<T> {
         self.words[index] &= !shift;
     }
 
+    /// sets every bit in the set
+    pub fn set_all(&mut self) {
+        for i in 0..self.size {
+            self.set(T::new(i));
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.words.iter_mut().for_each(|word| *word = 0);
+    }
+
+    /// `self = self | other`, returns whether the set has changed
+    pub fn union(&mut self, other: &Self) -> bool {
+        self.apply(other, |a, b| a | b)
+    }
+
+    /// `self = self & other`, returns whether the set has changed
+    pub fn intersect(&mut self, other: &Self) -> bool {
+        self.apply(other, |a, b| a & b)
+    }
+
+    /// `self = self - other`, returns whether the set has changed
+    pub fn subtract(&mut self, other: &Self) -> bool {
+        self.apply(other, |a, b| a & !b)
+    }
+
+    fn apply(&mut self, other: &Self, f: impl Fn(Word, Word) -> Word) -> bool {
+        assert_eq!(self.size, other.size);
+        let mut has_changed = false;
+        for (word, &other) in self.words.iter_mut().zip(&other.words) {
+            let new = f(*word, other);
+            has_changed |= new != *word;
+            *word = new;
+        }
+        has_changed
+    }
+
     fn bit_indices(idx: impl Idx) -> (usize, Word) {
         let idx = idx.index();
         let index = idx / WORD_BITS;
@@ -153,4 +190,36 @@ mod tests {
         assert_eq!(bitset.words[0], 33);
         assert!(!bitset.is_set(3));
     }
+
+    #[test]
+    fn test_bitset_set_operations() {
+        let mut a = Bitset::<usize>::new(10);
+        let mut b = Bitset::new(10);
+        a.set(1);
+        a.set(9);
+        b.set(1);
+        b.set(4);
+
+        let mut union = a.clone();
+        assert!(union.union(&b));
+        assert!(!union.union(&b));
+        assert_eq!(vec![1, 4, 9], union.iter().collect::<Vec<_>>());
+
+        let mut intersection = a.clone();
+        assert!(intersection.intersect(&b));
+        assert_eq!(vec![1], intersection.iter().collect::<Vec<_>>());
+
+        assert!(a.subtract(&b));
+        assert_eq!(vec![9], a.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_bitset_set_all() {
+        let mut bitset = Bitset::<usize>::new(10);
+        bitset.set_all();
+        // bits past the size must not be set
+        assert_eq!((0..10).collect::<Vec<_>>(), bitset.iter().collect::<Vec<_>>());
+        bitset.clear();
+        assert_eq!(bitset.iter().count(), 0);
+    }
 }
@@ -0,0 +1,484 @@
+//! expansion of `#[derive(..)]` attributes
+//! each derived trait generates an `impl <Trait> for <Type>` item that is appended to the
+//! module containing the type
+//! the traits themselves are not builtin and must be declared by the user as `#[lang]` traits
+//! (with the expected method signatures), generated code refers to them and to the intrinsics
+//! it calls through paths that can't be written in source, so is unaffected by what is in scope
+
+use crate::expand_error::ExpandError;
+use crate::Expander;
+use ast::*;
+use span::{kw, sym, Span, Symbol};
+
+impl<'a> Expander<'a> {
+    /// expands all derive attributes within `module` (and its submodules)
+    /// the parser has already checked that derives are only applied to structs and enums
+    crate fn expand_derives(&self, module: &mut Module) {
+        let mut impls = vec![];
+        for item in &mut module.items {
+            if let ItemKind::Mod(submodule) = &mut item.kind {
+                self.expand_derives(submodule);
+            }
+            for attr in &item.attrs {
+                if let AttrKind::Derive(traits) = &attr.kind {
+                    for &trait_ident in traits {
+                        let span = trait_ident.span;
+                        let dcx = DeriveCtxt { ecx: self, span, item: &**item };
+                        impls.extend(dcx.expand(trait_ident));
+                    }
+                }
+            }
+        }
+        module.items.extend(impls);
+    }
+}
+
+/// a constructor of the type being derived (either the struct itself or an enum variant)
+struct Ctor<'p> {
+    /// the path to the constructor, `S` or `E::V`
+    path: Vec<Ident>,
+    /// index of the variant (always zero for structs)
+    idx: usize,
+    kind: &'p VariantKind,
+}
+
+/// how to refer to a field of the value being derived
+#[derive(Clone, Copy)]
+enum FieldRef {
+    /// `<receiver>.<field>`
+    Field(Symbol, Ident),
+    /// a binding introduced by a constructor pattern
+    Binding(Symbol),
+}
+
+struct DeriveCtxt<'p, 'a> {
+    ecx: &'p Expander<'a>,
+    /// all generated nodes are given the span of the derived trait's identifier
+    span: Span,
+    item: &'p Item,
+}
+
+impl<'p, 'a> DeriveCtxt<'p, 'a> {
+    fn expand(&self, trait_ident: Ident) -> Option<P<Item>> {
+        let item = self.item;
+        let generics = match &item.kind {
+            ItemKind::Struct(generics, _) => generics,
+            ItemKind::Enum(generics, variants) => {
+                if variants.iter().any(|variant| matches!(variant.kind, VariantKind::Struct(..))) {
+                    let err = ExpandError::DeriveStructVariant(trait_ident, item.ident);
+                    self.ecx.sess.build_error(self.span, err).emit();
+                    return None;
+                }
+                generics
+            }
+            // derives on any other item are rejected by the parser
+            _ => return None,
+        };
+
+        if !generics.params.is_empty() {
+            let err = ExpandError::DeriveGeneric(trait_ident, item.ident);
+            self.ecx.sess.build_error(self.span, err).emit();
+            return None;
+        }
+
+        let method = match trait_ident.symbol {
+            sym::Eq => self.derive_eq(),
+            sym::Clone => self.derive_clone(trait_ident),
+            sym::Hash => self.derive_hash(trait_ident),
+            sym::Debug => self.derive_debug(trait_ident),
+            _ => {
+                let err = ExpandError::UnknownDerive(trait_ident);
+                self.ecx.sess.build_error(self.span, err).emit();
+                return None;
+            }
+        };
+
+        let kind = ItemKind::Impl {
+            generics: self.mk_generics(),
+            trait_path: Some(self.mk_path(&self.lang_trait_path(trait_ident))),
+            self_ty: self.mk_ty_path(item.ident.symbol),
+            items: vec![method],
+        };
+        Some(box Item {
+            span: self.span,
+            id: self.ecx.mk_id(),
+            vis: self.mk_private(),
+            ident: Ident::empty(),
+            attrs: vec![],
+            kind,
+        })
+    }
+
+    /// fn eq(self, other: Self) -> bool
+    /// compares each pair of fields with `==` (which may itself be overloaded)
+    fn derive_eq(&self) -> P<AssocItem> {
+        let ctors = self.ctors();
+        let has_many_ctors = ctors.len() > 1;
+        let body = self.match_ctors(kw::LSelf, &ctors, None, |ctor, self_fields| {
+            // differing constructors are never equal
+            let wildcard = if has_many_ctors { Some(self.mk_bool(false)) } else { None };
+            let ctors = std::slice::from_ref(ctor);
+            self.match_ctors(sym::other, ctors, wildcard, |_, other_fields| {
+                let pairs = self_fields.iter().zip(other_fields).rev();
+                pairs.fold(self.mk_bool(true), |acc, (&(l, _), (r, _))| {
+                    let cond = self.mk_expr(ExprKind::Bin(
+                        BinOp::Eq,
+                        self.mk_field_expr(l),
+                        self.mk_field_expr(r),
+                    ));
+                    self.mk_if(cond, acc, self.mk_bool(false))
+                })
+            })
+        });
+        let params = vec![self.mk_self_param(), self.mk_param(sym::other, kw::USelf)];
+        self.mk_method(sym::eq, params, Some(self.mk_ty_path(sym::bool)), body)
+    }
+
+    /// fn clone(self) -> Self
+    /// builtin types are copied and all other fields are cloned through `Clone::clone`
+    fn derive_clone(&self, trait_ident: Ident) -> P<AssocItem> {
+        let ctors = self.ctors();
+        let body = self.match_ctors(kw::LSelf, &ctors, None, |ctor, fields| {
+            let fields = fields
+                .into_iter()
+                .map(|(field, decl)| {
+                    let expr = self.mk_field_expr(field);
+                    if is_builtin_ty(&decl.ty) {
+                        (decl, expr)
+                    } else {
+                        (decl, self.mk_trait_call(trait_ident, sym::clone, expr))
+                    }
+                })
+                .collect();
+            self.mk_ctor_expr(ctor, fields)
+        });
+        let ret_ty = self.mk_ty_path(kw::USelf);
+        self.mk_method(sym::clone, vec![self.mk_self_param()], Some(ret_ty), body)
+    }
+
+    /// fn hash(self) -> int
+    /// combines the hashes of each field starting from the index of the variant
+    /// the combination wraps on overflow
+    /// floats are hashed by their bits and chars by their code point
+    fn derive_hash(&self, trait_ident: Ident) -> P<AssocItem> {
+        let ctors = self.ctors();
+        let body = self.match_ctors(kw::LSelf, &ctors, None, |ctor, fields| {
+            let seed = self.mk_int(ctor.idx as i64);
+            fields.into_iter().fold(seed, |acc, (field, decl)| {
+                let expr = self.mk_field_expr(field);
+                let hash = match prim_ty(&decl.ty) {
+                    Some(sym::int) => expr,
+                    Some(sym::bool) => self.mk_if(expr, self.mk_int(1), self.mk_int(0)),
+                    Some(sym::float) => self.mk_intrinsic_call(sym::float_to_bits, vec![expr]),
+                    Some(sym::char) => self.mk_intrinsic_call(sym::char_to_int, vec![expr]),
+                    Some(..) => unreachable!(),
+                    None if is_builtin_ty(&decl.ty) => self.mk_int(0),
+                    None => self.mk_trait_call(trait_ident, sym::hash, expr),
+                };
+                let acc = self.mk_wrapping_op(sym::wrapping_mul, acc, self.mk_int(31));
+                self.mk_wrapping_op(sym::wrapping_add, acc, hash)
+            })
+        });
+        let ret_ty = self.mk_ty_path(sym::int);
+        self.mk_method(sym::hash, vec![self.mk_self_param()], Some(ret_ty), body)
+    }
+
+    /// fn fmt(self)
+    /// there are no strings yet, so this prints the variant index (for enums) followed by
+    /// each `int` and `bool` field, delegating to `Debug::fmt` for fields of other user types
+    fn derive_debug(&self, trait_ident: Ident) -> P<AssocItem> {
+        let ctors = self.ctors();
+        let has_many_ctors = ctors.len() > 1;
+        let body = self.match_ctors(kw::LSelf, &ctors, None, |ctor, fields| {
+            let mut stmts = vec![];
+            if has_many_ctors {
+                stmts.push(self.mk_semi(self.mk_print(self.mk_int(ctor.idx as i64))));
+            }
+            for (field, decl) in fields {
+                let expr = self.mk_field_expr(field);
+                let expr = match prim_ty(&decl.ty) {
+                    Some(sym::int) => self.mk_print(expr),
+                    Some(sym::bool) =>
+                        self.mk_print(self.mk_if(expr, self.mk_int(1), self.mk_int(0))),
+                    Some(..) => continue,
+                    None if is_builtin_ty(&decl.ty) => continue,
+                    None => self.mk_trait_call(trait_ident, sym::fmt, expr),
+                };
+                stmts.push(self.mk_semi(expr));
+            }
+            self.mk_expr(ExprKind::Block(self.mk_block(stmts)))
+        });
+        self.mk_method(sym::fmt, vec![self.mk_self_param()], None, body)
+    }
+
+    fn ctors(&self) -> Vec<Ctor<'p>> {
+        let item = self.item;
+        let ident = Ident::new(self.span, item.ident.symbol);
+        match &item.kind {
+            ItemKind::Struct(_, kind) => vec![Ctor { path: vec![ident], idx: 0, kind }],
+            ItemKind::Enum(_, variants) => variants
+                .iter()
+                .enumerate()
+                .map(|(idx, variant)| Ctor {
+                    path: vec![ident, Ident::new(self.span, variant.ident.symbol)],
+                    idx,
+                    kind: &variant.kind,
+                })
+                .collect(),
+            _ => unreachable!(),
+        }
+    }
+
+    /// match <scrut> {
+    ///     <ctor>(<scrut>_0, <scrut>_1, ...) => <body>,
+    ///     ...
+    ///     _ => <wildcard>,
+    /// }
+    /// the fields of a struct with named fields are accessed directly instead of matching
+    fn match_ctors(
+        &self,
+        scrut: Symbol,
+        ctors: &[Ctor<'p>],
+        wildcard: Option<P<Expr>>,
+        mut body: impl FnMut(&Ctor<'p>, Vec<(FieldRef, &'p FieldDecl)>) -> P<Expr>,
+    ) -> P<Expr> {
+        if let [ctor] = ctors {
+            if let VariantKind::Struct(decls) = ctor.kind {
+                let fields = decls
+                    .iter()
+                    .map(|decl| (FieldRef::Field(scrut, decl.ident.unwrap()), decl))
+                    .collect();
+                return body(ctor, fields);
+            }
+        }
+
+        let mut arms = ctors
+            .iter()
+            .map(|ctor| {
+                let (pat, fields) = self.mk_ctor_pat(scrut, ctor);
+                let body = body(ctor, fields);
+                self.mk_arm(pat, body)
+            })
+            .collect::<Vec<_>>();
+        arms.extend(wildcard.map(|body| self.mk_arm(self.mk_pat(PatternKind::Wildcard), body)));
+        self.mk_expr(ExprKind::Match(self.mk_var(scrut), arms))
+    }
+
+    fn mk_ctor_pat(
+        &self,
+        scrut: Symbol,
+        ctor: &Ctor<'p>,
+    ) -> (P<Pattern>, Vec<(FieldRef, &'p FieldDecl)>) {
+        let path = self.mk_path(&ctor.path);
+        match ctor.kind {
+            VariantKind::Tuple(decls) => {
+                let names = (0..decls.len())
+                    .map(|i| Symbol::intern(&format!("{}_{}", scrut, i)))
+                    .collect::<Vec<_>>();
+                let pats = names
+                    .iter()
+                    .map(|&name| {
+                        let ident = Ident::new(self.span, name);
+                        self.mk_pat(PatternKind::Ident(ident, None, Mutability::Imm))
+                    })
+                    .collect();
+                let fields = names.into_iter().map(FieldRef::Binding).zip(decls).collect();
+                (self.mk_pat(PatternKind::Variant(path, pats)), fields)
+            }
+            VariantKind::Unit => (self.mk_pat(PatternKind::Path(path)), vec![]),
+            VariantKind::Struct(..) => unreachable!("struct variants are rejected in `expand`"),
+        }
+    }
+
+    /// constructs `ctor` from the given field values
+    fn mk_ctor_expr(&self, ctor: &Ctor<'p>, fields: Vec<(&'p FieldDecl, P<Expr>)>) -> P<Expr> {
+        let path = self.mk_path(&ctor.path);
+        let kind = match ctor.kind {
+            VariantKind::Struct(..) => ExprKind::Struct(
+                path,
+                fields
+                    .into_iter()
+                    .map(|(decl, expr)| Field {
+                        id: self.ecx.mk_id(),
+                        span: self.span,
+                        ident: decl.ident.unwrap(),
+                        expr,
+                    })
+                    .collect(),
+            ),
+            VariantKind::Tuple(..) => ExprKind::Call(
+                self.mk_expr(ExprKind::Path(path)),
+                fields.into_iter().map(|(_, expr)| expr).collect(),
+            ),
+            VariantKind::Unit => ExprKind::Path(path),
+        };
+        self.mk_expr(kind)
+    }
+
+    fn mk_field_expr(&self, field: FieldRef) -> P<Expr> {
+        match field {
+            FieldRef::Field(receiver, ident) =>
+                self.mk_expr(ExprKind::Field(self.mk_var(receiver), ident)),
+            FieldRef::Binding(name) => self.mk_var(name),
+        }
+    }
+
+    /// `{{lang}}::<trait>`, which refers to the trait declared with `#[lang]` regardless of
+    /// what is in scope where the type is defined
+    fn lang_trait_path(&self, trait_ident: Ident) -> [Ident; 2] {
+        [Ident::new(self.span, kw::LangItem), Ident::new(self.span, trait_ident.symbol)]
+    }
+
+    /// {{lang}}::<trait>::<method>(<arg>)
+    fn mk_trait_call(&self, trait_ident: Ident, method: Symbol, arg: P<Expr>) -> P<Expr> {
+        let [lang, trait_ident] = self.lang_trait_path(trait_ident);
+        let path = self.mk_path(&[lang, trait_ident, Ident::new(self.span, method)]);
+        self.mk_expr(ExprKind::Call(self.mk_expr(ExprKind::Path(path)), vec![arg]))
+    }
+
+    fn mk_intrinsic_call(&self, name: Symbol, args: Vec<P<Expr>>) -> P<Expr> {
+        let f = self.mk_expr(ExprKind::Path(self.ecx.mk_intrinsic_path(self.span, name)));
+        self.mk_expr(ExprKind::Call(f, args))
+    }
+
+    fn mk_print(&self, arg: P<Expr>) -> P<Expr> {
+        self.mk_intrinsic_call(sym::print, vec![arg])
+    }
+
+    fn mk_wrapping_op(&self, op: Symbol, l: P<Expr>, r: P<Expr>) -> P<Expr> {
+        self.mk_intrinsic_call(op, vec![l, r])
+    }
+
+    fn mk_method(
+        &self,
+        name: Symbol,
+        params: Vec<Param>,
+        ret_ty: Option<P<Ty>>,
+        body: P<Expr>,
+    ) -> P<AssocItem> {
+        let sig = FnSig { constness: Constness::NotConst, params, ret_ty };
+        let kind = AssocItemKind::Fn(sig, self.mk_generics(), Some(body));
+        box Item {
+            span: self.span,
+            id: self.ecx.mk_id(),
+            vis: self.mk_private(),
+            ident: Ident::new(self.span, name),
+            attrs: vec![],
+            kind,
+        }
+    }
+
+    fn mk_self_param(&self) -> Param {
+        let ident = Ident::new(self.span, kw::LSelf);
+        Param {
+            span: self.span,
+            id: self.ecx.mk_id(),
+            pattern: self.mk_pat(PatternKind::Ident(ident, None, Mutability::Mut)),
+            ty: self.mk_ty(TyKind::ImplicitSelf),
+        }
+    }
+
+    fn mk_param(&self, name: Symbol, ty: Symbol) -> Param {
+        let ident = Ident::new(self.span, name);
+        Param {
+            span: self.span,
+            id: self.ecx.mk_id(),
+            pattern: self.mk_pat(PatternKind::Ident(ident, None, Mutability::Imm)),
+            ty: self.mk_ty_path(ty),
+        }
+    }
+
+    fn mk_if(&self, cond: P<Expr>, thn: P<Expr>, els: P<Expr>) -> P<Expr> {
+        let thn = self.mk_block(vec![self.mk_stmt(StmtKind::Expr(thn))]);
+        let els = self.mk_block(vec![self.mk_stmt(StmtKind::Expr(els))]);
+        let els = self.mk_expr(ExprKind::Block(els));
+        self.mk_expr(ExprKind::If(cond, thn, Some(els)))
+    }
+
+    fn mk_block(&self, stmts: Vec<P<Stmt>>) -> P<Block> {
+        box Block { span: self.span, id: self.ecx.mk_id(), is_unsafe: false, stmts }
+    }
+
+    fn mk_semi(&self, expr: P<Expr>) -> P<Stmt> {
+        self.mk_stmt(StmtKind::Semi(expr))
+    }
+
+    fn mk_arm(&self, pat: P<Pattern>, body: P<Expr>) -> Arm {
+        Arm { id: self.ecx.mk_id(), span: self.span, pat, body, guard: None }
+    }
+
+    fn mk_var(&self, name: Symbol) -> P<Expr> {
+        self.mk_expr(ExprKind::Path(self.mk_path(&[Ident::new(self.span, name)])))
+    }
+
+    fn mk_bool(&self, b: bool) -> P<Expr> {
+        self.mk_expr(ExprKind::Lit(Lit::Bool(b)))
+    }
+
+    fn mk_int(&self, i: i64) -> P<Expr> {
+        self.mk_expr(ExprKind::Lit(Lit::Int(i)))
+    }
+
+    fn mk_path(&self, idents: &[Ident]) -> Path {
+        let segments = idents
+            .iter()
+            .map(|&ident| PathSegment { ident, id: self.ecx.mk_id(), args: None })
+            .collect();
+        Path { id: self.ecx.mk_id(), span: self.span, segments }
+    }
+
+    fn mk_ty_path(&self, name: Symbol) -> P<Ty> {
+        let path = self.mk_path(&[Ident::new(self.span, name)]);
+        self.mk_ty(TyKind::Path(path))
+    }
+
+    fn mk_generics(&self) -> Generics {
+        Generics { span: self.span, params: vec![] }
+    }
+
+    fn mk_private(&self) -> Visibility {
+        Visibility { span: self.span, node: VisibilityKind::Private }
+    }
+
+    fn mk_expr(&self, kind: ExprKind) -> P<Expr> {
+        box Expr { span: self.span, id: self.ecx.mk_id(), kind }
+    }
+
+    fn mk_pat(&self, kind: PatternKind) -> P<Pattern> {
+        box Pattern { span: self.span, id: self.ecx.mk_id(), kind }
+    }
+
+    fn mk_stmt(&self, kind: StmtKind) -> P<Stmt> {
+        box Stmt { span: self.span, id: self.ecx.mk_id(), kind }
+    }
+
+    fn mk_ty(&self, kind: TyKind) -> P<Ty> {
+        box Ty { span: self.span, id: self.ecx.mk_id(), kind }
+    }
+}
+
+/// returns the primitive type named by `ty` (if any)
+/// this is purely syntactic as derives are expanded before name resolution
+fn prim_ty(ty: &Ty) -> Option<Symbol> {
+    match &ty.kind {
+        TyKind::Paren(ty) => prim_ty(ty),
+        TyKind::Path(Path { segments, .. }) => match segments.as_slice() {
+            [segment] if segment.args.is_none() => match segment.ident.symbol {
+                sym::int | sym::bool | sym::float | sym::char => Some(segment.ident.symbol),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// whether `ty` is a type that the derived traits are not implemented for (and so is handled
+/// structurally instead), that is, anything that is not a path to a user defined type
+fn is_builtin_ty(ty: &Ty) -> bool {
+    match &ty.kind {
+        TyKind::Paren(ty) => is_builtin_ty(ty),
+        TyKind::Path(..) => prim_ty(ty).is_some(),
+        _ => true,
+    }
+}
@@ -0,0 +1,12 @@
+use ast::Ident;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ExpandError {
+    #[error("cannot derive `{0}`\nderivable traits are `Eq`, `Clone`, `Hash` and `Debug`")]
+    UnknownDerive(Ident),
+    #[error("cannot derive `{0}` for generic type `{1}`")]
+    DeriveGeneric(Ident, Ident),
+    #[error("cannot derive `{0}` for enum `{1}` as it has struct variants")]
+    DeriveStructVariant(Ident, Ident),
+}
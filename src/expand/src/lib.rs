@@ -0,0 +1,134 @@
+//! expansion of the ast, which runs between parsing and name resolution
+//! currently this only expands `#[derive(..)]` attributes
+
+#![feature(box_syntax)]
+#![feature(crate_visibility_modifier)]
+#![feature(decl_macro)]
+
+#[cfg(test)]
+mod tests;
+
+mod derive;
+mod expand_error;
+
+use ast::*;
+use index::Idx;
+use session::Session;
+use span::{kw, sym, Span, Symbol};
+use std::cell::Cell;
+
+pub struct Expander<'a> {
+    sess: &'a Session,
+    id_counter: Cell<usize>,
+    /// whether any generated code calls the compiler defined intrinsics
+    uses_intrinsics: Cell<bool>,
+}
+
+impl<'a> Expander<'a> {
+    pub fn new(sess: &'a Session) -> Self {
+        Self { sess, id_counter: Cell::new(0), uses_intrinsics: Cell::new(false) }
+    }
+
+    pub fn expand(&self, ast: &mut Ast) {
+        self.id_counter.set(ast.next_node_id.index());
+        self.expand_derives(&mut ast.module);
+        if self.uses_intrinsics.get() {
+            let intrinsics = self.mk_intrinsics_module(ast.module.span);
+            ast.module.items.push(intrinsics);
+        }
+        ast.next_node_id = self.mk_id();
+    }
+
+    crate fn mk_id(&self) -> NodeId {
+        let id = self.id_counter.get();
+        self.id_counter.set(id + 1);
+        NodeId::new(id)
+    }
+
+    /// `{{root}}::{{intrinsics}}::<name>`
+    crate fn mk_intrinsic_path(&self, span: Span, name: Symbol) -> Path {
+        self.uses_intrinsics.set(true);
+        let segments = [kw::PathRoot, kw::Intrinsics, name]
+            .iter()
+            .map(|&symbol| {
+                PathSegment { ident: Ident::new(span, symbol), id: self.mk_id(), args: None }
+            })
+            .collect();
+        Path { id: self.mk_id(), span, segments }
+    }
+
+    /// the intrinsics that generated code may call without the user declaring them
+    /// they are declared in a module that can't be named from source, so they neither
+    /// conflict with nor are visible to the user's own declarations
+    /// mod {{intrinsics}} {
+    ///     extern "l-intrinsic" {
+    ///         pub fn print(x: int);
+    ///         pub fn float_to_bits(x: float) -> int;
+    ///         pub fn char_to_int(x: char) -> int;
+    ///         pub fn wrapping_add(x: int, y: int) -> int;
+    ///         pub fn wrapping_mul(x: int, y: int) -> int;
+    ///     }
+    /// }
+    fn mk_intrinsics_module(&self, span: Span) -> P<Item> {
+        let intrinsics = [
+            (sym::print, &[sym::int][..], None),
+            (sym::float_to_bits, &[sym::float][..], Some(sym::int)),
+            (sym::char_to_int, &[sym::char][..], Some(sym::int)),
+            (sym::wrapping_add, &[sym::int, sym::int][..], Some(sym::int)),
+            (sym::wrapping_mul, &[sym::int, sym::int][..], Some(sym::int)),
+        ];
+        let foreign_items = intrinsics
+            .iter()
+            .map(|&(name, params, ret)| {
+                let params = params
+                    .iter()
+                    .zip(&[sym::x, sym::y])
+                    .map(|(&ty, &param)| {
+                        let ident = Ident::new(span, param);
+                        let kind = PatternKind::Ident(ident, None, Mutability::Imm);
+                        Param {
+                            span,
+                            id: self.mk_id(),
+                            pattern: box Pattern { span, id: self.mk_id(), kind },
+                            ty: self.mk_prim_ty(span, ty),
+                        }
+                    })
+                    .collect();
+                let ret_ty = ret.map(|ty| self.mk_prim_ty(span, ty));
+                let sig = FnSig { constness: Constness::NotConst, params, ret_ty };
+                let generics = Generics { span, params: vec![] };
+                box Item {
+                    span,
+                    id: self.mk_id(),
+                    vis: Visibility { span, node: VisibilityKind::Public },
+                    ident: Ident::new(span, name),
+                    attrs: vec![],
+                    kind: ForeignItemKind::Fn(sig, generics),
+                }
+            })
+            .collect();
+        let private = Visibility { span, node: VisibilityKind::Private };
+        let extern_block = box Item {
+            span,
+            id: self.mk_id(),
+            vis: private,
+            ident: Ident::empty(),
+            attrs: vec![],
+            kind: ItemKind::Extern(Abi::Intrinsic, foreign_items),
+        };
+        box Item {
+            span,
+            id: self.mk_id(),
+            vis: private,
+            ident: Ident::new(span, kw::Intrinsics),
+            attrs: vec![],
+            kind: ItemKind::Mod(Module { span, items: vec![extern_block] }),
+        }
+    }
+
+    fn mk_prim_ty(&self, span: Span, ty: Symbol) -> P<Ty> {
+        let segment = PathSegment { ident: Ident::new(span, ty), id: self.mk_id(), args: None };
+        let path = Path { id: self.mk_id(), span, segments: vec![segment] };
+        box Ty { span, id: self.mk_id(), kind: TyKind::Path(path) }
+    }
+}
@@ -0,0 +1,55 @@
+use ast::ItemKind;
+
+macro expand($src:expr) {{
+    let driver = ldriver::Driver::from_src($src);
+    driver.expand().unwrap()
+}}
+
+macro expect_expand_err($src:expr) {{
+    let driver = ldriver::Driver::from_src($src);
+    driver.expand().unwrap_err()
+}}
+
+#[test]
+fn expand_derives() {
+    let ast = expand!("#[derive(Eq, Clone)] struct S { x: int }");
+    // one impl is generated for each derived trait
+    assert_eq!(ast.module.items.len(), 3);
+    assert!(matches!(ast.module.items[1].kind, ItemKind::Impl { .. }));
+    assert!(matches!(ast.module.items[2].kind, ItemKind::Impl { .. }));
+}
+
+#[test]
+fn expand_derives_in_submodule() {
+    let ast = expand!("mod m { #[derive(Eq)] struct S; }");
+    match &ast.module.items[0].kind {
+        ItemKind::Mod(module) => assert_eq!(module.items.len(), 2),
+        _ => panic!("expected module"),
+    }
+}
+
+#[test]
+fn expand_intrinsics_module_only_when_used() {
+    // `Eq` doesn't call any intrinsics
+    let ast = expand!("#[derive(Eq)] struct S { x: int }");
+    assert_eq!(ast.module.items.len(), 2);
+    // `Debug` calls `print`, so the intrinsics module is appended
+    let ast = expand!("#[derive(Debug)] struct S { x: int }");
+    assert_eq!(ast.module.items.len(), 3);
+    assert!(matches!(ast.module.items[2].kind, ItemKind::Mod(..)));
+}
+
+#[test]
+fn expand_derive_unknown_trait() {
+    expect_expand_err!("#[derive(Ord)] struct S;");
+}
+
+#[test]
+fn expand_derive_for_generic_type() {
+    expect_expand_err!("#[derive(Clone)] struct S<T> { x: T }");
+}
+
+#[test]
+fn expand_derive_for_enum_with_struct_variant() {
+    expect_expand_err!("#[derive(Eq)] enum E { A { x: int } }");
+}
@@ -1,5 +1,5 @@
 use crate::At;
-use lcore::ty::{self, Ty, TyCtx, TypeRelation, TypeResult};
+use lcore::ty::{self, Ty, TyCtx, TypeError, TypeRelation, TypeResult};
 use std::ops::Deref;
 
 pub struct Equate<'a, 'tcx> {
@@ -20,11 +20,24 @@ impl<'a, 'tcx> TypeRelation<'tcx> for Equate<'a, 'tcx> {
 
         match (&a.kind, &b.kind) {
             _ if a == b => {}
-            (&ty::Infer(ty::TyVar(a_id)), &ty::Infer(ty::TyVar(b_id))) =>
+            (&ty::Infer(ty::TyVar(a_id)), &ty::Infer(ty::TyVar(b_id)))
+            | (&ty::Infer(ty::IntVar(a_id)), &ty::Infer(ty::IntVar(b_id)))
+            | (&ty::Infer(ty::FloatVar(a_id)), &ty::Infer(ty::FloatVar(b_id))) =>
                 type_vars.equate(a_id, b_id),
+            // a general type variable can be instantiated with any type, even a numeric variable
             (&ty::Infer(ty::TyVar(vid)), _) => type_vars.instantiate(vid, b)?,
             (_, &ty::Infer(ty::TyVar(vid))) => type_vars.instantiate(vid, a)?,
+            (&ty::Infer(ty::IntVar(vid)), _) if b.is_integral() => type_vars.instantiate(vid, b)?,
+            (_, &ty::Infer(ty::IntVar(vid))) if a.is_integral() => type_vars.instantiate(vid, a)?,
+            (&ty::Infer(ty::FloatVar(vid)), _) if b.is_floating_point() =>
+                type_vars.instantiate(vid, b)?,
+            (_, &ty::Infer(ty::FloatVar(vid))) if a.is_floating_point() =>
+                type_vars.instantiate(vid, a)?,
             (ty::Error, _) | (_, ty::Error) => return Ok(self.infcx.set_ty_err()),
+            // `!` is compatible with anything, so it places no constraint on numeric variables
+            (ty::Infer(..), ty::Never) | (ty::Never, ty::Infer(..)) => {}
+            // the numeric variable is being unified with an incompatible type
+            (ty::Infer(..), _) | (_, ty::Infer(..)) => return Err(TypeError::Mismatch(a, b)),
             _ => {
                 // drop the refcell borrow so the recursive call doesn't panic
                 drop(inner);
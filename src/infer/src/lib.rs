@@ -127,6 +127,8 @@ impl<'a, 'tcx> InferCtx<'a, 'tcx> {
                 let val = type_variables.probe(vid);
                 match val {
                     TyVarValue::Known(ty) => type_variables.instantiate_if_known(ty),
+                    // numeric variables have been defaulted by this point
+                    // so any remaining unknowns are general type variables
                     TyVarValue::Unknown => {
                         let span = type_variables.storage.tyvar_data[&vid].span;
                         self.emit_ty_err(span, TypeError::InferenceFailure)
@@ -152,22 +154,51 @@ impl<'a, 'tcx> InferCtx<'a, 'tcx> {
     /// if `ty` is an inference variable, attempts to resolve it at least one level deep
     pub fn partially_resolve_ty(&self, span: Span, ty: Ty<'tcx>) -> Ty<'tcx> {
         match ty.kind {
-            TyKind::Infer(infer) =>
-                self.partially_resolve_ty(span, self.resolve_infer_var(span, infer)),
+            TyKind::Infer(infer) => match self.resolve_infer_var(span, infer) {
+                // unknown numeric variables resolve to themselves
+                resolved if resolved == ty => ty,
+                resolved => self.partially_resolve_ty(span, resolved),
+            },
             _ => ty,
         }
     }
 
+    /// if `ty` is an inference variable that has been resolved, returns its value
+    /// unlike `partially_resolve_ty`, unknown inference variables are not an error
+    pub fn shallow_resolve_ty(&self, ty: Ty<'tcx>) -> Ty<'tcx> {
+        let mut inner = self.inner.borrow_mut();
+        let mut type_vars = inner.type_variables();
+        type_vars.instantiate_if_known(ty)
+    }
+
     /// returns the concrete type for a type variable and reports an error if it is unknown
+    /// an unknown numeric variable is not an error as it is at least known to be numeric
     pub fn resolve_infer_var(&self, span: Span, infer: InferTy) -> Ty<'tcx> {
         let mut inner = self.inner.borrow_mut();
         let mut tyvars = inner.type_variables();
-        let value = match infer {
-            TyVar(tyvid) => tyvars.probe(tyvid),
-        };
-        match value {
+        match tyvars.probe(infer.vid()) {
             TyVarValue::Known(ty) => ty,
-            TyVarValue::Unknown => self.emit_ty_err(span, TypeError::InferenceFailure),
+            TyVarValue::Unknown => match infer {
+                TyVar(..) => self.emit_ty_err(span, TypeError::InferenceFailure),
+                IntVar(..) | FloatVar(..) => self.tcx.mk_ty(TyKind::Infer(infer)),
+            },
+        }
+    }
+
+    /// instantiates all unknown integer and float variables with `int` and `float` respectively
+    pub fn default_numeric_infer_vars(&self) {
+        let mut inner = self.inner.borrow_mut();
+        let mut tyvars = inner.type_variables();
+        for index in 0..tyvars.storage.tyvar_data.len() {
+            let vid = TyVid { index: index as u32 };
+            let ty = match tyvars.storage.tyvar_data[&vid].kind {
+                TyVarKind::General => continue,
+                TyVarKind::Int => self.tcx.types.int,
+                TyVarKind::Float => self.tcx.types.float,
+            };
+            if let TyVarValue::Unknown = tyvars.probe(vid) {
+                tyvars.instantiate(vid, ty).unwrap();
+            }
         }
     }
 
@@ -187,8 +218,27 @@ impl<'a, 'tcx> InferCtx<'a, 'tcx> {
 
     /// create a fresh type inference variable
     pub fn new_infer_var(&self, span: Span) -> Ty<'tcx> {
-        let vid = self.inner.borrow_mut().type_variables().new_ty_var(span);
-        self.tcx.mk_ty(TyKind::Infer(InferTy::TyVar(vid)))
+        self.new_var(span, TyVarKind::General)
+    }
+
+    /// create a fresh inference variable for an integer literal
+    pub fn new_int_var(&self, span: Span) -> Ty<'tcx> {
+        self.new_var(span, TyVarKind::Int)
+    }
+
+    /// create a fresh inference variable for a float literal
+    pub fn new_float_var(&self, span: Span) -> Ty<'tcx> {
+        self.new_var(span, TyVarKind::Float)
+    }
+
+    fn new_var(&self, span: Span, kind: TyVarKind) -> Ty<'tcx> {
+        let vid = self.inner.borrow_mut().type_variables().new_ty_var(span, kind);
+        let infer = match kind {
+            TyVarKind::General => TyVar(vid),
+            TyVarKind::Int => IntVar(vid),
+            TyVarKind::Float => FloatVar(vid),
+        };
+        self.tcx.mk_ty(TyKind::Infer(infer))
     }
 
     pub fn node_ty(&self, id: ir::Id) -> Ty<'tcx> {
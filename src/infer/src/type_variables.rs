@@ -31,9 +31,18 @@ impl<'tcx> ut::UnifyValue for TyVarValue<'tcx> {
     }
 }
 
+/// restricts the types a type variable may be instantiated with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TyVarKind {
+    General,
+    Int,
+    Float,
+}
+
 #[derive(Debug)]
 pub struct TyVarData {
     pub span: Span,
+    pub kind: TyVarKind,
 }
 
 #[derive(Default, Debug)]
@@ -76,10 +85,12 @@ impl<'a, 'tcx> TypeVariableTable<'a, 'tcx> {
     }
 
     /// if `ty` is known, return its known type, otherwise just return `t`
+    /// a type variable may be known to be another (numeric) inference variable,
+    /// so this resolves until an unknown inference variable or a non-variable type is reached
     pub fn instantiate_if_known(&mut self, ty: Ty<'tcx>) -> Ty<'tcx> {
         match ty.kind {
-            TyKind::Infer(ty::TyVar(v)) => match self.probe(v) {
-                TyVarValue::Known(t) => t,
+            TyKind::Infer(infer) => match self.probe(infer.vid()) {
+                TyVarValue::Known(t) => self.instantiate_if_known(t),
                 TyVarValue::Unknown => ty,
             },
             _ => ty,
@@ -113,10 +124,10 @@ impl<'a, 'tcx> TypeVariableTable<'a, 'tcx> {
         self.eq_relations().probe_value(vid)
     }
 
-    pub fn new_ty_var(&mut self, span: Span) -> TyVid {
+    pub fn new_ty_var(&mut self, span: Span, kind: TyVarKind) -> TyVid {
         let mut tables = self.eq_relations();
         let key = tables.new_key(TyVarValue::Unknown);
-        self.storage.tyvar_data.insert(key.vid, TyVarData { span });
+        self.storage.tyvar_data.insert(key.vid, TyVarData { span, kind });
         key.vid
     }
 }
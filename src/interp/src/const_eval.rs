@@ -0,0 +1,40 @@
+//! compile time evaluation of constants, statics and anonymous constants
+//! (array lengths and enum discriminants)
+
+use crate::interp_error::InterpError;
+use crate::machine::{Interpreter, Mode};
+use error::{ErrorReported, LResult};
+use ir::DefId;
+use lcore::queries::Queries;
+use lcore::ty::{Const, ConstKind, Instance, TyCtx};
+use rustc_hash::FxHashSet;
+use std::cell::RefCell;
+
+crate fn provide(queries: &mut Queries) {
+    *queries = Queries { eval_const, ..*queries }
+}
+
+thread_local! {
+    /// the constants that are currently being evaluated
+    /// the query system does not detect cycles so we must do it ourselves
+    static EVALUATING: RefCell<FxHashSet<DefId>> = Default::default();
+}
+
+fn eval_const<'tcx>(tcx: TyCtx<'tcx>, def_id: DefId) -> LResult<&'tcx Const<'tcx>> {
+    if !EVALUATING.with(|evaluating| evaluating.borrow_mut().insert(def_id)) {
+        tcx.sess.emit_error(tcx.defs().span(def_id), InterpError::ConstCycle);
+        return Err(ErrorReported);
+    }
+    let result = self::eval_body(tcx, def_id);
+    EVALUATING.with(|evaluating| evaluating.borrow_mut().remove(&def_id));
+    let kind = result?;
+    Ok(tcx.intern_const(Const { kind, ty: tcx.type_of(def_id) }))
+}
+
+fn eval_body<'tcx>(tcx: TyCtx<'tcx>, def_id: DefId) -> LResult<ConstKind> {
+    // constants can't call `print` and don't unwind, so nothing is ever written
+    let (mut out, mut err) = (std::io::sink(), std::io::sink());
+    Interpreter::with_mode(tcx, Mode::Const, &mut out, &mut err)
+        .run(Instance::mono_item(def_id))
+        .map_err(|report| report.emit(tcx))
+}
@@ -0,0 +1,100 @@
+use crate::memory::AllocId;
+use crate::value::Value;
+use ast::{BinOp, Ident};
+use error::ErrorReported;
+use lcore::mir::{PanicKind, SpanInfo, UnaryOp};
+use lcore::ty::{ConstKind, Instance, Ty, TyCtx};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+crate enum InterpError<'tcx> {
+    #[error("missing `main` function")]
+    MissingMain,
+    #[error("main function must have type `fn() -> int`, found {0}")]
+    InvalidMainType(Ty<'tcx>),
+    #[error("entered unreachable code")]
+    Unreachable,
+    #[error("stack overflow: exceeded the maximum call depth of {0}")]
+    StackOverflow(usize),
+    #[error("invalid operand `{1:?}` of `{0}`")]
+    InvalidUnaryOperand(UnaryOp, Value<'tcx>),
+    #[error("invalid operands `{1:?}` and `{2:?}` of `{0}`")]
+    InvalidOperands(BinOp, Value<'tcx>, Value<'tcx>),
+    #[error("failed to write output: {0}")]
+    Io(std::io::Error),
+    #[error("undefined behaviour: {0}")]
+    UndefinedBehaviour(#[from] UndefinedBehaviour),
+    #[error("cycle detected when evaluating constant")]
+    ConstCycle,
+    #[error("evaluation of constant panicked: {0}")]
+    ConstPanic(PanicKind),
+    #[error("{0} are not supported in constants")]
+    UnsupportedInConst(&'static str),
+    #[error("cannot call non-const function `{0}` in a constant")]
+    NonConstFnCall(Ident),
+    #[error("evaluation of constant exceeded the limit of {0} steps")]
+    StepLimit(usize),
+    /// the errors have already been reported
+    /// e.g. the body failed to typecheck or a trait item has no impl for the type
+    #[error("error reported")]
+    ErrorReported,
+}
+
+/// operations whose behaviour is undefined, so the program cannot continue
+/// all but the use of uninitialized memory and type punning are only detected with `--check-ub`
+#[derive(Error, Debug, PartialEq)]
+pub enum UndefinedBehaviour {
+    #[error("use of uninitialized memory")]
+    Uninit,
+    #[error("access of {size} bytes at offset {offset} is out of bounds of `{alloc:?}`")]
+    OutOfBounds { alloc: AllocId, offset: u64, size: u64 },
+    #[error("access at address {addr:#x} is not aligned to {align} bytes")]
+    Misaligned { addr: i64, align: u64 },
+    #[error("pointer to `{0:?}` is dangling as the frame it belongs to has returned")]
+    Dangling(AllocId),
+    #[error("invalid enum discriminant `{0:?}`")]
+    InvalidDiscriminant(ConstKind),
+    /// memory was accessed through a pointer to a type other than the one that was written
+    #[error("expected {0}, but the memory holds a value of a different type")]
+    TypePunned(&'static str),
+}
+
+/// an error that stopped the interpreter along with the frames that were on the stack
+crate struct InterpReport<'tcx> {
+    crate err: InterpError<'tcx>,
+    /// the function and current location of each frame, starting from the innermost
+    crate backtrace: Vec<(Instance<'tcx>, SpanInfo)>,
+}
+
+impl<'tcx> InterpReport<'tcx> {
+    crate fn emit(self, tcx: TyCtx<'tcx>) -> ErrorReported {
+        if let InterpError::ErrorReported = self.err {
+            return ErrorReported;
+        }
+        let span = self.backtrace.first().map(|(_, info)| info.span).unwrap_or_default();
+        // undefined behaviour is often caused far from where it is detected
+        let note = match self.err {
+            InterpError::UndefinedBehaviour(..) => Some(self.fmt_backtrace()),
+            _ => None,
+        };
+        let mut diag = tcx.sess.build_error(span, self.err);
+        if let Some(note) = note {
+            diag.note(&note);
+        }
+        diag.emit();
+        ErrorReported
+    }
+
+    fn fmt_backtrace(&self) -> String {
+        let frames = self
+            .backtrace
+            .iter()
+            .enumerate()
+            .map(|(i, (instance, info))| {
+                let location = span::with_source_map(|map| map.span_to_location(info.span));
+                format!("{:>4}: `{}` at {}", i, instance, location)
+            })
+            .collect::<Vec<_>>();
+        format!("backtrace:\n{}", frames.join("\n"))
+    }
+}
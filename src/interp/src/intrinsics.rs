@@ -0,0 +1,69 @@
+use crate::interp_error::InterpError;
+use crate::machine::{InterpResult, Interpreter, ReturnTo};
+use crate::value::{FnPtr, Value};
+use lcore::ty::Instance;
+use span::sym;
+use std::io::Write;
+
+impl<'a, 'tcx> Interpreter<'a, 'tcx> {
+    crate fn call_intrinsic(
+        &mut self,
+        instance: Instance<'tcx>,
+        args: Vec<Value<'tcx>>,
+        ret: ReturnTo<'tcx>,
+    ) -> InterpResult<'tcx, ()> {
+        let ident = self.tcx.defs().ident(instance.def_id);
+        let value = match ident.symbol {
+            sym::print => {
+                writeln!(self.out, "{}", args[0].to_int()?).map_err(InterpError::Io)?;
+                Value::unit()
+            }
+            // `addr<T>: fn(&T) -> int`
+            sym::addr => Value::int(self.memory.address(args[0].to_ptr()?)),
+            // `ptr_cast<T, U>: fn(*T) -> *U`
+            // the pointer is unchanged, it is only read and written at a different type
+            sym::ptr_cast => Value::Ptr(args[0].to_ptr()?.clone()),
+            // `catch_unwind: fn(fn()) -> bool`
+            // the result is written when the closure returns or is unwound
+            sym::catch_unwind => {
+                let (lvalue, target) = match ret {
+                    ReturnTo::Caller { lvalue, target } => (lvalue, target),
+                    ReturnTo::Exit | ReturnTo::CatchUnwind { .. } => unreachable!(),
+                };
+                let FnPtr { instance, env } = args.into_iter().next().unwrap().to_fn()?;
+                let args = env.map(|env| *env).into_iter().collect();
+                return self.call_instance(instance, args, ReturnTo::CatchUnwind { lvalue, target });
+            }
+            sym::checked_add | sym::checked_sub | sym::checked_mul | sym::checked_div => {
+                let (l, r) = (args[0].to_int()?, args[1].to_int()?);
+                let (i, overflowed) = match ident.symbol {
+                    sym::checked_add => l.overflowing_add(r),
+                    sym::checked_sub => l.overflowing_sub(r),
+                    sym::checked_mul => l.overflowing_mul(r),
+                    // the dividend is returned if the division is not well defined
+                    sym::checked_div if r == 0 => (l, true),
+                    sym::checked_div => l.overflowing_div(r),
+                    _ => unreachable!(),
+                };
+                Value::Aggregate(vec![Value::int(i), Value::bool(overflowed)])
+            }
+            sym::wrapping_add | sym::wrapping_sub | sym::wrapping_mul => {
+                let (l, r) = (args[0].to_int()?, args[1].to_int()?);
+                let i = match ident.symbol {
+                    sym::wrapping_add => l.wrapping_add(r),
+                    sym::wrapping_sub => l.wrapping_sub(r),
+                    sym::wrapping_mul => l.wrapping_mul(r),
+                    _ => unreachable!(),
+                };
+                Value::int(i)
+            }
+            // `float_to_bits: fn(float) -> int`
+            sym::float_to_bits => Value::int(args[0].to_float()?.to_bits() as i64),
+            // `char_to_int: fn(char) -> int`
+            // chars are scalars holding their code point
+            sym::char_to_int => Value::int(args[0].to_int()?),
+            _ => panic!("unknown intrinsic `{}`", ident),
+        };
+        Ok(self.return_value(ret, value)?)
+    }
+}
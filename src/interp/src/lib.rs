@@ -0,0 +1,64 @@
+//! an interpreter that executes mir directly
+//! this allows running programs without llvm, and provides a second implementation
+//! of the semantics of the language to check the code generator against
+//! it also evaluates constants at compile time
+
+#![feature(decl_macro)]
+#![feature(crate_visibility_modifier)]
+#![feature(box_syntax, box_patterns)]
+
+#[macro_use]
+extern crate log;
+
+#[cfg(test)]
+mod tests;
+
+mod const_eval;
+mod interp_error;
+mod intrinsics;
+mod machine;
+mod memory;
+mod value;
+
+use error::LResult;
+use interp_error::InterpError;
+use lcore::queries::Queries;
+use lcore::ty::{ConstKind, Instance, TyCtx};
+use machine::Interpreter;
+use span::Span;
+use std::io::Write;
+
+pub fn provide(queries: &mut Queries) {
+    const_eval::provide(queries);
+}
+
+/// the exit code of a program that panicked (the same as for compiled programs)
+pub const PANIC_EXIT_CODE: i32 = 101;
+
+/// runs the `main` function of the program to completion, returning its exit code
+/// anything the program prints is written to `out` and panics are reported to `err`
+/// with `--check-ub`, memory accesses are checked for undefined behaviour as they are executed
+pub fn interpret<'tcx>(
+    tcx: TyCtx<'tcx>,
+    out: &mut dyn Write,
+    err: &mut dyn Write,
+) -> LResult<i32> {
+    let main = entry_instance(tcx)?;
+    match Interpreter::new(tcx, out, err).run(main) {
+        Ok(ConstKind::Int(code)) => Ok(code as i32),
+        Ok(c) => unreachable!("`main` returned `{:?}`", c),
+        Err(report) => Err(report.emit(tcx)),
+    }
+}
+
+fn entry_instance<'tcx>(tcx: TyCtx<'tcx>) -> LResult<Instance<'tcx>> {
+    let main = match tcx.ir.entry_id {
+        Some(main) => main,
+        None => return Err(tcx.sess.emit_error(Span::default(), InterpError::MissingMain)),
+    };
+    let ty = tcx.mk_fn_ptr(tcx.fn_sig(main));
+    if ty != tcx.types.main {
+        return Err(tcx.sess.emit_error(tcx.defs().span(main), InterpError::InvalidMainType(ty)));
+    }
+    Ok(Instance::mono_item(main))
+}
@@ -0,0 +1,579 @@
+use crate::interp_error::{InterpError, InterpReport, UndefinedBehaviour};
+use crate::memory::{AllocId, AllocKind, Memory, Pointer};
+use crate::value::{FnPtr, Value};
+use crate::PANIC_EXIT_CODE;
+use index::{Idx, IndexVec};
+use lcore::mir::*;
+use lcore::ty::layout::{self, layout_of};
+use lcore::ty::*;
+use mir::{eval_bin_op, eval_unary_op};
+use std::io::Write;
+
+/// the maximum number of frames on the stack
+/// deep recursion is reported as an error rather than overflowing the stack of the interpreter
+const MAX_CALL_DEPTH: usize = 10_000;
+
+/// the maximum number of statements and terminators executed when evaluating a constant
+/// this guarantees that the evaluation of a non-terminating constant terminates
+const STEP_LIMIT: usize = 1_000_000;
+
+crate type InterpResult<'tcx, T> = Result<T, Interrupt<'tcx>>;
+
+/// the reasons execution of the current frame may stop abruptly
+crate enum Interrupt<'tcx> {
+    /// a panic, which starts unwinding the stack
+    Panic(PanicKind),
+    /// an error that stops the program entirely
+    Error(InterpError<'tcx>),
+}
+
+impl<'tcx> From<InterpError<'tcx>> for Interrupt<'tcx> {
+    fn from(err: InterpError<'tcx>) -> Self {
+        Self::Error(err)
+    }
+}
+
+impl<'tcx> From<UndefinedBehaviour> for Interrupt<'tcx> {
+    fn from(ub: UndefinedBehaviour) -> Self {
+        Self::Error(InterpError::UndefinedBehaviour(ub))
+    }
+}
+
+/// what the interpreter is executing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+crate enum Mode {
+    /// the entry point of a program
+    Program,
+    /// the body of a constant, which may only call const functions
+    /// panics are reported as errors rather than unwinding
+    Const,
+}
+
+crate struct Interpreter<'a, 'tcx> {
+    crate tcx: TyCtx<'tcx>,
+    crate memory: Memory<'tcx>,
+    /// where the program prints to
+    crate out: &'a mut dyn Write,
+    /// where panics are reported
+    crate err: &'a mut dyn Write,
+    frames: Vec<Frame<'tcx>>,
+    mode: Mode,
+    /// the number of statements and terminators executed so far
+    steps: usize,
+    /// whether integer overflow and division by zero panic (as in compiled code)
+    checked: bool,
+    /// whether memory accesses and discriminants are checked for undefined behaviour
+    check_ub: bool,
+}
+
+struct Frame<'tcx> {
+    instance: Instance<'tcx>,
+    mir: &'tcx Mir<'tcx>,
+    locals: IndexVec<VarId, AllocId>,
+    block: BlockId,
+    stmt: usize,
+    ret: ReturnTo<'tcx>,
+}
+
+impl<'tcx> Frame<'tcx> {
+    fn jump(&mut self, block: BlockId) {
+        self.block = block;
+        self.stmt = 0;
+    }
+
+    /// the source information of the statement or terminator about to be executed
+    fn info(&self) -> SpanInfo {
+        let block = &self.mir.basic_blocks[self.block];
+        match block.stmts.get(self.stmt) {
+            Some(stmt) => stmt.info,
+            None => block.terminator().info,
+        }
+    }
+}
+
+/// a location in memory along with the type of the value stored there
+struct Place<'tcx> {
+    ptr: Pointer,
+    ty: Ty<'tcx>,
+}
+
+/// what happens when a frame returns or is unwound
+crate enum ReturnTo<'tcx> {
+    /// the entry point, whose return value is the exit code of the program
+    Exit,
+    /// writes the return value to `lvalue` in the caller and continues at `target`
+    Caller { lvalue: Lvalue<'tcx>, target: BlockId },
+    /// the closure called by `catch_unwind`
+    /// the caller receives whether the closure panicked
+    CatchUnwind { lvalue: Lvalue<'tcx>, target: BlockId },
+}
+
+impl<'a, 'tcx> Interpreter<'a, 'tcx> {
+    pub fn new(tcx: TyCtx<'tcx>, out: &'a mut dyn Write, err: &'a mut dyn Write) -> Self {
+        Self::with_mode(tcx, Mode::Program, out, err)
+    }
+
+    crate fn with_mode(
+        tcx: TyCtx<'tcx>,
+        mode: Mode,
+        out: &'a mut dyn Write,
+        err: &'a mut dyn Write,
+    ) -> Self {
+        // overflow is always an error during constant evaluation
+        let checked = mode == Mode::Const || tcx.sess.opts.debug_assertions();
+        let check_ub = tcx.sess.opts.check_ub;
+        Self {
+            tcx,
+            out,
+            err,
+            mode,
+            checked,
+            check_ub,
+            steps: 0,
+            memory: Default::default(),
+            frames: Default::default(),
+        }
+    }
+
+    /// runs `entry` to completion and returns its return value
+    /// (the exit code if `entry` is the main function)
+    pub fn run(mut self, entry: Instance<'tcx>) -> Result<ConstKind, InterpReport<'tcx>> {
+        if let Err(err) = self.push_frame(entry, vec![], ReturnTo::Exit) {
+            return Err(self.report(err));
+        }
+        loop {
+            let info = self.frame().info();
+            let result = match self.step() {
+                Ok(None) => continue,
+                Ok(Some(code)) => return Ok(code),
+                Err(Interrupt::Panic(kind)) => self.panic(info, kind),
+                Err(Interrupt::Error(err)) => Err(err),
+            };
+            match result {
+                Ok(None) => continue,
+                Ok(Some(code)) => return Ok(code),
+                Err(err) => return Err(self.report(err)),
+            }
+        }
+    }
+
+    fn report(&self, err: InterpError<'tcx>) -> InterpReport<'tcx> {
+        let backtrace =
+            self.frames.iter().rev().map(|frame| (frame.instance, frame.info())).collect();
+        InterpReport { err, backtrace }
+    }
+
+    fn frame(&self) -> &Frame<'tcx> {
+        self.frames.last().unwrap()
+    }
+
+    fn frame_mut(&mut self) -> &mut Frame<'tcx> {
+        self.frames.last_mut().unwrap()
+    }
+
+    /// executes the next statement or terminator of the current frame
+    /// returns the return value of the entry point if it returned
+    fn step(&mut self) -> InterpResult<'tcx, Option<ConstKind>> {
+        if self.mode == Mode::Const {
+            self.steps += 1;
+            if self.steps > STEP_LIMIT {
+                return Err(InterpError::StepLimit(STEP_LIMIT).into());
+            }
+        }
+        let &Frame { mir, block, stmt, .. } = self.frame();
+        let block = &mir.basic_blocks[block];
+        match block.stmts.get(stmt) {
+            Some(stmt) => {
+                self.eval_stmt(stmt)?;
+                self.frame_mut().stmt += 1;
+                Ok(None)
+            }
+            None => self.eval_terminator(block.terminator()),
+        }
+    }
+
+    crate fn push_frame(
+        &mut self,
+        instance: Instance<'tcx>,
+        args: Vec<Value<'tcx>>,
+        ret: ReturnTo<'tcx>,
+    ) -> Result<(), InterpError<'tcx>> {
+        if self.frames.len() >= MAX_CALL_DEPTH {
+            return Err(InterpError::StackOverflow(MAX_CALL_DEPTH));
+        }
+        let mir = match self.mode {
+            Mode::Program => self.tcx.instance_mir(instance),
+            Mode::Const => self.const_mir(instance)?,
+        };
+        debug_assert_eq!(args.len(), mir.argc);
+        let mut args = args.into_iter();
+        let locals = mir
+            .vars
+            .iter_enumerated()
+            .map(|(var, mir_var)| {
+                let ty = mir_var.ty.subst(self.tcx, instance.substs);
+                let is_arg = 1 <= var.index() && var.index() <= mir.argc;
+                let value = if is_arg {
+                    args.next().unwrap()
+                } else if ty.is_unit() {
+                    // unit values are never explicitly written
+                    Value::unit()
+                } else {
+                    Value::Uninit
+                };
+                self.memory.allocate(AllocKind::Stack, layout_of(self.tcx, ty), value)
+            })
+            .collect();
+        trace!("push frame `{}`", instance);
+        let frame = Frame { instance, mir, locals, block: ENTRY_BLOCK, stmt: 0, ret };
+        self.frames.push(frame);
+        Ok(())
+    }
+
+    /// the mir of a constant or const function that is to be evaluated
+    /// an empty body is built if typechecking failed, whose errors have already been reported
+    fn const_mir(&self, instance: Instance<'tcx>) -> Result<&'tcx Mir<'tcx>, InterpError<'tcx>> {
+        match self.tcx.sess.try_run(|| self.tcx.mir_of(instance.def_id)) {
+            Ok(mir) if !mir.basic_blocks.is_empty() => Ok(mir),
+            _ => Err(InterpError::ErrorReported),
+        }
+    }
+
+    fn pop_frame(&mut self) -> Frame<'tcx> {
+        let frame = self.frames.pop().unwrap();
+        trace!("pop frame `{}`", frame.instance);
+        frame.locals.iter().for_each(|&alloc| self.memory.deallocate(alloc));
+        frame
+    }
+
+    /// reports the panic and unwinds the stack
+    /// returns the exit code if nothing catches the panic
+    fn panic(
+        &mut self,
+        info: SpanInfo,
+        kind: PanicKind,
+    ) -> Result<Option<ConstKind>, InterpError<'tcx>> {
+        // a panicking constant is an error at the panic, so the stack is not unwound
+        if self.mode == Mode::Const {
+            return Err(InterpError::ConstPanic(kind));
+        }
+        let location = span::with_source_map(|map| map.span_to_location(info.span));
+        writeln!(self.err, "panicked at '{}', {}", kind, location).map_err(InterpError::Io)?;
+        self.unwind()
+    }
+
+    /// pops frames until reaching a `catch_unwind`
+    fn unwind(&mut self) -> Result<Option<ConstKind>, InterpError<'tcx>> {
+        loop {
+            match self.pop_frame().ret {
+                ReturnTo::Exit => return Ok(Some(ConstKind::Int(PANIC_EXIT_CODE as i64))),
+                ReturnTo::Caller { .. } => continue,
+                ReturnTo::CatchUnwind { lvalue, target } => {
+                    self.write_lvalue(lvalue, Value::bool(true))?;
+                    self.frame_mut().jump(target);
+                    return Ok(None);
+                }
+            }
+        }
+    }
+
+    fn monomorphize<T>(&self, t: T) -> T
+    where
+        T: TypeFoldable<'tcx>,
+    {
+        t.subst(self.tcx, self.frame().instance.substs)
+    }
+
+    fn eval_stmt(&mut self, stmt: &'tcx Stmt<'tcx>) -> InterpResult<'tcx, ()> {
+        match &stmt.kind {
+            StmtKind::Assign(lvalue, rvalue) => {
+                let value = self.eval_rvalue(rvalue)?;
+                self.write_lvalue(*lvalue, value)?;
+            }
+            StmtKind::StorageLive(..) | StmtKind::Nop => {}
+        }
+        Ok(())
+    }
+
+    /// the place that `lvalue` refers to
+    fn eval_lvalue(&self, lvalue: Lvalue<'tcx>) -> Result<Place<'tcx>, UndefinedBehaviour> {
+        let frame = self.frame();
+        let ptr = Pointer::new(frame.locals[lvalue.id]);
+        let mut place = Place { ptr, ty: self.monomorphize(frame.mir.vars[lvalue.id].ty) };
+        for proj in lvalue.projs {
+            place = match proj {
+                Projection::Field(field, ty) => {
+                    let offset = layout::field_offset(self.tcx, place.ty, field.index());
+                    Place { ptr: place.ptr.field(field.index(), offset), ty: self.monomorphize(ty) }
+                }
+                Projection::Deref => {
+                    let ptr = self.read(&place)?.to_ptr()?.clone();
+                    Place { ptr, ty: place.ty.deref_ty() }
+                }
+                // values are not laid out in memory, so only the type of the place changes
+                Projection::PointerCast(ty) => Place { ty: self.monomorphize(ty), ..place },
+            }
+        }
+        Ok(place)
+    }
+
+    fn check_access(&self, place: &Place<'tcx>) -> Result<(), UndefinedBehaviour> {
+        if !self.check_ub {
+            return Ok(());
+        }
+        self.memory.check_access(&place.ptr, layout_of(self.tcx, place.ty))
+    }
+
+    fn read(&self, place: &Place<'tcx>) -> Result<Value<'tcx>, UndefinedBehaviour> {
+        self.check_access(place)?;
+        self.memory.read(&place.ptr)
+    }
+
+    fn write(&mut self, place: &Place<'tcx>, value: Value<'tcx>) -> Result<(), UndefinedBehaviour> {
+        self.check_access(place)?;
+        self.memory.write(&place.ptr, value)
+    }
+
+    fn read_lvalue(&self, lvalue: Lvalue<'tcx>) -> Result<Value<'tcx>, UndefinedBehaviour> {
+        self.read(&self.eval_lvalue(lvalue)?)
+    }
+
+    fn write_lvalue(
+        &mut self,
+        lvalue: Lvalue<'tcx>,
+        value: Value<'tcx>,
+    ) -> Result<(), UndefinedBehaviour> {
+        let place = self.eval_lvalue(lvalue)?;
+        self.write(&place, value)
+    }
+
+    fn eval_operand(&self, operand: &Operand<'tcx>) -> Result<Value<'tcx>, InterpError<'tcx>> {
+        let value = match *operand {
+            Operand::Const(c) => Value::Scalar(c.kind),
+            Operand::Lvalue(lvalue) => self.read_lvalue(lvalue)?,
+            Operand::Item(def_id, substs) => {
+                let instance = Instance::resolve(self.tcx, def_id, self.monomorphize(substs))
+                    .map_err(|_| InterpError::ErrorReported)?;
+                Value::Fn(FnPtr { instance, env: None })
+            }
+        };
+        Ok(value)
+    }
+
+    /// reads the discriminant of the enum at `place`
+    fn read_discriminant(&self, place: Place<'tcx>) -> Result<Value<'tcx>, UndefinedBehaviour> {
+        let (adt, _) = place.ty.expect_adt();
+        let discr = self.read(&Place { ptr: place.ptr.field(0, 0), ty: self.tcx.types.discr })?;
+        match discr {
+            Value::Scalar(ConstKind::Discr(d)) if adt.variants.iter().any(|v| v.discr == d) =>
+                Ok(discr),
+            _ if !self.check_ub => Ok(discr),
+            _ => Err(UndefinedBehaviour::InvalidDiscriminant(discr.to_scalar()?)),
+        }
+    }
+
+    fn eval_rvalue(&mut self, rvalue: &Rvalue<'tcx>) -> InterpResult<'tcx, Value<'tcx>> {
+        let value = match rvalue {
+            Rvalue::Operand(operand) => self.eval_operand(operand)?,
+            Rvalue::Box(operand) => {
+                let value = self.eval_operand(operand)?;
+                let ty = self.monomorphize(operand.ty(self.tcx, self.frame().mir));
+                let alloc = self.memory.allocate(AllocKind::Heap, layout_of(self.tcx, ty), value);
+                Value::Ptr(Pointer::new(alloc))
+            }
+            Rvalue::Ref(lvalue) => Value::Ptr(self.eval_lvalue(*lvalue)?.ptr),
+            &Rvalue::Unary(op, ref operand) => {
+                let value = self.eval_operand(operand)?;
+                let c = match value {
+                    Value::Scalar(c) => c,
+                    Value::Uninit => return Err(UndefinedBehaviour::Uninit.into()),
+                    _ => return Err(InterpError::InvalidUnaryOperand(op, value).into()),
+                };
+                match eval_unary_op(op, c, self.checked) {
+                    Some(Ok(c)) => Value::Scalar(c),
+                    Some(Err(kind)) => return Err(Interrupt::Panic(kind)),
+                    None => return Err(InterpError::InvalidUnaryOperand(op, value).into()),
+                }
+            }
+            &Rvalue::Bin(op, ref l, ref r) => {
+                let (l, r) = (self.eval_operand(l)?, self.eval_operand(r)?);
+                let (lc, rc) = match (&l, &r) {
+                    // discriminants are compared against integers when matching
+                    (&Value::Scalar(ConstKind::Discr(d)), &Value::Scalar(rc @ ConstKind::Int(_))) =>
+                        (ConstKind::Int(d as i64), rc),
+                    (&Value::Scalar(lc @ ConstKind::Int(_)), &Value::Scalar(ConstKind::Discr(d))) =>
+                        (lc, ConstKind::Int(d as i64)),
+                    (&Value::Scalar(lc), &Value::Scalar(rc)) => (lc, rc),
+                    (Value::Uninit, _) | (_, Value::Uninit) =>
+                        return Err(UndefinedBehaviour::Uninit.into()),
+                    _ => return Err(InterpError::InvalidOperands(op, l, r).into()),
+                };
+                match eval_bin_op(op, lc, rc, self.checked) {
+                    Some(Ok(c)) => Value::Scalar(c),
+                    Some(Err(kind)) => return Err(Interrupt::Panic(kind)),
+                    None => return Err(InterpError::InvalidOperands(op, l, r).into()),
+                }
+            }
+            Rvalue::Discriminant(lvalue) => self.read_discriminant(self.eval_lvalue(*lvalue)?)?,
+            Rvalue::Cast(cast, operand, ty) => {
+                let value = self.eval_operand(operand)?;
+                match cast {
+                    // closures and function pointers share the same representation
+                    PointerCast::ReifyFn | PointerCast::ClosureFnPtr => value,
+                    PointerCast::Unsize => {
+                        let frame = self.frame();
+                        let operand_ty = self.monomorphize(operand.ty(self.tcx, frame.mir));
+                        let trait_def_id = self.monomorphize(*ty).deref_ty().expect_dyn();
+                        let vtable = self
+                            .tcx
+                            .vtable_entries((operand_ty.deref_ty(), trait_def_id))
+                            .map_err(|_| InterpError::ErrorReported)?;
+                        Value::Dyn(value.to_ptr()?.clone(), vtable)
+                    }
+                }
+            }
+            Rvalue::Closure(_, id, env) => {
+                let instance = Instance::closure(*id, self.frame().instance.substs);
+                let env = self.eval_operand(env)?;
+                Value::Fn(FnPtr { instance, env: Some(box env) })
+            }
+            Rvalue::Adt { adt, variant_idx, fields, .. } => {
+                let fields = fields.iter().map(|f| self.eval_operand(f)).collect::<Result<_, _>>()?;
+                match adt.kind {
+                    AdtKind::Struct => Value::Aggregate(fields),
+                    AdtKind::Enum => {
+                        let discr = ConstKind::Discr(adt.variants[*variant_idx].discr);
+                        Value::Aggregate(vec![Value::Scalar(discr), Value::Aggregate(fields)])
+                    }
+                }
+            }
+        };
+        Ok(value)
+    }
+
+    fn eval_terminator(
+        &mut self,
+        terminator: &'tcx Terminator<'tcx>,
+    ) -> InterpResult<'tcx, Option<ConstKind>> {
+        match &terminator.kind {
+            &TerminatorKind::Branch(block) => self.frame_mut().jump(block),
+            &TerminatorKind::Cond(ref cond, then, els) => {
+                let cond = self.eval_operand(cond)?.to_bool()?;
+                self.frame_mut().jump(if cond { then } else { els });
+            }
+            TerminatorKind::Switch { discr, arms, default } => {
+                let discr = self.eval_operand(discr)?.to_int()?;
+                let mut target = *default;
+                for (arm, block) in arms {
+                    if self.eval_operand(arm)?.to_int()? == discr {
+                        target = *block;
+                        break;
+                    }
+                }
+                self.frame_mut().jump(target);
+            }
+            TerminatorKind::Return => {
+                let value = self.read_lvalue(Lvalue::ret())?;
+                // the return value is read while the entry point is still on the stack to report
+                // errors at it
+                if let ReturnTo::Exit = self.frame().ret {
+                    return match value {
+                        Value::Scalar(c) => Ok(Some(c)),
+                        Value::Uninit => Err(UndefinedBehaviour::Uninit.into()),
+                        // `main` returns an integer, so this must be a constant
+                        _ => Err(InterpError::UnsupportedInConst("non-scalar values").into()),
+                    };
+                }
+                match self.pop_frame().ret {
+                    ReturnTo::Exit => unreachable!(),
+                    ReturnTo::Caller { lvalue, target } => {
+                        self.write_lvalue(lvalue, value)?;
+                        self.frame_mut().jump(target);
+                    }
+                    ReturnTo::CatchUnwind { lvalue, target } => {
+                        self.write_lvalue(lvalue, Value::bool(false))?;
+                        self.frame_mut().jump(target);
+                    }
+                }
+            }
+            &TerminatorKind::Panic(kind) => return Err(Interrupt::Panic(kind)),
+            TerminatorKind::Unreachable => return Err(InterpError::Unreachable.into()),
+            // calls have no cleanup to run, so a panic unwinds straight to a `catch_unwind`
+            &TerminatorKind::Call { ref f, ref args, lvalue, target, unwind: _ } => {
+                let args = args.iter().map(|arg| self.eval_operand(arg)).collect::<Result<_, _>>()?;
+                let ret = ReturnTo::Caller { lvalue, target };
+                self.eval_call(f, args, ret)?;
+            }
+        };
+        Ok(None)
+    }
+
+    fn eval_call(
+        &mut self,
+        f: &Operand<'tcx>,
+        mut args: Vec<Value<'tcx>>,
+        ret: ReturnTo<'tcx>,
+    ) -> InterpResult<'tcx, ()> {
+        if self.mode == Mode::Const {
+            self.check_const_call(f)?;
+        }
+        let FnPtr { instance, env } = self.eval_operand(f)?.to_fn()?;
+        // the environment of a closure is passed as its first argument
+        if let Some(box env) = env {
+            args.insert(0, env);
+        }
+        self.call_instance(instance, args, ret)
+    }
+
+    /// constants may only call const functions directly
+    fn check_const_call(&self, f: &Operand<'tcx>) -> Result<(), InterpError<'tcx>> {
+        let (def_id, substs) = match *f {
+            Operand::Item(def_id, substs) => (def_id, self.monomorphize(substs)),
+            _ => return Err(InterpError::UnsupportedInConst("calls through function pointers")),
+        };
+        let instance =
+            Instance::resolve(self.tcx, def_id, substs).map_err(|_| InterpError::ErrorReported)?;
+        match instance.kind {
+            InstanceKind::Item if self.tcx.defs().is_const_fn(instance.def_id) => Ok(()),
+            _ => Err(InterpError::NonConstFnCall(self.tcx.defs().ident(def_id))),
+        }
+    }
+
+    crate fn call_instance(
+        &mut self,
+        instance: Instance<'tcx>,
+        mut args: Vec<Value<'tcx>>,
+        ret: ReturnTo<'tcx>,
+    ) -> InterpResult<'tcx, ()> {
+        match instance.kind {
+            InstanceKind::Item | InstanceKind::Closure(..) =>
+                Ok(self.push_frame(instance, args, ret)?),
+            InstanceKind::Intrinsic => self.call_intrinsic(instance, args, ret),
+            // the receiver is replaced by the data pointer of the trait object
+            InstanceKind::Virtual(idx) => match args[0].clone() {
+                Value::Dyn(ptr, vtable) => {
+                    args[0] = Value::Ptr(ptr);
+                    self.call_instance(vtable[idx], args, ret)
+                }
+                Value::Uninit => Err(UndefinedBehaviour::Uninit.into()),
+                _ => Err(UndefinedBehaviour::TypePunned("a trait object").into()),
+            },
+        }
+    }
+
+    /// completes a call that did not push a frame by writing `value` to the return location
+    crate fn return_value(
+        &mut self,
+        ret: ReturnTo<'tcx>,
+        value: Value<'tcx>,
+    ) -> Result<(), UndefinedBehaviour> {
+        match ret {
+            ReturnTo::Caller { lvalue, target } => {
+                self.write_lvalue(lvalue, value)?;
+                self.frame_mut().jump(target);
+                Ok(())
+            }
+            ReturnTo::Exit | ReturnTo::CatchUnwind { .. } => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use crate::interp_error::UndefinedBehaviour;
+use crate::value::Value;
+use index::IndexVec;
+use lcore::ty::layout::Layout;
+
+index::newtype_index! {
+    pub struct AllocId {
+        DEBUG_FORMAT = "alloc{}",
+    }
+}
+
+/// the alignment of the base address of every allocation, which suffices for any type
+const ALLOC_BASE_ALIGN: i64 = 16;
+
+/// points into an allocation
+/// the path is the sequence of field indices to follow from the value of the allocation
+/// and the offset is the position in bytes of the place it points to
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Pointer {
+    pub alloc: AllocId,
+    pub path: Vec<usize>,
+    pub offset: u64,
+}
+
+impl Pointer {
+    pub fn new(alloc: AllocId) -> Self {
+        Self { alloc, path: vec![], offset: 0 }
+    }
+
+    /// points to the field `idx` which is `offset` bytes into the current place
+    pub fn field(mut self, idx: usize, offset: u64) -> Self {
+        self.path.push(idx);
+        self.offset += offset;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllocKind {
+    /// a local variable of a stack frame, freed when the frame is popped
+    Stack,
+    /// a gc box, which is never freed
+    Heap,
+}
+
+#[derive(Debug)]
+struct Allocation<'tcx> {
+    kind: AllocKind,
+    value: Value<'tcx>,
+    live: bool,
+    /// the address of the first byte of the allocation
+    base: i64,
+    size: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct Memory<'tcx> {
+    allocs: IndexVec<AllocId, Allocation<'tcx>>,
+    /// the base address of the next allocation
+    next_base: i64,
+}
+
+impl<'tcx> Memory<'tcx> {
+    pub fn allocate(&mut self, kind: AllocKind, layout: Layout, value: Value<'tcx>) -> AllocId {
+        // leave a gap between allocations so that addresses are never shared
+        let base = ALLOC_BASE_ALIGN + self.next_base;
+        let end = base + layout.size as i64;
+        self.next_base = (end + ALLOC_BASE_ALIGN - 1) / ALLOC_BASE_ALIGN * ALLOC_BASE_ALIGN;
+        self.allocs.push(Allocation { kind, value, live: true, base, size: layout.size })
+    }
+
+    pub fn deallocate(&mut self, alloc: AllocId) {
+        let allocation = &mut self.allocs[alloc];
+        debug_assert_eq!(allocation.kind, AllocKind::Stack, "gc boxes are never freed");
+        allocation.live = false;
+        allocation.value = Value::Uninit;
+    }
+
+    /// checks that a value with the given layout can be accessed at `ptr`
+    pub fn check_access(&self, ptr: &Pointer, layout: Layout) -> Result<(), UndefinedBehaviour> {
+        let allocation = &self.allocs[ptr.alloc];
+        if !allocation.live {
+            return Err(UndefinedBehaviour::Dangling(ptr.alloc));
+        }
+        if ptr.offset + layout.size > allocation.size {
+            let (alloc, offset, size) = (ptr.alloc, ptr.offset, layout.size);
+            return Err(UndefinedBehaviour::OutOfBounds { alloc, offset, size });
+        }
+        let addr = self.address(ptr);
+        if addr % layout.align as i64 != 0 {
+            return Err(UndefinedBehaviour::Misaligned { addr, align: layout.align });
+        }
+        Ok(())
+    }
+
+    /// reads the value pointed to by `ptr`
+    /// fields of uninitialized aggregates are themselves uninitialized
+    pub fn read(&self, ptr: &Pointer) -> Result<Value<'tcx>, UndefinedBehaviour> {
+        let mut value = &self.allocs[ptr.alloc].value;
+        for &idx in &ptr.path {
+            value = match value {
+                Value::Aggregate(fields) => match fields.get(idx) {
+                    Some(field) => field,
+                    None => return Ok(Value::Uninit),
+                },
+                Value::Uninit => return Ok(Value::Uninit),
+                _ => return Err(UndefinedBehaviour::TypePunned("an aggregate")),
+            }
+        }
+        Ok(value.clone())
+    }
+
+    /// writes `value` to the location pointed to by `ptr`
+    /// writing to a field of an uninitialized aggregate initializes only that field
+    pub fn write(&mut self, ptr: &Pointer, value: Value<'tcx>) -> Result<(), UndefinedBehaviour> {
+        let mut place = &mut self.allocs[ptr.alloc].value;
+        for &idx in &ptr.path {
+            if let Value::Uninit = place {
+                *place = Value::Aggregate(vec![]);
+            }
+            place = match place {
+                Value::Aggregate(fields) => {
+                    if fields.len() <= idx {
+                        fields.resize(1 + idx, Value::Uninit);
+                    }
+                    &mut fields[idx]
+                }
+                _ => return Err(UndefinedBehaviour::TypePunned("an aggregate")),
+            }
+        }
+        *place = value;
+        Ok(())
+    }
+
+    /// the address of the location pointed to by `ptr`
+    pub fn address(&self, ptr: &Pointer) -> i64 {
+        self.allocs[ptr.alloc].base + ptr.offset as i64
+    }
+}
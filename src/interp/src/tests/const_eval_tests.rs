@@ -0,0 +1,215 @@
+use super::*;
+
+#[test]
+fn const_eval_arithmetic() {
+    let src = r#"
+    const X: int = 2 + 3 * 4;
+    fn main() -> int { X }"#;
+    assert_eq!(interp_and_jit!(src), 14);
+}
+
+#[test]
+fn const_eval_refers_to_other_consts() {
+    let src = r#"
+    const X: int = 5;
+    const Y: int = X * 2;
+    fn main() -> int { Y - X }"#;
+    assert_eq!(interp_and_jit!(src), 5);
+}
+
+#[test]
+fn const_eval_static() {
+    let src = r#"
+    static X: int = 6;
+    fn main() -> int { X + 1 }"#;
+    assert_eq!(interp_and_jit!(src), 7);
+}
+
+#[test]
+fn const_eval_calls_const_fn() {
+    let src = r#"
+    const fn fib(n: int) -> int {
+        if n < 2 { n } else { fib(n - 1) + fib(n - 2) }
+    }
+
+    const X: int = fib(10);
+    fn main() -> int { X }"#;
+    assert_eq!(interp_and_jit!(src), 55);
+}
+
+#[test]
+fn const_eval_loops_and_locals() {
+    let src = r#"
+    const fn sum(n: int) -> int {
+        let mut i = 0;
+        let mut acc = 0;
+        while i <= n {
+            acc = acc + i;
+            i = i + 1;
+        };
+        acc
+    }
+
+    const X: int = sum(10);
+    fn main() -> int { X }"#;
+    assert_eq!(interp_and_jit!(src), 55);
+}
+
+#[test]
+fn const_eval_structs() {
+    let src = r#"
+    struct P {
+        x: int,
+        y: int,
+    }
+
+    const fn sum(p: P) -> int { p.x + p.y }
+
+    const X: int = sum(P { x: 2, y: 3 });
+    fn main() -> int { X }"#;
+    assert_eq!(interp_and_jit!(src), 5);
+}
+
+#[test]
+fn const_eval_call_to_non_const_fn() {
+    let src = r#"
+    fn f() -> int { 5 }
+    const X: int = f();
+    fn main() -> int { X }"#;
+    expect_compile_error!(src);
+}
+
+#[test]
+fn const_eval_division_by_zero() {
+    let src = r#"
+    const X: int = 1 / 0;
+    fn main() -> int { 0 }"#;
+    expect_compile_error!(src);
+}
+
+#[test]
+fn const_eval_overflow() {
+    let src = r#"
+    const X: int = 9223372036854775807 + 1;
+    fn main() -> int { 0 }"#;
+    expect_compile_error!(src);
+}
+
+#[test]
+fn const_eval_neg_overflow() {
+    let src = r#"
+    const X: int = -(-9223372036854775807 - 1);
+    fn main() -> int { 0 }"#;
+    expect_compile_error!(src);
+}
+
+#[test]
+fn const_eval_explicit_panic() {
+    let src = r#"
+    const fn f() -> int {
+        panic!("boom");
+        0
+    }
+    const X: int = f();
+    fn main() -> int { 0 }"#;
+    expect_compile_error!(src);
+}
+
+#[test]
+fn const_eval_cycle() {
+    let src = r#"
+    const X: int = Y;
+    const Y: int = X;
+    fn main() -> int { 0 }"#;
+    expect_compile_error!(src);
+}
+
+#[test]
+fn const_eval_non_terminating() {
+    let src = r#"
+    const fn f() -> int {
+        loop {};
+        0
+    }
+    const X: int = f();
+    fn main() -> int { 0 }"#;
+    expect_compile_error!(src);
+}
+
+#[test]
+fn const_eval_array_len() {
+    let src = r#"
+    const N: int = 4;
+    struct S {
+        xs: [int; N * 2],
+    }
+
+    fn main() -> int { 0 }"#;
+    compile!(src);
+}
+
+#[test]
+fn const_eval_negative_array_len() {
+    let src = r#"
+    struct S {
+        xs: [int; 1 - 2],
+    }
+
+    fn main() -> int { 0 }"#;
+    expect_compile_error!(src);
+}
+
+#[test]
+fn const_eval_array_len_cannot_refer_to_locals() {
+    let src = r#"
+    fn main() -> int {
+        let n = 2;
+        let xs: [int; n];
+        0
+    }"#;
+    expect_compile_error!(src);
+}
+
+#[test]
+fn const_eval_enum_discriminants() {
+    let src = r#"
+    const BASE: int = 3;
+    enum E {
+        A = BASE,
+        B,
+        C = 10,
+    }
+
+    fn main() -> int {
+        match E::B {
+            E::A => 1,
+            E::B => 2,
+            E::C => 3,
+        }
+    }"#;
+    assert_eq!(interp_and_jit!(src), 2);
+}
+
+#[test]
+fn const_eval_duplicate_discriminant() {
+    let src = r#"
+    enum E {
+        A = 1,
+        B = 0,
+        C,
+    }
+
+    fn main() -> int { 0 }"#;
+    expect_compile_error!(src);
+}
+
+#[test]
+fn const_eval_discriminant_out_of_range() {
+    let src = r#"
+    enum E {
+        A = 40000,
+    }
+
+    fn main() -> int { 0 }"#;
+    expect_compile_error!(src);
+}
@@ -0,0 +1,320 @@
+use super::*;
+use crate::PANIC_EXIT_CODE;
+
+#[test]
+fn interp_fib() {
+    let src = r#"
+    fn main() -> int { fib(10) }
+
+    fn fib(n: int) -> int {
+        if n < 2 { n } else { fib(n - 1) + fib(n - 2) }
+    }"#;
+    assert_eq!(interp_and_jit!(src), 55);
+}
+
+#[test]
+fn interp_loop_and_assignment() {
+    let src = r#"
+    fn main() -> int {
+        let mut x = 0;
+        loop {
+            if x > 5 {
+                break
+            };
+            x = x + 1;
+        };
+        x
+    }"#;
+    assert_eq!(interp_and_jit!(src), 6);
+}
+
+#[test]
+fn interp_struct_and_tuple_fields() {
+    let src = r#"
+    struct S { x: int, t: (int, bool) }
+
+    fn main() -> int {
+        let mut s = S { x: 2, t: (3, true) };
+        s.t.0 = 7;
+        if s.t.1 { s.x + s.t.0 } else { 0 }
+    }"#;
+    assert_eq!(interp_and_jit!(src), 9);
+}
+
+#[test]
+fn interp_enum_match() {
+    let src = r#"
+    enum E {
+        A(int, int),
+        B,
+    }
+
+    fn f(e: E) -> int {
+        match e {
+            E::A(x, y) => x - y,
+            E::B => 100,
+        }
+    }
+
+    fn main() -> int { f(E::A(9, 4)) + f(E::B) }"#;
+    assert_eq!(interp_and_jit!(src), 105);
+}
+
+#[test]
+fn interp_generic_function() {
+    let src = r#"
+    fn id<T>(x: T) -> T { x }
+
+    fn main() -> int {
+        if id(true) { id(8) } else { 0 }
+    }"#;
+    assert_eq!(interp_and_jit!(src), 8);
+}
+
+#[test]
+fn interp_box_deref_assign() {
+    let src = r#"
+    fn main() -> int {
+        let ptr = box 5;
+        mutate(ptr);
+        *ptr
+    }
+
+    fn mutate(ptr: &int) {
+        *ptr = 99;
+    }"#;
+    assert_eq!(interp!(src).0, 99);
+}
+
+#[test]
+fn interp_ref_to_local() {
+    let src = r#"
+    fn main() -> int {
+        let mut x = 1;
+        let p = &x;
+        *p = 4;
+        x
+    }"#;
+    assert_eq!(interp!(src).0, 4);
+}
+
+#[test]
+fn interp_closure_mutates_captured_var() {
+    let src = r#"
+    fn main() -> int {
+        let mut x = 5;
+        let inc = fn() => { x = x + 1; };
+        inc();
+        inc();
+        x
+    }"#;
+    assert_eq!(interp!(src).0, 7);
+}
+
+#[test]
+fn interp_closure_outlives_captured_mutable_var() {
+    let src = r#"
+    fn main() -> int {
+        let counter = fn() => {
+            let mut n = 0;
+            fn() => { n = n + 1; n }
+        };
+        let c = counter();
+        c();
+        c();
+        let d = counter();
+        c() * 10 + d()
+    }"#;
+    assert_eq!(interp_checked!(src).0, 31);
+}
+
+#[test]
+fn interp_closure_with_params() {
+    let src = r#"
+    fn apply(f: fn(int) -> int, x: int) -> int { f(x) }
+
+    fn main() -> int {
+        let k = 3;
+        apply(fn(x) => x * k, 4) + apply(double, 5)
+    }
+
+    fn double(x: int) -> int { x * 2 }"#;
+    assert_eq!(interp!(src).0, 22);
+}
+
+#[test]
+fn interp_dynamic_dispatch() {
+    let src = r#"
+    struct S { x: int }
+    struct T;
+
+    trait Get {
+        fn get(&self) -> int;
+    }
+
+    impl Get for S {
+        fn get(&self) -> int { self.x }
+    }
+
+    impl Get for T {
+        fn get(&self) -> int { 1 }
+    }
+
+    fn call(x: &dyn Get) -> int {
+        Get::get(x)
+    }
+
+    fn main() -> int {
+        call(box S { x: 5 }) + call(box T)
+    }"#;
+    assert_eq!(interp!(src).0, 6);
+}
+
+#[test]
+fn interp_print() {
+    let src = r#"
+    extern "l-intrinsic" {
+        fn print(i: int);
+    }
+
+    fn main() -> int {
+        print(1);
+        print(-2);
+        0
+    }"#;
+    assert_eq!(interp!(src), (0, "1\n-2\n".to_owned()));
+}
+
+#[test]
+fn interp_addr_is_stable() {
+    let src = r#"
+    extern "l-intrinsic" {
+        fn addr<T>(t: &T) -> int;
+    }
+
+    fn main() -> int {
+        let b = box 5;
+        let c = box 5;
+        if addr(b) == addr(b) { if addr(b) != addr(c) { 1 } else { 0 } } else { 0 }
+    }"#;
+    assert_eq!(interp!(src).0, 1);
+}
+
+#[test]
+fn interp_panic_exits_with_panic_code() {
+    let src = r#"
+    extern "l-intrinsic" {
+        fn print(i: int);
+    }
+
+    fn main() -> int {
+        print(1);
+        panic!("boom");
+        print(2);
+        0
+    }"#;
+    let (code, out, panics) = interp_with_panics!(src);
+    assert_eq!((code, out.as_str()), (PANIC_EXIT_CODE, "1\n"));
+    assert!(panics.starts_with("panicked at 'boom', "), "{}", panics);
+    assert_eq!(panics.lines().count(), 1);
+}
+
+#[test]
+fn interp_overflow_panics() {
+    let src = r#"
+    fn add(x: int, y: int) -> int { x + y }
+
+    fn main() -> int { add(9223372036854775807, 1) }"#;
+    assert_eq!(interp!(src).0, PANIC_EXIT_CODE);
+}
+
+#[test]
+fn interp_catch_unwind() {
+    let src = r#"
+    extern "l-intrinsic" {
+        fn print(i: int);
+        fn catch_unwind(f: fn()) -> bool;
+    }
+
+    fn fail() {
+        print(1);
+        panic!("caught");
+    }
+
+    fn succeed() {
+        print(2);
+    }
+
+    fn main() -> int {
+        if catch_unwind(fail) { print(3) } else { print(4) };
+        if catch_unwind(succeed) { print(3) } else { print(4) };
+        if catch_unwind(fn() => fail()) { print(5) } else { print(6) };
+        0
+    }"#;
+    assert_eq!(interp!(src), (0, "1\n3\n2\n4\n1\n5\n".to_owned()));
+}
+
+#[test]
+fn interp_reports_stack_overflow() {
+    let src = r#"
+    fn f(x: int) -> int { f(x + 1) }
+
+    fn main() -> int { f(0) }"#;
+    let driver = ldriver::Driver::from_src(src);
+    let (mut out, mut err) = (std::io::sink(), std::io::sink());
+    assert!(driver.interp_with_output(&mut out, &mut err).is_err());
+}
+
+#[test]
+fn interp_ptr_cast_between_enums_of_the_same_layout() {
+    let src = r#"
+    extern "l-intrinsic" {
+        fn ptr_cast<T, U>(p: *T) -> *U;
+    }
+
+    enum Opt {
+        Some(int),
+        None,
+    }
+
+    enum Either {
+        Left(int),
+        Right(int),
+    }
+
+    fn main() -> int {
+        let opt = Opt::Some(5);
+        let p: *Either = ptr_cast(unsafe { &opt });
+        unsafe {
+            match *p {
+                Either::Left(x) => x,
+                Either::Right(_) => 0,
+            }
+        }
+    }"#;
+    assert_eq!(interp_and_jit!(src), 5);
+}
+
+// the largest variant is not the most aligned one, so the content of the enum must be laid out
+// to suit both
+#[test]
+fn interp_enum_layout_with_differently_aligned_variants() {
+    let src = r#"
+    enum E {
+        A(bool, bool, bool, bool, bool, bool, bool, bool, bool),
+        B(int),
+    }
+
+    fn f(e: E) -> int {
+        match e {
+            E::A(x, _, _, _, _, _, _, _, y) => if x { if y { 1 } else { 2 } } else { 3 },
+            E::B(i) => i,
+        }
+    }
+
+    fn main() -> int {
+        let a = E::A(true, false, false, false, false, false, false, false, true);
+        f(a) + f(E::B(6))
+    }"#;
+    assert_eq!(interp_and_jit!(src), 7);
+}
@@ -0,0 +1,71 @@
+use crate::interp_error::InterpError;
+use crate::machine::Interpreter;
+
+mod const_eval_tests;
+mod interp_tests;
+mod output_tests;
+mod ub_tests;
+
+/// interprets the program, returning its exit code and everything it printed
+macro interp($src:expr) {{
+    let (code, out, _) = interp_with_panics!($src);
+    (code, out)
+}}
+
+/// same as `interp` but also returns the panics that were reported
+macro interp_with_panics($src:expr) {{
+    let (mut out, mut err) = (vec![], vec![]);
+    let driver = ldriver::Driver::from_src($src);
+    let code = driver.interp_with_output(&mut out, &mut err).unwrap();
+    (code, String::from_utf8(out).unwrap(), String::from_utf8(err).unwrap())
+}}
+
+/// same as `interp` but checks for undefined behaviour
+macro interp_checked($src:expr) {{
+    let (mut out, mut err) = (vec![], vec![]);
+    let driver = ldriver::Driver::from_src_with_opts($src, |opts| opts.check_ub = true);
+    let code = driver.interp_with_output(&mut out, &mut err).unwrap();
+    (code, String::from_utf8(out).unwrap())
+}}
+
+/// interprets the program checking for undefined behaviour, which it is expected to have
+/// returns the undefined behaviour and the names of the functions on the stack when it occurred
+macro interp_ub($src:expr) {{
+    let driver = ldriver::Driver::from_src_with_opts($src, |opts| opts.check_ub = true);
+    driver
+        .with_tcx(|tcx| {
+            let main = crate::entry_instance(tcx).unwrap();
+            let (mut out, mut err) = (std::io::sink(), std::io::sink());
+            let report = Interpreter::new(tcx, &mut out, &mut err).run(main).unwrap_err();
+            let backtrace = report
+                .backtrace
+                .iter()
+                .map(|(instance, _)| tcx.defs().ident(instance.def_id).to_string())
+                .collect::<Vec<_>>();
+            match report.err {
+                InterpError::UndefinedBehaviour(ub) => (ub, backtrace),
+                err => panic!("expected undefined behaviour, found `{}`", err),
+            }
+        })
+        .unwrap()
+}}
+
+/// checks that the interpreter and the llvm backend agree on the exit code of the program
+macro interp_and_jit($src:expr) {{
+    let (code, _) = interp!($src);
+    assert_eq!(code, ldriver::Driver::from_src($src).llvm_jit().unwrap());
+    code
+}}
+
+/// checks that the program compiles, which evaluates all of its constants
+macro compile($src:expr) {{
+    let driver = ldriver::Driver::from_src($src);
+    driver.llvm_compile().unwrap();
+}}
+
+macro expect_compile_error($src:expr) {{
+    let driver = ldriver::Driver::from_src($src);
+    if driver.llvm_compile().is_ok() {
+        panic!("expected compilation error")
+    }
+}}
@@ -0,0 +1,23 @@
+//! runs every program of the `output` test suite, which is otherwise run through llvm
+
+use super::*;
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn interp_output_test_suite() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../tests/ltests/output");
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("l") {
+            continue;
+        }
+        let src = fs::read_to_string(&path).unwrap();
+        let expected_stdout = fs::read_to_string(path.with_extension("stdout")).unwrap();
+        let (code, stdout) = interp!(&src);
+        assert_eq!(stdout, expected_stdout, "output of `{}` differs", path.display());
+        assert_eq!(code, ldriver::Driver::from_src(&src).llvm_jit().unwrap());
+        // correct programs have no undefined behaviour
+        assert_eq!(interp_checked!(&src), (code, stdout), "`{}` has ub", path.display());
+    }
+}
@@ -0,0 +1,330 @@
+use super::*;
+use crate::interp_error::UndefinedBehaviour;
+use crate::memory::{AllocKind, Memory, Pointer};
+use crate::value::Value;
+use lcore::ty::layout::Layout;
+use lcore::ty::ConstKind;
+
+#[test]
+fn ub_read_dangling_pointer() {
+    let src = r#"
+    fn dangle() -> *int {
+        let x = 5;
+        unsafe { &x }
+    }
+
+    fn main() -> int {
+        let p = dangle();
+        unsafe { *p }
+    }"#;
+    let (ub, backtrace) = interp_ub!(src);
+    assert!(matches!(ub, UndefinedBehaviour::Dangling(..)));
+    assert_eq!(backtrace, ["main"]);
+}
+
+#[test]
+fn ub_write_dangling_pointer() {
+    let src = r#"
+    fn dangle() -> *int {
+        let x = 5;
+        unsafe { &x }
+    }
+
+    fn write(p: *int) {
+        unsafe { *p = 1; }
+    }
+
+    fn main() -> int {
+        write(dangle());
+        0
+    }"#;
+    let (ub, backtrace) = interp_ub!(src);
+    assert!(matches!(ub, UndefinedBehaviour::Dangling(..)));
+    assert_eq!(backtrace, ["write", "main"]);
+    // the write goes unnoticed without the checks
+    assert_eq!(interp!(src).0, 0);
+}
+
+#[test]
+fn ub_pointer_into_live_frame() {
+    let src = r#"
+    fn write(p: *int) {
+        unsafe { *p = 7; }
+    }
+
+    fn main() -> int {
+        let x = 5;
+        write(unsafe { &x });
+        x
+    }"#;
+    assert_eq!(interp_checked!(src).0, 7);
+}
+
+#[test]
+fn ub_branch_on_uninit() {
+    let src = r#"
+    extern "l-intrinsic" {
+        fn ptr_cast<T, U>(p: *T) -> *U;
+    }
+
+    enum Opt {
+        Some(int),
+        None,
+    }
+
+    enum Either {
+        Left(int),
+        Right(int),
+    }
+
+    fn main() -> int {
+        let opt = Opt::None;
+        // `Opt::None` has the same discriminant as `Either::Right` but no field
+        let p: *Either = ptr_cast(unsafe { &opt });
+        let x = unsafe {
+            match *p {
+                Either::Left(x) => x,
+                Either::Right(x) => x,
+            }
+        };
+        if x > 0 { 1 } else { 2 }
+    }"#;
+    let (ub, backtrace) = interp_ub!(src);
+    assert_eq!(ub, UndefinedBehaviour::Uninit);
+    assert_eq!(backtrace, ["main"]);
+}
+
+#[test]
+fn ub_print_uninit() {
+    let src = r#"
+    extern "l-intrinsic" {
+        fn print(i: int);
+        fn ptr_cast<T, U>(p: *T) -> *U;
+    }
+
+    enum Opt {
+        Some(int),
+        None,
+    }
+
+    enum Either {
+        Left(int),
+        Right(int),
+    }
+
+    fn f(p: *Either) {
+        match unsafe { *p } {
+            Either::Left(x) => print(x),
+            Either::Right(x) => print(x),
+        }
+    }
+
+    fn main() -> int {
+        let opt = Opt::None;
+        f(ptr_cast(unsafe { &opt }));
+        0
+    }"#;
+    let (ub, backtrace) = interp_ub!(src);
+    assert_eq!(ub, UndefinedBehaviour::Uninit);
+    assert_eq!(backtrace, ["f", "main"]);
+}
+
+#[test]
+fn ub_read_out_of_bounds_through_cast_pointer() {
+    let src = r#"
+    extern "l-intrinsic" {
+        fn ptr_cast<T, U>(p: *T) -> *U;
+    }
+
+    fn main() -> int {
+        let x = 5;
+        let p: *(int, int) = ptr_cast(unsafe { &x });
+        unsafe { (*p).1 }
+    }"#;
+    let (ub, backtrace) = interp_ub!(src);
+    assert!(matches!(ub, UndefinedBehaviour::OutOfBounds { offset: 8, size: 8, .. }));
+    assert_eq!(backtrace, ["main"]);
+}
+
+#[test]
+fn ub_read_misaligned_through_cast_pointer() {
+    let src = r#"
+    extern "l-intrinsic" {
+        fn ptr_cast<T, U>(p: *T) -> *U;
+    }
+
+    fn main() -> int {
+        let t = (true, false, 5);
+        // the second field is one byte into the tuple
+        let p: *int = ptr_cast(unsafe { &t.1 });
+        unsafe { *p }
+    }"#;
+    let (ub, backtrace) = interp_ub!(src);
+    assert!(matches!(ub, UndefinedBehaviour::Misaligned { align: 8, .. }));
+    assert_eq!(backtrace, ["main"]);
+}
+
+#[test]
+fn ub_invalid_discriminant_through_cast_pointer() {
+    let src = r#"
+    extern "l-intrinsic" {
+        fn ptr_cast<T, U>(p: *T) -> *U;
+    }
+
+    enum E {
+        A = 7,
+    }
+
+    enum F {
+        X,
+        Y,
+    }
+
+    fn main() -> int {
+        let e = E::A;
+        let p: *F = ptr_cast(unsafe { &e });
+        unsafe {
+            match *p {
+                F::X => 1,
+                F::Y => 2,
+            }
+        }
+    }"#;
+    let (ub, backtrace) = interp_ub!(src);
+    assert_eq!(ub, UndefinedBehaviour::InvalidDiscriminant(ConstKind::Discr(7)));
+    assert_eq!(backtrace, ["main"]);
+}
+
+#[test]
+fn ub_project_field_of_scalar_through_cast_pointer() {
+    let src = r#"
+    extern "l-intrinsic" {
+        fn ptr_cast<T, U>(p: *T) -> *U;
+    }
+
+    fn main() -> int {
+        let x = 5;
+        let p: *(int, int) = ptr_cast(unsafe { &x });
+        unsafe { (*p).0 }
+    }"#;
+    let (ub, backtrace) = interp_ub!(src);
+    assert_eq!(ub, UndefinedBehaviour::TypePunned("an aggregate"));
+    assert_eq!(backtrace, ["main"]);
+}
+
+#[test]
+fn ub_print_tuple_through_cast_pointer() {
+    let src = r#"
+    extern "l-intrinsic" {
+        fn print(i: int);
+        fn ptr_cast<T, U>(p: *T) -> *U;
+    }
+
+    fn show(p: *int) {
+        print(unsafe { *p })
+    }
+
+    fn main() -> int {
+        let t = (1, 2);
+        show(ptr_cast(unsafe { &t }));
+        0
+    }"#;
+    let (ub, backtrace) = interp_ub!(src);
+    assert_eq!(ub, UndefinedBehaviour::TypePunned("a scalar"));
+    assert_eq!(backtrace, ["show", "main"]);
+}
+
+// matching must only test the fields of a variant once the discriminant of the scrutinee matches
+// as the fields of any other variant are uninitialized
+#[test]
+fn ub_match_literal_fields_of_other_variants() {
+    let src = r#"
+    enum E {
+        A(int),
+        B(int, int),
+        C,
+    }
+
+    fn f(e: E) -> int {
+        match e {
+            E::A(1) => 1,
+            E::B(x, 2) => x,
+            E::A(x) => x,
+            E::B(x, y) => x + y,
+            E::C => 0,
+        }
+    }
+
+    fn main() -> int {
+        f(E::A(1)) + f(E::B(3, 2)) + f(E::A(5)) + f(E::B(7, 1)) + f(E::C)
+    }"#;
+    assert_eq!(interp_checked!(src).0, 17);
+    assert_eq!(interp_and_jit!(src), 17);
+}
+
+#[test]
+fn ub_checks_accept_boxes_closures_and_trait_objects() {
+    let src = r#"
+    trait Area {
+        fn area(&self) -> int;
+    }
+
+    struct Square { side: int }
+
+    impl Area for Square {
+        fn area(&self) -> int { self.side * self.side }
+    }
+
+    fn call(shape: &dyn Area) -> int {
+        Area::area(shape)
+    }
+
+    fn apply(f: fn(int) -> int, x: int) -> int { f(x) }
+
+    fn main() -> int {
+        let k = 2;
+        let b = box 1;
+        apply(fn(x) => x * k, call(box Square { side: 3 })) + *b
+    }"#;
+    assert_eq!(interp_checked!(src).0, 19);
+}
+
+#[test]
+fn ub_out_of_bounds_access() {
+    let mut memory = Memory::default();
+    let alloc = memory.allocate(AllocKind::Stack, Layout::scalar(8), Value::int(0));
+    let ptr = Pointer::new(alloc);
+    assert_eq!(memory.check_access(&ptr, Layout::scalar(8)), Ok(()));
+    assert_eq!(
+        memory.check_access(&ptr.clone().field(1, 8), Layout::scalar(8)),
+        Err(UndefinedBehaviour::OutOfBounds { alloc, offset: 8, size: 8 })
+    );
+    assert_eq!(
+        memory.check_access(&ptr, Layout::new(16, 8)),
+        Err(UndefinedBehaviour::OutOfBounds { alloc, offset: 0, size: 16 })
+    );
+}
+
+#[test]
+fn ub_misaligned_access() {
+    let mut memory = Memory::default();
+    let alloc = memory.allocate(AllocKind::Heap, Layout::new(16, 8), Value::Uninit);
+    let ptr = Pointer::new(alloc).field(1, 4);
+    assert_eq!(memory.check_access(&ptr, Layout::scalar(4)), Ok(()));
+    let addr = memory.address(&ptr);
+    assert_eq!(
+        memory.check_access(&ptr, Layout::scalar(8)),
+        Err(UndefinedBehaviour::Misaligned { addr, align: 8 })
+    );
+}
+
+#[test]
+fn ub_access_deallocated_memory() {
+    let mut memory = Memory::default();
+    let alloc = memory.allocate(AllocKind::Stack, Layout::scalar(8), Value::int(0));
+    memory.deallocate(alloc);
+    assert_eq!(
+        memory.check_access(&Pointer::new(alloc), Layout::scalar(8)),
+        Err(UndefinedBehaviour::Dangling(alloc))
+    );
+}
@@ -0,0 +1,90 @@
+use crate::interp_error::UndefinedBehaviour;
+use crate::memory::Pointer;
+use lcore::ty::{ConstKind, Instance};
+
+/// a value at runtime
+/// values are kept structured rather than as bytes, their layout is only used for addresses
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value<'tcx> {
+    /// the contents of memory that has not been written to
+    Uninit,
+    Scalar(ConstKind),
+    Ptr(Pointer),
+    Fn(FnPtr<'tcx>),
+    /// a trait object, which is a pointer to its data paired with its vtable
+    Dyn(Pointer, &'tcx [Instance<'tcx>]),
+    /// structs, tuples and enums
+    /// an enum is its discriminant followed by the fields of its variant
+    Aggregate(Vec<Value<'tcx>>),
+}
+
+/// a function pointer value
+#[derive(Debug, Clone, PartialEq)]
+pub struct FnPtr<'tcx> {
+    pub instance: Instance<'tcx>,
+    /// the environment of a closure, which is passed as its first argument
+    /// items used as values have no environment
+    pub env: Option<Box<Value<'tcx>>>,
+}
+
+impl<'tcx> Value<'tcx> {
+    pub fn unit() -> Self {
+        Self::Scalar(ConstKind::Unit)
+    }
+
+    pub fn int(i: i64) -> Self {
+        Self::Scalar(ConstKind::Int(i))
+    }
+
+    pub fn bool(b: bool) -> Self {
+        Self::Scalar(ConstKind::Bool(b))
+    }
+
+    /// the scalar this value holds, which must be initialized as it is about to be used
+    pub fn to_scalar(&self) -> Result<ConstKind, UndefinedBehaviour> {
+        match *self {
+            Self::Scalar(c) => Ok(c),
+            Self::Uninit => Err(UndefinedBehaviour::Uninit),
+            _ => Err(UndefinedBehaviour::TypePunned("a scalar")),
+        }
+    }
+
+    pub fn to_int(&self) -> Result<i64, UndefinedBehaviour> {
+        match self.to_scalar()? {
+            ConstKind::Int(i) => Ok(i),
+            // discriminants are compared against integers
+            ConstKind::Discr(d) => Ok(d as i64),
+            _ => Err(UndefinedBehaviour::TypePunned("an integer")),
+        }
+    }
+
+    pub fn to_float(&self) -> Result<f64, UndefinedBehaviour> {
+        match self.to_scalar()? {
+            ConstKind::Float(f) => Ok(f),
+            _ => Err(UndefinedBehaviour::TypePunned("a float")),
+        }
+    }
+
+    pub fn to_bool(&self) -> Result<bool, UndefinedBehaviour> {
+        match self.to_scalar()? {
+            ConstKind::Bool(b) => Ok(b),
+            _ => Err(UndefinedBehaviour::TypePunned("a boolean")),
+        }
+    }
+
+    pub fn to_ptr(&self) -> Result<&Pointer, UndefinedBehaviour> {
+        match self {
+            Self::Ptr(ptr) => Ok(ptr),
+            Self::Uninit => Err(UndefinedBehaviour::Uninit),
+            _ => Err(UndefinedBehaviour::TypePunned("a pointer")),
+        }
+    }
+
+    pub fn to_fn(self) -> Result<FnPtr<'tcx>, UndefinedBehaviour> {
+        match self {
+            Self::Fn(f) => Ok(f),
+            Self::Uninit => Err(UndefinedBehaviour::Uninit),
+            _ => Err(UndefinedBehaviour::TypePunned("a function pointer")),
+        }
+    }
+}
@@ -4,10 +4,13 @@ macro_rules! arena_types {
         $macro!($args, [
             // HIR types
             [few] ir: ir::Ir<$tcx>,
+            [] anon_consts: ir::AnonConst<$tcx>,
             [] bodies: ir::Body<$tcx>,
             [] arms: ir::Arm<$tcx>,
+            [] attrs: ast::Attribute,
             [] blocks: ir::Block<$tcx>,
             [] generic_args: ir::GenericArgs<$tcx>,
+            [] generic_bounds: ir::GenericBound<$tcx>,
             [] generics: ir::Generics<$tcx>,
             [] expr: ir::Expr<$tcx>,
             [] field: ir::Field<$tcx>,
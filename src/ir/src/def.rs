@@ -83,6 +83,8 @@ impl HasDefKind for ast::ItemKind {
     fn def_kind(&self) -> DefKind {
         match self {
             ast::ItemKind::Fn(..) => DefKind::Fn,
+            ast::ItemKind::Const(..) => DefKind::Const,
+            ast::ItemKind::Static(..) => DefKind::Static,
             ast::ItemKind::Enum(..) => DefKind::Enum,
             ast::ItemKind::Struct(..) => DefKind::Struct,
             ast::ItemKind::Extern(..) => DefKind::Extern,
@@ -139,6 +141,8 @@ pub enum DefKind {
     Use,
     Mod,
     Trait,
+    Const,
+    Static,
     /// extern block
     Extern,
     /// constructor of enum variant
@@ -171,6 +175,8 @@ impl Display for DefKind {
             DefKind::Use => write!(f, "use import"),
             DefKind::Mod => write!(f, "module"),
             DefKind::Trait => write!(f, "trait"),
+            DefKind::Const => write!(f, "constant"),
+            DefKind::Static => write!(f, "static"),
         }
     }
 }
@@ -199,6 +205,8 @@ pub enum DefNode<'ir> {
     Variant(&'ir ir::Variant<'ir>),
     TyParam(&'ir ir::TyParam<'ir>),
     Field(&'ir ir::FieldDecl<'ir>),
+    /// the length of an array type or the discriminant of a variant
+    AnonConst(&'ir ir::AnonConst<'ir>),
 }
 
 impl<'ir> DefNode<'ir> {
@@ -212,6 +220,21 @@ impl<'ir> DefNode<'ir> {
             DefNode::Variant(..) => "variant",
             DefNode::TyParam(..) => "type parameter",
             DefNode::Field(..) => "field declaration",
+            DefNode::AnonConst(..) => "constant expression",
+        }
+    }
+
+    pub fn attrs(&self) -> &'ir [ast::Attribute] {
+        match self {
+            DefNode::Item(item) => item.attrs,
+            DefNode::ImplItem(item) => item.attrs,
+            DefNode::TraitItem(item) => item.attrs,
+            DefNode::ForeignItem(..)
+            | DefNode::Ctor(..)
+            | DefNode::Variant(..)
+            | DefNode::TyParam(..)
+            | DefNode::Field(..)
+            | DefNode::AnonConst(..) => &[],
         }
     }
 }
@@ -232,6 +255,7 @@ into_def_node!(TraitItem, &'ir ir::TraitItem<'ir>);
 into_def_node!(ForeignItem, &'ir ir::ForeignItem<'ir>);
 into_def_node!(Field, &'ir ir::FieldDecl<'ir>);
 into_def_node!(TyParam, &'ir ir::TyParam<'ir>);
+into_def_node!(AnonConst, &'ir ir::AnonConst<'ir>);
 
 impl<'ir> Into<DefNode<'ir>> for &'ir ir::Variant<'ir> {
     fn into(self) -> DefNode<'ir> {
@@ -1,6 +1,6 @@
 use crate::{self as ir, QPath, Res};
 use ast::{self, Ident, UnaryOp};
-use span::Span;
+use span::{Span, Symbol};
 
 #[derive(Debug)]
 pub struct Expr<'ir> {
@@ -53,6 +53,8 @@ pub enum ExprKind<'ir> {
     Struct(&'ir QPath<'ir>, &'ir [ir::Field<'ir>]),
     /// named field access `foo.x` or `tuple.1`
     Field(&'ir ir::Expr<'ir>, Ident),
+    /// panic!("msg")
+    Panic(Symbol),
     Break,
     Continue,
     Err,
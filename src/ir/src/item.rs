@@ -1,5 +1,5 @@
 use crate::{self as ir, DefId, DefKind};
-use ast::{Abi, Ident, Visibility};
+use ast::{Abi, Attribute, Ident, Visibility};
 use span::{Span};
 
 #[derive(Debug, Clone)]
@@ -8,13 +8,14 @@ pub struct Item<'ir> {
     pub span: Span,
     pub vis: Visibility,
     pub ident: Ident,
+    pub attrs: &'ir [Attribute],
     pub kind: ir::ItemKind<'ir>,
 }
 
 impl<'ir> Item<'ir> {
     pub fn body(&self) -> &ir::Body<'ir> {
         match &self.kind {
-            ItemKind::Fn(_, _, body) => body,
+            ItemKind::Fn(_, _, body) | ItemKind::Const(_, body) | ItemKind::Static(_, body) => body,
             _ => panic!(),
         }
     }
@@ -29,7 +30,11 @@ impl<'ir> Item<'ir> {
             | ItemKind::Struct(generics, _)
             | ItemKind::TypeAlias(generics, _)
             | ItemKind::Enum(generics, _) => Some(generics),
-            ItemKind::Mod(..) | ItemKind::Use(..) | ItemKind::Extern(..) => None,
+            ItemKind::Mod(..)
+            | ItemKind::Use(..)
+            | ItemKind::Extern(..)
+            | ItemKind::Const(..)
+            | ItemKind::Static(..) => None,
         }
     }
 }
@@ -38,6 +43,9 @@ impl<'ir> Item<'ir> {
 pub enum ItemKind<'ir> {
     Fn(&'ir ir::FnSig<'ir>, &'ir ir::Generics<'ir>, &'ir ir::Body<'ir>),
     Use(&'ir ir::Path<'ir>),
+    /// the body of a constant has no parameters
+    Const(&'ir ir::Ty<'ir>, &'ir ir::Body<'ir>),
+    Static(&'ir ir::Ty<'ir>, &'ir ir::Body<'ir>),
     TypeAlias(&'ir ir::Generics<'ir>, &'ir ir::Ty<'ir>),
     Struct(&'ir ir::Generics<'ir>, ir::VariantKind<'ir>),
     Enum(&'ir ir::Generics<'ir>, &'ir [ir::Variant<'ir>]),
@@ -79,9 +87,11 @@ pub enum ForeignItemKind<'ir> {
 #[derive(Debug)]
 pub struct TraitItem<'ir> {
     pub id: ir::Id,
+    pub trait_def_id: DefId,
     pub ident: Ident,
     pub span: Span,
     pub vis: Visibility,
+    pub attrs: &'ir [Attribute],
     pub generics: &'ir ir::Generics<'ir>,
     pub kind: TraitItemKind<'ir>,
 }
@@ -93,6 +103,7 @@ pub struct ImplItem<'ir> {
     pub ident: Ident,
     pub span: Span,
     pub vis: Visibility,
+    pub attrs: &'ir [Attribute],
     pub generics: &'ir ir::Generics<'ir>,
     pub kind: ImplItemKind<'ir>,
 }
@@ -115,6 +126,21 @@ pub enum TraitItemKind<'ir> {
     Fn(&'ir ir::FnSig<'ir>, Option<&'ir ir::Body<'ir>>),
 }
 
+impl<'ir> TraitItemKind<'ir> {
+    pub fn def_kind(&self) -> DefKind {
+        match self {
+            TraitItemKind::Fn(..) => DefKind::AssocFn,
+        }
+    }
+
+    /// whether the trait provides a default implementation of this item
+    pub fn has_default(&self) -> bool {
+        match self {
+            TraitItemKind::Fn(_, body) => body.is_some(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TraitItemRef {
     pub id: ir::TraitItemId,
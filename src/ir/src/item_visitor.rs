@@ -51,6 +51,8 @@ where
             | ir::ItemKind::Enum(..)
             | ir::ItemKind::Mod(..)
             | ir::ItemKind::Struct(..)
+            | ir::ItemKind::Const(..)
+            | ir::ItemKind::Static(..)
             | ir::ItemKind::Trait { .. }
             | ir::ItemKind::Impl { .. } => ir::walk_item(self, item),
         }
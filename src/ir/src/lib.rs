@@ -139,7 +139,7 @@ impl Display for DefId {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Id {
     /// id of the immediately enclosing item
     pub def: DefId,
@@ -174,6 +174,8 @@ pub struct Variant<'ir> {
     /// index of the variant in the enum
     pub idx: VariantIdx,
     pub kind: VariantKind<'ir>,
+    /// the explicitly specified discriminant
+    pub discr: Option<&'ir ir::AnonConst<'ir>>,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -253,6 +255,16 @@ pub struct TyParam<'ir> {
     pub ident: Ident,
     pub index: ParamIdx,
     pub default: Option<&'ir ir::Ty<'ir>>,
+    pub bounds: &'ir [ir::GenericBound<'ir>],
+}
+
+/// `Trait` or `Fn(A, B) -> C`
+#[derive(Debug)]
+pub struct GenericBound<'ir> {
+    pub span: Span,
+    pub path: &'ir ir::Path<'ir>,
+    /// the parenthesized signature of a `Fn*` bound represented as a function type
+    pub sig: Option<&'ir ir::Ty<'ir>>,
 }
 
 #[derive(Debug)]
@@ -267,6 +279,15 @@ impl<'ir> Body<'ir> {
     }
 }
 
+/// a constant expression that is not an item of its own
+/// its body is typechecked and evaluated as if it were a constant item of type `int`
+#[derive(Debug)]
+pub struct AnonConst<'ir> {
+    pub id: ir::Id,
+    pub span: Span,
+    pub body: &'ir ir::Body<'ir>,
+}
+
 #[derive(Debug)]
 pub enum MatchSource {
     Match,
@@ -284,6 +305,7 @@ pub struct Arm<'ir> {
 
 #[derive(Debug)]
 pub struct FnSig<'ir> {
+    pub constness: ast::Constness,
     // rest of parameter information is in `Body`
     pub inputs: &'ir [ir::Ty<'ir>],
     pub output: Option<&'ir ir::Ty<'ir>>,
@@ -22,7 +22,10 @@ pub enum TyKind<'ir> {
     Box(&'ir ir::Ty<'ir>),
     Fn(&'ir [ir::Ty<'ir>], Option<&'ir ir::Ty<'ir>>),
     Path(&'ir QPath<'ir>),
-    Array(&'ir ir::Ty<'ir>),
+    /// dyn Trait
+    Dyn(&'ir ir::Path<'ir>),
+    /// [<ty>; <const>]
+    Array(&'ir ir::Ty<'ir>, &'ir ir::AnonConst<'ir>),
     Tuple(&'ir [ir::Ty<'ir>]),
     Ptr(&'ir ir::Ty<'ir>),
     Infer,
@@ -34,6 +37,7 @@ impl<'ir> Display for Ty<'ir> {
         match self.kind {
             ir::TyKind::Box(ty) => write!(f, "box {}", ty),
             ir::TyKind::Path(qpath) => write!(f, "{}", qpath),
+            ir::TyKind::Dyn(path) => write!(f, "dyn {}", path),
             _ => todo!(),
         }
     }
@@ -33,6 +33,10 @@ pub trait Visitor<'ir>: Sized {
         walk_ty_param(self, param)
     }
 
+    fn visit_generic_bound(&mut self, bound: &'ir ir::GenericBound<'ir>) {
+        walk_generic_bound(self, bound)
+    }
+
     fn visit_vis(&mut self, _vis: Visibility) {
     }
 
@@ -110,6 +114,10 @@ pub trait Visitor<'ir>: Sized {
     fn visit_trait_item(&mut self, trait_item: &'ir ir::TraitItem<'ir>) {
         walk_trait_item(self, trait_item);
     }
+
+    fn visit_anon_const(&mut self, anon_const: &'ir ir::AnonConst<'ir>) {
+        walk_anon_const(self, anon_const);
+    }
 }
 
 pub fn walk_ir<'ir>(v: &mut impl Visitor<'ir>, ir: &'ir ir::Ir<'ir>) {
@@ -158,6 +166,12 @@ pub fn walk_variant<'ir>(v: &mut impl Visitor<'ir>, variant: &'ir ir::Variant<'i
     v.visit_id(variant.id);
     v.visit_ident(variant.ident);
     v.visit_variant_kind(&variant.kind);
+    variant.discr.iter().for_each(|discr| v.visit_anon_const(discr));
+}
+
+pub fn walk_anon_const<'ir>(v: &mut impl Visitor<'ir>, anon_const: &'ir ir::AnonConst<'ir>) {
+    v.visit_id(anon_const.id);
+    v.visit_body(anon_const.body);
 }
 
 pub fn walk_field_decl<'ir>(v: &mut impl Visitor<'ir>, decl: &'ir ir::FieldDecl<'ir>) {
@@ -178,6 +192,12 @@ pub fn walk_variant_kind<'ir>(v: &mut impl Visitor<'ir>, kind: &'ir ir::VariantK
 pub fn walk_ty_param<'ir>(v: &mut impl Visitor<'ir>, param: &'ir ir::TyParam<'ir>) {
     v.visit_id(param.id);
     param.default.iter().for_each(|ty| v.visit_ty(ty));
+    param.bounds.iter().for_each(|bound| v.visit_generic_bound(bound));
+}
+
+pub fn walk_generic_bound<'ir>(v: &mut impl Visitor<'ir>, bound: &'ir ir::GenericBound<'ir>) {
+    v.visit_path(bound.path);
+    bound.sig.iter().for_each(|ty| v.visit_ty(ty));
 }
 
 pub fn walk_generics<'ir>(v: &mut impl Visitor<'ir>, generics: &'ir ir::Generics<'ir>) {
@@ -210,6 +230,10 @@ pub fn walk_item<'ir, V: Visitor<'ir>>(v: &mut V, item: &'ir ir::Item<'ir>) {
             v.visit_body(body);
         }
         ir::ItemKind::Use(path) => v.visit_path(path),
+        ir::ItemKind::Const(ty, body) | ir::ItemKind::Static(ty, body) => {
+            v.visit_ty(ty);
+            v.visit_body(body);
+        }
         ir::ItemKind::TypeAlias(generics, ty) => {
             v.visit_generics(generics);
             v.visit_ty(ty);
@@ -293,7 +317,10 @@ pub fn walk_expr<'ir, V: Visitor<'ir>>(v: &mut V, expr: &'ir ir::Expr<'ir>) {
             v.visit_expr(base);
             v.visit_ident(*ident);
         }
-        ir::ExprKind::Err | ir::ExprKind::Break | ir::ExprKind::Continue => {}
+        ir::ExprKind::Err
+        | ir::ExprKind::Panic(..)
+        | ir::ExprKind::Break
+        | ir::ExprKind::Continue => {}
     }
 }
 
@@ -331,10 +358,14 @@ pub fn walk_ty<'ir, V: Visitor<'ir>>(v: &mut V, ty: &'ir ir::Ty<'ir>) {
             if let Some(ty) = ret {
                 v.visit_ty(ty);
             }
+        }
+        ir::TyKind::Box(ty) | ir::TyKind::Ptr(ty) => v.visit_ty(ty),
+        ir::TyKind::Array(ty, len) => {
             v.visit_ty(ty);
+            v.visit_anon_const(len);
         }
-        ir::TyKind::Box(ty) | ir::TyKind::Ptr(ty) | ir::TyKind::Array(ty) => v.visit_ty(ty),
         ir::TyKind::Path(qpath) => v.visit_qpath(qpath),
+        ir::TyKind::Dyn(path) => v.visit_path(path),
         ir::TyKind::Tuple(tys) => tys.iter().for_each(|ty| v.visit_ty(ty)),
         ir::TyKind::Err | ir::TyKind::Infer => {}
     }
@@ -6,11 +6,13 @@ macro_rules! arena_types {
     ($macro:path, $args:tt, $tcx:lifetime) => (
         $macro!($args, [
             [] adt_def: AdtTy,
+            [] bounds: Bound<$tcx>,
             [] consts: Const<$tcx>,
             [] fields: FieldTy,
             [] generics: Generics<$tcx>,
             [] inherent_impls: InherentImpls,
             [] instances: Instances<$tcx>,
+            [] lang_items: LangItems,
             [] mir: Mir<$tcx>,
             [] trait_impls: TraitImpls,
             [] typeck_tables: TypeckTables<$tcx>,
@@ -2,7 +2,7 @@
 use crate::ty::TyCtx;
 use ast::Ident;
 use ir::{DefId, DefKind, DefNode};
-use span::Span;
+use span::{kw, Span};
 
 impl<'tcx> TyCtx<'tcx> {
     pub fn impl_item(self, id: ir::ImplItemId) -> &'tcx ir::ImplItem<'tcx> {
@@ -37,13 +37,16 @@ impl<'tcx> DefMap<'tcx> {
             DefNode::Ctor(variant) | DefNode::Variant(variant) => variant.span,
             DefNode::TyParam(param) => param.span,
             DefNode::Field(field) => field.span,
+            DefNode::AnonConst(anon_const) => anon_const.span,
         }
     }
 
     pub fn body(&self, def_id: DefId) -> &'tcx ir::Body<'tcx> {
         match self.get(def_id) {
             DefNode::Item(item) => match item.kind {
-                ir::ItemKind::Fn(.., body) => body,
+                ir::ItemKind::Fn(.., body)
+                | ir::ItemKind::Const(_, body)
+                | ir::ItemKind::Static(_, body) => body,
                 _ => panic!(),
             },
             DefNode::ImplItem(impl_item) => match impl_item.kind {
@@ -52,6 +55,7 @@ impl<'tcx> DefMap<'tcx> {
             DefNode::TraitItem(trait_item) => match trait_item.kind {
                 ir::TraitItemKind::Fn(_, body) => body.unwrap(),
             },
+            DefNode::AnonConst(anon_const) => anon_const.body,
             DefNode::ForeignItem(..)
             | DefNode::Ctor(..)
             | DefNode::Variant(..)
@@ -70,8 +74,11 @@ impl<'tcx> DefMap<'tcx> {
                 | ir::ItemKind::Struct(generics, _)
                 | ir::ItemKind::Trait { generics, .. }
                 | ir::ItemKind::Impl { generics, .. } => generics,
-                ir::ItemKind::Mod(..) | ir::ItemKind::Use(..) | ir::ItemKind::Extern(..) =>
-                    panic!(),
+                ir::ItemKind::Mod(..)
+                | ir::ItemKind::Use(..)
+                | ir::ItemKind::Extern(..)
+                | ir::ItemKind::Const(..)
+                | ir::ItemKind::Static(..) => panic!(),
             },
             DefNode::ImplItem(impl_item) => impl_item.generics,
             DefNode::TraitItem(trait_item) => trait_item.generics,
@@ -80,7 +87,7 @@ impl<'tcx> DefMap<'tcx> {
             },
             // these inherit the generics of their parents
             DefNode::Ctor(variant) | DefNode::Variant(variant) => self.generics(variant.adt_def_id),
-            DefNode::Field(..) | DefNode::TyParam(..) =>
+            DefNode::Field(..) | DefNode::TyParam(..) | DefNode::AnonConst(..) =>
                 panic!("def node has no generics: {}", node.descr()),
         }
     }
@@ -97,6 +104,36 @@ impl<'tcx> DefMap<'tcx> {
                 adt_ident.concat_as_path(variant.ident)
             }
             DefNode::Field(field) => field.ident,
+            DefNode::AnonConst(anon_const) => Ident::new(anon_const.span, kw::Empty),
         }
     }
+
+    /// whether the node is a constant or static (including anonymous constants)
+    /// these are never generic
+    pub fn is_const(&self, def_id: DefId) -> bool {
+        match self.get(def_id) {
+            DefNode::Item(item) =>
+                matches!(item.kind, ir::ItemKind::Const(..) | ir::ItemKind::Static(..)),
+            DefNode::AnonConst(..) => true,
+            _ => false,
+        }
+    }
+
+    /// whether the function may be called during constant evaluation
+    pub fn is_const_fn(&self, def_id: DefId) -> bool {
+        let sig = match self.get(def_id) {
+            DefNode::Item(item) => match item.kind {
+                ir::ItemKind::Fn(sig, ..) => sig,
+                _ => return false,
+            },
+            DefNode::ImplItem(impl_item) => match impl_item.kind {
+                ir::ImplItemKind::Fn(sig, _) => sig,
+            },
+            DefNode::TraitItem(trait_item) => match trait_item.kind {
+                ir::TraitItemKind::Fn(sig, _) => sig,
+            },
+            _ => return false,
+        };
+        sig.constness == ast::Constness::Const
+    }
 }
@@ -57,7 +57,14 @@ impl<'a, 'tcx> Formatter<'a, 'tcx> {
             writeln!(self.writer, "basic_block {:?}:", i)?;
             block.mir_fmt(self)?;
         }
-        writeln!(self)
+        writeln!(self)?;
+
+        let mut closures = self.mir.closures.iter().collect::<Vec<_>>();
+        closures.sort_by_key(|&(id, _)| id);
+        for (id, closure) in closures {
+            write!(self, "closure<{}> {}", id, closure)?;
+        }
+        Ok(())
     }
 
     fn fmt_iter<'i, I, T>(&mut self, iter: &'i I) -> fmt::Result
@@ -121,6 +128,10 @@ impl<'tcx> MirFmt<'tcx> for mir::StmtKind<'tcx> {
         f.indent()?;
         match self {
             mir::StmtKind::Assign(lvalue, rvalue) => f.fmt_assign(lvalue, rvalue),
+            mir::StmtKind::StorageLive(var) => {
+                write!(f, "storage_live ")?;
+                var.mir_fmt(f)
+            }
             mir::StmtKind::Nop => write!(f, "nop"),
         }?;
         writeln!(f)
@@ -201,11 +212,19 @@ impl<'tcx> MirFmt<'tcx> for mir::Rvalue<'tcx> {
                 write!(f, "{}", op)?;
                 operand.mir_fmt(f)
             }
-            mir::Rvalue::Closure(..) => write!(f, "<closure>"),
+            mir::Rvalue::Closure(_, id, env) => {
+                write!(f, "closure<{}>(", id)?;
+                env.mir_fmt(f)?;
+                write!(f, ")")
+            }
             mir::Rvalue::Discriminant(lvalue) => {
                 write!(f, "discr ")?;
                 lvalue.mir_fmt(f)
             }
+            mir::Rvalue::Cast(_, operand, ty) => {
+                operand.mir_fmt(f)?;
+                write!(f, " as {}", ty)
+            }
         }
     }
 }
@@ -234,14 +253,17 @@ impl<'tcx> MirFmt<'tcx> for mir::TerminatorKind<'tcx> {
             mir::TerminatorKind::Branch(block) => writeln!(fmt, "branch {:?}", block),
             mir::TerminatorKind::Return => writeln!(fmt, "return"),
             mir::TerminatorKind::Unreachable => writeln!(fmt, "unreachable"),
-            mir::TerminatorKind::Call { f, args, lvalue, target, unwind: _ } => {
+            mir::TerminatorKind::Call { f, args, lvalue, target, unwind } => {
                 lvalue.mir_fmt(fmt)?;
                 let ty = fmt.mir.vars[lvalue.id].ty;
                 write!(fmt, ":{} ← call ", ty)?;
                 f.mir_fmt(fmt)?;
                 write!(fmt, "(")?;
                 fmt.fmt_iter(args)?;
-                writeln!(fmt, ") -> [{:?}]", target)?;
+                match unwind {
+                    Some(unwind) => writeln!(fmt, ") -> [{:?}, unwind: {:?}]", target, unwind)?,
+                    None => writeln!(fmt, ") -> [{:?}]", target)?,
+                }
                 writeln!(fmt)
             }
             mir::TerminatorKind::Switch { discr, arms, default } => {
@@ -267,7 +289,7 @@ impl<'tcx> MirFmt<'tcx> for mir::TerminatorKind<'tcx> {
                 write!(fmt, " then {:?}", a)?;
                 writeln!(fmt, " else {:?}", b)
             }
-            mir::TerminatorKind::Abort => writeln!(fmt, "abort"),
+            mir::TerminatorKind::Panic(kind) => writeln!(fmt, "panic({:?})", kind.to_string()),
         }?;
         writeln!(fmt)
     }
@@ -9,11 +9,12 @@ pub use traverse::{postorder, preorder, rpo};
 pub use visit::MirVisitor;
 
 use crate::mir;
-use crate::ty::{AdtTy, Const, List, Projection, SubstsRef, Ty, TyCtx};
-use ast::Mutability;
+use crate::ty::{AdtTy, Const, List, PointerCast, Projection, SubstsRef, Ty, TyCtx};
+use ast::{BinOp, Ident, Mutability};
 use index::{Idx, IndexVec};
 use ir::{DefId, VariantIdx};
-use span::Span;
+use rustc_hash::FxHashMap;
+use span::{Span, Symbol};
 use std::ops::{Deref, DerefMut};
 
 index::newtype_index! {
@@ -37,6 +38,9 @@ pub struct Mir<'tcx> {
     pub basic_blocks: IndexVec<BlockId, BasicBlock<'tcx>>,
     pub vars: IndexVec<VarId, Var<'tcx>>,
     pub argc: usize,
+    /// the lifted bodies of all closures defined within this body (including nested closures)
+    /// the first argument of each lifted body is a pointer to the environment of the closure
+    pub closures: FxHashMap<ir::Id, &'tcx Mir<'tcx>>,
 }
 
 impl<'tcx> Mir<'tcx> {
@@ -53,6 +57,17 @@ impl<'tcx> Mir<'tcx> {
         // so 1..1 + argc are the parameters
         (1 + self.argc..self.vars.len()).map(VarId::new)
     }
+
+    /// the predecessors of each block in the control flow graph
+    pub fn predecessors(&self) -> IndexVec<BlockId, Vec<BlockId>> {
+        let mut predecessors = IndexVec::from_elem_n(vec![], self.len());
+        for (id, block) in self.basic_blocks.iter_enumerated() {
+            for successor in block.terminator().successors() {
+                predecessors[successor].push(id);
+            }
+        }
+        predecessors
+    }
 }
 
 impl<'tcx> std::fmt::Display for Mir<'tcx> {
@@ -87,6 +102,9 @@ pub struct Stmt<'tcx> {
 #[derive(Clone, Debug, PartialEq)]
 pub enum StmtKind<'tcx> {
     Assign(Lvalue<'tcx>, Rvalue<'tcx>),
+    /// marks the point where the binding of a local comes into scope
+    /// the local is uninitialized until it is next assigned to
+    StorageLive(VarId),
     Nop,
 }
 
@@ -131,6 +149,8 @@ pub struct Var<'tcx> {
     pub kind: VarKind,
     pub ty: Ty<'tcx>,
     pub mtbl: Mutability,
+    /// the name of the binding that declared this variable (if any)
+    pub ident: Option<Ident>,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -189,8 +209,12 @@ pub enum Rvalue<'tcx> {
     Ref(Lvalue<'tcx>),
     /// reads the discriminant of an enum
     Discriminant(Lvalue<'tcx>),
-    /// TODO temporary representation, incomplete
-    Closure(Ty<'tcx>),
+    /// a pointer cast such as `&T` to `&dyn Trait`
+    /// the type is the type after the cast
+    Cast(PointerCast, Operand<'tcx>, Ty<'tcx>),
+    /// pairs the lifted body of the closure with its environment
+    /// the operand is a pointer to the environment (a boxed tuple of the captured upvars)
+    Closure(Ty<'tcx>, ir::Id, Operand<'tcx>),
     Adt {
         adt: &'tcx AdtTy,
         variant_idx: VariantIdx,
@@ -222,7 +246,9 @@ impl<'tcx> Terminator<'tcx> {
                 Some(target).into_iter().chain(unwind).collect(),
             TerminatorKind::Switch { ref arms, default, .. } =>
                 arms.iter().map(|(_, b)| *b).chain(Some(default)).collect(),
-            TerminatorKind::Abort | TerminatorKind::Return | TerminatorKind::Unreachable => vec![],
+            TerminatorKind::Panic(..)
+            | TerminatorKind::Return
+            | TerminatorKind::Unreachable => vec![],
         }
     }
 
@@ -234,7 +260,9 @@ impl<'tcx> Terminator<'tcx> {
             TerminatorKind::Call { target, unwind: Some(unwind), .. } => vec![target, unwind],
             TerminatorKind::Switch { arms, default, .. } =>
                 arms.iter_mut().map(|(_, b)| b).chain(Some(default)).collect(),
-            TerminatorKind::Abort | TerminatorKind::Return | TerminatorKind::Unreachable => vec![],
+            TerminatorKind::Panic(..)
+            | TerminatorKind::Return
+            | TerminatorKind::Unreachable => vec![],
         }
     }
 }
@@ -253,7 +281,8 @@ pub enum TerminatorKind<'tcx> {
     Cond(Operand<'tcx>, BlockId, BlockId),
     Return,
     Unreachable,
-    Abort,
+    /// reports the panic (and its location) and starts unwinding
+    Panic(PanicKind),
     Call {
         f: Operand<'tcx>,
         args: Vec<Operand<'tcx>>,
@@ -261,6 +290,7 @@ pub enum TerminatorKind<'tcx> {
         lvalue: Lvalue<'tcx>,
         /// the block to branch to after the call (if no unwind)
         target: BlockId,
+        /// the cleanup block to branch to if the call panics
         unwind: Option<BlockId>,
     },
     /// if `discr` evaluates to the `Operand`, then the respective block is executed
@@ -271,6 +301,42 @@ pub enum TerminatorKind<'tcx> {
     },
 }
 
+/// the reason for a panic
+/// explicit panics and compiler inserted checks all go through the same runtime entry point
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PanicKind {
+    /// `panic!("msg")`
+    Explicit(Symbol),
+    /// no arm of a match expression matched the scrutinee
+    Unreachable,
+    /// the result of an integer operation doesn't fit in an `int`
+    Overflow(BinOp),
+    /// the negation of the minimum integer doesn't fit in an `int`
+    NegOverflow,
+    DivisionByZero,
+}
+
+impl std::fmt::Display for PanicKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PanicKind::Explicit(msg) => write!(f, "{}", msg),
+            PanicKind::Unreachable => write!(f, "entered unreachable code"),
+            PanicKind::Overflow(op) => {
+                let op = match op {
+                    BinOp::Add => "add",
+                    BinOp::Sub => "subtract",
+                    BinOp::Mul => "multiply",
+                    BinOp::Div => "divide",
+                    _ => unreachable!("`{}` cannot overflow", op),
+                };
+                write!(f, "attempt to {} with overflow", op)
+            }
+            PanicKind::NegOverflow => write!(f, "attempt to negate with overflow"),
+            PanicKind::DivisionByZero => write!(f, "attempt to divide by zero"),
+        }
+    }
+}
+
 // instead of these could maybe just implement deref from mir -> basic blocks
 impl<'tcx> Deref for Mir<'tcx> {
     type Target = IndexVec<BlockId, BasicBlock<'tcx>>;
@@ -32,7 +32,7 @@ pub trait MirVisitor<'tcx> {
     fn walk_stmt(&mut self, stmt: &Stmt<'tcx>) {
         match &stmt.kind {
             StmtKind::Assign(lvalue, rvalue) => self.visit_assignment(stmt.info, lvalue, rvalue),
-            StmtKind::Nop => {}
+            StmtKind::StorageLive(_) | StmtKind::Nop => {}
         }
     }
 
@@ -58,14 +58,16 @@ pub trait MirVisitor<'tcx> {
 
     fn walk_rvalue(&mut self, info: SpanInfo, rvalue: &Rvalue<'tcx>) {
         match rvalue {
-            Rvalue::Box(operand) | Rvalue::Operand(operand) | Rvalue::Unary(_, operand) =>
-                self.visit_operand(info, operand),
+            Rvalue::Box(operand)
+            | Rvalue::Operand(operand)
+            | Rvalue::Unary(_, operand)
+            | Rvalue::Cast(_, operand, _)
+            | Rvalue::Closure(_, _, operand) => self.visit_operand(info, operand),
             Rvalue::Bin(_, l, r) => {
                 self.visit_operand(info, l);
                 self.visit_operand(info, r);
             }
             Rvalue::Ref(lvalue) | Rvalue::Discriminant(lvalue) => self.visit_lvalue(info, lvalue),
-            Rvalue::Closure(..) => {}
             Rvalue::Adt { adt, variant_idx, substs, fields } => {
                 let (..) = (adt, variant_idx, substs);
                 fields.iter().for_each(|field| self.visit_operand(info, field));
@@ -106,7 +108,7 @@ pub trait MirVisitor<'tcx> {
                 let _ = default;
             }
             TerminatorKind::Cond(operand, _, _) => self.visit_operand(terminator.info, operand),
-            TerminatorKind::Abort => {}
+            TerminatorKind::Panic(..) => {}
         };
     }
 }
@@ -1,5 +1,6 @@
 use crate::mir::Mir;
 use crate::ty::*;
+use error::LResult;
 use ir::DefId;
 use rustc_hash::FxHashMap;
 use std::cell::RefCell;
@@ -92,13 +93,18 @@ define_query_context! {
         ([inherent_impls_of] [DefId] [&'tcx [DefId]])
         ([trait_impls] [()] [&'tcx TraitImpls])
         ([trait_impls_of] [DefId] [&'tcx [DefId]])
+        ([impls_of_trait] [DefId] [&'tcx [DefId]])
+        ([is_object_safe] [DefId] [bool])
+        ([lang_items] [()] [&'tcx LangItems])
 
         // mir
         ([mir_of] [DefId] [&'tcx Mir<'tcx>])
         ([instance_mir] [Instance<'tcx>] [&'tcx Mir<'tcx>])
+        ([eval_const] [DefId] [LResult<&'tcx Const<'tcx>>])
 
         // codegen
         ([monomorphization_instances] [()] [&'tcx Instances<'tcx>])
-        ([resolve_instance] [(DefId, SubstsRef<'tcx>)] [Instance<'tcx>])
+        ([resolve_instance] [(DefId, SubstsRef<'tcx>)] [LResult<Instance<'tcx>>])
+        ([vtable_entries] [(Ty<'tcx>, DefId)] [LResult<&'tcx [Instance<'tcx>]>])
     }
 }
@@ -20,6 +20,10 @@ impl<'tcx> Adjustment<'tcx> {
 pub enum PointerCast {
     /// from fndef to fnptr
     ReifyFn,
+    /// from a closure that captures nothing to fnptr
+    ClosureFnPtr,
+    /// from `&T` to `&dyn Trait` where `T: Trait`
+    Unsize,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -1,28 +1,153 @@
 use crate::queries::Queries;
-use crate::ty::{Subst, Substs, SubstsRef, Ty, TyCtx};
+use crate::ty::{Subst, Substs, SubstsRef, Ty, TyCtx, TyKind, TypeError};
 use ast::Abi;
+use error::LResult;
+use index::Idx;
 use ir::DefId;
 use rustc_hash::FxHashSet;
 use std::fmt::{self, Display, Formatter};
 
 crate fn provide(queries: &mut Queries) {
-    *queries = Queries { resolve_instance, ..*queries }
+    *queries = Queries { resolve_instance, vtable_entries, ..*queries }
 }
 
 fn resolve_instance<'tcx>(
     tcx: TyCtx<'tcx>,
     (def_id, substs): (DefId, SubstsRef<'tcx>),
-) -> Instance<'tcx> {
+) -> LResult<Instance<'tcx>> {
     match tcx.defs().get(def_id) {
         // can just treat constructors as normal items
         ir::DefNode::Item(..) | ir::DefNode::ImplItem(..) | ir::DefNode::Ctor(..) =>
-            Instance::item(def_id, substs),
-        ir::DefNode::TraitItem(..) => todo!(),
+            Ok(Instance::item(def_id, substs)),
+        ir::DefNode::TraitItem(trait_item) => resolve_trait_item_instance(tcx, trait_item, substs),
         ir::DefNode::ForeignItem(item) if item.abi == Abi::Intrinsic =>
-            Instance::intrinsic(def_id, substs),
+            Ok(Instance::intrinsic(def_id, substs)),
         ir::DefNode::ForeignItem(_) => todo!(),
-        ir::DefNode::Field(..) | ir::DefNode::Variant(..) | ir::DefNode::TyParam(..) =>
-            unreachable!(),
+        ir::DefNode::Field(..)
+        | ir::DefNode::Variant(..)
+        | ir::DefNode::TyParam(..)
+        | ir::DefNode::AnonConst(..) => unreachable!(),
+    }
+}
+
+/// resolves a trait item to the implementation for the `Self` type in `substs`
+/// if the impl does not define the item then the default provided by the trait is used
+fn resolve_trait_item_instance<'tcx>(
+    tcx: TyCtx<'tcx>,
+    trait_item: &'tcx ir::TraitItem<'tcx>,
+    substs: SubstsRef<'tcx>,
+) -> LResult<Instance<'tcx>> {
+    let self_ty = substs[0];
+    // calls through a trait object can only be resolved at runtime using its vtable
+    if let TyKind::Dyn(..) = self_ty.kind {
+        let idx = trait_item_refs(tcx, trait_item.trait_def_id)
+            .iter()
+            .position(|item_ref| item_ref.id.0 == trait_item.id.def)
+            .unwrap();
+        return Ok(Instance::virtual_item(trait_item.id.def, substs, idx));
+    }
+
+    let trait_generics = tcx.generics_of(trait_item.trait_def_id);
+    // the substitutions for the generic parameters of the item itself (excluding the trait's)
+    let item_substs = &substs[trait_generics.params.len()..];
+
+    for &impl_def_id in tcx.impls_of_trait(trait_item.trait_def_id) {
+        let impl_item_refs = match tcx.ir.items[&impl_def_id].kind {
+            ir::ItemKind::Impl { impl_item_refs, .. } => impl_item_refs,
+            _ => unreachable!(),
+        };
+
+        let impl_substs = match match_impl_self_ty(tcx, impl_def_id, self_ty) {
+            Some(impl_substs) => impl_substs,
+            None => continue,
+        };
+
+        let impl_item = impl_item_refs
+            .iter()
+            .map(|item_ref| tcx.impl_item(item_ref.id))
+            .find(|impl_item| impl_item.ident == trait_item.ident);
+
+        return Ok(match impl_item {
+            Some(impl_item) => {
+                let substs = impl_substs.into_iter().chain(item_substs.iter().copied());
+                Instance::item(impl_item.id.def, tcx.mk_substs(substs))
+            }
+            None => {
+                assert!(trait_item.kind.has_default(), "impl is missing a trait item");
+                Instance::item(trait_item.id.def, substs)
+            }
+        });
+    }
+
+    // typeck should have rejected this, but report it rather than crashing if it slipped through
+    let trait_ident = tcx.ir.items[&trait_item.trait_def_id].ident;
+    Err(tcx.sess.emit_error(trait_item.span, TypeError::TraitNotImplemented(self_ty, trait_ident)))
+}
+
+/// attempts to match the self type of an impl against the (concrete) type `ty`
+/// returns the substitutions for the impl's generic parameters on success
+fn match_impl_self_ty<'tcx>(
+    tcx: TyCtx<'tcx>,
+    impl_def_id: DefId,
+    ty: Ty<'tcx>,
+) -> Option<SubstsRef<'tcx>> {
+    let impl_ty = tcx.type_of(impl_def_id);
+    let mut substs = vec![None; tcx.generics_of(impl_def_id).params.len()];
+    if !match_ty(impl_ty, ty, &mut substs) {
+        return None;
+    }
+    // any impl parameter that is unconstrained by the self type is not resolvable
+    let substs = substs.into_iter().collect::<Option<Vec<_>>>()?;
+    Some(tcx.mk_substs(substs))
+}
+
+fn match_ty<'tcx>(impl_ty: Ty<'tcx>, ty: Ty<'tcx>, substs: &mut [Option<Ty<'tcx>>]) -> bool {
+    match (impl_ty.kind, ty.kind) {
+        (TyKind::Param(param), _) => match substs[param.idx.index()] {
+            Some(t) => t == ty,
+            None => {
+                substs[param.idx.index()] = Some(ty);
+                true
+            }
+        },
+        (TyKind::Box(t), TyKind::Box(u)) | (TyKind::Ptr(t), TyKind::Ptr(u)) =>
+            match_ty(t, u, substs),
+        (TyKind::Array(t, m), TyKind::Array(u, n)) => m == n && match_ty(t, u, substs),
+        (TyKind::Tuple(xs), TyKind::Tuple(ys)) => match_substs(xs, ys, substs),
+        (TyKind::Adt(adtx, xs), TyKind::Adt(adty, ys)) =>
+            adtx == adty && match_substs(xs, ys, substs),
+        (TyKind::FnPtr(f), TyKind::FnPtr(g)) =>
+            match_substs(f.params, g.params, substs) && match_ty(f.ret, g.ret, substs),
+        _ => impl_ty == ty,
+    }
+}
+
+fn match_substs<'tcx>(
+    xs: SubstsRef<'tcx>,
+    ys: SubstsRef<'tcx>,
+    substs: &mut [Option<Ty<'tcx>>],
+) -> bool {
+    xs.len() == ys.len() && xs.into_iter().zip(ys).all(|(x, y)| match_ty(x, y, substs))
+}
+
+/// the instances that make up the vtable of `ty` as a trait object of the given trait
+/// the entries are in the same order as the items are declared in the trait
+fn vtable_entries<'tcx>(
+    tcx: TyCtx<'tcx>,
+    (ty, trait_def_id): (Ty<'tcx>, DefId),
+) -> LResult<&'tcx [Instance<'tcx>]> {
+    let substs = tcx.mk_substs(std::iter::once(ty));
+    let entries = trait_item_refs(tcx, trait_def_id)
+        .iter()
+        .map(|item_ref| Instance::resolve(tcx, item_ref.id.0, substs))
+        .collect::<LResult<Vec<_>>>()?;
+    Ok(tcx.alloc_iter(entries))
+}
+
+fn trait_item_refs<'tcx>(tcx: TyCtx<'tcx>, trait_def_id: DefId) -> &'tcx [ir::TraitItemRef] {
+    match tcx.ir.items[&trait_def_id].kind {
+        ir::ItemKind::Trait { trait_item_refs, .. } => trait_item_refs,
+        _ => unreachable!(),
     }
 }
 
@@ -44,7 +169,8 @@ impl<'tcx> Instance<'tcx> {
         ty.subst(tcx, self.substs)
     }
 
-    pub fn resolve(tcx: TyCtx<'tcx>, def_id: DefId, substs: SubstsRef<'tcx>) -> Self {
+    /// fails if `def_id` is a trait item and `substs` has no impl of the trait
+    pub fn resolve(tcx: TyCtx<'tcx>, def_id: DefId, substs: SubstsRef<'tcx>) -> LResult<Self> {
         tcx.resolve_instance((def_id, substs))
     }
 
@@ -57,15 +183,30 @@ impl<'tcx> Instance<'tcx> {
         Instance { substs, def_id, kind: InstanceKind::Intrinsic }
     }
 
+    fn virtual_item(def_id: DefId, substs: SubstsRef<'tcx>, idx: usize) -> Self {
+        Instance { substs, def_id, kind: InstanceKind::Virtual(idx) }
+    }
+
     pub fn mono_item(def_id: DefId) -> Self {
         Self::item(def_id, Substs::empty())
     }
+
+    /// the lifted body of the closure `id` where `substs` are the substitutions of
+    /// the enclosing instance (closures share the generics of the item they are defined in)
+    pub fn closure(id: ir::Id, substs: SubstsRef<'tcx>) -> Self {
+        Instance { substs, def_id: id.def, kind: InstanceKind::Closure(id) }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum InstanceKind {
     Item,
     Intrinsic,
+    /// a trait item called through a trait object
+    /// the index is the position of the item in the vtable
+    Virtual(usize),
+    /// the lifted body of a closure
+    Closure(ir::Id),
 }
 
 impl<'tcx> Display for Instance<'tcx> {
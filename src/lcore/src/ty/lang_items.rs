@@ -0,0 +1,137 @@
+use ast::BinOp;
+use ir::DefId;
+use rustc_hash::FxHashMap;
+
+/// traits that are known to the compiler
+/// these are declared by the user with a `#[lang]` attribute
+/// and are identified by the name of the trait
+/// `Eq`, `Clone`, `Hash` and `Debug` are also the traits that can be derived
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LangItem {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    BitAnd,
+    BitOr,
+    Eq,
+    Ord,
+    Fn,
+    FnMut,
+    FnOnce,
+    Clone,
+    Hash,
+    Debug,
+}
+
+impl LangItem {
+    pub const ALL: [LangItem; 14] = [
+        LangItem::Add,
+        LangItem::Sub,
+        LangItem::Mul,
+        LangItem::Div,
+        LangItem::BitAnd,
+        LangItem::BitOr,
+        LangItem::Eq,
+        LangItem::Ord,
+        LangItem::Fn,
+        LangItem::FnMut,
+        LangItem::FnOnce,
+        LangItem::Clone,
+        LangItem::Hash,
+        LangItem::Debug,
+    ];
+
+    /// the traits that are implemented by closures and function pointers
+    pub const FN_TRAITS: [LangItem; 3] = [LangItem::Fn, LangItem::FnMut, LangItem::FnOnce];
+
+    /// the kind of closure that the `Fn*` lang item corresponds to
+    pub fn closure_kind(self) -> Option<ClosureKind> {
+        match self {
+            LangItem::Fn => Some(ClosureKind::Fn),
+            LangItem::FnMut => Some(ClosureKind::FnMut),
+            LangItem::FnOnce => Some(ClosureKind::FnOnce),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LangItem::Add => "Add",
+            LangItem::Sub => "Sub",
+            LangItem::Mul => "Mul",
+            LangItem::Div => "Div",
+            LangItem::BitAnd => "BitAnd",
+            LangItem::BitOr => "BitOr",
+            LangItem::Eq => "Eq",
+            LangItem::Ord => "Ord",
+            LangItem::Fn => "Fn",
+            LangItem::FnMut => "FnMut",
+            LangItem::FnOnce => "FnOnce",
+            LangItem::Clone => "Clone",
+            LangItem::Hash => "Hash",
+            LangItem::Debug => "Debug",
+        }
+    }
+
+    /// the lang item trait used to overload the binary operator `op`
+    /// along with the name of the trait method the operator desugars to
+    /// e.g. `a + b` desugars to `Add::add(a, b)`
+    pub fn binop(op: BinOp) -> (LangItem, &'static str) {
+        match op {
+            BinOp::Add => (LangItem::Add, "add"),
+            BinOp::Sub => (LangItem::Sub, "sub"),
+            BinOp::Mul => (LangItem::Mul, "mul"),
+            BinOp::Div => (LangItem::Div, "div"),
+            BinOp::And => (LangItem::BitAnd, "bitand"),
+            BinOp::Or => (LangItem::BitOr, "bitor"),
+            BinOp::Eq => (LangItem::Eq, "eq"),
+            BinOp::Neq => (LangItem::Eq, "ne"),
+            BinOp::Lt => (LangItem::Ord, "lt"),
+            BinOp::Gt => (LangItem::Ord, "gt"),
+        }
+    }
+}
+
+/// the `Fn*` traits that a closure implements
+/// each kind of closure also implements the traits of the kinds after it
+/// (i.e. an `Fn` closure is also `FnMut` and `FnOnce`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ClosureKind {
+    /// the closure does not mutate its upvars
+    Fn,
+    /// the closure mutates its upvars
+    FnMut,
+    FnOnce,
+}
+
+impl ClosureKind {
+    /// whether a closure of this kind implements the `Fn*` trait of kind `kind`
+    pub fn extends(self, kind: ClosureKind) -> bool {
+        self <= kind
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LangItems {
+    pub items: FxHashMap<LangItem, DefId>,
+}
+
+impl LangItems {
+    pub fn get(&self, item: LangItem) -> Option<DefId> {
+        self.items.get(&item).copied()
+    }
+
+    /// whether `def_id` is one of the `Fn*` traits
+    pub fn is_fn_trait(&self, def_id: DefId) -> bool {
+        self.fn_trait_kind(def_id).is_some()
+    }
+
+    /// the closure kind of the `Fn*` trait `def_id` (if it is one)
+    pub fn fn_trait_kind(&self, def_id: DefId) -> Option<ClosureKind> {
+        LangItem::FN_TRAITS
+            .iter()
+            .find(|&&item| self.get(item) == Some(def_id))
+            .and_then(|item| item.closure_kind())
+    }
+}
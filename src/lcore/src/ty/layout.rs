@@ -0,0 +1,138 @@
+//! the size and alignment of types, and the offsets of their fields
+//! this is shared by the code generator, which lays out its llvm types accordingly, and the
+//! interpreter, which uses it to check that accesses are in bounds and aligned
+
+use crate::ty::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    pub size: u64,
+    pub align: u64,
+}
+
+impl Layout {
+    pub const fn new(size: u64, align: u64) -> Self {
+        Self { size, align }
+    }
+
+    pub const fn scalar(size: u64) -> Self {
+        Self::new(size, size)
+    }
+
+    /// the layout of a pointer, which is a pair of pointers if it points to a trait object
+    fn ptr(pointee: Ty<'_>) -> Self {
+        match pointee.kind {
+            TyKind::Dyn(..) => Self::new(16, 8),
+            _ => Self::scalar(8),
+        }
+    }
+}
+
+/// the layout of the discriminant of an enum (an `i16`)
+const DISCR_LAYOUT: Layout = Layout::scalar(2);
+
+fn align_to(offset: u64, align: u64) -> u64 {
+    (offset + align - 1) / align * align
+}
+
+/// lays out the types consecutively with padding for alignment (like a C struct)
+/// returns the layout of the whole and the offset of each type
+fn layout_fields<'tcx>(
+    tcx: TyCtx<'tcx>,
+    tys: impl IntoIterator<Item = Ty<'tcx>>,
+) -> (Layout, Vec<u64>) {
+    let mut offsets = vec![];
+    let mut size = 0;
+    let mut align = 1;
+    for ty in tys {
+        let layout = layout_of(tcx, ty);
+        size = align_to(size, layout.align);
+        offsets.push(size);
+        size += layout.size;
+        align = align.max(layout.align);
+    }
+    (Layout::new(align_to(size, align), align), offsets)
+}
+
+fn layout_variant<'tcx>(
+    tcx: TyCtx<'tcx>,
+    variant: &'tcx VariantTy,
+    substs: SubstsRef<'tcx>,
+) -> (Layout, Vec<u64>) {
+    layout_fields(tcx, variant.fields.iter().map(|f| f.ty(tcx, substs)))
+}
+
+fn layout_struct<'tcx>(
+    tcx: TyCtx<'tcx>,
+    adt: &'tcx AdtTy,
+    substs: SubstsRef<'tcx>,
+) -> (Layout, Vec<u64>) {
+    layout_variant(tcx, adt.single_variant(), substs)
+}
+
+/// the layout of the content of an enum, which is large enough to hold the fields of any variant
+/// the size is a multiple of the alignment
+pub fn enum_content_layout<'tcx>(
+    tcx: TyCtx<'tcx>,
+    adt: &'tcx AdtTy,
+    substs: SubstsRef<'tcx>,
+) -> Layout {
+    let content = adt
+        .variants
+        .iter()
+        .map(|variant| layout_variant(tcx, variant, substs).0)
+        .fold(Layout::new(0, 1), |acc, layout| {
+            Layout::new(acc.size.max(layout.size), acc.align.max(layout.align))
+        });
+    Layout::new(align_to(content.size, content.align), content.align)
+}
+
+/// the offset of the content of an enum, which follows the discriminant
+fn enum_content_offset<'tcx>(tcx: TyCtx<'tcx>, adt: &'tcx AdtTy, substs: SubstsRef<'tcx>) -> u64 {
+    align_to(DISCR_LAYOUT.size, enum_content_layout(tcx, adt, substs).align)
+}
+
+pub fn layout_of<'tcx>(tcx: TyCtx<'tcx>, ty: Ty<'tcx>) -> Layout {
+    match ty.kind {
+        TyKind::Bool => Layout::scalar(1),
+        TyKind::Discr => DISCR_LAYOUT,
+        TyKind::Char => Layout::scalar(4),
+        TyKind::Float | TyKind::Int => Layout::scalar(8),
+        TyKind::Never => Layout::new(0, 1),
+        TyKind::Box(pointee) | TyKind::Ptr(pointee) => Layout::ptr(pointee),
+        // a function pointer is paired with the environment of the closure
+        TyKind::FnPtr(..) | TyKind::Opaque(..) => Layout::new(16, 8),
+        TyKind::Array(ty, n) => {
+            let layout = layout_of(tcx, ty);
+            Layout::new(layout.size * n as u64, layout.align)
+        }
+        TyKind::Tuple(tys) => layout_fields(tcx, tys).0,
+        TyKind::Adt(adt, substs) => match adt.kind {
+            AdtKind::Struct => layout_struct(tcx, adt, substs).0,
+            AdtKind::Enum => {
+                let content = enum_content_layout(tcx, adt, substs);
+                let offset = align_to(DISCR_LAYOUT.size, content.align);
+                let align = content.align.max(DISCR_LAYOUT.align);
+                Layout::new(align_to(offset + content.size, align), align)
+            }
+        },
+        TyKind::Dyn(..) => panic!("trait objects are unsized"),
+        TyKind::Error | TyKind::Infer(..) | TyKind::Param(..) =>
+            panic!("cannot lay out type `{}`", ty),
+    }
+}
+
+/// the offset of the field `idx` within a value of type `ty`
+/// the fields of an enum are its discriminant and its content
+pub fn field_offset<'tcx>(tcx: TyCtx<'tcx>, ty: Ty<'tcx>, idx: usize) -> u64 {
+    match ty.kind {
+        TyKind::Tuple(tys) => layout_fields(tcx, tys).1[idx],
+        TyKind::Array(elem, _) => layout_of(tcx, elem).size * idx as u64,
+        TyKind::Adt(adt, substs) => match adt.kind {
+            AdtKind::Struct => layout_struct(tcx, adt, substs).1[idx],
+            AdtKind::Enum if idx == 0 => 0,
+            AdtKind::Enum => enum_content_offset(tcx, adt, substs),
+        },
+        _ => panic!("cannot project field `{}` of `{}`", idx, ty),
+    }
+}
@@ -1,6 +1,8 @@
 mod adjustments;
 mod codec;
 mod instance;
+mod lang_items;
+pub mod layout;
 mod list;
 mod relate;
 mod substs;
@@ -12,13 +14,14 @@ mod type_error;
 pub use adjustments::{Adjuster, Adjustment, AdjustmentKind, PointerCast};
 use ena::unify::UnifyKey;
 pub use instance::{Instance, InstanceKind, Instances};
+pub use lang_items::{ClosureKind, LangItem, LangItems};
 pub use list::List;
 pub use relate::{Relate, TypeRelation};
 pub use substs::*;
 pub use tables::TypeckTables;
 pub use tcx::{tls, GlobalCtx, TyCtx};
 pub use traverse::*;
-pub use type_error::{TypeError, TypeResult};
+pub use type_error::{ObjectSafetyViolation, TypeError, TypeResult};
 pub use InferTy::*;
 pub use TyKind::*;
 
@@ -90,7 +93,7 @@ impl Display for TyVid {
 impl<'tcx> TypeVisitor<'tcx> for TyVidVisitor {
     fn visit_ty(&mut self, ty: Ty<'tcx>) -> bool {
         match ty.kind {
-            TyKind::Infer(InferTy::TyVar(v)) => v == self.tyvid,
+            TyKind::Infer(infer) => infer.vid() == self.tyvid,
             _ => ty.inner_visit_with(self),
         }
     }
@@ -115,6 +118,21 @@ impl<'tcx> Type<'tcx> {
         }
     }
 
+    /// the signature of a function pointer or closure
+    pub fn expect_callable(&self) -> FnSig<'tcx> {
+        match self.kind {
+            TyKind::FnPtr(fn_sig) | TyKind::Opaque(_, fn_sig) => fn_sig,
+            _ => panic!("expected callable type, found {}", self),
+        }
+    }
+
+    pub fn expect_dyn(&self) -> DefId {
+        match self.kind {
+            TyKind::Dyn(trait_def_id) => trait_def_id,
+            _ => panic!("expected TyKind::Dyn, found {}", self),
+        }
+    }
+
     pub fn is_box(&self) -> bool {
         match self.kind {
             TyKind::Box(..) => true,
@@ -122,6 +140,22 @@ impl<'tcx> Type<'tcx> {
         }
     }
 
+    /// whether an integer inference variable may be instantiated with this type
+    pub fn is_integral(&self) -> bool {
+        match self.kind {
+            TyKind::Int => true,
+            _ => false,
+        }
+    }
+
+    /// whether a float inference variable may be instantiated with this type
+    pub fn is_floating_point(&self) -> bool {
+        match self.kind {
+            TyKind::Float => true,
+            _ => false,
+        }
+    }
+
     pub fn deref_ty(&self) -> Ty<'tcx> {
         match self.kind {
             TyKind::Box(ty) | TyKind::Ptr(ty) => ty,
@@ -180,12 +214,16 @@ pub enum TyKind<'tcx> {
     Infer(InferTy),
     Ptr(Ty<'tcx>),
     Param(ParamTy),
-    Opaque(DefId, SubstsRef<'tcx>),
+    /// the unique (unnameable) type of a closure expression with the given signature
+    Opaque(ir::Id, FnSig<'tcx>),
     Adt(&'tcx AdtTy, SubstsRef<'tcx>),
+    /// dyn Trait
+    /// trait objects are unsized and are only used behind a pointer
+    Dyn(DefId),
 }
 
 /// this is the type-level representation of the type of a function
-#[derive(Eq, Hash, PartialEq, Clone, Copy, Serialize)]
+#[derive(Eq, Hash, PartialEq, Clone, Copy, Debug, Serialize)]
 pub struct FnSig<'tcx> {
     pub params: SubstsRef<'tcx>,
     pub ret: Ty<'tcx>,
@@ -209,6 +247,16 @@ pub struct TyParam<'tcx> {
     pub ident: Ident,
     pub index: ParamIdx,
     pub default: Option<Ty<'tcx>>,
+    pub bounds: &'tcx [Bound<'tcx>],
+}
+
+/// a trait bound on a type parameter
+/// `Fn*` bounds additionally carry the signature in their parentheses
+#[derive(Eq, Hash, PartialEq, Clone, Copy, Debug)]
+pub struct Bound<'tcx> {
+    pub span: Span,
+    pub trait_def_id: DefId,
+    pub sig: Option<FnSig<'tcx>>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -279,6 +327,8 @@ pub struct VariantTy {
     pub ident: Ident,
     pub ctor_kind: CtorKind,
     pub fields: Vec<FieldTy>,
+    /// the value stored in the tag of an enum to identify this variant
+    pub discr: i16,
 }
 
 /// the type representation of a field
@@ -364,8 +414,8 @@ impl<'tcx> HasTyFlags for SubstsRef<'tcx> {
 impl<'tcx> TyFlag for TyKind<'tcx> {
     fn ty_flags(&self) -> TyFlags {
         match self {
-            TyKind::FnPtr(sig) => sig.ty_flags(),
-            TyKind::Opaque(_, tys) | TyKind::Tuple(tys) => tys.ty_flags(),
+            TyKind::FnPtr(sig) | TyKind::Opaque(_, sig) => sig.ty_flags(),
+            TyKind::Tuple(tys) => tys.ty_flags(),
             TyKind::Infer(..) => TyFlags::HAS_INFER,
             TyKind::Param(..) => TyFlags::HAS_PARAM,
             TyKind::Adt(_, substs) => substs.ty_flags(),
@@ -375,6 +425,7 @@ impl<'tcx> TyFlag for TyKind<'tcx> {
             | TyKind::Never
             | TyKind::Bool
             | TyKind::Char
+            | TyKind::Dyn(..)
             | TyKind::Int => TyFlags::empty(),
             TyKind::Error => TyFlags::HAS_ERROR,
         }
@@ -398,7 +449,9 @@ impl<'tcx> Display for TyKind<'tcx> {
             TyKind::Tuple(tys) => write!(f, "({})", tys),
             TyKind::Param(param_ty) => write!(f, "{}", param_ty),
             TyKind::Adt(adt, substs) => write!(f, "{}<{}>", adt.ident, substs),
-            TyKind::Opaque(_, _) => write!(f, "opaque"),
+            TyKind::Opaque(_, sig) => write!(f, "[closure {}]", sig),
+            TyKind::Dyn(def_id) =>
+                write!(f, "dyn {}", tls::with_tcx(|tcx| tcx.defs().ident(*def_id))),
             TyKind::Bool => write!(f, "bool"),
             TyKind::Char => write!(f, "char"),
             TyKind::Int => write!(f, "int"),
@@ -450,12 +503,26 @@ impl Display for ParamTy {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum InferTy {
     TyVar(TyVid),
+    /// an inference variable that may only be instantiated with an integral type
+    IntVar(TyVid),
+    /// an inference variable that may only be instantiated with a floating point type
+    FloatVar(TyVid),
+}
+
+impl InferTy {
+    pub fn vid(self) -> TyVid {
+        match self {
+            Self::TyVar(vid) | Self::IntVar(vid) | Self::FloatVar(vid) => vid,
+        }
+    }
 }
 
 impl Display for InferTy {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             Self::TyVar(vid) => write!(f, "{}", vid),
+            Self::IntVar(..) => write!(f, "{{integer}}"),
+            Self::FloatVar(..) => write!(f, "{{float}}"),
         }
     }
 }
@@ -469,6 +536,23 @@ pub struct UpvarId {
     pub var_id: ir::Id,
 }
 
+/// how a closure captures one of its upvars
+#[derive(Debug, Hash, Eq, PartialEq, Clone, Copy)]
+pub enum CaptureMode {
+    /// the value of the variable is copied into the environment of the closure
+    ByValue,
+    /// a pointer to the variable is stored in the environment of the closure
+    /// so mutations are visible to both the closure and the enclosing function
+    /// the variable itself is allocated in a gc box so the pointer outlives its frame
+    ByRef,
+}
+
+#[derive(Debug, Hash, Eq, PartialEq, Clone, Copy)]
+pub struct UpvarCapture {
+    pub upvar_id: UpvarId,
+    pub mode: CaptureMode,
+}
+
 impl<'tcx> Display for Type<'tcx> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.kind)
@@ -484,7 +568,7 @@ pub struct Const<'tcx> {
 
 // deriving partialeq even with custom hash impl should be fine
 // just avoid the nasty NaN business
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ConstKind {
     Float(f64),
     Int(i64),
@@ -528,5 +612,8 @@ pub struct InherentImpls {
 
 #[derive(Clone, Debug, Default)]
 pub struct TraitImpls {
+    /// the trait impls of each type, keyed by the `DefId` of the type
     pub trait_impls: FxHashMap<DefId, Vec<DefId>>,
+    /// the impls of each trait, keyed by the `DefId` of the trait
+    pub impls_of_trait: FxHashMap<DefId, Vec<DefId>>,
 }
@@ -29,6 +29,8 @@ pub trait TypeRelation<'tcx>: Sized {
             (_, ty::Never) => Ok(a),
             (ty::Never, _) => Ok(b),
             (ty::FnPtr(f), ty::FnPtr(g)) => Ok(tcx.mk_fn_ptr(self.relate(f, g)?)),
+            (ty::Opaque(x, f), ty::Opaque(y, g)) if x == y =>
+                Ok(tcx.mk_opaque_ty(x, self.relate(f, g)?)),
             (ty::Infer(_), _) | (_, ty::Infer(_)) => panic!(),
             _ => TypeResult::Err(TypeError::Mismatch(a, b)),
         }
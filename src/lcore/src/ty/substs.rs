@@ -80,7 +80,7 @@ impl<'tcx> TypeFolder<'tcx> for InferVarSubstsFolder<'tcx> {
             return ty;
         }
         match ty.kind {
-            TyKind::Infer(InferTy::TyVar(tyvid)) => self.substs[tyvid.index as usize],
+            TyKind::Infer(infer) => self.substs[infer.vid().index as usize],
             _ => ty.inner_fold_with(self),
         }
     }
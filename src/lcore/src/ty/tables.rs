@@ -1,4 +1,7 @@
-use crate::ty::{Adjustment, SubstsRef, Ty, TypeFoldable, TypeFolder, TypeVisitor, UpvarId};
+use crate::ty::{
+    Adjustment, CaptureMode, ClosureKind, SubstsRef, Ty, TypeFoldable, TypeFolder, TypeVisitor,
+    UpvarCapture,
+};
 use ir::{self, DefId, FieldIdx, LocalId, Res};
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::collections::hash_map::Entry;
@@ -17,7 +20,13 @@ pub struct TypeckTables<'tcx> {
     field_indices: FxHashMap<LocalId, FieldIdx>,
     /// the resolution of a type relative path
     type_relative_resolutions: FxHashMap<LocalId, Res>,
-    upvar_captures: FxHashMap<ir::Id, FxHashSet<UpvarId>>,
+    /// the upvars captured by each closure in the order they are stored in its environment
+    upvar_captures: FxHashMap<ir::Id, Vec<UpvarCapture>>,
+    /// the variables that are captured by reference, which are allocated in a gc box
+    /// so that the closures referring to them may outlive the frame they are declared in
+    boxed_vars: FxHashSet<ir::Id>,
+    /// the `Fn*` traits each closure implements
+    closure_kinds: FxHashMap<ir::Id, ClosureKind>,
 }
 
 impl<'tcx> TypeckTables<'tcx> {
@@ -29,29 +38,47 @@ impl<'tcx> TypeckTables<'tcx> {
             adjustments: Default::default(),
             field_indices: Default::default(),
             upvar_captures: Default::default(),
+            boxed_vars: Default::default(),
+            closure_kinds: Default::default(),
             type_relative_resolutions: Default::default(),
         }
     }
 
-    pub fn upvar_captures_for_closure(&self, closure_id: ir::Id) -> &FxHashSet<UpvarId> {
+    pub fn def_id(&self) -> DefId {
+        self.def_id
+    }
+
+    pub fn upvar_captures_for_closure(&self, closure_id: ir::Id) -> &[UpvarCapture] {
         &self.upvar_captures[&closure_id]
     }
 
-    pub fn record_upvar_capture_for_closure(
+    pub fn record_upvar_captures_for_closure(
         &mut self,
         closure_id: ir::Id,
-        upvars: FxHashSet<UpvarId>,
+        captures: Vec<UpvarCapture>,
     ) {
+        for capture in &captures {
+            if capture.mode == CaptureMode::ByRef {
+                self.boxed_vars.insert(capture.upvar_id.var_id);
+            }
+        }
         match self.upvar_captures.entry(closure_id) {
-            Entry::Vacant(entry) => entry.insert(upvars),
+            Entry::Vacant(entry) => entry.insert(captures),
             Entry::Occupied(_) => panic!("upvars already set for closure `{}`", closure_id),
         };
     }
 
-    pub fn record_upvar_capture(&mut self, upvar: UpvarId) {
-        if self.upvar_captures.entry(upvar.closure_id).or_default().insert(upvar) {
-            panic!("variable captured twice by the same closure")
-        }
+    pub fn closure_kind(&self, closure_id: ir::Id) -> ClosureKind {
+        self.closure_kinds[&closure_id]
+    }
+
+    pub fn record_closure_kind(&mut self, closure_id: ir::Id, kind: ClosureKind) {
+        self.closure_kinds.insert(closure_id, kind);
+    }
+
+    /// whether the variable `id` is allocated in a gc box
+    pub fn is_boxed_var(&self, id: ir::Id) -> bool {
+        self.boxed_vars.contains(&id)
     }
 
     pub fn node_type(&self, id: ir::Id) -> Ty<'tcx> {
@@ -181,6 +208,8 @@ impl<'tcx> TypeFoldable<'tcx> for TypeckTables<'tcx> {
             node_substs: self.node_substs.iter().map(|(&k, v)| (k, v.fold_with(folder))).collect(),
             field_indices: self.field_indices.clone(),
             upvar_captures: self.upvar_captures.clone(),
+            boxed_vars: self.boxed_vars.clone(),
+            closure_kinds: self.closure_kinds.clone(),
             type_relative_resolutions: self.type_relative_resolutions.clone(),
         }
     }
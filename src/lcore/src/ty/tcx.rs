@@ -83,8 +83,8 @@ impl<'tcx> TyCtx<'tcx> {
         self.mk_ty(TyKind::Adt(adt_ty, substs))
     }
 
-    pub fn mk_opaque_ty(self, def: DefId, substs: SubstsRef<'tcx>) -> Ty<'tcx> {
-        self.mk_ty(TyKind::Opaque(def, substs))
+    pub fn mk_opaque_ty(self, id: ir::Id, sig: FnSig<'tcx>) -> Ty<'tcx> {
+        self.mk_ty(TyKind::Opaque(id, sig))
     }
 
     pub fn mk_array_ty(self, ty: Ty<'tcx>, n: usize) -> Ty<'tcx> {
@@ -111,6 +111,10 @@ impl<'tcx> TyCtx<'tcx> {
         self.mk_ty(TyKind::Box(ty))
     }
 
+    pub fn mk_dyn_ty(self, trait_def_id: DefId) -> Ty<'tcx> {
+        self.mk_ty(TyKind::Dyn(trait_def_id))
+    }
+
     pub fn mk_ty_param(self, def_id: DefId, idx: ParamIdx, ident: Ident) -> Ty<'tcx> {
         self.mk_ty(TyKind::Param(ParamTy { def_id, idx, ident }))
     }
@@ -55,8 +55,9 @@ impl<'tcx> TypeFoldable<'tcx> for Ty<'tcx> {
             TyKind::Array(ty, n) => TyKind::Array(ty.fold_with(folder), n),
             TyKind::Tuple(tys) => TyKind::Tuple(tys.fold_with(folder)),
             TyKind::Adt(adt, substs) => TyKind::Adt(adt, substs.fold_with(folder)),
-            TyKind::Opaque(def, substs) => TyKind::Opaque(def, substs.fold_with(folder)),
+            TyKind::Opaque(id, sig) => TyKind::Opaque(id, sig.fold_with(folder)),
             TyKind::Param(_)
+            | TyKind::Dyn(_)
             | TyKind::Infer(_)
             | TyKind::Char
             | TyKind::Discr
@@ -84,12 +85,12 @@ impl<'tcx> TypeFoldable<'tcx> for Ty<'tcx> {
         V: TypeVisitor<'tcx>,
     {
         match self.kind {
-            TyKind::FnPtr(sig) => sig.visit_with(visitor),
+            TyKind::FnPtr(sig) | TyKind::Opaque(_, sig) => sig.visit_with(visitor),
             TyKind::Ptr(ty) | TyKind::Box(ty) | TyKind::Array(ty, _) => ty.visit_with(visitor),
             TyKind::Tuple(tys) => tys.visit_with(visitor),
-            TyKind::Opaque(_, substs) => substs.visit_with(visitor),
             TyKind::Adt(_, substs) => substs.visit_with(visitor),
             TyKind::Param(..)
+            | TyKind::Dyn(..)
             | TyKind::Infer(..)
             | TyKind::Discr
             | TyKind::Never
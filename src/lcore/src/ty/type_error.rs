@@ -1,7 +1,7 @@
-use crate::ty::{Ty, TyVid};
-use ast::Ident;
+use crate::ty::{ClosureKind, Ty, TyVid};
+use ast::{BinOp, Ident, UnaryOp};
 use error::LError;
-use ir::{self, Res};
+use ir::{self, DefKind, Res};
 use thiserror::Error;
 
 pub type TypeResult<'tcx, T> = Result<T, TypeError<'tcx>>;
@@ -36,6 +36,55 @@ pub enum TypeError<'tcx> {
     RequireUnsafeCtx,
     #[error("type annotations required")]
     InferenceFailure,
+    #[error("missing item `{0}` in implementation of trait `{1}`")]
+    MissingTraitItem(Ident, Ident),
+    #[error("`{0}` is not a member of trait `{1}`")]
+    NotTraitMember(Ident, Ident),
+    #[error("the trait `{1}` is not implemented for `{0}`")]
+    TraitNotImplemented(Ty<'tcx>, Ident),
+    #[error("expected trait, found `{0}`")]
+    ExpectedTrait(String),
+    #[error("expected type, found {0} `{1}`")]
+    ExpectedType(DefKind, Ident),
+    #[error("the trait `{0}` cannot be made into an object because {1}")]
+    ObjectSafety(Ident, ObjectSafetyViolation),
+    #[error("expected a closure that implements `{0:?}`, but this closure only implements `{1:?}`")]
+    ClosureKindMismatch(ClosureKind, ClosureKind),
+    #[error("only closures that capture nothing can be coerced to function pointers")]
+    CapturingClosureToFnPtr,
+    #[error("parenthesized arguments are only allowed on `Fn*` traits, found `{0}`")]
+    ParenthesizedArgsOnNonFnTrait(String),
+    #[error("the `{0}` bound requires a parenthesized signature")]
+    MissingFnBoundSig(String),
+    #[error("cannot apply binary operator `{0}` to type `{1}` as there is no `{2}` trait")]
+    UnsupportedBinOp(BinOp, Ty<'tcx>, &'static str),
+    #[error("cannot apply unary operator `{0}` to type `{1}`")]
+    UnsupportedUnaryOp(UnaryOp, Ty<'tcx>),
+    #[error("array length must not be negative, found `{0}`")]
+    NegativeArrayLen(i64),
+    #[error("`{0}` is not a lang item")]
+    UnknownLangItem(Ident),
+    #[error("duplicate lang item `{0}`")]
+    DuplicateLangItem(Ident),
+    #[error("cannot implement items for the trait object type `{0}`")]
+    TraitObjectImpl(Ty<'tcx>),
+    #[error("discriminant value `{0}` is not within the range `0..={}`", i16::MAX)]
+    DiscriminantOutOfRange(i64),
+    #[error("discriminant value `{0}` is assigned to more than one variant")]
+    DuplicateDiscriminant(i64),
+}
+
+/// reasons a trait cannot be used as a trait object
+#[derive(Debug, Error)]
+pub enum ObjectSafetyViolation {
+    #[error("it has generic parameters")]
+    GenericTrait,
+    #[error("method `{0}` has generic parameters")]
+    GenericMethod(Ident),
+    #[error("method `{0}` does not take `&self` as its first parameter")]
+    NoReceiver(Ident),
+    #[error("method `{0}` references the `Self` type outside of its receiver")]
+    SelfReference(Ident),
 }
 
 impl<'tcx> LError for TypeError<'tcx> {
@@ -44,6 +93,7 @@ impl<'tcx> LError for TypeError<'tcx> {
             TypeError::TupleSizeMismatch(..) | TypeError::Mismatch(..) => "type mismatch",
             TypeError::InferenceFailure => "inference failure",
             TypeError::RequireUnsafeCtx => "",
+            TypeError::ObjectSafety(..) => "object safety violation",
             _ => "type error",
         }
     }
@@ -1,6 +1,6 @@
 use semver::Version;
 use serde::de::{self, Deserialize};
-use session::CompilerOptions;
+use session::{CompilerOptions, Profile};
 use std::collections::HashMap;
 use std::fmt;
 use std::fs;
@@ -48,6 +48,8 @@ crate fn load_config(opts: CompilerOptions) -> io::Result<LConfig> {
     };
 
     config.opts = opts;
+    let profile = if config.opts.release { &config.profile.release } else { &config.profile.dev };
+    config.opts.profile = profile.clone();
     config.validate()?;
     Ok(config)
 }
@@ -111,6 +113,17 @@ pub struct TomlConfig {
     pub dependencies: Dependencies,
     #[serde(default = "BinConfig::default")]
     pub bin: BinConfig,
+    #[serde(default = "Profiles::default")]
+    pub profile: Profiles,
+}
+
+/// the `[profile.dev]` and `[profile.release]` sections
+#[derive(Debug, Default, Deserialize)]
+pub struct Profiles {
+    #[serde(default = "Profile::default")]
+    pub dev: Profile,
+    #[serde(default = "Profile::default")]
+    pub release: Profile,
 }
 
 #[derive(Debug, Deserialize)]
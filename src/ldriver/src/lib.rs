@@ -25,16 +25,16 @@ extern crate log;
 
 use ast::{ExprKind, P};
 use astlowering::AstLoweringCtx;
-use codegen::CodegenCtx;
+use codegen::{llvm_opt_level, CodegenCtx};
 use codespan_reporting::diagnostic::Diagnostic;
 use codespan_reporting::files::SimpleFiles;
 use codespan_reporting::term;
 use config::LConfig;
 use error::{ErrorFormat, ErrorReported, LResult};
+use expand::Expander;
 use index::IndexVec;
 use inkwell::context::Context as LLVMCtx;
 
-use inkwell::OptimizationLevel;
 use ir::{PkgId, Resolutions};
 use lcore::{GlobalCtx, TyCtx};
 use lex::{Lexer, Tok};
@@ -42,7 +42,7 @@ use log::LevelFilter;
 use meta::PkgMetadata;
 use parse::Parser;
 use resolve::{Resolver, ResolverArenas};
-pub use session::{CompilerOptions, Session};
+pub use session::{CompilerOptions, EmitKind, Session};
 use span::{sym, SourceMap, ROOT_FILE_IDX, SPAN_GLOBALS};
 use std::env::temp_dir;
 use std::fs::File;
@@ -134,13 +134,20 @@ impl<'tcx> Driver<'tcx> {
     // this can't be made #[cfg(test)] for some reason
     // as some test code complains this doesn't exist
     pub fn from_src(src: &str) -> Self {
+        Self::from_src_with_opts(src, |_| {})
+    }
+
+    /// same as `from_src` but allows configuring the compiler options
+    pub fn from_src_with_opts(src: &str, f: impl FnOnce(&mut CompilerOptions)) -> Self {
         let tempdir = tempfile::tempdir().unwrap();
         // into_place ensure the tempdir is *not* dropped
         // we need it later in the `run` stage
         let main_path = tempdir.into_path().join("main.l");
         let mut file = File::create(&main_path).unwrap();
         file.write(src.as_bytes()).unwrap();
-        Self::new(LConfig::from_main_path(main_path))
+        let mut config = LConfig::from_main_path(main_path);
+        f(&mut config.opts);
+        Self::new(config)
     }
 
     pub fn new(config: LConfig) -> Self {
@@ -170,8 +177,15 @@ impl<'tcx> Driver<'tcx> {
         check_errors!(self, ast.unwrap())
     }
 
+    /// parses and then expands the ast (i.e. derives)
+    pub fn expand(&self) -> LResult<P<ast::Ast>> {
+        let mut ast = self.parse()?;
+        Expander::new(&self.sess).expand(&mut ast);
+        check_errors!(self, ast)
+    }
+
     pub fn gen_ir(&'tcx self) -> LResult<(&'tcx ir::Ir<'tcx>, Resolutions)> {
-        let ast = self.parse()?;
+        let ast = self.expand()?;
         let mut resolver = Resolver::new(&self.sess, &self.resolver_arenas, &self.dependencies);
         resolver.resolve(&ast);
         let lctx = AstLoweringCtx::new(&self.ir_arena, &self.sess, &mut resolver);
@@ -195,38 +209,37 @@ impl<'tcx> Driver<'tcx> {
     }
 
     pub fn create_codegen_ctx(&'tcx self) -> LResult<CodegenCtx<'tcx>> {
-        self.with_tcx(|tcx| CodegenCtx::new(tcx, &self.llvm_ctx))
+        self.with_tcx(|tcx| CodegenCtx::new(tcx, &self.llvm_ctx))?
     }
 
     pub fn build(&'tcx self) -> LResult<()> {
-        self.llvm_compile()?;
-        Ok(())
+        let cctx = self.llvm_compile()?;
+        let _ = cctx.emit(&self.out_dir(), self.sess.opts.emit());
+        check_errors!(self, ())
     }
 
     pub fn llvm_compile(&'tcx self) -> LResult<CodegenCtx<'tcx>> {
         let cctx = self.create_codegen_ctx()?;
         cctx.codegen()?;
-        check_errors!(self);
-        let ir_path = self.root_path.join("llvm-ir.ll");
-        cctx.module.print_to_file(&ir_path).unwrap_or_else(|err| panic!("{}", err));
-        // let bitcode_path = self.root_path.join("build.bc");
-        // assert!(cctx.module.write_bitcode_to_path(&bitcode_path));
-        let output_path = self.root_path.join("l.out");
-        std::process::Command::new("clang")
-            .arg(&ir_path)
-            .arg("-o")
-            .arg(output_path)
-            .arg("-lgc")
-            .stdout(std::process::Stdio::null())
-            .stderr(std::process::Stdio::null())
-            .status()
-            .expect("failed to link using clang (is `clang` on your path?)");
-        Ok(cctx)
+        check_errors!(self, cctx)
+    }
+
+    /// the directory the outputs are written to
+    pub fn out_dir(&self) -> PathBuf {
+        self.sess.opts.out_dir.clone().unwrap_or_else(|| self.root_path.clone())
     }
 
     pub fn run(&'tcx self) -> LResult<Option<i32>> {
-        self.build()?;
-        let path = self.root_path.join("l.out");
+        let cctx = self.llvm_compile()?;
+        // running always requires the executable, regardless of what else was requested
+        let mut emit = self.sess.opts.emit().to_vec();
+        if !emit.contains(&EmitKind::Link) {
+            emit.push(EmitKind::Link);
+        }
+        let out_dir = self.out_dir();
+        let _ = cctx.emit(&out_dir, &emit);
+        check_errors!(self);
+        let path = codegen::output_path(&out_dir, EmitKind::Link);
         assert!(path.exists());
         Ok(std::process::Command::new(path).status().expect("io error").code())
     }
@@ -234,16 +247,35 @@ impl<'tcx> Driver<'tcx> {
     // TODO does not link to libgc so will segfault if run with anything that uses `box`
     pub fn llvm_jit(&'tcx self) -> LResult<i32> {
         let cctx = self.llvm_compile()?;
-        let jit = cctx.module.create_jit_execution_engine(OptimizationLevel::None).unwrap();
+        let opt_level = llvm_opt_level(self.sess.opts.opt_level());
+        let jit = cctx.module.create_jit_execution_engine(opt_level).unwrap();
         let main = cctx.module.get_function(sym::main.as_str()).unwrap();
         let val = unsafe { jit.run_function_as_main(main, &[]) };
         Ok(val)
     }
 
+    /// runs the program using the mir interpreter instead of compiling it
+    pub fn interp(&'tcx self) -> LResult<i32> {
+        self.interp_with_output(&mut std::io::stdout(), &mut std::io::stderr())
+    }
+
+    /// same as `interp` but writes the output of the program to `out` and its panics to `err`
+    pub fn interp_with_output(
+        &'tcx self,
+        out: &mut dyn Write,
+        err: &mut dyn Write,
+    ) -> LResult<i32> {
+        self.with_tcx(|tcx| interp::interpret(tcx, out, err))?
+    }
+
     pub fn has_errors(&self) -> bool {
         self.sess.has_errors()
     }
 
+    pub fn warning_count(&self) -> usize {
+        self.sess.warning_count()
+    }
+
     pub fn lex(&self) -> LResult<Vec<Tok>> {
         let mut lexer = Lexer::new();
         let tokens = lexer.lex(ROOT_FILE_IDX);
@@ -34,6 +34,7 @@ fn analyze<'tcx>(tcx: TyCtx<'tcx>) {
             &mut ItemTypeValidationPass { tcx },
             &mut TypecheckPass { tcx },
             &mut MirLoweringPass { tcx },
+            &mut ConstEvalPass { tcx },
         ])
     })
 }
@@ -99,10 +100,32 @@ impl<'tcx> AnalysisPass<'tcx> for ItemTypeValidationPass<'tcx> {
         // TODO currently required to run some validation on impls, maybe can move elsewhere
         self.tcx.inherent_impls(());
         self.tcx.trait_impls(());
+        self.tcx.lang_items(());
         false
     }
 }
 
+/// evaluates all constants and statics, even those that are never used
+struct ConstEvalPass<'tcx> {
+    tcx: TyCtx<'tcx>,
+}
+
+impl<'tcx> AnalysisPass<'tcx> for ConstEvalPass<'tcx> {
+    fn name(&self) -> &'static str {
+        "const eval pass"
+    }
+
+    fn run_pass(&mut self) -> bool {
+        for item in self.tcx.ir.items.values() {
+            if let ir::ItemKind::Const(..) | ir::ItemKind::Static(..) = item.kind {
+                // errors have already been reported
+                let _ = self.tcx.eval_const(item.id.def);
+            }
+        }
+        true
+    }
+}
+
 impl_body_check_pass!(TypecheckPass, tcx, "type check pass", typeck, true);
 impl_body_check_pass!(MirLoweringPass, tcx, "mir lowering pass", mir_of, true);
 
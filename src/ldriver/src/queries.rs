@@ -8,6 +8,7 @@ crate fn query_ctx<'tcx>() -> QueryCtx<'tcx> {
     typeck::provide(&mut queries);
     mirgen::provide(&mut queries);
     mir::provide(&mut queries);
+    interp::provide(&mut queries);
     lcore::provide(&mut queries);
     codegen::provide(&mut queries);
 
@@ -34,12 +34,14 @@ lazy_static! {
         "type" => TokenType::Type,
         "unsafe" => TokenType::Unsafe,
         "const" => TokenType::Const,
+        "static" => TokenType::Static,
         "impl" => TokenType::Impl,
         "extern" => TokenType::Extern,
         "for" => TokenType::For,
         "loop" => TokenType::Loop,
         "while" => TokenType::While,
         "self" => TokenType::LSelf,
+        "dyn" => TokenType::Dyn,
     };
 }
 
@@ -82,9 +84,21 @@ impl Lexer {
                                 i += 1;
                                 span_index += 1;
                                 TokenType::RFArrow
+                            } else if tokens[i].kind == TokenKind::Eq {
+                                i += 1;
+                                span_index += 1;
+                                TokenType::Deq
                             } else {
                                 TokenType::Eq
                             },
+                        TokenKind::Not =>
+                            if tokens[i].kind == TokenKind::Eq {
+                                i += 1;
+                                span_index += 1;
+                                TokenType::Neq
+                            } else {
+                                TokenType::Not
+                            },
                         TokenKind::Colon =>
                             if tokens[i].kind == TokenKind::Colon {
                                 i += 1;
@@ -138,7 +152,6 @@ impl Lexer {
                         TokenKind::Tilde => TokenType::Tilde,
                         TokenKind::Question => TokenType::Question,
                         TokenKind::Dollar => TokenType::Dollar,
-                        TokenKind::Not => TokenType::Not,
                         TokenKind::Lt => TokenType::Lt,
                         TokenKind::Gt => TokenType::Gt,
                         TokenKind::And => TokenType::And,
@@ -185,9 +198,11 @@ pub enum TokenType {
     LSelf,
     Extern,
     Const,
+    Static,
     For,
     Loop,
     Impl,
+    Dyn,
     Unsafe,
     Match,
     Box,
@@ -264,6 +279,10 @@ pub enum TokenType {
     Dollar,
     /// "="
     Eq,
+    /// "=="
+    Deq,
+    /// "!="
+    Neq,
     /// "!"
     Not,
     /// "<"
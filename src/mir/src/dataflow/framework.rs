@@ -0,0 +1,200 @@
+//! a generic worklist based dataflow engine
+//! the state of an analysis is a `Bitset` which is propagated through the control flow graph
+//! until a fixpoint is reached
+
+use ds::Bitset;
+use index::{Idx, IndexVec};
+use lcore::mir::*;
+use std::collections::VecDeque;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Forward,
+    Backward,
+}
+
+pub trait Analysis<'tcx> {
+    /// the type that each bit of the state represents
+    type Idx: Idx;
+
+    const DIRECTION: Direction;
+
+    /// the number of bits in the state
+    fn domain_size(&self, mir: &Mir<'tcx>) -> usize;
+
+    /// the state on entry to the entry block for forward analyses,
+    /// and the state on exit of every block without successors for backward analyses
+    /// `state` initially holds the bottom value
+    fn initialize_start_block(&self, mir: &Mir<'tcx>, state: &mut Bitset<Self::Idx>);
+
+    /// the initial state of every block, this must be the identity of `join`
+    fn bottom_value(&self, mir: &Mir<'tcx>) -> Bitset<Self::Idx> {
+        Bitset::new(self.domain_size(mir))
+    }
+
+    /// merges `other` into `state` where control flow meets
+    /// returns whether `state` has changed
+    fn join(&self, state: &mut Bitset<Self::Idx>, other: &Bitset<Self::Idx>) -> bool {
+        state.union(other)
+    }
+
+    fn apply_stmt_effect(&self, state: &mut Bitset<Self::Idx>, stmt: &Stmt<'tcx>);
+
+    fn apply_terminator_effect(
+        &self,
+        state: &mut Bitset<Self::Idx>,
+        terminator: &Terminator<'tcx>,
+    );
+
+    fn iterate_to_fixpoint<'a>(self, mir: &'a Mir<'tcx>) -> Results<'a, 'tcx, Self>
+    where
+        Self: Sized,
+    {
+        Engine::new(mir, self).iterate_to_fixpoint()
+    }
+}
+
+/// observes the intermediate states of an analysis
+/// each method is given the state right before the effect of the visited
+/// statement or terminator is applied (in the direction of the analysis)
+pub trait ResultsVisitor<'tcx, I: Idx> {
+    fn visit_block_start(&mut self, _state: &Bitset<I>, _block: BlockId) {
+    }
+
+    fn visit_stmt(&mut self, _state: &Bitset<I>, _stmt: &Stmt<'tcx>) {
+    }
+
+    fn visit_terminator(&mut self, _state: &Bitset<I>, _terminator: &Terminator<'tcx>) {
+    }
+}
+
+struct Engine<'a, 'tcx, A: Analysis<'tcx>> {
+    mir: &'a Mir<'tcx>,
+    analysis: A,
+    /// the state at the start of each block in the direction of the analysis
+    /// i.e. the state on entry for forward analyses, and on exit for backward analyses
+    states: IndexVec<BlockId, Bitset<A::Idx>>,
+}
+
+impl<'a, 'tcx, A: Analysis<'tcx>> Engine<'a, 'tcx, A> {
+    fn new(mir: &'a Mir<'tcx>, analysis: A) -> Self {
+        let bottom = analysis.bottom_value(mir);
+        let mut states = IndexVec::from_elem_n(bottom, mir.len());
+        match A::DIRECTION {
+            Direction::Forward => analysis.initialize_start_block(mir, &mut states[ENTRY_BLOCK]),
+            Direction::Backward =>
+                for (id, block) in mir.basic_blocks.iter_enumerated() {
+                    if block.terminator().successors().is_empty() {
+                        analysis.initialize_start_block(mir, &mut states[id]);
+                    }
+                },
+        }
+        Self { mir, analysis, states }
+    }
+
+    fn iterate_to_fixpoint(mut self) -> Results<'a, 'tcx, A> {
+        let mir = self.mir;
+        // visiting the blocks in rpo (or postorder for backward analyses) initially
+        // means most blocks will have seen all their inputs before they are first processed
+        let order = match A::DIRECTION {
+            Direction::Forward => rpo(mir).map(|(id, _)| id).collect::<VecDeque<_>>(),
+            Direction::Backward => postorder(mir).map(|(id, _)| id).collect(),
+        };
+        let mut queued = Bitset::new(mir.len());
+        order.iter().for_each(|&id| queued.set(id));
+        let mut worklist = order;
+        let predecessors = mir.predecessors();
+
+        while let Some(id) = worklist.pop_front() {
+            queued.unset(id);
+            let mut state = self.states[id].clone();
+            let block = &mir[id];
+            let next = match A::DIRECTION {
+                Direction::Forward => {
+                    apply_block_effects_forward(&self.analysis, &mut state, block, &mut ());
+                    block.terminator().successors()
+                }
+                Direction::Backward => {
+                    apply_block_effects_backward(&self.analysis, &mut state, block, &mut ());
+                    predecessors[id].clone()
+                }
+            };
+            for next in next {
+                if self.analysis.join(&mut self.states[next], &state) && queued.insert(next) {
+                    worklist.push_back(next);
+                }
+            }
+        }
+
+        Results { mir, analysis: self.analysis, states: self.states }
+    }
+}
+
+impl<'tcx, I: Idx> ResultsVisitor<'tcx, I> for () {
+}
+
+fn apply_block_effects_forward<'tcx, A: Analysis<'tcx>>(
+    analysis: &A,
+    state: &mut Bitset<A::Idx>,
+    block: &BasicBlock<'tcx>,
+    visitor: &mut impl ResultsVisitor<'tcx, A::Idx>,
+) {
+    for stmt in &block.stmts {
+        visitor.visit_stmt(state, stmt);
+        analysis.apply_stmt_effect(state, stmt);
+    }
+    let terminator = block.terminator();
+    visitor.visit_terminator(state, terminator);
+    analysis.apply_terminator_effect(state, terminator);
+}
+
+fn apply_block_effects_backward<'tcx, A: Analysis<'tcx>>(
+    analysis: &A,
+    state: &mut Bitset<A::Idx>,
+    block: &BasicBlock<'tcx>,
+    visitor: &mut impl ResultsVisitor<'tcx, A::Idx>,
+) {
+    let terminator = block.terminator();
+    visitor.visit_terminator(state, terminator);
+    analysis.apply_terminator_effect(state, terminator);
+    for stmt in block.stmts.iter().rev() {
+        visitor.visit_stmt(state, stmt);
+        analysis.apply_stmt_effect(state, stmt);
+    }
+}
+
+/// the fixpoint of an analysis
+pub struct Results<'a, 'tcx, A: Analysis<'tcx>> {
+    mir: &'a Mir<'tcx>,
+    analysis: A,
+    states: IndexVec<BlockId, Bitset<A::Idx>>,
+}
+
+impl<'a, 'tcx, A: Analysis<'tcx>> Results<'a, 'tcx, A> {
+    /// replays the analysis over every block, passing the intermediate states to `visitor`
+    pub fn visit_with(&self, visitor: &mut impl ResultsVisitor<'tcx, A::Idx>) {
+        for (id, block) in self.mir.basic_blocks.iter_enumerated() {
+            let mut state = self.states[id].clone();
+            visitor.visit_block_start(&state, id);
+            match A::DIRECTION {
+                Direction::Forward =>
+                    apply_block_effects_forward(&self.analysis, &mut state, block, visitor),
+                Direction::Backward =>
+                    apply_block_effects_backward(&self.analysis, &mut state, block, visitor),
+            }
+        }
+    }
+
+    /// the state after the effects of all of `block` have been applied
+    pub fn block_end_state(&self, block: BlockId) -> Bitset<A::Idx> {
+        let mut state = self.states[block].clone();
+        let block = &self.mir[block];
+        match A::DIRECTION {
+            Direction::Forward =>
+                apply_block_effects_forward(&self.analysis, &mut state, block, &mut ()),
+            Direction::Backward =>
+                apply_block_effects_backward(&self.analysis, &mut state, block, &mut ()),
+        }
+        state
+    }
+}
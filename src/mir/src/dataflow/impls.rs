@@ -0,0 +1,204 @@
+//! the analyses built on top of the dataflow framework
+
+use super::framework::{Analysis, Direction};
+use super::move_paths::{MovePathIdx, MovePaths};
+use super::LvalueReads;
+use ds::Bitset;
+use lcore::mir::*;
+use lcore::ty::Projection;
+
+/// the move paths that are initialized on some path to a given point
+pub struct MaybeInitializedPaths<'a, 'tcx> {
+    move_paths: &'a MovePaths<'tcx>,
+}
+
+/// the move paths that are initialized on every path to a given point
+pub struct DefinitelyInitializedPaths<'a, 'tcx> {
+    move_paths: &'a MovePaths<'tcx>,
+}
+
+/// the locals whose current value may be read later
+pub struct MaybeLiveLocals;
+
+impl<'a, 'tcx> MaybeInitializedPaths<'a, 'tcx> {
+    pub fn new(move_paths: &'a MovePaths<'tcx>) -> Self {
+        Self { move_paths }
+    }
+
+    fn init(&self, state: &mut Bitset<MovePathIdx>, lvalue: &Lvalue<'tcx>) {
+        if let Some(path) = self.move_paths.find_assigned(lvalue) {
+            self.move_paths.with_descendants(path).into_iter().for_each(|path| state.set(path));
+        }
+    }
+}
+
+impl<'a, 'tcx> Analysis<'tcx> for MaybeInitializedPaths<'a, 'tcx> {
+    type Idx = MovePathIdx;
+
+    const DIRECTION: Direction = Direction::Forward;
+
+    fn domain_size(&self, _mir: &Mir<'tcx>) -> usize {
+        self.move_paths.paths.len()
+    }
+
+    fn initialize_start_block(&self, mir: &Mir<'tcx>, state: &mut Bitset<MovePathIdx>) {
+        // everything except the locals (i.e. arguments and upvars) start off initialized
+        for (id, var) in mir.vars.iter_enumerated() {
+            if var.kind != VarKind::Local {
+                let root = self.move_paths.root(id);
+                self.move_paths.with_descendants(root).into_iter().for_each(|path| state.set(path));
+            }
+        }
+    }
+
+    fn apply_stmt_effect(&self, state: &mut Bitset<MovePathIdx>, stmt: &Stmt<'tcx>) {
+        match stmt.kind {
+            StmtKind::Assign(lvalue, _) => self.init(state, &lvalue),
+            StmtKind::StorageLive(id) =>
+                for path in self.move_paths.with_descendants(self.move_paths.root(id)) {
+                    state.unset(path);
+                },
+            StmtKind::Nop => {}
+        }
+    }
+
+    fn apply_terminator_effect(
+        &self,
+        state: &mut Bitset<MovePathIdx>,
+        terminator: &Terminator<'tcx>,
+    ) {
+        if let TerminatorKind::Call { lvalue, .. } = terminator.kind {
+            self.init(state, &lvalue);
+        }
+    }
+}
+
+impl<'a, 'tcx> DefinitelyInitializedPaths<'a, 'tcx> {
+    pub fn new(move_paths: &'a MovePaths<'tcx>) -> Self {
+        Self { move_paths }
+    }
+
+    fn init(&self, state: &mut Bitset<MovePathIdx>, path: MovePathIdx) {
+        let paths = self.move_paths;
+        paths.with_descendants(path).into_iter().for_each(|path| state.set(path));
+        // a parent becomes initialized once all of its fields are
+        for parent in paths.parents(path) {
+            let children = &paths.paths[parent].children;
+            if !paths.is_complete(parent) || !children.iter().all(|&child| state.is_set(child)) {
+                break;
+            }
+            state.set(parent);
+        }
+    }
+
+    fn uninit(&self, state: &mut Bitset<MovePathIdx>, path: MovePathIdx) {
+        let paths = self.move_paths;
+        paths.with_descendants(path).into_iter().for_each(|path| state.unset(path));
+        paths.parents(path).for_each(|path| state.unset(path));
+    }
+}
+
+impl<'a, 'tcx> Analysis<'tcx> for DefinitelyInitializedPaths<'a, 'tcx> {
+    type Idx = MovePathIdx;
+
+    const DIRECTION: Direction = Direction::Forward;
+
+    fn domain_size(&self, _mir: &Mir<'tcx>) -> usize {
+        self.move_paths.paths.len()
+    }
+
+    fn bottom_value(&self, mir: &Mir<'tcx>) -> Bitset<MovePathIdx> {
+        let mut state = Bitset::new(self.domain_size(mir));
+        state.set_all();
+        state
+    }
+
+    fn initialize_start_block(&self, mir: &Mir<'tcx>, state: &mut Bitset<MovePathIdx>) {
+        state.clear();
+        for (id, var) in mir.vars.iter_enumerated() {
+            if var.kind != VarKind::Local {
+                self.init(state, self.move_paths.root(id));
+            }
+        }
+    }
+
+    fn join(&self, state: &mut Bitset<MovePathIdx>, other: &Bitset<MovePathIdx>) -> bool {
+        state.intersect(other)
+    }
+
+    fn apply_stmt_effect(&self, state: &mut Bitset<MovePathIdx>, stmt: &Stmt<'tcx>) {
+        match stmt.kind {
+            StmtKind::Assign(lvalue, _) =>
+                if let Some(path) = self.move_paths.find_assigned(&lvalue) {
+                    self.init(state, path);
+                },
+            StmtKind::StorageLive(id) => self.uninit(state, self.move_paths.root(id)),
+            StmtKind::Nop => {}
+        }
+    }
+
+    fn apply_terminator_effect(
+        &self,
+        state: &mut Bitset<MovePathIdx>,
+        terminator: &Terminator<'tcx>,
+    ) {
+        if let TerminatorKind::Call { lvalue, .. } = terminator.kind {
+            if let Some(path) = self.move_paths.find_assigned(&lvalue) {
+                self.init(state, path);
+            }
+        }
+    }
+}
+
+impl MaybeLiveLocals {
+    /// writing to an entire local kills it, writing through a pointer reads the pointer
+    fn def(state: &mut Bitset<VarId>, lvalue: &Lvalue<'_>) {
+        if lvalue.projs.is_empty() {
+            state.unset(lvalue.id);
+        } else if lvalue.projs.iter().any(|proj| proj == Projection::Deref) {
+            state.set(lvalue.id);
+        }
+    }
+
+    /// every lvalue visited by `f` is read and so is live
+    fn uses<'tcx>(state: &mut Bitset<VarId>, f: impl FnOnce(&mut dyn MirVisitor<'tcx>)) {
+        f(&mut LvalueReads(|_: SpanInfo, lvalue: &Lvalue<'tcx>| state.set(lvalue.id)))
+    }
+}
+
+impl<'tcx> Analysis<'tcx> for MaybeLiveLocals {
+    type Idx = VarId;
+
+    const DIRECTION: Direction = Direction::Backward;
+
+    fn domain_size(&self, mir: &Mir<'tcx>) -> usize {
+        mir.vars.len()
+    }
+
+    fn initialize_start_block(&self, _mir: &Mir<'tcx>, _state: &mut Bitset<VarId>) {}
+
+    fn apply_stmt_effect(&self, state: &mut Bitset<VarId>, stmt: &Stmt<'tcx>) {
+        match &stmt.kind {
+            StmtKind::Assign(lvalue, rvalue) => {
+                Self::def(state, lvalue);
+                Self::uses(state, |uses| uses.visit_rvalue(stmt.info, rvalue));
+            }
+            StmtKind::StorageLive(id) => state.unset(*id),
+            StmtKind::Nop => {}
+        }
+    }
+
+    fn apply_terminator_effect(&self, state: &mut Bitset<VarId>, terminator: &Terminator<'tcx>) {
+        let info = terminator.info;
+        match &terminator.kind {
+            TerminatorKind::Call { f, args, lvalue, .. } => {
+                Self::def(state, lvalue);
+                Self::uses(state, |uses| {
+                    uses.visit_operand(info, f);
+                    args.iter().for_each(|arg| uses.visit_operand(info, arg));
+                });
+            }
+            _ => Self::uses(state, |uses| uses.visit_terminator(terminator)),
+        }
+    }
+}
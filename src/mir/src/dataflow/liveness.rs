@@ -0,0 +1,65 @@
+//! warnings for unused variables and assignments whose values are never read
+
+use super::{var_name, Analysis, MaybeLiveLocals, ResultsVisitor};
+use crate::error::MirError;
+use crate::uses::UseCounts;
+use ds::Bitset;
+use lcore::mir::*;
+use lcore::ty::TyCtx;
+
+pub fn check_liveness<'a, 'tcx>(tcx: TyCtx<'tcx>, mir: &'a Mir<'tcx>) {
+    let uses = UseCounts::new(mir);
+
+    // only user declared bindings are linted, and the `_` prefix opts out
+    // a variable that is never read at all is reported once as unused instead of at each assignment
+    let mut checked = Bitset::new(mir.vars.len());
+    for (id, var) in mir.vars.iter_enumerated() {
+        if var.kind != VarKind::Local || uses.declared.is_unset(id) {
+            continue;
+        }
+        let name = var_name(var);
+        if name.starts_with('_') {
+            continue;
+        }
+        if uses.reads[id] == 0 {
+            tcx.sess.emit_warning(var.info.span, MirError::UnusedVariable(name));
+        } else if uses.borrowed.is_unset(id) {
+            // a borrowed variable may be read through its reference
+            checked.set(id);
+        }
+    }
+
+    MaybeLiveLocals.iterate_to_fixpoint(mir).visit_with(&mut LivenessChecker { tcx, mir, checked });
+}
+
+/// reports assignments to locals that are dead immediately after the assignment
+struct LivenessChecker<'a, 'tcx> {
+    tcx: TyCtx<'tcx>,
+    mir: &'a Mir<'tcx>,
+    /// the locals that assignments are checked for
+    checked: Bitset<VarId>,
+}
+
+impl<'a, 'tcx> LivenessChecker<'a, 'tcx> {
+    fn check_assignment(&self, state: &Bitset<VarId>, info: SpanInfo, lvalue: &Lvalue<'tcx>) {
+        let id = lvalue.id;
+        if lvalue.projs.is_empty() && self.checked.is_set(id) && state.is_unset(id) {
+            let name = var_name(&self.mir.vars[id]);
+            self.tcx.sess.emit_warning(info.span, MirError::UnusedAssignment(name));
+        }
+    }
+}
+
+impl<'a, 'tcx> ResultsVisitor<'tcx, VarId> for LivenessChecker<'a, 'tcx> {
+    fn visit_stmt(&mut self, state: &Bitset<VarId>, stmt: &Stmt<'tcx>) {
+        if let StmtKind::Assign(lvalue, _) = &stmt.kind {
+            self.check_assignment(state, stmt.info, lvalue);
+        }
+    }
+
+    fn visit_terminator(&mut self, state: &Bitset<VarId>, terminator: &Terminator<'tcx>) {
+        if let TerminatorKind::Call { lvalue, .. } = &terminator.kind {
+            self.check_assignment(state, terminator.info, lvalue);
+        }
+    }
+}
@@ -1,89 +1,210 @@
-mod error;
+mod framework;
+mod impls;
+mod liveness;
+mod move_paths;
+
+pub use framework::{Analysis, Direction, Results, ResultsVisitor};
+pub use impls::{DefinitelyInitializedPaths, MaybeInitializedPaths, MaybeLiveLocals};
+pub use move_paths::{MovePath, MovePathIdx, MovePaths};
 
 use ast::Mutability;
 use ds::Bitset;
-use lcore::mir::{self, *};
+use index::IndexVec;
+use lcore::mir::*;
 use lcore::ty::TyCtx;
-use std::ops::Deref;
+use span::Span;
 
-use self::error::MirError;
+use crate::error::MirError;
 
 pub fn analyze<'a, 'tcx>(tcx: TyCtx<'tcx>, mir: &'a Mir<'tcx>) {
-    MirAnalysisCtxt::new(tcx, mir).analyze()
+    let move_paths = MovePaths::build(mir);
+    let results = DefinitelyInitializedPaths::new(&move_paths).iterate_to_fixpoint(mir);
+    results.visit_with(&mut UninitChecker {
+        tcx,
+        mir,
+        move_paths: &move_paths,
+        results: &results,
+        predecessors: mir.predecessors(),
+        block: ENTRY_BLOCK,
+        declared: vec![],
+    });
+    MaybeInitializedPaths::new(&move_paths)
+        .iterate_to_fixpoint(mir)
+        .visit_with(&mut ImmutabilityChecker { tcx, mir, move_paths: &move_paths });
+    liveness::check_liveness(tcx, mir);
 }
 
-struct MirAnalysisCtxt<'a, 'tcx> {
+/// the name of a variable as written in its binding
+/// (compiler introduced locals such as the self reference of a named closure have none)
+fn var_name(var: &Var<'_>) -> String {
+    match var.ident {
+        Some(ident) => ident.to_string(),
+        None => "<unnamed>".to_owned(),
+    }
+}
+
+/// reports reads of paths that are not definitely initialized
+struct UninitChecker<'a, 'r, 'tcx> {
     tcx: TyCtx<'tcx>,
     mir: &'a Mir<'tcx>,
-    // bit is set to one if variable is initialized
-    initialized: Bitset<VarId>,
+    move_paths: &'a MovePaths<'tcx>,
+    results: &'r Results<'a, 'tcx, DefinitelyInitializedPaths<'a, 'tcx>>,
+    predecessors: IndexVec<BlockId, Vec<BlockId>>,
+    /// the block currently being visited
+    block: BlockId,
+    /// the locals declared so far in the current block
+    declared: Vec<VarId>,
 }
 
-impl<'a, 'tcx> MirAnalysisCtxt<'a, 'tcx> {
-    pub fn new(tcx: TyCtx<'tcx>, mir: &'a Mir<'tcx>) -> Self {
-        Self { tcx, mir, initialized: Bitset::new(mir.vars.len()) }
+impl<'a, 'r, 'tcx> UninitChecker<'a, 'r, 'tcx> {
+    fn check_reads(&self, state: &Bitset<MovePathIdx>, f: impl FnOnce(&mut dyn MirVisitor<'tcx>)) {
+        let check_read =
+            |info: SpanInfo, lvalue: &Lvalue<'tcx>| self.check_read(state, info, lvalue);
+        f(&mut LvalueReads(check_read))
     }
 
-    pub fn analyze(&mut self) {
-        // can't just call `visit_mir`, as that visits blocks in some arbitrary order
-        for (_, block) in mir::rpo(self.mir) {
-            self.visit_basic_block(block);
+    /// assigning through a projection that is not a field reads its base
+    fn check_assigned(&self, state: &Bitset<MovePathIdx>, info: SpanInfo, lvalue: &Lvalue<'tcx>) {
+        if self.move_paths.find_assigned(lvalue).is_none() {
+            self.check_read(state, info, lvalue);
         }
     }
-}
 
-impl<'a, 'tcx> MirAnalysisCtxt<'a, 'tcx> {
-    fn is_init(&self, lvalue: &Lvalue<'tcx>) -> bool {
-        !self.is_uninit(lvalue)
+    fn check_read(&self, state: &Bitset<MovePathIdx>, info: SpanInfo, lvalue: &Lvalue<'tcx>) {
+        let var = self.mir.vars[lvalue.id];
+        let path = self.move_paths.find(lvalue);
+        // only locals can possibly be uninitialized
+        if var.kind != VarKind::Local || state.is_set(path) {
+            return;
+        }
+        let path = self.first_uninit_path(state, path);
+        let path_str = self.move_paths.path_str(self.mir, path);
+        let err = MirError::UninitializedVariable(path_str.clone());
+        let mut diag = self.tcx.sess.build_error(info.span, err);
+        match self.find_uninit_branch(path, lvalue.id) {
+            Some(span) => diag.labelled_span(
+                span,
+                format!("`{}` is not initialized on every path out of this branch", path_str),
+            ),
+            None => diag.labelled_span(var.info.span, format!("`{}` declared here", path_str)),
+        };
+        diag.emit();
     }
 
-    fn is_uninit(&self, lvalue: &Lvalue<'tcx>) -> bool {
-        // we only need to check `lvalue.id` as all the projections of an lvalue will
-        // also be uninit if the variable itself is uninit
-        // we only need to check locals for initialization
-        let varkind = self.mir.vars[lvalue.id].kind;
-        varkind == VarKind::Local && self.initialized.is_unset(lvalue.id)
+    /// the first path that is uninitialized in `state` out of `path` and its fields
+    fn first_uninit_path(&self, state: &Bitset<MovePathIdx>, path: MovePathIdx) -> MovePathIdx {
+        if !self.move_paths.is_complete(path) {
+            return path;
+        }
+        let children = &self.move_paths.paths[path].children;
+        match children.iter().find(|&&child| state.is_unset(child)) {
+            Some(&child) => self.first_uninit_path(state, child),
+            None => path,
+        }
+    }
+
+    /// walks backwards from the current block along the edges where `path` is uninitialized
+    /// until a branch is found, as that is where the path diverges from the initialization
+    fn find_uninit_branch(&self, path: MovePathIdx, var: VarId) -> Option<Span> {
+        if self.declared.contains(&var) {
+            return None;
+        }
+        let declares = |block: BlockId| {
+            self.mir[block].stmts.iter().any(|stmt| stmt.kind == StmtKind::StorageLive(var))
+        };
+        let mut visited = Bitset::new(self.mir.len());
+        let mut stack = vec![self.block];
+        while let Some(block) = stack.pop() {
+            if !visited.insert(block) {
+                continue;
+            }
+            for &pred in &self.predecessors[block] {
+                if self.results.block_end_state(pred).is_set(path) {
+                    continue;
+                }
+                let terminator = self.mir[pred].terminator();
+                if terminator.successors().len() > 1 {
+                    return Some(terminator.info.span);
+                }
+                if !declares(pred) {
+                    stack.push(pred);
+                }
+            }
+        }
+        None
     }
 }
 
-impl<'a, 'tcx> MirVisitor<'tcx> for MirAnalysisCtxt<'a, 'tcx> {
-    fn visit_stmt(&mut self, stmt: &Stmt<'tcx>) {
+impl<'a, 'r, 'tcx> ResultsVisitor<'tcx, MovePathIdx> for UninitChecker<'a, 'r, 'tcx> {
+    fn visit_block_start(&mut self, _state: &Bitset<MovePathIdx>, block: BlockId) {
+        self.block = block;
+        self.declared.clear();
+    }
+
+    fn visit_stmt(&mut self, state: &Bitset<MovePathIdx>, stmt: &Stmt<'tcx>) {
         match &stmt.kind {
-            StmtKind::Assign(lvalue, _) => {
-                // only have to check `lvalue.id` as its projections inherits its mutability
-                let var = self.mir.vars[lvalue.id];
-                // if the variable is uninitialized, then we consider it an
-                // initialization not an assignment
-                if self.is_init(lvalue) && var.mtbl == Mutability::Imm {
-                    self.sess.emit_error(
-                        stmt.info.span,
-                        MirError::AssignmentToImmutableVar(var.info.span),
-                    );
-                }
-                self.initialized.set(lvalue.id);
+            StmtKind::Assign(lvalue, rvalue) => {
+                self.check_assigned(state, stmt.info, lvalue);
+                self.check_reads(state, |reads| reads.visit_rvalue(stmt.info, rvalue));
             }
+            StmtKind::StorageLive(var) => self.declared.push(*var),
             StmtKind::Nop => {}
         }
-
-        self.walk_stmt(stmt);
     }
 
-    fn visit_lvalue(&mut self, info: SpanInfo, lvalue: &Lvalue<'tcx>) {
-        if self.is_uninit(lvalue) {
-            self.sess.emit_error(
-                info.span,
-                MirError::UninitializedVariable(self.mir.vars[lvalue.id].info.span),
-            );
+    fn visit_terminator(&mut self, state: &Bitset<MovePathIdx>, terminator: &Terminator<'tcx>) {
+        let info = terminator.info;
+        match &terminator.kind {
+            // the lvalue of a call is written to, not read
+            TerminatorKind::Call { f, args, lvalue, .. } => {
+                self.check_assigned(state, info, lvalue);
+                self.check_reads(state, |reads| {
+                    reads.visit_operand(info, f);
+                    args.iter().for_each(|arg| reads.visit_operand(info, arg));
+                });
+            }
+            _ => self.check_reads(state, |reads| reads.visit_terminator(terminator)),
         }
+    }
+}
 
-        self.walk_lvalue(info, lvalue)
+/// reports assignments to immutable variables that may already be initialized
+struct ImmutabilityChecker<'a, 'tcx> {
+    tcx: TyCtx<'tcx>,
+    mir: &'a Mir<'tcx>,
+    move_paths: &'a MovePaths<'tcx>,
+}
+
+impl<'a, 'tcx> ResultsVisitor<'tcx, MovePathIdx> for ImmutabilityChecker<'a, 'tcx> {
+    fn visit_stmt(&mut self, state: &Bitset<MovePathIdx>, stmt: &Stmt<'tcx>) {
+        if let StmtKind::Assign(lvalue, _) = &stmt.kind {
+            // only have to check `lvalue.id` as its projections inherits its mutability
+            let var = self.mir.vars[lvalue.id];
+            if var.mtbl == Mutability::Mut {
+                return;
+            }
+            // if no part of the path is possibly initialized, then we consider it an
+            // initialization not an assignment
+            let path = self.move_paths.find(lvalue);
+            let mut overlapping = self.move_paths.with_descendants(path);
+            overlapping.extend(self.move_paths.parents(path));
+            if overlapping.into_iter().any(|path| state.is_set(path)) {
+                self.tcx
+                    .sess
+                    .emit_error(stmt.info.span, MirError::AssignmentToImmutableVar(var.info.span));
+            }
+        }
     }
 }
 
-impl<'tcx> Deref for MirAnalysisCtxt<'_, 'tcx> {
-    type Target = TyCtx<'tcx>;
+/// calls the closure on every lvalue that is visited
+struct LvalueReads<F>(F);
 
-    fn deref(&self) -> &Self::Target {
-        &self.tcx
+impl<'tcx, F> MirVisitor<'tcx> for LvalueReads<F>
+where
+    F: FnMut(SpanInfo, &Lvalue<'tcx>),
+{
+    fn visit_lvalue(&mut self, info: SpanInfo, lvalue: &Lvalue<'tcx>) {
+        (self.0)(info, lvalue)
     }
 }
@@ -0,0 +1,157 @@
+//! a move path is a place whose initialization is tracked separately from its parent,
+//! i.e. a variable followed by some (possibly zero) field projections
+//! this allows structures to be initialized one field at a time
+
+use index::{Idx, IndexVec};
+use ir::FieldIdx;
+use lcore::mir::*;
+use lcore::ty::{Projection, Ty, TyKind};
+
+index::newtype_index!(
+    pub struct MovePathIdx {
+        DEBUG_FORMAT = "mp{}"
+    }
+);
+
+#[derive(Debug)]
+pub struct MovePath<'tcx> {
+    pub var: VarId,
+    pub ty: Ty<'tcx>,
+    pub parent: Option<MovePathIdx>,
+    /// the field this path projects out of its parent
+    pub field: Option<FieldIdx>,
+    pub children: Vec<MovePathIdx>,
+}
+
+#[derive(Debug)]
+pub struct MovePaths<'tcx> {
+    pub paths: IndexVec<MovePathIdx, MovePath<'tcx>>,
+    /// the path of each variable with no projections
+    roots: IndexVec<VarId, MovePathIdx>,
+}
+
+impl<'tcx> MovePaths<'tcx> {
+    /// creates a path for every variable and every field projection of a variable in `mir`
+    pub fn build(mir: &Mir<'tcx>) -> Self {
+        let mut paths = IndexVec::new();
+        let roots = mir
+            .vars
+            .iter_enumerated()
+            .map(|(var, v)| {
+                paths.push(MovePath { var, ty: v.ty, parent: None, field: None, children: vec![] })
+            })
+            .collect();
+        let mut move_paths = Self { paths, roots };
+        let mut builder = MovePathsBuilder(&mut move_paths);
+        builder.visit_mir(mir);
+        move_paths
+    }
+
+    pub fn root(&self, var: VarId) -> MovePathIdx {
+        self.roots[var]
+    }
+
+    /// the path of the longest prefix of `lvalue` that consists only of field projections
+    pub fn find(&self, lvalue: &Lvalue<'tcx>) -> MovePathIdx {
+        let mut path = self.roots[lvalue.id];
+        for proj in lvalue.projs {
+            match proj {
+                Projection::Field(field, _) => path = self.child(path, field).unwrap(),
+                Projection::Deref | Projection::PointerCast(..) => break,
+            }
+        }
+        path
+    }
+
+    /// returns the path that `lvalue` initializes when assigned to
+    /// assigning through a pointer does not initialize any tracked path
+    pub fn find_assigned(&self, lvalue: &Lvalue<'tcx>) -> Option<MovePathIdx> {
+        if lvalue.projs.iter().all(|proj| matches!(proj, Projection::Field(..))) {
+            Some(self.find(lvalue))
+        } else {
+            None
+        }
+    }
+
+    fn child(&self, path: MovePathIdx, field: FieldIdx) -> Option<MovePathIdx> {
+        self.paths[path].children.iter().copied().find(|&c| self.paths[c].field == Some(field))
+    }
+
+    /// `path` and all its descendants
+    pub fn with_descendants(&self, path: MovePathIdx) -> Vec<MovePathIdx> {
+        let mut paths = vec![path];
+        let mut i = 0;
+        while i < paths.len() {
+            paths.extend(&self.paths[paths[i]].children);
+            i += 1;
+        }
+        paths
+    }
+
+    /// the proper ancestors of `path`, starting from its parent
+    pub fn parents(&self, path: MovePathIdx) -> impl Iterator<Item = MovePathIdx> + '_ {
+        std::iter::successors(self.paths[path].parent, move |&path| self.paths[path].parent)
+    }
+
+    /// whether every field of `path` has a path of its own
+    /// a path is initialized once each of its fields are
+    pub fn is_complete(&self, path: MovePathIdx) -> bool {
+        let path = &self.paths[path];
+        let field_count = match path.ty.kind {
+            TyKind::Tuple(tys) => tys.len(),
+            TyKind::Adt(adt, _) if !adt.is_enum() => adt.single_variant().fields.len(),
+            _ => return false,
+        };
+        field_count > 0 && path.children.len() == field_count
+    }
+
+    /// the source representation of `path`, i.e. `x.0.1`
+    pub fn path_str(&self, mir: &Mir<'tcx>, path: MovePathIdx) -> String {
+        let path = &self.paths[path];
+        match path.parent {
+            Some(parent) => {
+                let parent_str = self.path_str(mir, parent);
+                let field = path.field.unwrap();
+                match self.paths[parent].ty.kind {
+                    TyKind::Adt(adt, _) if !adt.is_enum() => {
+                        let ident = adt.single_variant().fields[field.index()].ident;
+                        format!("{}.{}", parent_str, ident)
+                    }
+                    _ => format!("{}.{}", parent_str, field.index()),
+                }
+            }
+            None => super::var_name(&mir.vars[path.var]),
+        }
+    }
+}
+
+struct MovePathsBuilder<'a, 'tcx>(&'a mut MovePaths<'tcx>);
+
+impl<'a, 'tcx> MirVisitor<'tcx> for MovePathsBuilder<'a, 'tcx> {
+    fn visit_lvalue(&mut self, _info: SpanInfo, lvalue: &Lvalue<'tcx>) {
+        let paths = &mut self.0;
+        let mut path = paths.roots[lvalue.id];
+        for proj in lvalue.projs {
+            let (field, ty) = match proj {
+                Projection::Field(field, ty) => (field, ty),
+                Projection::Deref | Projection::PointerCast(..) => break,
+            };
+            path = match paths.child(path, field) {
+                Some(child) => child,
+                None => {
+                    let var = lvalue.id;
+                    let child = MovePath {
+                        var,
+                        ty,
+                        parent: Some(path),
+                        field: Some(field),
+                        children: vec![],
+                    };
+                    let child = paths.paths.push(child);
+                    paths.paths[path].children.push(child);
+                    child
+                }
+            };
+        }
+    }
+}
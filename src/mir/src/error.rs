@@ -0,0 +1,19 @@
+use lcore::mir::PanicKind;
+use span::Span;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+crate enum MirError {
+    #[error("use of possibly uninitialized `{}`", .0)]
+    UninitializedVariable(String),
+    #[error("assignment to immutable variable `{}`", .0.to_string())]
+    AssignmentToImmutableVar(Span),
+    #[error("unused variable `{}`", .0)]
+    UnusedVariable(String),
+    #[error("value assigned to `{}` is never read", .0)]
+    UnusedAssignment(String),
+    #[error("unreachable statement")]
+    UnreachableStatement,
+    #[error("this operation will panic at runtime: {}", .0)]
+    UnconditionalPanic(PanicKind),
+}
@@ -12,12 +12,19 @@ mod tests;
 
 mod const_eval;
 mod dataflow;
+mod error;
+mod ops;
 mod opt;
 mod typecheck;
+mod uses;
 
-pub use dataflow::analyze;
+pub use dataflow::{
+    analyze, Analysis, DefinitelyInitializedPaths, Direction, MaybeInitializedPaths,
+    MaybeLiveLocals, MovePath, MovePathIdx, MovePaths, Results, ResultsVisitor,
+};
 use lcore::queries::Queries;
-pub use opt::{early_opt, late_opt};
+pub use ops::{eval_bin_op, eval_unary_op};
+pub use opt::{early_opt, inline, late_opt};
 pub use typecheck::typecheck;
 
 pub fn provide(queries: &mut Queries) {
@@ -0,0 +1,84 @@
+//! evaluation of operations on constants
+//! shared by constant propagation and the interpreter
+
+use ast::BinOp;
+use lcore::mir::{PanicKind, UnaryOp};
+use lcore::ty::ConstKind;
+
+/// returns `None` if the operation can't be evaluated at compile time
+/// and `Err` if the operation will panic at runtime
+pub fn eval_bin_op(
+    op: BinOp,
+    l: ConstKind,
+    r: ConstKind,
+    checked: bool,
+) -> Option<Result<ConstKind, PanicKind>> {
+    let kind = match (l, r) {
+        (ConstKind::Int(l), ConstKind::Int(r)) => return Some(eval_int_op(op, l, r, checked)),
+        (ConstKind::Float(l), ConstKind::Float(r)) => match op {
+            BinOp::Add => ConstKind::Float(l + r),
+            BinOp::Sub => ConstKind::Float(l - r),
+            BinOp::Mul => ConstKind::Float(l * r),
+            BinOp::Div => ConstKind::Float(l / r),
+            BinOp::Lt => ConstKind::Bool(l < r),
+            BinOp::Gt => ConstKind::Bool(l > r),
+            BinOp::Eq => ConstKind::Bool(l == r),
+            BinOp::Neq => ConstKind::Bool(l != r),
+            BinOp::And | BinOp::Or => return None,
+        },
+        (ConstKind::Bool(l), ConstKind::Bool(r)) => match op {
+            BinOp::And => ConstKind::Bool(l & r),
+            BinOp::Or => ConstKind::Bool(l | r),
+            BinOp::Eq => ConstKind::Bool(l == r),
+            BinOp::Neq => ConstKind::Bool(l != r),
+            _ => return None,
+        },
+        (ConstKind::Discr(l), ConstKind::Discr(r)) => match op {
+            BinOp::Eq => ConstKind::Bool(l == r),
+            BinOp::Neq => ConstKind::Bool(l != r),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(Ok(kind))
+}
+
+/// without runtime checks, overflowing addition, subtraction and multiplication wrap
+/// whereas division by zero or of the minimum integer by `-1` is always an error
+fn eval_int_op(op: BinOp, l: i64, r: i64, checked: bool) -> Result<ConstKind, PanicKind> {
+    let (i, overflowed) = match op {
+        BinOp::Add => l.overflowing_add(r),
+        BinOp::Sub => l.overflowing_sub(r),
+        BinOp::Mul => l.overflowing_mul(r),
+        BinOp::Div if r == 0 => return Err(PanicKind::DivisionByZero),
+        BinOp::Div if l == i64::MIN && r == -1 => return Err(PanicKind::Overflow(op)),
+        BinOp::Div => (l / r, false),
+        BinOp::And => (l & r, false),
+        BinOp::Or => (l | r, false),
+        BinOp::Lt => return Ok(ConstKind::Bool(l < r)),
+        BinOp::Gt => return Ok(ConstKind::Bool(l > r)),
+        BinOp::Eq => return Ok(ConstKind::Bool(l == r)),
+        BinOp::Neq => return Ok(ConstKind::Bool(l != r)),
+    };
+    if overflowed && checked { Err(PanicKind::Overflow(op)) } else { Ok(ConstKind::Int(i)) }
+}
+
+/// returns `None` if the operation can't be evaluated at compile time
+/// and `Err` if negating the minimum integer with runtime checks enabled
+pub fn eval_unary_op(
+    op: UnaryOp,
+    c: ConstKind,
+    checked: bool,
+) -> Option<Result<ConstKind, PanicKind>> {
+    let kind = match (op, c) {
+        (UnaryOp::Neg, ConstKind::Int(i)) => match i.overflowing_neg() {
+            (_, true) if checked => return Some(Err(PanicKind::NegOverflow)),
+            (i, _) => ConstKind::Int(i),
+        },
+        (UnaryOp::Neg, ConstKind::Float(f)) => ConstKind::Float(-f),
+        (UnaryOp::Not, ConstKind::Int(i)) => ConstKind::Int(!i),
+        (UnaryOp::Not, ConstKind::Bool(b)) => ConstKind::Bool(!b),
+        _ => return None,
+    };
+    Some(Ok(kind))
+}
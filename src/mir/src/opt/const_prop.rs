@@ -0,0 +1,199 @@
+//! constant propagation and folding
+//! the locals that are known to hold a constant are replaced by that constant,
+//! operations on constants are evaluated and branches on constants become unconditional
+
+use super::{rvalue_operands_mut, terminator_operands_mut};
+use crate::ops::{eval_bin_op, eval_unary_op};
+use crate::error::MirError;
+use crate::uses::UseCounts;
+use ds::Bitset;
+use lcore::mir::*;
+use lcore::ty::{Const, ConstKind, TyCtx};
+use rustc_hash::FxHashMap;
+
+pub fn propagate_constants<'tcx>(tcx: TyCtx<'tcx>, mir: &mut Mir<'tcx>) {
+    let checked = tcx.sess.opts.debug_assertions();
+    let tracked = self::tracked_vars(mir);
+    loop {
+        let consts = self::single_assignment_consts(mir, &tracked);
+        let mut changed = false;
+        for block in &mut mir.basic_blocks {
+            let mut propagator =
+                ConstPropagator { tcx, checked, tracked: &tracked, known: consts.clone() };
+            changed |= propagator.propagate_block(block);
+        }
+        if !changed {
+            break;
+        }
+        // branches that were made unconditional may have left some blocks unreachable
+        super::remove_dead_blocks(mir);
+    }
+}
+
+/// reports the operations on constants that are guaranteed to panic
+/// this should be run after `propagate_constants` so nothing is reported for unreachable code
+pub fn report_const_panics<'tcx>(tcx: TyCtx<'tcx>, mir: &Mir<'tcx>) {
+    let checked = tcx.sess.opts.debug_assertions();
+    for stmt in mir.basic_blocks.iter().flat_map(|block| &block.stmts) {
+        let result = match &stmt.kind {
+            StmtKind::Assign(_, Rvalue::Bin(op, Operand::Const(l), Operand::Const(r))) =>
+                eval_bin_op(*op, l.kind, r.kind, checked),
+            StmtKind::Assign(_, Rvalue::Unary(op, Operand::Const(c))) =>
+                eval_unary_op(*op, c.kind, checked),
+            _ => continue,
+        };
+        if let Some(Err(panic)) = result {
+            tcx.sess.emit_error(stmt.info.span, MirError::UnconditionalPanic(panic));
+        }
+    }
+}
+
+/// reports the panics that constant propagation would find without optimizing `mir` itself
+/// so whether a program compiles does not depend on the optimization level
+pub fn check_const_panics<'tcx>(tcx: TyCtx<'tcx>, mir: &Mir<'tcx>) {
+    let mut propagated = mir.clone();
+    self::propagate_constants(tcx, &mut propagated);
+    self::report_const_panics(tcx, &propagated);
+}
+
+/// the variables whose values can be tracked
+/// this excludes any variable that is borrowed as it may be written to through the pointer
+fn tracked_vars(mir: &Mir<'_>) -> Bitset<VarId> {
+    let uses = UseCounts::new(mir);
+    let mut tracked = Bitset::new(mir.vars.len());
+    for (id, var) in mir.vars.iter_enumerated() {
+        if var.kind != VarKind::Upvar && uses.borrowed.is_unset(id) {
+            tracked.set(id);
+        }
+    }
+    tracked
+}
+
+/// the locals that are only ever assigned a single constant
+/// these hold that constant wherever they are read, as reads of uninitialized locals are rejected
+fn single_assignment_consts<'tcx>(
+    mir: &Mir<'tcx>,
+    tracked: &Bitset<VarId>,
+) -> FxHashMap<VarId, &'tcx Const<'tcx>> {
+    let writes = UseCounts::new(mir).writes;
+    let mut consts = FxHashMap::default();
+    for stmt in mir.basic_blocks.iter().flat_map(|block| &block.stmts) {
+        match &stmt.kind {
+            StmtKind::Assign(lvalue, Rvalue::Operand(Operand::Const(c)))
+                if lvalue.projs.is_empty() =>
+            {
+                consts.insert(lvalue.id, *c);
+            }
+            _ => {}
+        }
+    }
+    consts.retain(|&var, _| {
+        let kind = mir.vars[var].kind;
+        matches!(kind, VarKind::Tmp | VarKind::Local) && tracked.is_set(var) && writes[var] == 1
+    });
+    consts
+}
+
+struct ConstPropagator<'a, 'tcx> {
+    tcx: TyCtx<'tcx>,
+    /// whether integer overflow panics rather than wraps
+    checked: bool,
+    tracked: &'a Bitset<VarId>,
+    /// the constant value of each variable at the current point in the block
+    known: FxHashMap<VarId, &'tcx Const<'tcx>>,
+}
+
+impl<'a, 'tcx> ConstPropagator<'a, 'tcx> {
+    /// returns whether anything was changed
+    fn propagate_block(&mut self, block: &mut BasicBlock<'tcx>) -> bool {
+        let mut changed = false;
+        for stmt in &mut block.stmts {
+            match &mut stmt.kind {
+                StmtKind::Assign(lvalue, rvalue) => {
+                    changed |= self.substitute(rvalue_operands_mut(rvalue));
+                    if let Some(c) = self.fold(rvalue) {
+                        *rvalue = Rvalue::Operand(Operand::Const(c));
+                        changed = true;
+                    }
+                    self.record_assignment(lvalue, rvalue);
+                }
+                StmtKind::StorageLive(var) => {
+                    self.known.remove(&*var);
+                }
+                StmtKind::Nop => {}
+            }
+        }
+
+        let terminator = block.terminator_mut();
+        changed |= self.substitute(terminator_operands_mut(&mut terminator.kind));
+        if let Some(target) = self::const_target(&terminator.kind) {
+            terminator.kind = TerminatorKind::Branch(target);
+            changed = true;
+        }
+        changed
+    }
+
+    /// replaces the uses of variables with known values with the value
+    fn substitute(&self, operands: Vec<&mut Operand<'tcx>>) -> bool {
+        let mut changed = false;
+        for operand in operands {
+            if let Operand::Lvalue(lvalue) = operand {
+                if !lvalue.projs.is_empty() {
+                    continue;
+                }
+                if let Some(&c) = self.known.get(&lvalue.id) {
+                    *operand = Operand::Const(c);
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+
+    /// evaluates the rvalue if all its operands are constants
+    /// operations that panic are left as is
+    fn fold(&self, rvalue: &Rvalue<'tcx>) -> Option<&'tcx Const<'tcx>> {
+        let kind = match rvalue {
+            Rvalue::Bin(op, Operand::Const(l), Operand::Const(r)) =>
+                eval_bin_op(*op, l.kind, r.kind, self.checked)?.ok()?,
+            Rvalue::Unary(op, Operand::Const(c)) => eval_unary_op(*op, c.kind, self.checked)?.ok()?,
+            _ => return None,
+        };
+        Some(self.tcx.mk_const(kind))
+    }
+
+    fn record_assignment(&mut self, lvalue: &Lvalue<'tcx>, rvalue: &Rvalue<'tcx>) {
+        match rvalue {
+            Rvalue::Operand(Operand::Const(c))
+                if lvalue.projs.is_empty() && self.tracked.is_set(lvalue.id) =>
+            {
+                self.known.insert(lvalue.id, *c);
+            }
+            _ => {
+                self.known.remove(&lvalue.id);
+            }
+        }
+    }
+}
+
+/// the block a branch on a constant will always go to
+fn const_target(kind: &TerminatorKind<'_>) -> Option<BlockId> {
+    match kind {
+        TerminatorKind::Cond(Operand::Const(c), then, els) => match c.kind {
+            ConstKind::Bool(b) => Some(if b { *then } else { *els }),
+            _ => None,
+        },
+        TerminatorKind::Switch { discr: Operand::Const(c), arms, default } => {
+            for (arm, block) in arms {
+                match arm {
+                    Operand::Const(arm) if arm.kind == c.kind => return Some(*block),
+                    Operand::Const(_) => {}
+                    // can't tell whether this arm matches
+                    _ => return None,
+                }
+            }
+            Some(*default)
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,247 @@
+//! copy propagation
+//! `mirgen` writes the value of nearly every subexpression into a temporary,
+//! so most temporaries are only ever copied into some other place
+
+use super::{rvalue_operands_mut, terminator_operands_mut};
+use crate::uses::UseCounts;
+use index::IndexVec;
+use lcore::mir::*;
+use lcore::ty::{Projection, TyCtx};
+
+pub fn propagate_copies<'tcx>(tcx: TyCtx<'tcx>, mir: &mut Mir<'tcx>) {
+    let uses = UseCounts::new(mir);
+    let forwarder = CopyForwarder { tcx, vars: &mir.vars, uses: &uses };
+    for block in &mut mir.basic_blocks {
+        forwarder.forward_copies(block);
+    }
+
+    let uses = UseCounts::new(mir);
+    let predecessors = mir.predecessors();
+    for id in mir.basic_blocks.indices() {
+        self::propagate_call_destination(mir, &uses, &predecessors, id);
+        self::propagate_destinations(&mir.vars, &uses, &mut mir.basic_blocks[id]);
+    }
+}
+
+/// whether `var` is a temporary that is only written to once and never borrowed
+fn is_single_assignment_tmp(vars: &IndexVec<VarId, Var<'_>>, uses: &UseCounts, var: VarId) -> bool {
+    vars[var].kind == VarKind::Tmp && uses.writes[var] == 1 && uses.borrowed.is_unset(var)
+}
+
+/// replaces the reads of a temporary `t` that holds a copy of `src` with `src` itself
+/// t = src
+/// x = t + 1
+/// ->
+/// t = src
+/// x = src + 1
+/// this is only done within the block of the copy and up until `src` is next written to
+struct CopyForwarder<'a, 'tcx> {
+    tcx: TyCtx<'tcx>,
+    vars: &'a IndexVec<VarId, Var<'tcx>>,
+    uses: &'a UseCounts,
+}
+
+impl<'a, 'tcx> CopyForwarder<'a, 'tcx> {
+    fn forward_copies(&self, block: &mut BasicBlock<'tcx>) {
+        for i in 0..block.stmts.len() {
+            let (tmp, src) = match &block.stmts[i].kind {
+                StmtKind::Assign(lvalue, Rvalue::Operand(Operand::Lvalue(src)))
+                    if lvalue.projs.is_empty()
+                        && is_single_assignment_tmp(self.vars, self.uses, lvalue.id)
+                        && self.is_stable(src) =>
+                    (lvalue.id, *src),
+                _ => continue,
+            };
+            if self.forward_copy(&mut block.stmts[i + 1..], tmp, src) {
+                let terminator = block.terminator_mut();
+                for operand in terminator_operands_mut(&mut terminator.kind) {
+                    self.replace_operand(operand, tmp, src);
+                }
+                if let TerminatorKind::Call { lvalue, .. } = &mut terminator.kind {
+                    self.replace_lvalue(lvalue, tmp, src);
+                }
+            }
+        }
+    }
+
+    /// `src` can only be changed by assigning to it directly
+    fn is_stable(&self, src: &Lvalue<'tcx>) -> bool {
+        self.vars[src.id].kind != VarKind::Upvar
+            && self.uses.borrowed.is_unset(src.id)
+            && src.projs.iter().all(|proj| proj != Projection::Deref)
+    }
+
+    /// returns whether `src` is unchanged at the end of `stmts`
+    fn forward_copy(&self, stmts: &mut [Stmt<'tcx>], tmp: VarId, src: Lvalue<'tcx>) -> bool {
+        for stmt in stmts {
+            match &mut stmt.kind {
+                StmtKind::Assign(lvalue, rvalue) => {
+                    let overwrites_src = lvalue.id == src.id;
+                    // the fields of an aggregate are written one at a time so
+                    // its operands may observe the partially written `src`
+                    if overwrites_src && matches!(rvalue, Rvalue::Adt { .. }) {
+                        return false;
+                    }
+                    self.replace_lvalue(lvalue, tmp, src);
+                    self.replace_rvalue(rvalue, tmp, src);
+                    if overwrites_src {
+                        return false;
+                    }
+                }
+                StmtKind::StorageLive(var) if *var == src.id => return false,
+                StmtKind::StorageLive(_) | StmtKind::Nop => {}
+            }
+        }
+        true
+    }
+
+    fn replace_rvalue(&self, rvalue: &mut Rvalue<'tcx>, tmp: VarId, src: Lvalue<'tcx>) {
+        if let Rvalue::Ref(lvalue) | Rvalue::Discriminant(lvalue) = rvalue {
+            self.replace_lvalue(lvalue, tmp, src);
+        }
+        for operand in rvalue_operands_mut(rvalue) {
+            self.replace_operand(operand, tmp, src);
+        }
+    }
+
+    fn replace_operand(&self, operand: &mut Operand<'tcx>, tmp: VarId, src: Lvalue<'tcx>) {
+        if let Operand::Lvalue(lvalue) = operand {
+            self.replace_lvalue(lvalue, tmp, src);
+        }
+    }
+
+    /// `tmp.x.y` becomes `src.x.y`
+    fn replace_lvalue(&self, lvalue: &mut Lvalue<'tcx>, tmp: VarId, src: Lvalue<'tcx>) {
+        if lvalue.id != tmp {
+            return;
+        }
+        let projs = src.projs.iter().chain(lvalue.projs.iter()).collect::<Vec<_>>();
+        *lvalue = Lvalue { id: src.id, projs: self.tcx.intern_lvalue_projections(&projs) };
+    }
+}
+
+/// writes the value of a temporary directly to the place it is copied to
+/// t = <rvalue>
+/// StorageLive(x)
+/// x = t
+/// ->
+/// StorageLive(x)
+/// x = <rvalue>
+fn propagate_destinations<'tcx>(
+    vars: &IndexVec<VarId, Var<'tcx>>,
+    uses: &UseCounts,
+    block: &mut BasicBlock<'tcx>,
+) {
+    for i in 0..block.stmts.len() {
+        let (tmp, rvalue) = match &block.stmts[i].kind {
+            StmtKind::Assign(lvalue, rvalue)
+                if lvalue.projs.is_empty()
+                    && is_single_assignment_tmp(vars, uses, lvalue.id)
+                    && uses.reads[lvalue.id] == 1 =>
+                (lvalue.id, rvalue),
+            _ => continue,
+        };
+        let j = match self::find_copy(&block.stmts[i + 1..], tmp) {
+            Some(j) => i + 1 + j,
+            None => continue,
+        };
+        let dest = match block.stmts[j].kind {
+            StmtKind::Assign(dest, _) => dest,
+            _ => unreachable!(),
+        };
+        if self::may_observe_partial_write(uses, dest, rvalue) {
+            continue;
+        }
+        match std::mem::replace(&mut block.stmts[i].kind, StmtKind::Nop) {
+            StmtKind::Assign(_, rvalue) => block.stmts[j].kind = StmtKind::Assign(dest, rvalue),
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// a copy into `tmp` at the block with id `id` can be propagated into the call that writes `tmp`
+/// as long as that block is only reached by the call
+///     call f() -> t
+/// bb:
+///     StorageLive(x)
+///     x = t
+/// ->
+///     StorageLive(x)
+///     call f() -> x
+/// bb:
+fn propagate_call_destination<'tcx>(
+    mir: &mut Mir<'tcx>,
+    uses: &UseCounts,
+    predecessors: &IndexVec<BlockId, Vec<BlockId>>,
+    id: BlockId,
+) {
+    let (tmp, target) = match &mir.basic_blocks[id].terminator().kind {
+        TerminatorKind::Call { lvalue, target, .. }
+            if lvalue.projs.is_empty()
+                && is_single_assignment_tmp(&mir.vars, uses, lvalue.id)
+                && uses.reads[lvalue.id] == 1
+                && *target != id
+                && predecessors[*target] == [id] =>
+            (lvalue.id, *target),
+        _ => return,
+    };
+    let j = match self::find_copy(&mir.basic_blocks[target].stmts, tmp) {
+        Some(j) => j,
+        None => return,
+    };
+    let dest = match mir.basic_blocks[target].stmts[j].kind {
+        StmtKind::Assign(dest, _) => dest,
+        _ => unreachable!(),
+    };
+    // the call may write through a pointer that `dest` is projected through
+    if dest.projs.iter().any(|proj| proj == Projection::Deref) {
+        return;
+    }
+
+    let stmts = &mut mir.basic_blocks[target].stmts;
+    let declares_dest = stmts[..j].iter().any(|stmt| stmt.kind != StmtKind::Nop);
+    stmts[..=j].iter_mut().for_each(|stmt| stmt.kind = StmtKind::Nop);
+    let block = &mut mir.basic_blocks[id];
+    if declares_dest {
+        let info = block.terminator().info;
+        block.stmts.push(Stmt { info, kind: StmtKind::StorageLive(dest.id) });
+    }
+    match &mut block.terminator_mut().kind {
+        TerminatorKind::Call { lvalue, .. } => *lvalue = dest,
+        _ => unreachable!(),
+    }
+}
+
+/// finds the index of the statement `x = tmp` where `x` is declared by the
+/// statements before it if at all
+fn find_copy(stmts: &[Stmt<'_>], tmp: VarId) -> Option<usize> {
+    let j = stmts
+        .iter()
+        .position(|stmt| !matches!(stmt.kind, StmtKind::StorageLive(_) | StmtKind::Nop))?;
+    let dest = match &stmts[j].kind {
+        StmtKind::Assign(dest, Rvalue::Operand(Operand::Lvalue(lvalue)))
+            if lvalue.id == tmp && lvalue.projs.is_empty() && dest.id != tmp =>
+            dest,
+        _ => return None,
+    };
+    let declares_dest = |stmt: &Stmt<'_>| match stmt.kind {
+        StmtKind::StorageLive(var) => var == dest.id,
+        _ => stmt.kind == StmtKind::Nop,
+    };
+    if stmts[..j].iter().all(declares_dest) { Some(j) } else { None }
+}
+
+/// the fields of an aggregate are written one at a time, so its operands must not depend on
+/// the destination
+fn may_observe_partial_write(uses: &UseCounts, dest: Lvalue<'_>, rvalue: &Rvalue<'_>) -> bool {
+    let fields = match rvalue {
+        Rvalue::Adt { fields, .. } => fields,
+        _ => return false,
+    };
+    uses.borrowed.is_set(dest.id)
+        || dest.projs.iter().any(|proj| proj == Projection::Deref)
+        || fields.iter().any(|field| match field {
+            Operand::Lvalue(lvalue) => lvalue.id == dest.id,
+            Operand::Const(..) | Operand::Item(..) => false,
+        })
+}
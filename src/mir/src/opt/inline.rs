@@ -0,0 +1,269 @@
+//! inlining of small functions and functions marked `#[inline]`
+//! a call to an inlined function is replaced by a copy of the (already inlined) callee body
+
+use ast::{AttrKind, BinOp};
+use index::{Idx, IndexVec};
+use lcore::mir::*;
+use lcore::ty::{HasTyFlags, Instance, InstanceKind, Subst, TyCtx};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+/// the maximum cost of a function that is inlined without being asked to
+const INLINE_THRESHOLD: usize = 30;
+/// the maximum cost of a function marked `#[inline]`
+const HINT_THRESHOLD: usize = 100;
+/// the cost of a call is higher than other operations as it may do arbitrary work
+const CALL_COST: usize = 5;
+
+/// returns whether any call was inlined
+pub fn inline_calls<'tcx>(tcx: TyCtx<'tcx>, caller: Instance<'tcx>, mir: &mut Mir<'tcx>) -> bool {
+    let mut changed = false;
+    let mut call_graph = CallGraph::new(tcx);
+    // the inlined blocks are appended to the end so only the original calls are visited
+    // the calls within the inlined blocks have already been considered when the callee was inlined
+    for id in mir.basic_blocks.indices() {
+        let callee = match self::resolve_callee(tcx, caller, mir[id].terminator()) {
+            Some(callee) => callee,
+            None => continue,
+        };
+        // this must be checked before requesting the callee's body as that would
+        // otherwise depend on the body currently being built
+        if call_graph.may_reach(callee, caller) {
+            continue;
+        }
+        let body = tcx.instance_mir(callee);
+        let unwind = match mir[id].terminator().kind {
+            TerminatorKind::Call { unwind, .. } => unwind,
+            _ => unreachable!(),
+        };
+        // panics unwind straight out of the function they occur in without a landing pad
+        // so the caller's cleanup would be skipped if the callee panicked after being inlined
+        if unwind.is_some() && self::may_panic(body) {
+            continue;
+        }
+        if self::should_inline(tcx, callee, body) {
+            self::inline_call(tcx, mir, id, callee, body);
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// the function called by `terminator` if it is a candidate for inlining
+fn resolve_callee<'tcx>(
+    tcx: TyCtx<'tcx>,
+    caller: Instance<'tcx>,
+    terminator: &Terminator<'tcx>,
+) -> Option<Instance<'tcx>> {
+    let (def_id, substs) = match terminator.kind {
+        TerminatorKind::Call { f: Operand::Item(def_id, substs), .. } =>
+            (def_id, substs.subst(tcx, caller.substs)),
+        _ => return None,
+    };
+    if substs.has_ty_params() {
+        return None;
+    }
+    let callee = Instance::resolve(tcx, def_id, substs).ok()?;
+    match callee.kind {
+        InstanceKind::Item => Some(callee),
+        _ => None,
+    }
+}
+
+fn should_inline<'tcx>(tcx: TyCtx<'tcx>, callee: Instance<'tcx>, body: &Mir<'tcx>) -> bool {
+    // the body is empty if it failed to typecheck
+    if body.basic_blocks.is_empty() || self::defines_closures(body) {
+        return false;
+    }
+    let attrs = tcx.defs().get(callee.def_id).attrs();
+    let threshold = if attrs.iter().any(|attr| attr.kind == AttrKind::Inline) {
+        HINT_THRESHOLD
+    } else {
+        INLINE_THRESHOLD
+    };
+    self::cost(body) <= threshold
+}
+
+/// closure instances take their substitutions from the body they are instantiated in
+/// so moving their definition into another body would change their meaning
+fn defines_closures(mir: &Mir<'_>) -> bool {
+    mir.basic_blocks.iter().flat_map(|block| &block.stmts).any(|stmt| {
+        matches!(stmt.kind, StmtKind::Assign(_, Rvalue::Closure(..)))
+    })
+}
+
+fn cost(mir: &Mir<'_>) -> usize {
+    let mut cost = 0;
+    for block in &mir.basic_blocks {
+        cost += block.stmts.iter().filter(|stmt| matches!(stmt.kind, StmtKind::Assign(..))).count();
+        cost += match block.terminator().kind {
+            TerminatorKind::Call { .. } => CALL_COST,
+            _ => 1,
+        };
+    }
+    cost
+}
+
+/// whether the body may panic without going through a call
+/// (the arithmetic operations may be checked for overflow and division by zero)
+fn may_panic(mir: &Mir<'_>) -> bool {
+    mir.basic_blocks.iter().any(|block| {
+        matches!(block.terminator().kind, TerminatorKind::Panic(..))
+            || block.stmts.iter().any(|stmt| match stmt.kind {
+                StmtKind::Assign(_, Rvalue::Bin(op, ..)) =>
+                    matches!(op, BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div),
+                StmtKind::Assign(_, Rvalue::Unary(op, _)) => op == UnaryOp::Neg,
+                _ => false,
+            })
+    })
+}
+
+/// the instances referenced by each body, built lazily over a single inlining pass
+/// this uses the bodies before inlining so it never depends on `instance_mir`
+struct CallGraph<'tcx> {
+    tcx: TyCtx<'tcx>,
+    /// `None` if the callees are unknown (i.e. the instance is a call through a trait object)
+    callees: FxHashMap<Instance<'tcx>, Option<Vec<Instance<'tcx>>>>,
+}
+
+impl<'tcx> CallGraph<'tcx> {
+    fn new(tcx: TyCtx<'tcx>) -> Self {
+        Self { tcx, callees: Default::default() }
+    }
+
+    fn callees_of(&mut self, instance: Instance<'tcx>) -> Option<&[Instance<'tcx>]> {
+        let tcx = self.tcx;
+        self.callees
+            .entry(instance)
+            .or_insert_with(|| {
+                let mir = match instance.kind {
+                    InstanceKind::Item => tcx.mir_of(instance.def_id),
+                    InstanceKind::Closure(id) => tcx.mir_of(instance.def_id).closures[&id],
+                    InstanceKind::Intrinsic => return Some(vec![]),
+                    // a call through a trait object may end up anywhere
+                    InstanceKind::Virtual(..) => return None,
+                };
+                let mut collector = CalleeCollector { tcx, instance, callees: vec![] };
+                collector.visit_mir(mir);
+                Some(collector.callees)
+            })
+            .as_deref()
+    }
+
+    /// whether `from` may call `to` directly or indirectly
+    /// a recursive function is never inlined into itself
+    fn may_reach(&mut self, from: Instance<'tcx>, to: Instance<'tcx>) -> bool {
+        let mut visited = FxHashSet::default();
+        let mut stack = vec![from];
+        while let Some(instance) = stack.pop() {
+            if instance == to {
+                return true;
+            }
+            if !visited.insert(instance) {
+                continue;
+            }
+            match self.callees_of(instance) {
+                Some(callees) => stack.extend_from_slice(callees),
+                None => return true,
+            }
+        }
+        false
+    }
+}
+
+/// collects the instances referenced by the body of `instance`
+struct CalleeCollector<'tcx> {
+    tcx: TyCtx<'tcx>,
+    instance: Instance<'tcx>,
+    callees: Vec<Instance<'tcx>>,
+}
+
+impl<'tcx> MirVisitor<'tcx> for CalleeCollector<'tcx> {
+    fn visit_rvalue(&mut self, info: SpanInfo, rvalue: &Rvalue<'tcx>) {
+        if let &Rvalue::Closure(_, id, _) = rvalue {
+            self.callees.push(Instance::closure(id, self.instance.substs));
+        }
+        self.walk_rvalue(info, rvalue);
+    }
+
+    fn visit_operand(&mut self, _info: SpanInfo, operand: &Operand<'tcx>) {
+        if let &Operand::Item(def_id, substs) = operand {
+            let substs = substs.subst(self.tcx, self.instance.substs);
+            // a callee that fails to resolve has been reported and can't be inlined anyway
+            if let Ok(callee) = Instance::resolve(self.tcx, def_id, substs) {
+                self.callees.push(callee);
+            }
+        }
+    }
+}
+
+/// replaces the call that terminates the block `id` with the body of `callee`
+/// bb0:
+///     call f(x) -> y
+/// ->
+/// bb0:
+///     param = x
+///     branch <callee entry>
+/// <callee blocks where returns branch to the block below>
+/// bbn:
+///     y = ret
+///     branch <call target>
+/// if the call unwinds to a cleanup block, so does everything in the callee that
+/// would otherwise have unwound to its caller
+/// the body of a generic callee is instantiated with the (concrete) substitutions of the call
+fn inline_call<'tcx>(
+    tcx: TyCtx<'tcx>,
+    mir: &mut Mir<'tcx>,
+    id: BlockId,
+    instance: Instance<'tcx>,
+    callee: &Mir<'tcx>,
+) {
+    let terminator = mir[id].terminator.take().unwrap();
+    let info = terminator.info;
+    let (args, lvalue, target, cleanup) = match terminator.kind {
+        TerminatorKind::Call { args, lvalue, target, unwind, .. } =>
+            (args, lvalue, target, unwind),
+        _ => unreachable!(),
+    };
+
+    // the variables of the callee (including its return value and parameters)
+    // become temporaries of the caller
+    let vars: IndexVec<VarId, VarId> = callee
+        .vars
+        .iter()
+        .map(|&var| {
+            let ty = var.ty.subst(tcx, instance.substs);
+            mir.vars.push(Var { kind: VarKind::Tmp, ty, ..var })
+        })
+        .collect();
+    let offset = mir.basic_blocks.len();
+    let return_block = BlockId::new(offset + callee.basic_blocks.len());
+    for block in &callee.basic_blocks {
+        let mut block = block.clone();
+        super::rename_vars(&mut block, |var| vars[var]);
+        super::subst_block(tcx, &mut block, instance.substs);
+        let terminator = block.terminator_mut();
+        for successor in terminator.successors_mut() {
+            *successor = BlockId::new(successor.index() + offset);
+        }
+        match terminator.kind {
+            TerminatorKind::Return => terminator.kind = TerminatorKind::Branch(return_block),
+            TerminatorKind::Call { ref mut unwind, .. } if unwind.is_none() => *unwind = cleanup,
+            _ => {}
+        }
+        mir.basic_blocks.push(block);
+    }
+
+    let ret = Operand::Lvalue(Lvalue::from(vars[RET_VAR]));
+    mir.basic_blocks.push(BasicBlock {
+        stmts: vec![Stmt { info, kind: StmtKind::Assign(lvalue, Rvalue::Operand(ret)) }],
+        terminator: Some(Terminator { info, kind: TerminatorKind::Branch(target) }),
+    });
+
+    let block = &mut mir[id];
+    for (arg, param) in args.into_iter().zip(callee.arg_iter()) {
+        let param = Lvalue::from(vars[param]);
+        block.stmts.push(Stmt { info, kind: StmtKind::Assign(param, Rvalue::Operand(arg)) });
+    }
+    let entry = BlockId::new(offset);
+    block.terminator = Some(Terminator { info, kind: TerminatorKind::Branch(entry) });
+}
@@ -1,14 +1,60 @@
+mod const_prop;
+mod copy_prop;
+mod inline;
+mod simplify_cfg;
+mod simplify_locals;
+
+use crate::error::MirError;
 use ds::Bitset;
 use index::Idx;
 use itertools::Itertools;
-use lcore::mir::{self, BlockId, Mir};
+use lcore::mir::{self, *};
+use lcore::ty::{ConstKind, Instance, Projection, Subst, SubstsRef};
 use lcore::TyCtx;
+use session::OptLevel;
 
-pub fn early_opt<'a, 'tcx>(_tcx: TyCtx<'tcx>, mir: &'a mut Mir<'tcx>) {
+pub fn early_opt<'a, 'tcx>(tcx: TyCtx<'tcx>, mir: &'a mut Mir<'tcx>) {
+    self::lint_unreachable_code(tcx, mir);
     self::remove_dead_blocks(mir);
 }
 
-pub fn late_opt<'a, 'tcx>(_tcx: TyCtx<'tcx>, _mir: &'a mut Mir<'tcx>) {
+pub fn late_opt<'a, 'tcx>(tcx: TyCtx<'tcx>, mir: &'a mut Mir<'tcx>) {
+    if tcx.sess.opts.opt_level() > OptLevel::O0 {
+        const_prop::propagate_constants(tcx, mir);
+        const_prop::report_const_panics(tcx, mir);
+    } else {
+        const_prop::check_const_panics(tcx, mir);
+    }
+    self::simplify(tcx, mir);
+}
+
+/// inlines the calls made by `instance` into its body
+/// this is run on request of `instance_mir` as it requires the (inlined) bodies of the callees
+pub fn inline<'tcx>(
+    tcx: TyCtx<'tcx>,
+    instance: Instance<'tcx>,
+    mir: &'tcx Mir<'tcx>,
+) -> &'tcx Mir<'tcx> {
+    if tcx.sess.opts.opt_level() == OptLevel::O0 {
+        return mir;
+    }
+    let mut inlined = mir.clone();
+    if !inline::inline_calls(tcx, instance, &mut inlined) {
+        return mir;
+    }
+    // the arguments are often constants so there is a lot more to propagate now
+    // panics are not reported again as they would be reported in the callee already
+    const_prop::propagate_constants(tcx, &mut inlined);
+    self::simplify(tcx, &mut inlined);
+    tcx.alloc(inlined)
+}
+
+/// cleans up after mirgen and the other optimizations
+fn simplify<'tcx>(tcx: TyCtx<'tcx>, mir: &mut Mir<'tcx>) {
+    // these only remove temporaries so the user's variables are still there for debugging
+    copy_prop::propagate_copies(tcx, mir);
+    simplify_locals::simplify_locals(tcx, mir);
+    simplify_cfg::simplify_cfg(mir);
 }
 
 #[cfg(test)]
@@ -27,8 +73,7 @@ mod test {
 /// this should be run before `typecheck` as some unreachable blocks
 /// may be type incorrect
 fn remove_dead_blocks<'a, 'tcx>(mir: &'a mut Mir<'tcx>) {
-    let mut reachable = Bitset::new(mir.len());
-    mir::preorder(mir).for_each(|(block_id, _)| reachable.set(block_id));
+    let reachable = self::reachable_blocks(mir);
 
     // this number is to essentially ensure an error if it isn't overwritten
     let mut swaps = (0..mir.len()).map(BlockId::new).collect_vec();
@@ -79,3 +124,157 @@ fn remove_dead_blocks<'a, 'tcx>(mir: &'a mut Mir<'tcx>) {
         }
     }
 }
+
+fn reachable_blocks(mir: &Mir<'_>) -> Bitset<BlockId> {
+    let mut reachable = Bitset::new(mir.len());
+    mir::preorder(mir).for_each(|(block_id, _)| reachable.set(block_id));
+    reachable
+}
+
+/// warns about user written code in unreachable blocks (e.g. after a `return`)
+/// each group of connected unreachable blocks is reported once at its earliest statement
+fn lint_unreachable_code<'tcx>(tcx: TyCtx<'tcx>, mir: &Mir<'tcx>) {
+    let reachable = self::reachable_blocks(mir);
+    let predecessors = mir.predecessors();
+    let mut visited = Bitset::new(mir.len());
+    for root in mir.basic_blocks.indices() {
+        if reachable.is_set(root) || !predecessors[root].is_empty() {
+            continue;
+        }
+        let mut spans = vec![];
+        let mut stack = vec![root];
+        while let Some(block) = stack.pop() {
+            if reachable.is_set(block) || !visited.insert(block) {
+                continue;
+            }
+            let block = &mir[block];
+            spans.extend(block.stmts.iter().filter(|stmt| is_user_stmt(stmt)).map(|s| s.info.span));
+            let terminator = block.terminator();
+            match terminator.kind {
+                TerminatorKind::Call { .. }
+                | TerminatorKind::Cond(..)
+                | TerminatorKind::Switch { .. }
+                | TerminatorKind::Panic(..) => spans.push(terminator.info.span),
+                TerminatorKind::Branch(..)
+                | TerminatorKind::Return
+                | TerminatorKind::Unreachable => {}
+            }
+            stack.extend(terminator.successors());
+        }
+        if let Some(span) = spans.into_iter().min_by_key(|span| span.start()) {
+            tcx.sess.emit_warning(span, MirError::UnreachableStatement);
+        }
+    }
+}
+
+/// the unit assignments are generated for blocks without a final expression
+fn is_user_stmt(stmt: &Stmt<'_>) -> bool {
+    match &stmt.kind {
+        StmtKind::Assign(_, Rvalue::Operand(Operand::Const(c))) => c.kind != ConstKind::Unit,
+        StmtKind::Assign(..) | StmtKind::StorageLive(..) => true,
+        StmtKind::Nop => false,
+    }
+}
+
+fn rvalue_operands_mut<'a, 'tcx>(rvalue: &'a mut Rvalue<'tcx>) -> Vec<&'a mut Operand<'tcx>> {
+    match rvalue {
+        Rvalue::Box(operand)
+        | Rvalue::Operand(operand)
+        | Rvalue::Unary(_, operand)
+        | Rvalue::Cast(_, operand, _)
+        | Rvalue::Closure(_, _, operand) => vec![operand],
+        Rvalue::Bin(_, l, r) => vec![l, r],
+        Rvalue::Adt { fields, .. } => fields.iter_mut().collect(),
+        Rvalue::Ref(..) | Rvalue::Discriminant(..) => vec![],
+    }
+}
+
+fn terminator_operands_mut<'a, 'tcx>(
+    kind: &'a mut TerminatorKind<'tcx>,
+) -> Vec<&'a mut Operand<'tcx>> {
+    match kind {
+        TerminatorKind::Cond(operand, ..) => vec![operand],
+        TerminatorKind::Call { f, args, .. } => std::iter::once(f).chain(args).collect(),
+        TerminatorKind::Switch { discr, arms, .. } =>
+            std::iter::once(discr).chain(arms.iter_mut().map(|(arm, _)| arm)).collect(),
+        TerminatorKind::Branch(..)
+        | TerminatorKind::Return
+        | TerminatorKind::Unreachable
+        | TerminatorKind::Panic(..) => vec![],
+    }
+}
+
+/// substitutes `substs` into every type mentioned in `block`
+/// constants are left alone as their types are never generic
+fn subst_block<'tcx>(tcx: TyCtx<'tcx>, block: &mut BasicBlock<'tcx>, substs: SubstsRef<'tcx>) {
+    let subst_lvalue = |lvalue: &mut Lvalue<'tcx>| {
+        let projs = lvalue
+            .projs
+            .iter()
+            .map(|&proj| match proj {
+                Projection::Deref => Projection::Deref,
+                Projection::Field(field, ty) => Projection::Field(field, ty.subst(tcx, substs)),
+                Projection::PointerCast(ty) => Projection::PointerCast(ty.subst(tcx, substs)),
+            })
+            .collect_vec();
+        lvalue.projs = tcx.intern_lvalue_projections(&projs);
+    };
+    let subst_operand = |operand: &mut Operand<'tcx>| match operand {
+        Operand::Lvalue(lvalue) => subst_lvalue(lvalue),
+        Operand::Item(_, item_substs) => *item_substs = item_substs.subst(tcx, substs),
+        Operand::Const(_) => {}
+    };
+    for stmt in &mut block.stmts {
+        if let StmtKind::Assign(lvalue, rvalue) = &mut stmt.kind {
+            subst_lvalue(lvalue);
+            match rvalue {
+                Rvalue::Ref(lvalue) | Rvalue::Discriminant(lvalue) => subst_lvalue(lvalue),
+                Rvalue::Cast(_, _, ty) | Rvalue::Closure(ty, ..) => *ty = ty.subst(tcx, substs),
+                Rvalue::Adt { substs: adt_substs, .. } =>
+                    *adt_substs = adt_substs.subst(tcx, substs),
+                _ => {}
+            }
+            for operand in rvalue_operands_mut(rvalue) {
+                subst_operand(operand);
+            }
+        }
+    }
+    let terminator = block.terminator_mut();
+    for operand in terminator_operands_mut(&mut terminator.kind) {
+        subst_operand(operand);
+    }
+    if let TerminatorKind::Call { lvalue, .. } = &mut terminator.kind {
+        subst_lvalue(lvalue);
+    }
+}
+
+/// replaces every variable mentioned in `block` with `f(var)`
+fn rename_vars(block: &mut BasicBlock<'_>, f: impl Fn(VarId) -> VarId) {
+    let rename = |lvalue: &mut Lvalue<'_>| lvalue.id = f(lvalue.id);
+    for stmt in &mut block.stmts {
+        match &mut stmt.kind {
+            StmtKind::Assign(lvalue, rvalue) => {
+                rename(lvalue);
+                if let Rvalue::Ref(lvalue) | Rvalue::Discriminant(lvalue) = rvalue {
+                    rename(lvalue);
+                }
+                for operand in rvalue_operands_mut(rvalue) {
+                    if let Operand::Lvalue(lvalue) = operand {
+                        rename(lvalue);
+                    }
+                }
+            }
+            StmtKind::StorageLive(var) => *var = f(*var),
+            StmtKind::Nop => {}
+        }
+    }
+    let terminator = block.terminator_mut();
+    for operand in terminator_operands_mut(&mut terminator.kind) {
+        if let Operand::Lvalue(lvalue) = operand {
+            rename(lvalue);
+        }
+    }
+    if let TerminatorKind::Call { lvalue, .. } = &mut terminator.kind {
+        rename(lvalue);
+    }
+}
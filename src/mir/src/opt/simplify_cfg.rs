@@ -0,0 +1,147 @@
+//! control flow graph simplification
+//! removes the redundant blocks and jumps that are left behind by mirgen and the other passes
+
+use ds::Bitset;
+use index::IndexVec;
+use itertools::Itertools;
+use lcore::mir::*;
+
+pub fn simplify_cfg(mir: &mut Mir<'_>) {
+    loop {
+        let mut changed = self::collapse_branches(mir);
+        changed |= self::thread_jumps(mir);
+        changed |= self::merge_blocks(mir);
+        changed |= self::dedup_return_blocks(mir);
+        if !changed {
+            break;
+        }
+        super::remove_dead_blocks(mir);
+    }
+}
+
+/// replaces the conditional branches whose targets are all the same block with a plain branch
+fn collapse_branches(mir: &mut Mir<'_>) -> bool {
+    let mut changed = false;
+    for block in &mut mir.basic_blocks {
+        let terminator = block.terminator_mut();
+        let target = match &terminator.kind {
+            TerminatorKind::Cond(_, then, els) if then == els => *then,
+            TerminatorKind::Switch { arms, default, .. }
+                if arms.iter().all(|(_, block)| block == default) =>
+                *default,
+            _ => continue,
+        };
+        terminator.kind = TerminatorKind::Branch(target);
+        changed = true;
+    }
+    changed
+}
+
+/// jumps to an empty block that just branches elsewhere go straight to where it branches
+/// bb0: branch bb1
+/// bb1: branch bb2
+/// ->
+/// bb0: branch bb2
+fn thread_jumps(mir: &mut Mir<'_>) -> bool {
+    let targets: IndexVec<BlockId, BlockId> =
+        mir.basic_blocks.indices().map(|block| self::jump_target(mir, block)).collect();
+    let mut changed = false;
+    for block in &mut mir.basic_blocks {
+        for successor in block.terminator_mut().successors_mut() {
+            if targets[*successor] != *successor {
+                *successor = targets[*successor];
+                changed = true;
+            }
+        }
+    }
+    changed
+}
+
+/// the block that control actually ends up at when jumping to `block`
+/// i.e. the first block along its chain of empty branching blocks
+/// a chain that loops back onto itself (i.e. `loop {}`) is left alone
+fn jump_target(mir: &Mir<'_>, mut block: BlockId) -> BlockId {
+    let mut visited = Bitset::new(mir.len());
+    while let TerminatorKind::Branch(target) = mir[block].terminator().kind {
+        if !mir[block].stmts.is_empty() || !visited.insert(block) {
+            break;
+        }
+        block = target;
+    }
+    block
+}
+
+/// appends a block to its only predecessor if that predecessor unconditionally branches to it
+/// bb0:
+///     x = 1
+///     branch bb1
+/// bb1:
+///     y = x
+///     return
+/// ->
+/// bb0:
+///     x = 1
+///     y = x
+///     return
+fn merge_blocks(mir: &mut Mir<'_>) -> bool {
+    let predecessors = mir.predecessors();
+    let mut changed = false;
+    for id in mir.basic_blocks.indices() {
+        let target = match mir[id].terminator().kind {
+            TerminatorKind::Branch(target)
+                if target != id && target != ENTRY_BLOCK && predecessors[target] == [id] =>
+                target,
+            _ => continue,
+        };
+        // the merged block is now unreachable and is removed with the other dead blocks
+        let info = mir[target].terminator().info;
+        let unreachable = Terminator { info, kind: TerminatorKind::Unreachable };
+        let merged = std::mem::replace(
+            &mut mir[target],
+            BasicBlock { stmts: vec![], terminator: Some(unreachable) },
+        );
+        let block = &mut mir[id];
+        block.stmts.extend(merged.stmts);
+        block.terminator = merged.terminator;
+        changed = true;
+    }
+    changed
+}
+
+/// redirects the jumps to a returning block to the first block that does exactly the same
+/// the entry block is never a candidate as it can't be jumped to (it's where llvm puts the allocas)
+fn dedup_return_blocks(mir: &mut Mir<'_>) -> bool {
+    let returns = mir
+        .basic_blocks
+        .iter_enumerated()
+        .filter(|&(id, block)| {
+            id != ENTRY_BLOCK && matches!(block.terminator().kind, TerminatorKind::Return)
+        })
+        .map(|(id, _)| id)
+        .collect_vec();
+    let mut replacements: IndexVec<BlockId, BlockId> = mir.basic_blocks.indices().collect();
+    for (i, &block) in returns.iter().enumerate() {
+        let duplicate = returns[..i]
+            .iter()
+            .find(|&&other| replacements[other] == other && same_stmts(&mir[other], &mir[block]));
+        if let Some(&other) = duplicate {
+            replacements[block] = other;
+        }
+    }
+
+    let mut changed = false;
+    for block in &mut mir.basic_blocks {
+        for successor in block.terminator_mut().successors_mut() {
+            if replacements[*successor] != *successor {
+                *successor = replacements[*successor];
+                changed = true;
+            }
+        }
+    }
+    changed
+}
+
+/// the spans are ignored as they don't affect what the block does
+fn same_stmts(a: &BasicBlock<'_>, b: &BasicBlock<'_>) -> bool {
+    a.stmts.len() == b.stmts.len() && a.stmts.iter().zip(&b.stmts).all(|(s, t)| s.kind == t.kind)
+}
@@ -0,0 +1,88 @@
+//! removes the stores to temporaries that are never read and then the temporaries themselves
+
+use crate::uses::UseCounts;
+use ast::BinOp;
+use index::{Idx, IndexVec};
+use lcore::mir::*;
+use lcore::TyCtx;
+
+pub fn simplify_locals<'tcx>(tcx: TyCtx<'tcx>, mir: &mut Mir<'tcx>) {
+    self::remove_dead_stores(tcx, mir);
+    self::strip_nops(mir);
+    self::compact_vars(mir);
+}
+
+/// replaces the assignments to temporaries that are never read with `Nop`s
+/// removing a store may make the temporaries it reads dead too,
+/// so this is repeated until nothing changes
+fn remove_dead_stores<'tcx>(tcx: TyCtx<'tcx>, mir: &mut Mir<'tcx>) {
+    let checked = tcx.sess.opts.debug_assertions();
+    loop {
+        let uses = UseCounts::new(mir);
+        let mut changed = false;
+        for stmt in mir.basic_blocks.iter_mut().flat_map(|block| &mut block.stmts) {
+            let is_dead = match &stmt.kind {
+                StmtKind::Assign(lvalue, rvalue) =>
+                    mir.vars[lvalue.id].kind == VarKind::Tmp
+                        && uses.reads[lvalue.id] == 0
+                        && !self::may_panic(rvalue, checked),
+                StmtKind::StorageLive(..) | StmtKind::Nop => false,
+            };
+            if is_dead {
+                stmt.kind = StmtKind::Nop;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+}
+
+/// the runtime checks of an operation must still run even if its result is unused
+/// division is checked even without debug assertions
+fn may_panic(rvalue: &Rvalue<'_>, checked: bool) -> bool {
+    match rvalue {
+        Rvalue::Bin(op, ..) => match op {
+            BinOp::Div => true,
+            BinOp::Add | BinOp::Sub | BinOp::Mul => checked,
+            BinOp::Lt | BinOp::Gt | BinOp::Eq | BinOp::Neq | BinOp::And | BinOp::Or => false,
+        },
+        Rvalue::Unary(UnaryOp::Neg, _) => checked,
+        _ => false,
+    }
+}
+
+fn strip_nops(mir: &mut Mir<'_>) {
+    for block in &mut mir.basic_blocks {
+        block.stmts.retain(|stmt| stmt.kind != StmtKind::Nop);
+    }
+}
+
+/// removes the temporaries that are no longer used and renumbers the remaining variables
+/// the return variable and the arguments keep their ids as they are not temporaries
+fn compact_vars(mir: &mut Mir<'_>) {
+    let uses = UseCounts::new(mir);
+    let mut map = IndexVec::from_elem_n(None, mir.vars.len());
+    let mut vars = IndexVec::with_capacity(mir.vars.len());
+    for (id, var) in mir.vars.iter_enumerated() {
+        if var.kind != VarKind::Tmp || uses.reads[id] > 0 || uses.writes[id] > 0 {
+            map[id] = Some(vars.push(*var));
+        }
+    }
+    if vars.len() == mir.vars.len() {
+        return;
+    }
+
+    for block in &mut mir.basic_blocks {
+        // the only remaining mention of a removed temporary may be its declaration
+        block.stmts.retain(|stmt| match stmt.kind {
+            StmtKind::StorageLive(var) => map[var].is_some(),
+            _ => true,
+        });
+        super::rename_vars(block, |var| map[var].unwrap());
+    }
+    debug_assert!(mir.arg_iter().all(|arg| map[arg] == Some(arg)));
+    debug_assert_eq!(map[VarId::new(0)], Some(VarId::new(0)));
+    mir.vars = vars;
+}
@@ -0,0 +1,181 @@
+use super::*;
+use lcore::mir::{Mir, Rvalue, StmtKind, TerminatorKind};
+
+fn has_cond(mir: &Mir<'_>) -> bool {
+    mir.basic_blocks.iter().any(|block| matches!(block.terminator().kind, TerminatorKind::Cond(..)))
+}
+
+fn has_bin_op(mir: &Mir<'_>) -> bool {
+    mir.basic_blocks
+        .iter()
+        .flat_map(|block| &block.stmts)
+        .any(|stmt| matches!(stmt.kind, StmtKind::Assign(_, Rvalue::Bin(..))))
+}
+
+#[test]
+fn const_prop_folds_branch_on_constant() {
+    let src = r#"
+    fn main() -> int {
+        let x = 5;
+        if x > 3 { 1 } else { 2 }
+    }"#;
+
+    let driver = optimized_driver!(src);
+    let mir_has_cond = driver.with_tcx(|tcx| has_cond(tcx.mir_of(tcx.ir.entry_id.unwrap())));
+    assert!(!mir_has_cond.unwrap());
+}
+
+#[test]
+fn const_prop_is_disabled_at_opt_level_zero() {
+    let src = r#"
+    fn main() -> int {
+        let x = 5;
+        if x > 3 { 1 } else { 2 }
+    }"#;
+
+    let driver = ldriver::Driver::from_src(src);
+    let mir_has_cond = driver.with_tcx(|tcx| has_cond(tcx.mir_of(tcx.ir.entry_id.unwrap())));
+    assert!(mir_has_cond.unwrap());
+}
+
+#[test]
+fn const_prop_folds_match_on_literal() {
+    let src = r#"
+    fn main() -> int {
+        match 3 {
+            2 => 1,
+            3 => 2,
+            _ => 3,
+        }
+    }"#;
+
+    let driver = optimized_driver!(src);
+    let mir_has_cond = driver.with_tcx(|tcx| has_cond(tcx.mir_of(tcx.ir.entry_id.unwrap())));
+    assert!(!mir_has_cond.unwrap());
+    assert_eq!(optimized_driver!(src).llvm_jit().unwrap(), 2);
+}
+
+#[test]
+fn const_prop_folds_arithmetic() {
+    let src = r#"
+    fn main() -> int {
+        let x = 2;
+        let y = x * 3;
+        -y + 13
+    }"#;
+
+    let driver = optimized_driver!(src);
+    let mir_has_bin_op = driver.with_tcx(|tcx| has_bin_op(tcx.mir_of(tcx.ir.entry_id.unwrap())));
+    assert!(!mir_has_bin_op.unwrap());
+    assert_eq!(optimized_driver!(src).llvm_jit().unwrap(), 7);
+}
+
+#[test]
+fn const_prop_preserves_loop_semantics() {
+    let src = r#"
+    fn main() -> int {
+        let mut x = 0;
+        let step = 2;
+        loop {
+            if x > 5 {
+                break
+            };
+            x = x + step;
+        };
+        x
+    }"#;
+
+    assert_eq!(optimized_driver!(src).llvm_jit().unwrap(), 6);
+}
+
+#[test]
+fn const_prop_does_not_propagate_through_borrows() {
+    let src = r#"
+    fn main() -> int {
+        let mut x = 1;
+        mutate(unsafe { &x });
+        x
+    }
+
+    fn mutate(ptr: &int) {
+        *ptr = 5;
+    }"#;
+
+    assert_eq!(optimized_driver!(src).llvm_jit().unwrap(), 5);
+}
+
+#[test]
+fn const_prop_reports_division_by_zero() {
+    let src = r#"
+    fn main() -> int {
+        let x = 0;
+        5 / x
+    }"#;
+
+    assert!(optimized_driver!(src).llvm_compile().is_err());
+}
+
+#[test]
+fn const_prop_reports_overflow() {
+    let src = r#"
+    fn main() -> int {
+        let x = 4611686018427387904;
+        x + x
+    }"#;
+
+    assert!(optimized_driver!(src).llvm_compile().is_err());
+}
+
+#[test]
+fn const_prop_reports_neg_overflow() {
+    let src = r#"
+    fn main() -> int {
+        let x = -9223372036854775807 - 1;
+        -x
+    }"#;
+
+    assert!(optimized_driver!(src).llvm_compile().is_err());
+}
+
+#[test]
+fn const_prop_wraps_overflow_without_debug_assertions() {
+    let src = r#"
+    fn main() -> int {
+        let x = 4611686018427387904;
+        x + x + 7
+    }"#;
+
+    let driver = ldriver::Driver::from_src_with_opts(src, |opts| opts.release = true);
+    assert!(driver.llvm_compile().is_ok());
+}
+
+#[test]
+fn const_prop_ignores_panics_in_dead_code() {
+    let src = r#"
+    fn main() -> int {
+        if false { 1 / 0 } else { 2 }
+    }"#;
+
+    assert!(optimized_driver!(src).llvm_compile().is_ok());
+}
+
+#[test]
+fn const_panics_are_reported_at_opt_level_zero() {
+    let src = r#"
+    fn main() -> int {
+        let x = 4611686018427387904;
+        x + x
+    }"#;
+
+    assert!(ldriver::Driver::from_src(src).llvm_compile().is_err());
+}
+
+#[test]
+fn const_panics_in_dead_code_are_ignored_at_opt_level_zero() {
+    let src = r#"
+    fn main() -> int {
+        if false { 1 / 0 } else { 2 }
+    }"#;
+
+    assert!(ldriver::Driver::from_src(src).llvm_compile().is_ok());
+}
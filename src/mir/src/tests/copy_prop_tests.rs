@@ -0,0 +1,131 @@
+use lcore::mir::{Mir, VarKind};
+
+fn has_tmps(mir: &Mir<'_>) -> bool {
+    mir.vars.iter().any(|var| var.kind == VarKind::Tmp)
+}
+
+#[test]
+fn copy_prop_removes_copied_tmps() {
+    let src = r#"
+    fn main() -> int {
+        let x = 1 + 2;
+        let y = { x };
+        y
+    }"#;
+
+    let driver = ldriver::Driver::from_src(src);
+    let mir_has_tmps = driver.with_tcx(|tcx| has_tmps(tcx.mir_of(tcx.ir.entry_id.unwrap())));
+    assert!(!mir_has_tmps.unwrap());
+}
+
+#[test]
+fn copy_prop_writes_call_result_to_destination() {
+    let src = r#"
+    fn f() -> int { 5 }
+
+    fn main() -> int {
+        let x = f();
+        x
+    }"#;
+
+    let driver = ldriver::Driver::from_src(src);
+    let mir_has_tmps = driver.with_tcx(|tcx| has_tmps(tcx.mir_of(tcx.ir.entry_id.unwrap())));
+    assert!(!mir_has_tmps.unwrap());
+    assert_eq!(ldriver::Driver::from_src(src).llvm_jit().unwrap(), 5);
+}
+
+#[test]
+fn copy_prop_preserves_struct_field_swap() {
+    let src = r#"
+    struct S { x: int, y: int }
+
+    fn main() -> int {
+        let s = S { x: 1, y: 2 };
+        let t = S { x: s.y, y: s.x };
+        t.x * 10 + t.y
+    }"#;
+
+    let driver = ldriver::Driver::from_src(src);
+    assert_eq!(driver.llvm_jit().unwrap(), 21);
+}
+
+#[test]
+fn copy_prop_in_loop() {
+    let src = r#"
+    fn main() -> int {
+        let mut i = 0;
+        let mut sum = 0;
+        loop {
+            if i > 4 {
+                break
+            };
+            sum = sum + i;
+            i = i + 1;
+        };
+        sum
+    }"#;
+
+    let driver = ldriver::Driver::from_src(src);
+    assert_eq!(driver.llvm_jit().unwrap(), 10);
+}
+
+#[test]
+fn copy_prop_does_not_forward_past_write_to_source() {
+    let src = r#"
+    fn main() -> int {
+        let mut x = 1;
+        let y = { x };
+        x = 5;
+        y
+    }"#;
+
+    let driver = ldriver::Driver::from_src(src);
+    assert_eq!(driver.llvm_jit().unwrap(), 1);
+}
+
+#[test]
+fn dead_store_elimination_keeps_checked_arithmetic() {
+    let src = r#"
+    fn main() -> int {
+        let x = 4611686018427387904;
+        // borrowing `x` stops the overflow from being reported at compile time
+        let _p = unsafe { &x };
+        x + x;
+        0
+    }"#;
+
+    let driver = ldriver::Driver::from_src(src);
+    let mir_has_tmps = driver.with_tcx(|tcx| has_tmps(tcx.mir_of(tcx.ir.entry_id.unwrap())));
+    assert!(mir_has_tmps.unwrap());
+}
+
+#[test]
+fn dead_store_elimination_keeps_checked_negation() {
+    let src = r#"
+    fn main() -> int {
+        let x = -9223372036854775807 - 1;
+        let _p = unsafe { &x };
+        -x;
+        0
+    }"#;
+
+    let driver = ldriver::Driver::from_src(src);
+    let mir_has_tmps = driver.with_tcx(|tcx| has_tmps(tcx.mir_of(tcx.ir.entry_id.unwrap())));
+    assert!(mir_has_tmps.unwrap());
+}
+
+#[test]
+fn dead_store_elimination_keeps_division_in_release() {
+    let src = r#"
+    fn main() -> int {
+        let x = 2;
+        // borrowing `x` stops constant propagation from folding the division
+        let _p = unsafe { &x };
+        1 / x;
+        0
+    }"#;
+
+    let driver = ldriver::Driver::from_src_with_opts(src, |opts| opts.release = true);
+    let mir_has_tmps = driver.with_tcx(|tcx| has_tmps(tcx.mir_of(tcx.ir.entry_id.unwrap())));
+    assert!(mir_has_tmps.unwrap());
+}
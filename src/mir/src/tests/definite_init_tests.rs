@@ -0,0 +1,150 @@
+use super::*;
+
+#[test]
+fn var_initialized_before_loop() {
+    let src = r#"
+    fn main() -> int {
+        let mut i = 0;
+        let mut y = 1;
+        loop {
+            i = i + y;
+            y = 2;
+            if i > 5 {
+                break
+            };
+        };
+        i
+    }"#;
+
+    analyze!(src);
+}
+
+#[test]
+fn var_initialized_in_loop_then_used_after_break() {
+    let src = r#"
+    fn main() -> int {
+        let x;
+        loop {
+            x = 5;
+            break
+        };
+        x
+    }"#;
+
+    analyze!(src);
+}
+
+#[test]
+fn early_return_on_uninit_path() {
+    // the only path that reaches the use of `x` initializes it
+    let src = r#"
+    fn main() -> int {
+        let x;
+        if false {
+            return 4;
+        } else {
+            x = 5;
+        };
+        x
+    }"#;
+
+    analyze!(src);
+}
+
+#[test]
+fn detect_use_of_uninit_after_early_return_on_init_path() {
+    let src = r#"
+    fn main() -> int {
+        let x;
+        if false {
+            x = 5;
+            return x;
+        };
+        x
+    }"#;
+
+    expect_analysis_error!(src);
+}
+
+#[test]
+fn var_initialized_in_every_match_arm() {
+    let src = r#"
+    fn main() -> int {
+        let x;
+        match 8 {
+            8 => x = 1,
+            _ => x = 2,
+        };
+        x
+    }"#;
+
+    analyze!(src);
+}
+
+#[test]
+fn detect_use_of_var_uninit_in_some_match_arm() {
+    let src = r#"
+    fn main() -> int {
+        let x;
+        match 8 {
+            8 => x = 1,
+            _ => {},
+        };
+        x
+    }"#;
+
+    expect_analysis_error!(src);
+}
+
+#[test]
+fn struct_initialized_field_by_field() {
+    let src = r#"
+    struct S { x: int, y: int }
+    fn main() -> int {
+        let s: S;
+        s.x = 1;
+        s.y = 2;
+        let t = s;
+        t.x + t.y
+    }"#;
+
+    analyze!(src);
+}
+
+#[test]
+fn tuple_field_read_after_partial_init() {
+    let src = r#"
+    fn main() -> int {
+        let pair: (int, int);
+        pair.0 = 5;
+        pair.0
+    }"#;
+
+    analyze!(src);
+}
+
+#[test]
+fn detect_use_of_partially_initialized_struct() {
+    let src = r#"
+    struct S { x: int, y: int }
+    fn main() -> int {
+        let s: S;
+        s.x = 1;
+        let t = s;
+        t.x
+    }"#;
+
+    expect_analysis_error!(src);
+}
+
+#[test]
+fn detect_use_of_uninit_field() {
+    let src = r#"
+    fn main() -> int {
+        let pair: (int, int);
+        pair.0 = 5;
+        pair.1
+    }"#;
+
+    expect_analysis_error!(src);
+}
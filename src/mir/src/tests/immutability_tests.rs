@@ -0,0 +1,82 @@
+use super::*;
+
+#[test]
+fn detect_assignment_to_immutable_var() {
+    let src = r#"
+    fn main() -> int {
+        let x = 5;
+        x = 6;
+        x
+    }"#;
+
+    expect_analysis_error!(src);
+}
+
+#[test]
+fn detect_assignment_to_immutable_var_in_loop() {
+    // the assignment is only an initialization on the first iteration
+    let src = r#"
+    fn main() -> int {
+        let x;
+        let mut i = 0;
+        loop {
+            x = 6;
+            if i > 5 {
+                break
+            };
+            i = i + 1;
+        };
+        x
+    }"#;
+
+    expect_analysis_error!(src);
+}
+
+#[test]
+fn detect_assignment_to_immutable_var_initialized_on_some_branch() {
+    let src = r#"
+    fn main() -> int {
+        let x;
+        if false {
+            x = 5;
+        };
+        x = 6;
+        x
+    }"#;
+
+    expect_analysis_error!(src);
+}
+
+#[test]
+fn deferred_initialization_of_immutable_var() {
+    let src = r#"
+    fn main() -> int {
+        let x;
+        if false {
+            x = 5;
+        } else {
+            x = 6;
+        };
+        x
+    }"#;
+
+    analyze!(src);
+}
+
+#[test]
+fn immutable_binding_in_loop_is_rebound_each_iteration() {
+    let src = r#"
+    fn main() -> int {
+        let mut i = 0;
+        loop {
+            let x = i;
+            if x > 5 {
+                break
+            };
+            i = i + 1;
+        };
+        i
+    }"#;
+
+    analyze!(src);
+}
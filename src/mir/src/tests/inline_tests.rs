@@ -0,0 +1,154 @@
+use super::*;
+use lcore::mir::{Mir, TerminatorKind};
+use lcore::ty::{Instance, TyCtx};
+
+fn call_count(mir: &Mir<'_>) -> usize {
+    mir.basic_blocks
+        .iter()
+        .filter(|block| matches!(block.terminator().kind, TerminatorKind::Call { .. }))
+        .count()
+}
+
+fn main_call_count(tcx: TyCtx<'_>) -> usize {
+    call_count(tcx.instance_mir(Instance::mono_item(tcx.ir.entry_id.unwrap())))
+}
+
+#[test]
+fn inline_small_function() {
+    let src = r#"
+    fn add(a: int, b: int) -> int { a + b }
+
+    fn main() -> int {
+        add(2, 3)
+    }"#;
+
+    assert_eq!(optimized_driver!(src).with_tcx(main_call_count).unwrap(), 0);
+    assert_eq!(optimized_driver!(src).llvm_jit().unwrap(), 5);
+}
+
+#[test]
+fn inline_is_disabled_at_opt_level_zero() {
+    let src = r#"
+    fn add(a: int, b: int) -> int { a + b }
+
+    fn main() -> int {
+        add(2, 3)
+    }"#;
+
+    let driver = ldriver::Driver::from_src(src);
+    assert_eq!(driver.with_tcx(main_call_count).unwrap(), 1);
+}
+
+#[test]
+fn inline_enables_const_prop() {
+    let src = r#"
+    fn choose(x: int) -> int {
+        if x > 0 { 1 } else { 2 }
+    }
+
+    fn main() -> int {
+        choose(5)
+    }"#;
+
+    let driver = optimized_driver!(src);
+    let has_cond = driver.with_tcx(|tcx| {
+        let mir = tcx.instance_mir(Instance::mono_item(tcx.ir.entry_id.unwrap()));
+        mir.basic_blocks
+            .iter()
+            .any(|block| matches!(block.terminator().kind, TerminatorKind::Cond(..)))
+    });
+    assert!(!has_cond.unwrap());
+    assert_eq!(optimized_driver!(src).llvm_jit().unwrap(), 1);
+}
+
+#[test]
+fn inline_does_not_inline_recursive_function() {
+    let src = r#"
+    fn fact(n: int) -> int {
+        if n < 2 { 1 } else { n * fact(n - 1) }
+    }
+
+    fn main() -> int {
+        fact(5)
+    }"#;
+
+    assert_eq!(optimized_driver!(src).with_tcx(main_call_count).unwrap(), 1);
+    assert_eq!(optimized_driver!(src).llvm_jit().unwrap(), 120);
+}
+
+#[test]
+fn inline_mutually_recursive_functions() {
+    let src = r#"
+    fn is_even(n: int) -> bool {
+        if n == 0 { true } else { is_odd(n - 1) }
+    }
+
+    fn is_odd(n: int) -> bool {
+        if n == 0 { false } else { is_even(n - 1) }
+    }
+
+    fn main() -> int {
+        if is_even(10) { 1 } else { 0 }
+    }"#;
+
+    assert_eq!(optimized_driver!(src).llvm_jit().unwrap(), 1);
+}
+
+const LARGE_FN: &str = r#"
+    fn large(x: int) -> int {
+        let a = x * 3 + x;
+        let b = a * 3 + x;
+        let c = b * 3 + x;
+        let d = c * 3 + x;
+        let e = d * 3 + x;
+        let f = e * 3 + x;
+        let g = f * 3 + x;
+        let h = g * 3 + x;
+        let i = h * 3 + x;
+        let j = i * 3 + x;
+        let k = j * 3 + x;
+        let l = k * 3 + x;
+        let m = l * 3 + x;
+        let n = m * 3 + x;
+        let o = n * 3 + x;
+        let p = o * 3 + x;
+        p - o
+    }
+
+    fn main() -> int {
+        large(0) + 7
+    }"#;
+
+#[test]
+fn inline_skips_large_function() {
+    assert_eq!(optimized_driver!(LARGE_FN).with_tcx(main_call_count).unwrap(), 1);
+    assert_eq!(optimized_driver!(LARGE_FN).llvm_jit().unwrap(), 7);
+}
+
+#[test]
+fn inline_large_function_with_hint() {
+    let src = format!("#[inline] {}", LARGE_FN.trim_start());
+    assert_eq!(optimized_driver!(&src).with_tcx(main_call_count).unwrap(), 0);
+    assert_eq!(optimized_driver!(&src).llvm_jit().unwrap(), 7);
+}
+
+#[test]
+fn inline_generic_function() {
+    let src = r#"
+    #[inline]
+    fn pick<T>(b: bool, x: T, y: T) -> T {
+        if b { x } else { y }
+    }
+
+    #[inline]
+    fn snd<T>(pair: (T, int)) -> int {
+        pair.1
+    }
+
+    fn main() -> int {
+        pick(false, 1, 2) + snd((true, 5))
+    }"#;
+
+    assert_eq!(optimized_driver!(src).with_tcx(main_call_count).unwrap(), 0);
+    assert_eq!(optimized_driver!(src).llvm_jit().unwrap(), 7);
+}
@@ -0,0 +1,142 @@
+use super::*;
+
+#[test]
+fn no_warnings_for_used_variables() {
+    let src = r#"
+    fn main() -> int {
+        let mut i = 0;
+        let x = 5;
+        loop {
+            i = i + x;
+            if i > 20 {
+                break
+            };
+        };
+        i
+    }"#;
+
+    expect_warnings!(src, 0);
+}
+
+#[test]
+fn warn_unused_let() {
+    let src = r#"
+    fn main() -> int {
+        let x = 5;
+        0
+    }"#;
+
+    expect_warnings!(src, 1);
+}
+
+#[test]
+fn underscore_prefixed_bindings_are_exempt() {
+    let src = r#"
+    fn main() -> int {
+        let _x = 5;
+        let mut _y = 5;
+        _y = 6;
+        0
+    }"#;
+
+    expect_warnings!(src, 0);
+}
+
+#[test]
+fn warn_overwritten_assignment() {
+    let src = r#"
+    fn main() -> int {
+        let mut x = 5;
+        x = 6;
+        x
+    }"#;
+
+    expect_warnings!(src, 1);
+}
+
+#[test]
+fn warn_assignment_never_read() {
+    let src = r#"
+    fn main() -> int {
+        let mut x = 5;
+        let y = x;
+        x = 6;
+        y
+    }"#;
+
+    expect_warnings!(src, 1);
+}
+
+#[test]
+fn assignment_read_by_next_loop_iteration() {
+    let src = r#"
+    fn main() -> int {
+        let mut i = 0;
+        let mut prev = 0;
+        loop {
+            if i > 5 {
+                break
+            };
+            prev = i;
+            i = i + 1;
+        };
+        prev
+    }"#;
+
+    expect_warnings!(src, 0);
+}
+
+#[test]
+fn assignment_to_borrowed_variable() {
+    let src = r#"
+    fn main() -> int {
+        let mut x = 5;
+        let p = unsafe { &x };
+        x = 6;
+        unsafe { *p }
+    }"#;
+
+    expect_warnings!(src, 0);
+}
+
+#[test]
+fn warn_unreachable_after_return() {
+    let src = r#"
+    fn main() -> int {
+        return 5;
+        6
+    }"#;
+
+    expect_warnings!(src, 1);
+}
+
+#[test]
+fn warn_unreachable_after_infinite_loop() {
+    let src = r#"
+    extern "l-intrinsic" {
+        fn print(i: int);
+    }
+
+    fn main() -> int {
+        loop {
+            print(1);
+        };
+        print(2);
+        0
+    }"#;
+
+    expect_warnings!(src, 1);
+}
+
+#[test]
+fn no_unreachable_warning_for_diverging_tail() {
+    let src = r#"
+    fn main() -> int {
+        if false {
+            return 5;
+        };
+        return 6;
+    }"#;
+
+    expect_warnings!(src, 0);
+}
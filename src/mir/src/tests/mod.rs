@@ -1,3 +1,10 @@
+mod const_prop_tests;
+mod copy_prop_tests;
+mod definite_init_tests;
+mod immutability_tests;
+mod inline_tests;
+mod liveness_tests;
+mod simplify_cfg_tests;
 mod uninit_tests;
 
 macro expect_analysis_error($src:expr) {{
@@ -5,10 +12,23 @@ macro expect_analysis_error($src:expr) {{
     // we don't really have a driver step called `gen_mir`
     // so we run the next closest thing which also compiles it
     // to llvm ir, but runs mirgen and analysis as an intermediate step
-    driver.llvm_compile().unwrap_or_else(|_| panic!("expected mirgen/mir analysis error"));
+    if driver.llvm_compile().is_ok() {
+        panic!("expected mirgen/mir analysis error")
+    }
 }}
 
 macro analyze($src:expr) {{
     let driver = ldriver::Driver::from_src($src);
     driver.llvm_compile().unwrap();
 }}
+
+macro expect_warnings($src:expr, $count:expr) {{
+    let driver = ldriver::Driver::from_src($src);
+    driver.llvm_compile().unwrap();
+    assert_eq!(driver.warning_count(), $count);
+}}
+
+/// a driver that runs the optimizations that are enabled above opt level zero
+macro optimized_driver($src:expr) {
+    ldriver::Driver::from_src_with_opts($src, |opts| opts.opt_level = Some(session::OptLevel::O1))
+}
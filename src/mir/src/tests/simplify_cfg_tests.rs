@@ -0,0 +1,87 @@
+use lcore::mir::{Mir, TerminatorKind};
+
+/// whether any block branches to a block that could have been merged into it or jumped over
+fn has_redundant_branch(mir: &Mir<'_>) -> bool {
+    let predecessors = mir.predecessors();
+    mir.basic_blocks.iter_enumerated().any(|(id, block)| match block.terminator().kind {
+        TerminatorKind::Branch(target) => {
+            let jumps_again = matches!(mir[target].terminator().kind, TerminatorKind::Branch(_));
+            (target != id && predecessors[target] == [id])
+                || (mir[target].stmts.is_empty() && jumps_again)
+        }
+        _ => false,
+    })
+}
+
+fn return_block_count(mir: &Mir<'_>) -> usize {
+    mir.basic_blocks
+        .iter()
+        .filter(|block| matches!(block.terminator().kind, TerminatorKind::Return))
+        .count()
+}
+
+#[test]
+fn simplify_cfg_merges_straight_line_blocks() {
+    let src = r#"
+    fn main() -> int {
+        let x = { let y = 1; { y + 1 } };
+        let z = if x > 1 { x } else { 0 };
+        z + { 3 }
+    }"#;
+
+    let driver = ldriver::Driver::from_src(src);
+    let redundant =
+        driver.with_tcx(|tcx| has_redundant_branch(tcx.mir_of(tcx.ir.entry_id.unwrap())));
+    assert!(!redundant.unwrap());
+    assert_eq!(ldriver::Driver::from_src(src).llvm_jit().unwrap(), 5);
+}
+
+#[test]
+fn simplify_cfg_preserves_match_and_loop_semantics() {
+    let src = r#"
+    fn main() -> int {
+        let mut i = 0;
+        let mut sum = 0;
+        loop {
+            if i > 4 {
+                break
+            };
+            sum = sum + match i {
+                1 => 10,
+                3 => 30,
+                _ => i,
+            };
+            i = i + 1;
+        };
+        sum
+    }"#;
+
+    let driver = ldriver::Driver::from_src(src);
+    let redundant =
+        driver.with_tcx(|tcx| has_redundant_branch(tcx.mir_of(tcx.ir.entry_id.unwrap())));
+    assert!(!redundant.unwrap());
+    assert_eq!(ldriver::Driver::from_src(src).llvm_jit().unwrap(), 46);
+}
+
+#[test]
+fn simplify_cfg_dedups_return_blocks() {
+    let src = r#"
+    fn f(b: bool) -> int {
+        if b {
+            return 1
+        };
+        1
+    }
+
+    fn main() -> int {
+        f(true) + f(false)
+    }"#;
+
+    let driver = ldriver::Driver::from_src(src);
+    let count = driver.with_tcx(|tcx| {
+        let (&f, _) = tcx.ir.items.iter().find(|(_, item)| item.ident.as_str() == "f").unwrap();
+        return_block_count(tcx.mir_of(f))
+    });
+    assert_eq!(count.unwrap(), 1);
+    assert_eq!(ldriver::Driver::from_src(src).llvm_jit().unwrap(), 2);
+}
@@ -10,3 +10,60 @@ fn detect_use_of_uninit_simple() {
 
     expect_analysis_error!(src);
 }
+
+#[test]
+fn detect_use_of_var_uninit_on_some_branch() {
+    let src = r#"
+    fn main() -> int {
+        let x;
+        if false {
+            x = 5;
+        };
+        x
+    }"#;
+
+    expect_analysis_error!(src);
+}
+
+#[test]
+fn detect_use_of_var_uninit_after_loop_back_edge() {
+    // `y` is read on the second iteration before it is reassigned
+    let src = r#"
+    fn main() -> int {
+        let mut i = 0;
+        loop {
+            let mut y;
+            if i > 0 {
+                i = y;
+            };
+            y = 1;
+            i = i + y;
+            if i > 5 {
+                break
+            };
+        };
+        i
+    }"#;
+
+    expect_analysis_error!(src);
+}
+
+#[test]
+fn detect_use_of_var_uninit_on_first_loop_iteration() {
+    // `y` is only initialized by the previous iteration
+    let src = r#"
+    fn main() -> int {
+        let mut i = 0;
+        let mut y;
+        loop {
+            i = i + y;
+            y = 1;
+            if i > 5 {
+                break
+            };
+        };
+        i
+    }"#;
+
+    expect_analysis_error!(src);
+}
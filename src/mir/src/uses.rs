@@ -0,0 +1,75 @@
+//! counts how each variable is used over an entire body
+
+use ds::Bitset;
+use index::IndexVec;
+use lcore::mir::*;
+use lcore::ty::Projection;
+
+/// the number of times each variable is read and written to
+crate struct UseCounts {
+    crate reads: IndexVec<VarId, usize>,
+    crate writes: IndexVec<VarId, usize>,
+    /// the variables that have a reference taken to them
+    crate borrowed: Bitset<VarId>,
+    /// the variables whose storage is marked live (i.e. those introduced by a binding)
+    crate declared: Bitset<VarId>,
+}
+
+impl UseCounts {
+    crate fn new(mir: &Mir<'_>) -> Self {
+        let n = mir.vars.len();
+        let mut uses = Self {
+            reads: IndexVec::from_elem_n(0, n),
+            writes: IndexVec::from_elem_n(0, n),
+            borrowed: Bitset::new(n),
+            declared: Bitset::new(n),
+        };
+        uses.visit_mir(mir);
+        uses
+    }
+
+    /// assigning through a pointer reads the pointer rather than writing to it
+    fn visit_assigned(&mut self, lvalue: &Lvalue<'_>) {
+        if lvalue.projs.iter().any(|proj| proj == Projection::Deref) {
+            self.reads[lvalue.id] += 1;
+        } else {
+            self.writes[lvalue.id] += 1;
+        }
+    }
+}
+
+impl<'tcx> MirVisitor<'tcx> for UseCounts {
+    fn visit_stmt(&mut self, stmt: &Stmt<'tcx>) {
+        if let StmtKind::StorageLive(id) = stmt.kind {
+            self.declared.set(id);
+        }
+        self.walk_stmt(stmt);
+    }
+
+    fn visit_assignment(&mut self, info: SpanInfo, lvalue: &Lvalue<'tcx>, rvalue: &Rvalue<'tcx>) {
+        self.visit_assigned(lvalue);
+        self.visit_rvalue(info, rvalue);
+    }
+
+    fn visit_rvalue(&mut self, info: SpanInfo, rvalue: &Rvalue<'tcx>) {
+        if let Rvalue::Ref(lvalue) = rvalue {
+            self.borrowed.set(lvalue.id);
+        }
+        self.walk_rvalue(info, rvalue);
+    }
+
+    fn visit_lvalue(&mut self, _info: SpanInfo, lvalue: &Lvalue<'tcx>) {
+        self.reads[lvalue.id] += 1;
+    }
+
+    fn visit_terminator(&mut self, terminator: &Terminator<'tcx>) {
+        match &terminator.kind {
+            TerminatorKind::Call { f, args, lvalue, .. } => {
+                self.visit_assigned(lvalue);
+                self.visit_operand(terminator.info, f);
+                args.iter().for_each(|arg| self.visit_operand(terminator.info, arg));
+            }
+            _ => self.walk_terminator(terminator),
+        }
+    }
+}
@@ -36,7 +36,7 @@ fn build_variant_ctor_inner<'tcx>(
 
     let mut vars = IndexVec::<VarId, Var<'tcx>>::default();
     let mut alloc_var = |info: SpanInfo, kind: VarKind, ty: Ty<'tcx>| {
-        let var = Var { mtbl: Mutability::Imm, info, kind, ty };
+        let var = Var { mtbl: Mutability::Imm, info, kind, ty, ident: None };
         vars.push(var)
     };
 
@@ -55,6 +55,11 @@ fn build_variant_ctor_inner<'tcx>(
     let rvalue = Rvalue::Adt { adt, variant_idx, substs, fields };
     cfg.push_assignment(info, ENTRY_BLOCK, lvalue, rvalue);
     cfg.terminate(info, ENTRY_BLOCK, TerminatorKind::Return);
-    let body = Mir { basic_blocks: cfg.basic_blocks, vars, argc: variant.fields.len() };
+    let body = Mir {
+        basic_blocks: cfg.basic_blocks,
+        vars,
+        argc: variant.fields.len(),
+        closures: Default::default(),
+    };
     Some(tcx.alloc(body))
 }
@@ -1,18 +1,85 @@
 use super::*;
+use lcore::ty::{CaptureMode, UpvarCapture};
+
+/// the closure whose lifted body is being built
+#[derive(Clone, Copy)]
+pub struct ClosureBody<'tcx> {
+    id: ir::Id,
+    ty: Ty<'tcx>,
+    /// the implicit argument that points to the environment of the closure
+    env: VarId,
+}
 
 impl<'a, 'tcx> MirBuilder<'a, 'tcx> {
+    /// a closure is converted into its lifted body paired with its environment
+    /// the environment is a gc allocated tuple of the captured upvars
     crate fn build_closure(
         &mut self,
-        block: BlockId,
+        mut block: BlockId,
         closure: &tir::Expr<'tcx>,
-        _upvars: &[tir::Expr<'tcx>],
-        _body: &tir::Body<'tcx>,
+        id: ir::Id,
+        body: &tir::Body<'tcx>,
+        upvars: &[tir::Expr<'tcx>],
+        captures: &[UpvarCapture],
     ) -> BlockAnd<Rvalue<'tcx>> {
-        // let _info = self.span_info(closure.span);
-        // TODO
-        // let _upvars =
-        //     upvars.iter().map(|upvar| set!(block = self.as_operand(block, upvar))).collect_vec();
-        // we need this to not crash during tests so we have a random impl currently
-        block.and(Rvalue::Closure(closure.ty))
+        let info = self.span_info(closure.span);
+        let env_tuple_ty = self.tcx.mk_tup_iter(upvars.iter().map(|upvar| upvar.ty));
+        let env_tuple = self.alloc_tmp(info, env_tuple_ty);
+        set!(block = self.build_tuple(block, env_tuple.into(), closure, upvars));
+
+        let env_ty = self.tcx.mk_box_ty(env_tuple_ty);
+        let env = self.alloc_tmp(info, env_ty);
+        let rvalue = Rvalue::Box(Operand::Lvalue(env_tuple.into()));
+        self.push_assignment(info, block, env.into(), rvalue);
+
+        let mut builder = MirBuilder::new(self.ctx, body);
+        builder.build_closure_body(closure, id, env_ty, upvars, captures);
+        let mir = crate::finish_mir(self.tcx, builder.complete());
+        self.ctx.closures.borrow_mut().insert(id, mir);
+
+        block.and(Rvalue::Closure(closure.ty, id, Operand::Lvalue(env.into())))
+    }
+
+    /// builds the lifted body of a closure which takes
+    /// a pointer to its environment as an implicit first argument
+    fn build_closure_body(
+        &mut self,
+        closure: &tir::Expr<'tcx>,
+        id: ir::Id,
+        env_ty: Ty<'tcx>,
+        upvars: &[tir::Expr<'tcx>],
+        captures: &[UpvarCapture],
+    ) {
+        let info = self.span_info(closure.span);
+        // the environment must be allocated before the explicit parameters
+        self.argc += 1;
+        let env = self.alloc_var(info, VarKind::Arg, env_ty);
+
+        for (i, (upvar, capture)) in upvars.iter().zip(captures).enumerate() {
+            let lvalue =
+                LvalueBuilder::from(env).project_deref().project_field(FieldIdx::new(i), upvar.ty);
+            let lvalue = match capture.mode {
+                CaptureMode::ByValue => lvalue,
+                CaptureMode::ByRef => lvalue.project_deref(),
+            };
+            self.upvars.insert(capture.upvar_id.var_id, lvalue.lvalue(self.tcx));
+        }
+
+        self.closure = Some(ClosureBody { id, ty: closure.ty, env });
+        let _ = self.build_body();
+    }
+
+    /// a named closure may refer to itself, so we bind its name to
+    /// a closure value made from its lifted body and its own environment
+    crate fn bind_closure_self(
+        &mut self,
+        info: SpanInfo,
+        block: BlockId,
+        closure: ClosureBody<'tcx>,
+    ) {
+        let ClosureBody { id, ty, env } = closure;
+        let var = self.alloc_local(id, info.span, ty);
+        let rvalue = Rvalue::Closure(ty, id, Operand::Lvalue(env.into()));
+        self.push_assignment(info, block, var.into(), rvalue);
     }
 }
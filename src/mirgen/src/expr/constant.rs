@@ -14,6 +14,7 @@ impl<'a, 'tcx> MirBuilder<'a, 'tcx> {
             | tir::ExprKind::Unary(..)
             | tir::ExprKind::Deref(..)
             | tir::ExprKind::Ref(..)
+            | tir::ExprKind::Cast(..)
             | tir::ExprKind::Bin(..)
             | tir::ExprKind::Field(..)
             | tir::ExprKind::Block(..)
@@ -25,6 +26,7 @@ impl<'a, 'tcx> MirBuilder<'a, 'tcx> {
             | tir::ExprKind::Ret(..)
             | tir::ExprKind::Assign(..)
             | tir::ExprKind::Loop(..)
+            | tir::ExprKind::Panic(..)
             | tir::ExprKind::Break
             | tir::ExprKind::Continue
             | tir::ExprKind::Adt { .. }
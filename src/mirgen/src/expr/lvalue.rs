@@ -54,7 +54,11 @@ impl<'a, 'tcx> MirBuilder<'a, 'tcx> {
 
     pub fn var_id_as_lvalue_builder(&mut self, id: ir::Id) -> LvalueBuilder<'tcx> {
         if let Some(&var_id) = self.var_ir_map.get(&id) {
-            LvalueBuilder::from(var_id)
+            let builder = LvalueBuilder::from(var_id);
+            // a boxed variable is accessed through its box
+            if self.is_boxed_var(id) { builder.project_deref() } else { builder }
+        } else if let Some(&lvalue) = self.upvars.get(&id) {
+            LvalueBuilder::from(lvalue)
         } else {
             panic!("no var found with id `{}`", id)
         }
@@ -80,6 +84,7 @@ impl<'a, 'tcx> MirBuilder<'a, 'tcx> {
             | tir::ExprKind::Const(..)
             | tir::ExprKind::Bin(..)
             | tir::ExprKind::Ref(..)
+            | tir::ExprKind::Cast(..)
             | tir::ExprKind::Unary(..)
             | tir::ExprKind::Block(..)
             | tir::ExprKind::ItemRef(..)
@@ -89,6 +94,7 @@ impl<'a, 'tcx> MirBuilder<'a, 'tcx> {
             | tir::ExprKind::Assign(..)
             | tir::ExprKind::Adt { .. }
             | tir::ExprKind::Closure { .. }
+            | tir::ExprKind::Panic(..)
             | tir::ExprKind::Break
             | tir::ExprKind::Continue
             | tir::ExprKind::Ret(..) => {
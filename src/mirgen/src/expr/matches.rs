@@ -58,7 +58,9 @@ impl<'a, 'b, 'tcx> PatternBuilder<'a, 'b, 'tcx> {
 
         for i in 0..arms.len() {
             let next_block_opt = self.pblocks.get(i + 1).copied();
-            let next_block = next_block_opt.unwrap_or_else(|| self.mk_abort(info));
+            // falling through the last arm means no pattern matched the scrutinee
+            let next_block =
+                next_block_opt.unwrap_or_else(|| self.mk_panic(info, PanicKind::Unreachable));
             let pblock = self.pblocks[i];
             let mut body_block = self.body_blocks[i];
             set!(
@@ -88,7 +90,7 @@ impl<'a, 'b, 'tcx> PatternBuilder<'a, 'b, 'tcx> {
         // predicate starts off as true by default
         let b = tcx.mk_const_bool(true);
         self.push_assignment(info, pblock, predicate, Rvalue::Operand(Operand::Const(b)));
-        set!(pblock = self.build_arm_predicate(pblock, predicate, scrut, &arm.pat));
+        set!(pblock = self.build_arm_predicate(pblock, next_block, predicate, scrut, &arm.pat));
         self.terminate(
             info,
             pblock,
@@ -101,10 +103,12 @@ impl<'a, 'b, 'tcx> PatternBuilder<'a, 'b, 'tcx> {
     }
 
     /// builds to code to test whether an arm's pattern matches
+    /// branches to `next_block` as soon as the scrutinee is known to be a different variant
     // BIG TODO clean this up
     fn build_arm_predicate(
         &mut self,
         mut pblock: BlockId,
+        next_block: BlockId,
         predicate: Lvalue<'tcx>,
         scrut: Lvalue<'tcx>,
         pat: &tir::Pattern<'tcx>,
@@ -115,7 +119,10 @@ impl<'a, 'b, 'tcx> PatternBuilder<'a, 'b, 'tcx> {
             tir::PatternKind::Wildcard => {}
             tir::PatternKind::Box(ref pat) => {
                 let deref_scrut = tcx.project_deref(scrut);
-                set!(pblock = self.build_arm_predicate(pblock, predicate, deref_scrut, pat));
+                set!(
+                    pblock =
+                        self.build_arm_predicate(pblock, next_block, predicate, deref_scrut, pat)
+                );
             }
             tir::PatternKind::Binding(_m, _ident, ref sub) => {
                 assert!(sub.is_none());
@@ -126,6 +133,7 @@ impl<'a, 'b, 'tcx> PatternBuilder<'a, 'b, 'tcx> {
                     set!(
                         pblock = self.build_arm_predicate(
                             pblock,
+                            next_block,
                             predicate,
                             tcx.project_field(scrut, *index, pat.ty),
                             pat
@@ -168,10 +176,8 @@ impl<'a, 'b, 'tcx> PatternBuilder<'a, 'b, 'tcx> {
                     discriminant_lvalue,
                     Rvalue::Discriminant(scrut),
                 );
-                // recall `idx` is the discriminant
-                // so we compare this with the discriminant of the scrutinee
-                assert!(idx.index() < i16::MAX as usize);
-                let discr = tcx.mk_const_discr(idx.index() as i16);
+                // compare the discriminant of the variant with that of the scrutinee
+                let discr = tcx.mk_const_discr(adt.variants[idx].discr);
                 let cmp_rvalue = set!(
                     pblock = self.build_binary_op(
                         pblock,
@@ -182,34 +188,25 @@ impl<'a, 'b, 'tcx> PatternBuilder<'a, 'b, 'tcx> {
                         Operand::Lvalue(discriminant_lvalue),
                     )
                 );
-
-                // TODO factor this out somehow
-                // `and` the predicate
                 let cmp_lvalue = self.alloc_tmp(info, tcx.types.bool).into();
                 self.push_assignment(info, pblock, cmp_lvalue, cmp_rvalue);
-                let and = set!(
-                    pblock = self.build_binary_op(
-                        pblock,
-                        pat.span,
-                        tcx.types.bool,
-                        BinOp::And,
-                        Operand::Lvalue(cmp_lvalue),
-                        Operand::Lvalue(predicate),
-                    )
+                // the fields are only tested once the discriminant matches
+                // as the fields of any other variant are not initialized
+                let variant_block = self.append_basic_block();
+                self.terminate(
+                    info,
+                    pblock,
+                    TerminatorKind::Cond(Operand::Lvalue(cmp_lvalue), variant_block, next_block),
                 );
-                self.push_assignment(info, pblock, predicate, and);
+                pblock = variant_block;
 
                 // project past the discriminant into the enum content
-                let variant_ty =
-                    tcx.mk_tup_iter(adt.variants[idx].fields.iter().map(|f| f.ty(tcx, substs)));
-                let enum_content_lvalue = LvalueBuilder::from(scrut)
-                    .project_field(FieldIdx::new(1), tcx.mk_adt_ty(adt, substs))
-                    .project_cast(variant_ty)
-                    .lvalue(tcx);
+                let enum_content_lvalue = self.project_variant_content(scrut, adt, substs, idx);
                 for (i, pat) in pats.iter().enumerate() {
                     set!(
                         pblock = self.build_arm_predicate(
                             pblock,
+                            next_block,
                             predicate,
                             tcx.project_field(enum_content_lvalue, FieldIdx::new(i), pat.ty),
                             pat
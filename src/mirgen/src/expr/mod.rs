@@ -5,6 +5,7 @@ use itertools::Itertools;
 use lcore::mir::*;
 
 use lcore::ty::Projection;
+pub use closure::ClosureBody;
 pub use lvalue::LvalueBuilder;
 
 mod closure;
@@ -39,12 +40,15 @@ impl<'a, 'tcx> MirBuilder<'a, 'tcx> {
             }
             // these expressions have `!` type, so have no return value so we can build them as a
             // expression statement
-            tir::ExprKind::Ret(..) | tir::ExprKind::Break | tir::ExprKind::Continue =>
-                self.build_expr_stmt(block, expr),
+            tir::ExprKind::Ret(..)
+            | tir::ExprKind::Panic(..)
+            | tir::ExprKind::Break
+            | tir::ExprKind::Continue => self.build_expr_stmt(block, expr),
             tir::ExprKind::Tuple(xs) => self.build_tuple(block, dest, expr, &xs),
             tir::ExprKind::Box(..)
             | tir::ExprKind::VarRef(..)
             | tir::ExprKind::Ref(..)
+            | tir::ExprKind::Cast(..)
             | tir::ExprKind::ItemRef(..)
             | tir::ExprKind::Field(..)
             | tir::ExprKind::Assign(..)
@@ -93,9 +97,9 @@ impl<'a, 'tcx> MirBuilder<'a, 'tcx> {
         target.unit()
     }
 
-    fn mk_abort(&mut self, info: SpanInfo) -> BlockId {
+    crate fn mk_panic(&mut self, info: SpanInfo, kind: PanicKind) -> BlockId {
         let block = self.append_basic_block();
-        self.terminate(info, block, TerminatorKind::Abort);
+        self.terminate(info, block, TerminatorKind::Panic(kind));
         block
     }
 
@@ -25,6 +25,7 @@ impl<'a, 'tcx> MirBuilder<'a, 'tcx> {
             | tir::ExprKind::Block(..)
             | tir::ExprKind::Match(..)
             | tir::ExprKind::Ref(..)
+            | tir::ExprKind::Cast(..)
             | tir::ExprKind::Assign(..)
             | tir::ExprKind::Ret(..)
             | tir::ExprKind::Bin(..)
@@ -32,6 +33,7 @@ impl<'a, 'tcx> MirBuilder<'a, 'tcx> {
             | tir::ExprKind::Tuple(..)
             | tir::ExprKind::Adt { .. }
             | tir::ExprKind::Closure { .. }
+            | tir::ExprKind::Panic(..)
             | tir::ExprKind::Break
             | tir::ExprKind::Continue => {
                 // create temporary var to hold the result
@@ -35,11 +35,11 @@ impl<'a, 'tcx> MirBuilder<'a, 'tcx> {
                 let rhs = set!(block = self.as_operand(block, r));
                 self.build_binary_op(block, expr.span, expr.ty, op, lhs, rhs)
             }
-            tir::ExprKind::Closure { ref upvars, ref body } =>
-                self.build_closure(block, expr, upvars, body),
+            tir::ExprKind::Closure { id, ref body, ref upvars, captures } =>
+                self.build_closure(block, expr, id, body, upvars, captures),
             tir::ExprKind::Unary(op, ref expr) => {
                 let operand = set!(block = self.as_operand(block, &expr));
-                block.and(Rvalue::Unary(op.into(), operand))
+                self.build_unary_op(block, op.into(), operand)
             }
             // assign is a bit out of place here,
             // as there is no direct rvalue variant for it
@@ -58,6 +58,10 @@ impl<'a, 'tcx> MirBuilder<'a, 'tcx> {
                 let lvalue = set!(block = self.as_lvalue(block, &expr));
                 block.and(Rvalue::Ref(lvalue))
             }
+            tir::ExprKind::Cast(cast, ref inner) => {
+                let operand = set!(block = self.as_operand(block, inner));
+                block.and(Rvalue::Cast(cast, operand, expr.ty))
+            }
             tir::ExprKind::Block(..)
             | tir::ExprKind::ItemRef(..)
             | tir::ExprKind::Loop(..)
@@ -69,6 +73,7 @@ impl<'a, 'tcx> MirBuilder<'a, 'tcx> {
             | tir::ExprKind::Const(..)
             | tir::ExprKind::Ret(..)
             | tir::ExprKind::VarRef(..)
+            | tir::ExprKind::Panic(..)
             | tir::ExprKind::Break
             | tir::ExprKind::Continue => {
                 let operand = set!(block = self.as_operand(block, expr));
@@ -77,6 +82,23 @@ impl<'a, 'tcx> MirBuilder<'a, 'tcx> {
         }
     }
 
+    crate fn build_unary_op(
+        &mut self,
+        block: BlockId,
+        op: UnaryOp,
+        operand: Operand<'tcx>,
+    ) -> BlockAnd<Rvalue<'tcx>> {
+        // fold unary operations on constants (notably negative literals) away
+        // an overflowing negation is left for the runtime check to report
+        if let Operand::Const(c) = operand {
+            let checked = self.tcx.sess.opts.debug_assertions();
+            if let Some(Ok(kind)) = mir::eval_unary_op(op, c.kind, checked) {
+                return block.and(Rvalue::Operand(Operand::Const(self.tcx.mk_const(kind))));
+            }
+        }
+        block.and(Rvalue::Unary(op, operand))
+    }
+
     crate fn build_binary_op(
         &mut self,
         block: BlockId,
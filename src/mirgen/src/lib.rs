@@ -26,6 +26,7 @@ extern crate smallvec;
 
 use cfg::Cfg;
 pub use ctor::build_variant_ctor;
+use expr::ClosureBody;
 pub use lowering_ctx::LoweringCtx;
 
 use ast::Mutability;
@@ -52,10 +53,13 @@ macro halt_on_error($tcx:expr) {{
 }}
 
 fn instance_mir<'tcx>(tcx: TyCtx<'tcx>, instance: Instance<'tcx>) -> &'tcx Mir<'tcx> {
-    match instance.kind {
+    let mir = match instance.kind {
         InstanceKind::Item => tcx.mir_of(instance.def_id),
+        InstanceKind::Closure(id) => tcx.mir_of(instance.def_id).closures[&id],
         InstanceKind::Intrinsic => unreachable!("intrinsics don't have mir"),
-    }
+        InstanceKind::Virtual(..) => unreachable!("virtual instances don't have mir"),
+    };
+    mir::inline(tcx, instance, mir)
 }
 
 fn mir_of<'tcx>(tcx: TyCtx<'tcx>, def_id: DefId) -> &'tcx Mir<'tcx> {
@@ -63,9 +67,12 @@ fn mir_of<'tcx>(tcx: TyCtx<'tcx>, def_id: DefId) -> &'tcx Mir<'tcx> {
     match node {
         DefNode::Ctor(variant) => self::build_variant_ctor(tcx, variant),
         DefNode::Item(item) => match item.kind {
-            ir::ItemKind::Fn(_, _, body) => self::build_mir(tcx, def_id, body),
+            ir::ItemKind::Fn(_, _, body)
+            | ir::ItemKind::Const(_, body)
+            | ir::ItemKind::Static(_, body) => self::build_mir(tcx, def_id, body),
             _ => panic!(),
         },
+        DefNode::AnonConst(anon_const) => self::build_mir(tcx, def_id, anon_const.body),
         DefNode::ImplItem(item) => match item.kind {
             ir::ImplItemKind::Fn(_, body) => self::build_mir(tcx, def_id, body),
         },
@@ -113,6 +120,8 @@ pub fn build_tir<'tcx>(tcx: TyCtx<'tcx>) -> LResult<tir::Prog<'tcx>> {
             // note that no tir is generated for enum constructors
             // the constructor code is generated at mir level only
             ir::ItemKind::TypeAlias(..) | ir::ItemKind::Enum(..) | ir::ItemKind::Struct(..) => {}
+            // constants are evaluated rather than lowered into tir
+            ir::ItemKind::Const(..) | ir::ItemKind::Static(..) => {}
             ir::ItemKind::Mod(..)
             | ir::ItemKind::Use(..)
             | ir::ItemKind::Trait { .. }
@@ -145,11 +154,17 @@ impl<'a, 'tcx> FnVisitor<'tcx> for MirDump<'a, 'tcx> {
 
 /// lowers `tir::Body` into `mir::Body`
 pub fn build_fn<'a, 'tcx>(ctx: &'a LoweringCtx<'tcx>, body: tir::Body<'tcx>) -> &'tcx Mir<'tcx> {
-    let tcx = ctx.tcx;
     let mut builder = MirBuilder::new(ctx, &body);
     let _ = builder.build_body();
-    let mir = ctx.alloc(builder.complete());
+    let mut mir = builder.complete();
+    // the closures are only stored in the outermost body
+    mir.closures = std::mem::take(&mut *ctx.closures.borrow_mut());
+    finish_mir(ctx.tcx, mir)
+}
 
+/// runs the analyses and optimizations on a newly built body
+fn finish_mir<'tcx>(tcx: TyCtx<'tcx>, mir: Mir<'tcx>) -> &'tcx Mir<'tcx> {
+    let mir = tcx.alloc(mir);
     mir::early_opt(tcx, mir);
     mir::typecheck(tcx, mir);
     mir::analyze(tcx, mir);
@@ -173,6 +188,8 @@ impl<'a, 'tcx> MirBuilder<'a, 'tcx> {
             cfg: Default::default(),
             vars: Default::default(),
             var_ir_map: Default::default(),
+            upvars: Default::default(),
+            closure: None,
         };
         let info = builder.span_info(span);
         builder.alloc_var(info, VarKind::Ret, body_ty);
@@ -180,7 +197,12 @@ impl<'a, 'tcx> MirBuilder<'a, 'tcx> {
     }
 
     fn complete(self) -> Mir<'tcx> {
-        Mir { basic_blocks: self.cfg.basic_blocks, vars: self.vars, argc: self.argc }
+        Mir {
+            basic_blocks: self.cfg.basic_blocks,
+            vars: self.vars,
+            argc: self.argc,
+            closures: Default::default(),
+        }
     }
 
     /// entry point to building
@@ -190,13 +212,23 @@ impl<'a, 'tcx> MirBuilder<'a, 'tcx> {
         self.with_scope(info, |this| {
             for param in &this.body.params {
                 let box tir::Pattern { id, span, ty, .. } = param.pat;
-                let lvalue = Lvalue::from(this.alloc_arg(id, span, ty));
-                if let tir::PatternKind::Binding(..) = param.pat.kind {
+                let lvalue = match param.pat.kind {
+                    // a boxed parameter is moved into its box like any other binding
+                    tir::PatternKind::Binding(..) if this.is_boxed_var(id) =>
+                        Lvalue::from(this.alloc_var(this.span_info(span), VarKind::Arg, ty)),
                     // nothing meaningful to recursively bind to
-                    continue;
-                }
+                    tir::PatternKind::Binding(_, ident, _) => {
+                        let arg = this.alloc_arg(id, span, ty);
+                        this.vars[arg].ident = Some(ident);
+                        continue;
+                    }
+                    _ => Lvalue::from(this.alloc_arg(id, span, ty)),
+                };
                 set!(block = this.bind_pat_to_lvalue(block, &param.pat, lvalue));
             }
+            if let Some(closure) = this.closure {
+                this.bind_closure_self(info, block, closure);
+            }
             set!(block = this.write_expr(block, Lvalue::ret(), &this.body.expr));
             this.terminate(info, block, TerminatorKind::Return);
             block.unit()
@@ -212,6 +244,9 @@ struct MirBuilder<'a, 'tcx> {
     cfg: Cfg<'tcx>,
     vars: IndexVec<VarId, Var<'tcx>>,
     var_ir_map: FxHashMap<ir::Id, VarId>,
+    /// the lvalues of the upvars captured by the closure being built (if any)
+    upvars: FxHashMap<ir::Id, Lvalue<'tcx>>,
+    closure: Option<ClosureBody<'tcx>>,
     argc: usize,
 }
 
@@ -224,6 +259,11 @@ impl<'a, 'tcx> MirBuilder<'a, 'tcx> {
         Lvalue::new(RET_VAR)
     }
 
+    /// whether the variable `id` is allocated in a gc box as it is captured by reference
+    fn is_boxed_var(&self, id: ir::Id) -> bool {
+        self.ctx.tables.is_boxed_var(id)
+    }
+
     fn alloc_tmp(&mut self, info: SpanInfo, ty: Ty<'tcx>) -> VarId {
         self.alloc_var(info, VarKind::Tmp, ty)
     }
@@ -261,7 +301,7 @@ impl<'a, 'tcx> MirBuilder<'a, 'tcx> {
 
     fn alloc_var(&mut self, info: SpanInfo, kind: VarKind, ty: Ty<'tcx>) -> VarId {
         // make it mutable by default, this can be unset later
-        let var = Var { mtbl: Mutability::Mut, info, kind, ty };
+        let var = Var { mtbl: Mutability::Mut, info, kind, ty, ident: None };
         self.vars.push(var)
     }
 }
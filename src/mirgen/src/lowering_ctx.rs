@@ -8,7 +8,9 @@ use ir::{self, CtorKind, DefId, DefKind, FieldIdx, Res, VariantIdx};
 use itertools::Itertools;
 use lcore::mir::Mir;
 use lcore::ty::*;
+use rustc_hash::FxHashMap;
 use span::Span;
+use std::cell::RefCell;
 use std::marker::PhantomData;
 use std::ops::Deref;
 
@@ -19,6 +21,8 @@ pub struct LoweringCtx<'tcx> {
     // currently used for allocating temporary patterns
     // for exhaustiveness and usefulness checks
     crate arena: DroplessArena,
+    /// the lifted bodies of the closures built so far
+    crate closures: RefCell<FxHashMap<ir::Id, &'tcx Mir<'tcx>>>,
 }
 
 impl<'tcx> Deref for LoweringCtx<'tcx> {
@@ -31,7 +35,7 @@ impl<'tcx> Deref for LoweringCtx<'tcx> {
 
 impl<'tcx> LoweringCtx<'tcx> {
     pub fn new(tcx: TyCtx<'tcx>, tables: &'tcx TypeckTables<'tcx>) -> Self {
-        Self { tcx, tables, arena: Default::default() }
+        Self { tcx, tables, arena: Default::default(), closures: Default::default() }
     }
 
     /// ir -> tir
@@ -110,7 +114,7 @@ impl<'tcx> Tir<'tcx> for ir::Item<'tcx> {
     type Output = tir::Item<'tcx>;
 
     fn to_tir(&self, ctx: &mut LoweringCtx<'tcx>) -> Self::Output {
-        let &Self { span, id, ident, vis, ref kind } = self;
+        let &Self { span, id, ident, vis, ref kind, .. } = self;
         match kind {
             ir::ItemKind::Fn(_sig, generics, body) => {
                 let ty = ctx.type_of(self.id.def);
@@ -119,6 +123,8 @@ impl<'tcx> Tir<'tcx> for ir::Item<'tcx> {
             }
             ir::ItemKind::Extern(..) => todo!(),
             ir::ItemKind::Use(..)
+            | ir::ItemKind::Const(..)
+            | ir::ItemKind::Static(..)
             | ir::ItemKind::Enum(..)
             | ir::ItemKind::Mod(..)
             | ir::ItemKind::Struct(..)
@@ -134,7 +140,9 @@ impl<'tcx> Tir<'tcx> for ir::Param<'tcx> {
 
     fn to_tir(&self, ctx: &mut LoweringCtx<'tcx>) -> Self::Output {
         let &Self { id, span, ref pat } = self;
-        tir::Param { id, span, pat: box pat.to_tir(ctx) }
+        let pat = pat.to_tir(ctx);
+        MatchCtxt { lcx: ctx }.check_irrefutable(&pat);
+        tir::Param { id, span, pat: box pat }
     }
 }
 
@@ -225,23 +233,24 @@ impl<'tcx> LoweringCtx<'tcx> {
     // https://stackoverflow.com/questions/43171341/swift-function-object-wrapper-in-apple-swift
     fn lower_closure(&mut self, closure: &ir::Expr, body: &ir::Body<'tcx>) -> tir::ExprKind<'tcx> {
         let body = box body.to_tir(self);
-        let upvar_captures = self.tables.upvar_captures_for_closure(closure.id);
-        let upvars =
-            upvar_captures.iter().map(|&upvar| self.capture_upvar(closure, upvar)).collect();
-        tir::ExprKind::Closure { body, upvars }
+        let captures = self.tables.upvar_captures_for_closure(closure.id);
+        let upvars = captures.iter().map(|&capture| self.capture_upvar(closure, capture)).collect();
+        tir::ExprKind::Closure { id: closure.id, body, upvars, captures }
     }
 
-    /// manually constructs a (mutable?) borrow expression to an upvar
-    fn capture_upvar(&mut self, closure: &ir::Expr, upvar: UpvarId) -> tir::Expr<'tcx> {
-        let id = upvar.var_id;
+    /// manually constructs the expression that initializes the captured upvar
+    /// this is either a copy of the upvar or a pointer to it depending on how it is captured
+    fn capture_upvar(&mut self, closure: &ir::Expr, capture: UpvarCapture) -> tir::Expr<'tcx> {
+        let id = capture.upvar_id.var_id;
         let span = closure.span;
         let ty = self.node_ty(id);
         // rebuild the `VarRef` expressions that the upvar refers to
-        let captured = box tir::Expr { span, ty, kind: tir::ExprKind::VarRef(id) };
-        // construct a mutable pointer expression to the captured upvar
-        let borrow_expr =
-            tir::Expr { span, ty: self.mk_ptr_ty(ty), kind: tir::ExprKind::Ref(captured) };
-        borrow_expr
+        let captured = tir::Expr { span, ty, kind: tir::ExprKind::VarRef(id) };
+        match capture.mode {
+            CaptureMode::ByValue => captured,
+            CaptureMode::ByRef =>
+                tir::Expr { span, ty: self.mk_ptr_ty(ty), kind: tir::ExprKind::Ref(box captured) },
+        }
     }
 }
 
@@ -266,11 +275,9 @@ impl<'tcx> Tir<'tcx> for ir::Let<'tcx> {
     type Output = tir::Let<'tcx>;
 
     fn to_tir(&self, ctx: &mut LoweringCtx<'tcx>) -> Self::Output {
-        tir::Let {
-            id: self.id,
-            pat: box self.pat.to_tir(ctx),
-            init: self.init.map(|init| box init.to_tir(ctx)),
-        }
+        let pat = self.pat.to_tir(ctx);
+        MatchCtxt { lcx: ctx }.check_irrefutable(&pat);
+        tir::Let { id: self.id, pat: box pat, init: self.init.map(|init| box init.to_tir(ctx)) }
     }
 }
 
@@ -359,6 +366,15 @@ impl<'tcx> LoweringCtx<'tcx> {
                         variant_idx: VariantIdx::new(0),
                     }
                 }
+                // constants are evaluated at compile time and their value is used directly
+                DefKind::Const | DefKind::Static => match self.tcx.eval_const(def_id) {
+                    Ok(c) => tir::ExprKind::Const(c),
+                    // the error has already been reported, so any value of the right type will do
+                    Err(..) => tir::ExprKind::Const(self.tcx.intern_const(Const {
+                        kind: ConstKind::Unit,
+                        ty: self.node_ty(expr.id),
+                    })),
+                },
                 DefKind::Ctor(..) => todo!(),
                 DefKind::Extern => todo!(),
                 DefKind::Impl => todo!(),
@@ -415,7 +431,11 @@ impl<'tcx> LoweringCtx<'tcx> {
         let kind = match kind {
             ir::ExprKind::Box(expr) => tir::ExprKind::Box(box expr.to_tir(self)),
             ir::ExprKind::Bin(op, l, r) =>
-                tir::ExprKind::Bin(*op, box l.to_tir(self), box r.to_tir(self)),
+                match self.tables.type_relative_resolutions().get(expr.id) {
+                    // overloaded operators resolve to the method of the lang item trait
+                    Some(&Res::Def(def_id, _)) => self.lower_overloaded_binop(expr, def_id, l, r),
+                    _ => tir::ExprKind::Bin(*op, box l.to_tir(self), box r.to_tir(self)),
+                },
             ir::ExprKind::Unary(UnaryOp::Deref, expr) =>
                 tir::ExprKind::Deref(box expr.to_tir(self)),
             ir::ExprKind::Unary(UnaryOp::Ref, expr) => tir::ExprKind::Ref(box expr.to_tir(self)),
@@ -450,6 +470,7 @@ impl<'tcx> LoweringCtx<'tcx> {
                 tir::ExprKind::Assign(box l.to_tir(self), box r.to_tir(self)),
             ir::ExprKind::Field(base, _) =>
                 tir::ExprKind::Field(box base.to_tir(self), self.tables.field_index(expr.id)),
+            ir::ExprKind::Panic(msg) => tir::ExprKind::Panic(*msg),
             ir::ExprKind::Break => tir::ExprKind::Break,
             ir::ExprKind::Continue => tir::ExprKind::Continue,
             ir::ExprKind::Err => unreachable!(),
@@ -457,6 +478,20 @@ impl<'tcx> LoweringCtx<'tcx> {
         tir::Expr { span, kind, ty }
     }
 
+    /// lowers `l op r` to a call `Trait::method(l, r)`
+    fn lower_overloaded_binop(
+        &mut self,
+        expr: &ir::Expr<'tcx>,
+        def_id: DefId,
+        l: &ir::Expr<'tcx>,
+        r: &ir::Expr<'tcx>,
+    ) -> tir::ExprKind<'tcx> {
+        let substs = self.expr_substs(expr);
+        let ty = self.type_of(def_id).subst(self.tcx, substs);
+        let f = tir::Expr { span: expr.span, ty, kind: tir::ExprKind::ItemRef(def_id, substs) };
+        tir::ExprKind::Call(box f, vec![l.to_tir(self), r.to_tir(self)])
+    }
+
     fn lower_expr_adjusted(&mut self, expr: &ir::Expr<'tcx>) -> tir::Expr<'tcx> {
         let tir = self.lower_expr(expr);
         let adjustments = self.tables.adjustments_for_expr(expr);
@@ -479,8 +514,9 @@ impl<'tcx> LoweringCtx<'tcx> {
         let span = expr.span;
         let kind = match adjustment.kind {
             AdjustmentKind::Deref => tir::ExprKind::Deref(box expr),
-            AdjustmentKind::NeverToAny => todo!(),
-            AdjustmentKind::Cast(_cast) => todo!(),
+            // the expression diverges so there is no value to convert
+            AdjustmentKind::NeverToAny => return tir::Expr { ty: adjustment.ty, ..expr },
+            AdjustmentKind::Cast(cast) => tir::ExprKind::Cast(cast, box expr),
         };
         tir::Expr { ty: adjustment.ty, span, kind }
     }
@@ -1,5 +1,10 @@
+use crate::expr::LvalueBuilder;
 use crate::{set, BlockAnd, BlockAndExt, MirBuilder};
+use ast::{Ident, Mutability};
+use index::Idx;
+use ir::{FieldIdx, VariantIdx};
 use lcore::mir::*;
+use lcore::ty::{AdtTy, SubstsRef};
 
 impl<'a, 'tcx> MirBuilder<'a, 'tcx> {
     /// binds each subpattern to the relevant value
@@ -8,23 +13,27 @@ impl<'a, 'tcx> MirBuilder<'a, 'tcx> {
     /// bind (a, b) l
     /// a <- l.0; b <- l.1
     ///
+    /// refutable patterns are reported when lowering to tir,
+    /// the parts of them that may fail to match are ignored here
     crate fn bind_pat_to_lvalue(
         &mut self,
         mut block: BlockId,
         irref_pat: &tir::Pattern<'tcx>,
         lvalue: Lvalue<'tcx>,
     ) -> BlockAnd<()> {
-        debug_assert!(irref_pat.is_irrefutable());
         let info = self.span_info(irref_pat.span);
         match irref_pat.kind {
             // we know `pat` is also irrefutable as the outer pat was irrefutable
             tir::PatternKind::Box(ref pat) =>
                 self.bind_pat_to_lvalue(block, pat, self.tcx.project_deref(lvalue)),
-            tir::PatternKind::Binding(m, _, _) => {
-                let &tir::Pattern { id, span, ty, .. } = irref_pat;
-                let rvalue = Rvalue::Operand(Operand::Lvalue(lvalue));
-                let local = self.alloc_local(id, span, ty);
-                self.vars[local].mtbl = m;
+            tir::PatternKind::Binding(m, ident, _) => {
+                let local = self.declare_binding(block, irref_pat, m, ident);
+                let operand = Operand::Lvalue(lvalue);
+                let rvalue = if self.is_boxed_var(irref_pat.id) {
+                    Rvalue::Box(operand)
+                } else {
+                    Rvalue::Operand(operand)
+                };
                 self.push_assignment(info, block, local.into(), rvalue);
                 block.unit()
             }
@@ -43,9 +52,75 @@ impl<'a, 'tcx> MirBuilder<'a, 'tcx> {
                 }
                 block.unit()
             }
-            tir::PatternKind::Lit(_) => panic!("refutable binding"),
-            tir::PatternKind::Variant(..) => todo!(),
-            tir::PatternKind::Wildcard => block.unit(),
+            tir::PatternKind::Variant(adt, substs, idx, ref pats) => {
+                let content = self.project_variant_content(lvalue, adt, substs, idx);
+                for (i, pat) in pats.iter().enumerate() {
+                    let lvalue = self.tcx.project_field(content, FieldIdx::new(i), pat.ty);
+                    set!(block = self.bind_pat_to_lvalue(block, pat, lvalue));
+                }
+                block.unit()
+            }
+            tir::PatternKind::Lit(_) | tir::PatternKind::Wildcard => block.unit(),
+        }
+    }
+
+    /// declares the bindings of an irrefutable pattern without initializing them
+    /// used for `let` statements without an initializer
+    crate fn declare_pat(&mut self, block: BlockId, irref_pat: &tir::Pattern<'tcx>) {
+        let info = self.span_info(irref_pat.span);
+        match irref_pat.kind {
+            tir::PatternKind::Box(ref pat) => self.declare_pat(block, pat),
+            tir::PatternKind::Binding(m, ident, _) => {
+                let local = self.declare_binding(block, irref_pat, m, ident);
+                // the box must exist before the variable is assigned through it,
+                // so it is allocated with uninitialized contents
+                if self.is_boxed_var(irref_pat.id) {
+                    let contents = self.alloc_tmp(info, irref_pat.ty);
+                    let rvalue = Rvalue::Box(Operand::Lvalue(contents.into()));
+                    self.push_assignment(info, block, local.into(), rvalue);
+                }
+            }
+            tir::PatternKind::Field(ref fs) =>
+                fs.iter().for_each(|f| self.declare_pat(block, &f.pat)),
+            tir::PatternKind::Variant(.., ref pats) =>
+                pats.iter().for_each(|pat| self.declare_pat(block, pat)),
+            tir::PatternKind::Lit(_) | tir::PatternKind::Wildcard => {}
         }
     }
+
+    /// projects past the discriminant of an enum into the fields of the given variant
+    crate fn project_variant_content(
+        &self,
+        lvalue: Lvalue<'tcx>,
+        adt: &'tcx AdtTy,
+        substs: SubstsRef<'tcx>,
+        idx: VariantIdx,
+    ) -> Lvalue<'tcx> {
+        let tcx = self.tcx;
+        let variant_ty =
+            tcx.mk_tup_iter(adt.variants[idx].fields.iter().map(|f| f.ty(tcx, substs)));
+        LvalueBuilder::from(lvalue)
+            .project_field(FieldIdx::new(1), tcx.mk_adt_ty(adt, substs))
+            .project_cast(variant_ty)
+            .lvalue(tcx)
+    }
+
+    /// allocates the local of a binding pattern and marks the start of its storage
+    /// the local of a boxed variable holds its box instead of its value
+    fn declare_binding(
+        &mut self,
+        block: BlockId,
+        binding: &tir::Pattern<'tcx>,
+        m: Mutability,
+        ident: Ident,
+    ) -> VarId {
+        let &tir::Pattern { id, span, ty, .. } = binding;
+        let info = self.span_info(span);
+        let ty = if self.is_boxed_var(id) { self.tcx.mk_box_ty(ty) } else { ty };
+        let local = self.alloc_local(id, span, ty);
+        self.vars[local].mtbl = m;
+        self.vars[local].ident = Some(ident);
+        self.push(block, Stmt { info, kind: StmtKind::StorageLive(local) });
+        local
+    }
 }
@@ -16,6 +16,17 @@ enum PatternError<'p, 'tcx> {
     NonexhaustiveMatch(Witness<'p, 'tcx>),
     #[error("redundant pattern")]
     RedundantPattern,
+    #[error("refutable pattern in binding")]
+    RefutableBinding,
+}
+
+impl<'p, 'tcx> MatchCtxt<'p, 'tcx> {
+    /// the patterns of `let` statements and parameters must always match
+    crate fn check_irrefutable(&self, pat: &tir::Pattern<'tcx>) {
+        if pat.is_refutable() {
+            self.tcx.sess.emit_error(pat.span, PatternError::RefutableBinding);
+        }
+    }
 }
 
 impl<'p, 'tcx> Deref for MatchCtxt<'p, 'tcx> {
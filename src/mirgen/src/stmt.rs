@@ -4,11 +4,16 @@ impl<'a, 'tcx> MirBuilder<'a, 'tcx> {
     crate fn build_stmt(&mut self, mut block: BlockId, stmt: &tir::Stmt<'tcx>) -> BlockAnd<()> {
         match &stmt.kind {
             tir::StmtKind::Let(tir::Let { pat, init, .. }) => {
-                let lvalue = match init {
-                    Some(expr) => set!(block = self.as_lvalue(block, expr)),
-                    None => self.alloc_tmp(self.span_info(stmt.span), pat.ty).into(),
-                };
-                self.bind_pat_to_lvalue(block, &pat, lvalue)
+                match init {
+                    Some(expr) => {
+                        let lvalue = set!(block = self.as_lvalue(block, expr));
+                        self.bind_pat_to_lvalue(block, &pat, lvalue)
+                    }
+                    None => {
+                        self.declare_pat(block, &pat);
+                        block.unit()
+                    }
+                }
             }
             tir::StmtKind::Expr(expr) => self.build_expr_stmt(block, expr),
         }
@@ -39,6 +44,10 @@ impl<'a, 'tcx> MirBuilder<'a, 'tcx> {
                 self.push_assignment(info, block, lvalue, rvalue);
                 block.unit()
             }
+            tir::ExprKind::Panic(msg) => {
+                self.terminate(info, block, TerminatorKind::Panic(PanicKind::Explicit(*msg)));
+                self.append_basic_block().unit()
+            }
             tir::ExprKind::Break => self.break_scope(info, block, BreakType::Break),
             tir::ExprKind::Continue => self.break_scope(info, block, BreakType::Continue),
             tir::ExprKind::Box(..)
@@ -51,6 +60,7 @@ impl<'a, 'tcx> MirBuilder<'a, 'tcx> {
             | tir::ExprKind::ItemRef(..)
             | tir::ExprKind::Tuple(..)
             | tir::ExprKind::Ref(..)
+            | tir::ExprKind::Cast(..)
             | tir::ExprKind::Deref(..)
             | tir::ExprKind::Field(..)
             | tir::ExprKind::Call(..)
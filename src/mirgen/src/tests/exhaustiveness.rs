@@ -65,3 +65,31 @@ fn check_nonexhaustive_box_pattern() {
 
     expect_analysis_error!(src);
 }
+
+#[test]
+fn check_refutable_let_binding() {
+    let src = r#"
+    enum Option<T> {
+        Some(T),
+        None,
+    }
+
+    fn f() -> int {
+        let Option::Some(x) = Option::Some(5);
+        x
+    }
+    "#;
+
+    expect_analysis_error!(src);
+}
+
+#[test]
+fn check_refutable_param() {
+    let src = r#"
+    fn f((x, 1): (int, int)) -> int {
+        x
+    }
+    "#;
+
+    expect_analysis_error!(src);
+}
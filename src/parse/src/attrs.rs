@@ -0,0 +1,69 @@
+//! validation of the items that attributes are applied to
+//! the attributes themselves are handled by later passes (e.g. `derive` is expanded by `expand`)
+
+use crate::*;
+use ast::*;
+
+impl<'a> Parser<'a> {
+    /// checks the target of each attribute within `module` (and its submodules)
+    crate fn check_attrs(&self, module: &Module) {
+        for item in &module.items {
+            match &item.kind {
+                ItemKind::Mod(submodule) => self.check_attrs(submodule),
+                ItemKind::Trait { items, .. } | ItemKind::Impl { items, .. } =>
+                    for item in items {
+                        self.check_no_derives(&item.attrs);
+                        self.check_no_lang(&item.attrs);
+                    },
+                ItemKind::Extern(_, items) =>
+                    for item in items {
+                        self.check_no_derives(&item.attrs);
+                        self.check_no_inline(&item.attrs);
+                        self.check_no_lang(&item.attrs);
+                    },
+                _ => {}
+            }
+
+            for attr in &item.attrs {
+                match &attr.kind {
+                    AttrKind::Derive(..) =>
+                        if !matches!(item.kind, ItemKind::Struct(..) | ItemKind::Enum(..)) {
+                            self.build_err(attr.span, ParseError::InvalidDeriveTarget).emit();
+                        },
+                    AttrKind::Inline =>
+                        if !matches!(item.kind, ItemKind::Fn(..)) {
+                            self.build_err(attr.span, ParseError::InvalidInlineTarget).emit();
+                        },
+                    AttrKind::Lang =>
+                        if !matches!(item.kind, ItemKind::Trait { .. }) {
+                            self.build_err(attr.span, ParseError::InvalidLangTarget).emit();
+                        },
+                }
+            }
+        }
+    }
+
+    fn check_no_derives(&self, attrs: &[Attribute]) {
+        for attr in attrs {
+            if let AttrKind::Derive(..) = attr.kind {
+                self.build_err(attr.span, ParseError::InvalidDeriveTarget).emit();
+            }
+        }
+    }
+
+    fn check_no_inline(&self, attrs: &[Attribute]) {
+        for attr in attrs {
+            if let AttrKind::Inline = attr.kind {
+                self.build_err(attr.span, ParseError::InvalidInlineTarget).emit();
+            }
+        }
+    }
+
+    fn check_no_lang(&self, attrs: &[Attribute]) {
+        for attr in attrs {
+            if let AttrKind::Lang = attr.kind {
+                self.build_err(attr.span, ParseError::InvalidLangTarget).emit();
+            }
+        }
+    }
+}
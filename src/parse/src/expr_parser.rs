@@ -6,7 +6,7 @@ const UNARY_OPS: [TokenType; 4] =
     [TokenType::Not, TokenType::Minus, TokenType::Star, TokenType::And];
 const POSTFIX_OPS: [TokenType; 3] =
     [TokenType::Dot, TokenType::OpenSqBracket, TokenType::OpenParen];
-const CMP_OPS: [TokenType; 2] = [TokenType::Lt, TokenType::Gt];
+const CMP_OPS: [TokenType; 4] = [TokenType::Lt, TokenType::Gt, TokenType::Deq, TokenType::Neq];
 const TERM_OPS: [TokenType; 2] = [TokenType::Plus, TokenType::Minus];
 const FACTOR_OPS: [TokenType; 2] = [TokenType::Star, TokenType::Slash];
 
@@ -154,6 +154,8 @@ impl<'a> Parse<'a> for PrimaryExprParser {
             };
             let path = parser.mk_path(self_kw.span, vec![segment]);
             Ok(parser.mk_expr(self_kw.span, ExprKind::Path(path)))
+        } else if let Some(panic_kw) = parser.accept_macro(sym::panic) {
+            PanicParser { panic_kw }.parse(parser)
         } else if parser.is_ident()?.is_some() {
             PathExprParser.parse(parser)
         } else if let Some(tok) = parser.accept(TokenType::False) {
@@ -272,6 +274,12 @@ mod test {
         assert_eq!(expr, "(((1 2) 3) 4)")
     }
 
+    #[test]
+    fn parse_panic() {
+        let expr = fmt_expr!("panic!(\"oh no\")");
+        assert_eq!(expr, "panic!(\"oh no\")")
+    }
+
     #[test]
     fn test_parser_span() {
         let expr = parse_expr!("    3");
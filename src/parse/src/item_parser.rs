@@ -1,14 +1,15 @@
 use crate::*;
 use ast::*;
 use lex::TokenType;
-use span::{with_source_map, ModuleKind};
+use span::{sym, with_source_map, ModuleKind};
 use std::convert::TryFrom;
 
-const ITEM_KEYWORDS: [TokenType; 10] = [
+const ITEM_KEYWORDS: [TokenType; 11] = [
     TokenType::Fn,
     TokenType::Struct,
     TokenType::Enum,
     TokenType::Const,
+    TokenType::Static,
     TokenType::Impl,
     TokenType::Extern,
     TokenType::Type,
@@ -23,6 +24,21 @@ impl<'a> Parse<'a> for ItemParser {
     type Output = P<Item>;
 
     fn parse(&mut self, parser: &mut Parser<'a>) -> ParseResult<'a, Self::Output> {
+        let mut attrs = vec![];
+        while let Some(pound) = parser.accept(TokenType::Pound) {
+            attrs.push(AttrParser { pound }.parse(parser)?);
+        }
+        let mut item = self.parse_item_without_attrs(parser)?;
+        item.attrs = attrs;
+        Ok(item)
+    }
+}
+
+impl ItemParser {
+    fn parse_item_without_attrs<'a>(
+        &mut self,
+        parser: &mut Parser<'a>,
+    ) -> ParseResult<'a, P<Item>> {
         let vis = VisibilityParser.parse(parser)?;
         // these items have a different syntax to the rest
         if let Some(_impl_kw) = parser.accept(TokenType::Impl) {
@@ -40,11 +56,20 @@ impl<'a> Parse<'a> for ItemParser {
             return Ok(parser.mk_item(vis.span.merge(name.span), vis, name, ItemKind::Mod(module)));
         }
 
-        let kw = parser.expect_one_of(&ITEM_KEYWORDS)?;
+        let mut kw = parser.expect_one_of(&ITEM_KEYWORDS)?;
+        let mut constness = Constness::NotConst;
+        // `const fn` is a function declaration, not a constant
+        if kw.ttype == TokenType::Const {
+            if let Some(fn_kw) = parser.accept(TokenType::Fn) {
+                kw = fn_kw;
+                constness = Constness::Const;
+            }
+        }
         let ident = parser.expect_ident()?;
         let (kind_span, kind) = parser.with_span(
             &mut |parser: &mut Parser<'a>| match kw.ttype {
-                TokenType::Fn => FnParser.parse(parser),
+                TokenType::Fn => FnParser { constness }.parse(parser),
+                TokenType::Const | TokenType::Static => ConstParser { kw }.parse(parser),
                 TokenType::Struct => StructDeclParser.parse(parser),
                 TokenType::Enum => EnumParser.parse(parser),
                 TokenType::Type => TypeAliasParser.parse(parser),
@@ -60,6 +85,37 @@ impl<'a> Parse<'a> for ItemParser {
     }
 }
 
+/// #[<attr>]
+/// the only supported attribute is currently `derive`
+/// <attr> = derive(<ident>, ...) | inline | lang
+pub struct AttrParser {
+    pound: Tok,
+}
+
+impl<'a> Parse<'a> for AttrParser {
+    type Output = Attribute;
+
+    fn parse(&mut self, parser: &mut Parser<'a>) -> ParseResult<'a, Self::Output> {
+        parser.expect(TokenType::OpenSqBracket)?;
+        let name = parser.expect_ident()?;
+        let kind = match name.symbol {
+            sym::derive => {
+                parser.expect(TokenType::OpenParen)?;
+                let inner = PunctuatedParser {
+                    inner: |parser: &mut Parser<'a>| parser.expect_ident(),
+                    separator: TokenType::Comma,
+                };
+                AttrKind::Derive(ParenParser { inner }.parse(parser)?)
+            }
+            sym::inline => AttrKind::Inline,
+            sym::lang => AttrKind::Lang,
+            _ => return Err(parser.build_err(name.span, ParseError::UnknownAttribute(name))),
+        };
+        let close_bracket = parser.expect(TokenType::CloseSqBracket)?;
+        Ok(Attribute { span: self.pound.span.merge(close_bracket.span), kind })
+    }
+}
+
 pub struct ModuleParser;
 
 impl<'a> Parse<'a> for ModuleParser {
@@ -153,9 +209,9 @@ impl<'a> Parse<'a> for ExternParser {
             if let Some(close_brace) = parser.accept(TokenType::CloseBrace) {
                 break close_brace;
             }
-            let box Item { span, id, kind, vis, ident } = parser.parse_item()?;
+            let box Item { span, id, kind, vis, ident, attrs } = parser.parse_item()?;
             match ForeignItemKind::try_from(kind) {
-                Ok(kind) => foreign_items.push(box Item { span, id, vis, ident, kind }),
+                Ok(kind) => foreign_items.push(box Item { span, id, vis, ident, attrs, kind }),
                 Err(kind) => parser.build_err(span, ParseError::InvalidForeignItem(kind)).emit(),
             };
         };
@@ -176,16 +232,16 @@ impl<'a> Parse<'a> for TraitParser {
     type Output = ItemKind;
 
     fn parse(&mut self, parser: &mut Parser<'a>) -> ParseResult<'a, Self::Output> {
-        parser.expect(TokenType::OpenBrace)?;
         let generics = parser.parse_generics()?;
+        parser.expect(TokenType::OpenBrace)?;
         let items = ItemParser
             .many()
             .parse(parser)?
             .into_iter()
             .filter_map(|item| {
-                let Item { span, id, vis, ident, .. } = *item;
-                match AssocItemKind::try_from(item.kind) {
-                    Ok(kind) => Some(box Item { span, id, vis, ident, kind }),
+                let Item { span, id, vis, ident, attrs, kind } = *item;
+                match AssocItemKind::try_from(kind) {
+                    Ok(kind) => Some(box Item { span, id, vis, ident, attrs, kind }),
                     Err(kind) => {
                         parser.build_err(span, ParseError::InvalidTraitItem(kind)).emit();
                         None
@@ -220,9 +276,9 @@ impl<'a> Parse<'a> for ImplParser {
             if let Some(close_brace) = parser.accept(TokenType::CloseBrace) {
                 break close_brace;
             }
-            let box Item { span, id, kind, vis, ident } = parser.parse_item()?;
+            let box Item { span, id, kind, vis, ident, attrs } = parser.parse_item()?;
             match AssocItemKind::try_from(kind) {
-                Ok(kind) => items.push(box Item { span, id, vis, ident, kind }),
+                Ok(kind) => items.push(box Item { span, id, vis, ident, attrs, kind }),
                 Err(kind) => parser.build_err(span, ParseError::InvalidImplItem(kind)).emit(),
             };
         };
@@ -232,6 +288,29 @@ impl<'a> Parse<'a> for ImplParser {
     }
 }
 
+/// assumes that { <vis> (const|static) <ident> } has already been parsed
+/// : <ty> = <expr>;
+pub struct ConstParser {
+    kw: Tok,
+}
+
+impl<'a> Parse<'a> for ConstParser {
+    type Output = ItemKind;
+
+    fn parse(&mut self, parser: &mut Parser<'a>) -> ParseResult<'a, Self::Output> {
+        parser.expect(TokenType::Colon)?;
+        let ty = parser.parse_ty(false);
+        parser.expect(TokenType::Eq)?;
+        let expr = parser.parse_expr();
+        parser.expect(TokenType::Semi)?;
+        match self.kw.ttype {
+            TokenType::Const => Ok(ItemKind::Const(ty, expr)),
+            TokenType::Static => Ok(ItemKind::Static(ty, expr)),
+            _ => unreachable!(),
+        }
+    }
+}
+
 pub struct TypeAliasParser;
 
 impl<'a> Parse<'a> for TypeAliasParser {
@@ -283,8 +362,12 @@ impl<'a> Parse<'a> for VariantParser {
     fn parse(&mut self, parser: &mut Parser<'a>) -> ParseResult<'a, Self::Output> {
         let ident = parser.expect_uident()?;
         let kind = VariantKindParser.parse(parser)?;
+        let discr = match parser.accept(TokenType::Eq) {
+            Some(_eq) => Some(AnonConstParser.parse(parser)?),
+            None => None,
+        };
         let span = ident.span.merge(parser.empty_span());
-        Ok(Variant { id: parser.mk_id(), span, kind, ident })
+        Ok(Variant { id: parser.mk_id(), span, kind, ident, discr })
     }
 }
 
@@ -342,15 +425,18 @@ impl<'a> Parse<'a> for EnumParser {
     }
 }
 
-pub struct FnParser;
+pub struct FnParser {
+    constness: Constness,
+}
 
 impl<'a> Parse<'a> for FnParser {
     type Output = ItemKind;
 
-    /// assumes that { <vis> fn <ident> } has already been parsed
+    /// assumes that { <vis> const? fn <ident> } has already been parsed
     fn parse(&mut self, parser: &mut Parser<'a>) -> ParseResult<'a, Self::Output> {
         let generics = GenericsParser.parse(parser)?;
-        let sig = FnSigParser { require_type_annotations: true }.parse(parser)?;
+        let mut sig = FnSigParser { require_type_annotations: true }.parse(parser)?;
+        sig.constness = self.constness;
         let block = if let Some(open_brace) = parser.accept(TokenType::OpenBrace) {
             Some(parser.parse_block(open_brace)?)
         } else {
@@ -381,6 +467,25 @@ mod tests {
         let _prog = parse!("enum B { T(bool), F { x: bool, y: &int } }");
     }
 
+    #[test]
+    fn parse_enum_with_discriminants() {
+        let _prog = parse!("enum B { T = 1, F = 1 + 1 }");
+        let _prog = parse!("enum B { T(bool) = 4, F }");
+    }
+
+    #[test]
+    fn parse_const_and_static() {
+        let _prog = parse!("const N: int = 5;");
+        let _prog = parse!("pub static S: bool = !false;");
+        let _prog = parse!("const fn f(x: int) -> int { x }");
+    }
+
+    #[test]
+    fn parse_array_ty() {
+        let _prog = parse!("struct S { xs: [int; 4] }");
+        let _prog = parse!("const N: int = 2; struct S { xs: [[bool; N]; N * 2] }");
+    }
+
     #[test]
     fn parse_struct() {
         let _prog = parse!("struct S { x: int }");
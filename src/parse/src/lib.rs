@@ -8,6 +8,7 @@ extern crate log;
 #[cfg(test)]
 mod tests;
 
+mod attrs;
 mod expr_parser;
 mod item_parser;
 mod parse_error;
@@ -26,7 +27,7 @@ use parse_error::{ParseError, ParseResult};
 pub use parser::Parser;
 use pattern_parser::*;
 use prog_parser::AstParser;
-use span::{kw, Span};
+use span::{kw, sym, Span};
 use stmt_parser::StmtParser;
 use ty_parser::*;
 
@@ -170,6 +171,35 @@ impl<'a> Parse<'a> for RetParser {
     }
 }
 
+/// panic!("msg")
+pub struct PanicParser {
+    pub panic_kw: Tok,
+}
+
+impl<'a> Parse<'a> for PanicParser {
+    type Output = P<Expr>;
+
+    fn parse(&mut self, parser: &mut Parser<'a>) -> ParseResult<'a, Self::Output> {
+        parser.expect(TokenType::OpenParen)?;
+        let msg = parser.expect_str()?;
+        let close_paren = parser.expect(TokenType::CloseParen)?;
+        let span = self.panic_kw.span.merge(close_paren.span);
+        Ok(parser.mk_expr(span, ExprKind::Panic(msg.symbol)))
+    }
+}
+
+/// an expression in a position that requires a constant
+pub struct AnonConstParser;
+
+impl<'a> Parse<'a> for AnonConstParser {
+    type Output = AnonConst;
+
+    fn parse(&mut self, parser: &mut Parser<'a>) -> ParseResult<'a, Self::Output> {
+        let expr = parser.parse_expr();
+        Ok(AnonConst { id: parser.mk_id(), expr })
+    }
+}
+
 pub struct FnSigParser {
     pub require_type_annotations: bool,
 }
@@ -188,7 +218,7 @@ impl<'a> Parse<'a> for FnSigParser {
             output = Some(parser.mk_infer_ty())
         }
 
-        Ok(FnSig { params, ret_ty: output })
+        Ok(FnSig { constness: Constness::NotConst, params, ret_ty: output })
     }
 }
 
@@ -769,8 +799,29 @@ impl<'a> Parse<'a> for TyParamParser {
 
     fn parse(&mut self, parser: &mut Parser<'a>) -> ParseResult<'a, Self::Output> {
         let ident = parser.expect_ident()?;
+        let bounds = match parser.accept(TokenType::Colon) {
+            Some(_) => PunctuatedParser { inner: GenericBoundParser, separator: TokenType::Plus }
+                .parse(parser)?,
+            None => vec![],
+        };
         let default = parser.accept(TokenType::Eq).map(|_| parser.parse_ty(false));
-        // eventually parse bounds here
-        Ok(TyParam { span: ident.span, id: parser.mk_id(), ident, default })
+        Ok(TyParam { span: ident.span, id: parser.mk_id(), ident, default, bounds })
+    }
+}
+
+/// <path> ((<ty>...) (-> <ty>)?)?
+pub struct GenericBoundParser;
+
+impl<'a> Parse<'a> for GenericBoundParser {
+    type Output = GenericBound;
+
+    fn parse(&mut self, parser: &mut Parser<'a>) -> ParseResult<'a, Self::Output> {
+        let path = parser.parse_type_path()?;
+        // the `Fn(A, B) -> C` sugar is parsed into a function type
+        let sig = parser.try_parse(&mut FnTyParser.spanned(false)).map(|(span, (inputs, output))| {
+            parser.mk_ty(path.span.merge(span), TyKind::Fn(inputs, output))
+        });
+        let span = sig.as_ref().map_or(path.span, |sig| sig.span);
+        Ok(GenericBound { span, path, sig })
     }
 }
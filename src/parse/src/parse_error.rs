@@ -49,4 +49,12 @@ pub enum ParseError {
     ElidedTypeNotAllowedInThisContext,
     #[error("unterminated string literal")]
     UnterminatedStringLiteral,
+    #[error("unknown attribute `{0}`")]
+    UnknownAttribute(Ident),
+    #[error("`derive` may only be applied to structs and enums")]
+    InvalidDeriveTarget,
+    #[error("`inline` may only be applied to functions")]
+    InvalidInlineTarget,
+    #[error("`lang` may only be applied to traits")]
+    InvalidLangTarget,
 }
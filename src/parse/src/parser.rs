@@ -39,6 +39,7 @@ impl<'a> Parser<'a> {
     pub fn parse(&mut self) -> Option<P<Ast>> {
         self.with_file(ROOT_FILE_IDX, |parser| {
             let ast = AstParser.parse(parser).map_err(|err| err.emit()).ok()?;
+            parser.check_attrs(&ast.module);
             validate::AstValidator::default().visit_ast(&ast);
             Some(ast)
         })
@@ -261,7 +262,7 @@ impl<'a> Parser<'a> {
             _ => {}
         }
 
-        box Item { span, id: self.mk_id(), ident, vis, kind }
+        box Item { span, id: self.mk_id(), ident, vis, attrs: vec![], kind }
     }
 
     // same as next except the return value is suppressed
@@ -371,6 +372,17 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// accepts the `name!` prefix of an invocation of the built-in macro `name`
+    crate fn accept_macro(&mut self, name: Symbol) -> Option<Tok> {
+        let tok = self.safe_peek().ok()?;
+        let next = self.tokens.get(self.idx + 1)?;
+        if tok.ttype != TokenType::Ident(name) || next.ttype != TokenType::Not {
+            return None;
+        }
+        self.idx += 2;
+        Some(tok)
+    }
+
     crate fn accept_str(&mut self) -> Option<Ident> {
         self.expect_str().ok()
     }
@@ -8,7 +8,7 @@ impl<'a> Parse<'a> for AstParser {
 
     fn parse(&mut self, parser: &mut Parser<'a>) -> ParseResult<'a, Self::Output> {
         let module = ModuleParser.parse(parser)?;
-        Ok(box Ast { module })
+        Ok(box Ast { module, next_node_id: parser.mk_id() })
     }
 }
 
@@ -19,6 +19,64 @@ fn parse_fn_sig_missing_type_annotation() {
     expect_parse_err!(src);
 }
 
+#[test]
+fn parse_derive_attribute() {
+    let src = "#[derive(Eq, Clone)] struct S { x: int }";
+    let driver = ldriver::Driver::from_src(src);
+    let ast = driver.parse().unwrap();
+    let attrs = &ast.module.items[0].attrs;
+    assert_eq!(attrs.len(), 1);
+    match &attrs[0].kind {
+        AttrKind::Derive(traits) => assert_eq!(
+            traits.iter().map(|ident| ident.to_string()).collect::<Vec<_>>(),
+            vec!["Eq", "Clone"]
+        ),
+        AttrKind::Inline | AttrKind::Lang => panic!("expected derive attribute"),
+    }
+    // impls are generated during expansion, not parsing
+    assert_eq!(ast.module.items.len(), 1);
+}
+
+#[test]
+fn parse_unknown_attribute() {
+    let src = "#[cold] fn f() {}";
+    expect_parse_err!(src);
+}
+
+#[test]
+fn parse_inline_attribute() {
+    let src = "#[inline] fn f() {}";
+    let driver = ldriver::Driver::from_src(src);
+    let ast = driver.parse().unwrap();
+    assert_eq!(ast.module.items[0].attrs[0].kind, AttrKind::Inline);
+}
+
+#[test]
+fn parse_inline_on_struct() {
+    let src = "#[inline] struct S;";
+    expect_parse_err!(src);
+}
+
+#[test]
+fn parse_lang_attribute() {
+    let src = "#[lang] trait Add {}";
+    let driver = ldriver::Driver::from_src(src);
+    let ast = driver.parse().unwrap();
+    assert_eq!(ast.module.items[0].attrs[0].kind, AttrKind::Lang);
+}
+
+#[test]
+fn parse_lang_on_fn() {
+    let src = "#[lang] fn f() {}";
+    expect_parse_err!(src);
+}
+
+#[test]
+fn parse_derive_on_fn() {
+    let src = "#[derive(Eq)] fn f() {}";
+    expect_parse_err!(src);
+}
+
 macro parse_expr($src:expr) {{
     let driver = ldriver::Driver::from_src($src);
     driver.parse_expr().unwrap()
@@ -52,6 +110,12 @@ fn parse_assign() {
     let _expr = parse_expr!("x = y = 2");
 }
 
+#[test]
+fn parse_equality_operators() {
+    assert_eq!(fmt_expr!("x == y"), "(== x y)");
+    assert_eq!(fmt_expr!("x != y + 1"), "(!= x (+ y 1))");
+}
+
 #[test]
 fn parse_nested_if() {
     let _expr = parse_expr!("if false { 5 } else if true { 6 } else { 7 }");
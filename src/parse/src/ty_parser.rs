@@ -37,8 +37,13 @@ impl<'a> Parse<'a> for TyParser {
             Ok(parser.mk_ty(star.span.merge(ty.span), TyKind::Ptr(ty)))
         } else if let Some(lsq) = parser.accept(TokenType::OpenSqBracket) {
             let ty = self.parse(parser)?;
+            parser.expect(TokenType::Semi)?;
+            let len = AnonConstParser.parse(parser)?;
             let rsq = parser.expect(TokenType::CloseSqBracket)?;
-            Ok(parser.mk_ty(lsq.span.merge(rsq.span), TyKind::Array(ty)))
+            Ok(parser.mk_ty(lsq.span.merge(rsq.span), TyKind::Array(ty, len)))
+        } else if let Some(dyn_kw) = parser.accept(TokenType::Dyn) {
+            let path = parser.parse_type_path()?;
+            Ok(parser.mk_ty(dyn_kw.span.merge(path.span), TyKind::Dyn(path)))
         } else if parser.is_ident()?.is_some() {
             let path = parser.parse_type_path()?;
             Ok(parser.mk_ty(path.span, TyKind::Path(path)))
@@ -1,6 +1,6 @@
 use crate::*;
 use ast::*;
-use ir::{CtorKind, DefKind, HasDefKind};
+use ir::{CtorKind, DefKind, HasDefKind, Res};
 
 /// collects all `DefId`s
 /// this forward declares all "hoisted" things such as items & constructors
@@ -10,6 +10,7 @@ use ir::{CtorKind, DefKind, HasDefKind};
 /// type parameters (in generics)
 /// variants and constructors
 /// fields declarations
+/// anonymous constants
 pub struct DefCollector<'a, 'r> {
     resolver: &'a mut Resolver<'r>,
     curr_mod: ModuleId,
@@ -41,7 +42,13 @@ impl<'ast, 'r> Visitor<'ast> for DefCollector<'ast, 'r> {
     }
 
     fn visit_item(&mut self, item: &'ast Item) {
-        self.resolver.def_item(self.curr_mod, item.ident, item.id, item.kind.def_kind());
+        let def_kind = item.kind.def_kind();
+        let def_id = self.resolver.def_item(self.curr_mod, item.ident, item.id, def_kind);
+        if item.attrs.iter().any(|attr| attr.kind == AttrKind::Lang) {
+            // duplicate lang items are reported during typeck, the first one is used until then
+            let res = Res::Def(def_id, def_kind);
+            self.resolver.lang_items.entry(item.ident.symbol).or_insert(res);
+        }
         match &item.kind {
             ItemKind::Enum(..) => {
                 // enums introduce a new namespace represented as a module
@@ -89,6 +96,11 @@ impl<'ast, 'r> Visitor<'ast> for DefCollector<'ast, 'r> {
     fn visit_ty_param(&mut self, ty_param: &'ast TyParam) {
         self.resolver.define(ty_param.id);
     }
+
+    fn visit_anon_const(&mut self, anon_const: &'ast AnonConst) {
+        self.resolver.define(anon_const.id);
+        ast::walk_anon_const(self, anon_const);
+    }
 }
 
 impl<'a> Resolver<'a> {
@@ -56,6 +56,11 @@ impl<'a, 'r, 'ast> LateResolver<'a, 'r, 'ast> {
                 let res = this.resolver.def_ty_param(param.id, ParamIdx::new(index));
                 this.scopes[NS::Type].def(param.ident, res)
             }
+            // bounds may refer to any of the parameters so they are resolved after all are defined
+            for bound in generics.params.iter().flat_map(|param| &param.bounds) {
+                this.resolve_path(&bound.path, NS::Type);
+                bound.sig.iter().for_each(|ty| this.visit_ty(ty));
+            }
             f(this)
         })
     }
@@ -90,15 +95,20 @@ impl<'a, 'r, 'ast> LateResolver<'a, 'r, 'ast> {
             ItemKind::Fn(_, g, _) | ItemKind::TypeAlias(g, _) =>
                 self.with_generics(g, |r| ast::walk_item(r, item)),
             ItemKind::Enum(g, _) | ItemKind::Struct(g, _) => self.resolve_adt(g, item),
-            ItemKind::Extern(..) => ast::walk_item(self, item),
+            ItemKind::Extern(..) | ItemKind::Const(..) | ItemKind::Static(..) =>
+                ast::walk_item(self, item),
             ItemKind::Mod(module) =>
                 self.with_module(item.ident, |this| ast::walk_module(this, module)),
             ItemKind::Use(..) => {}
-            ItemKind::Trait { generics, items } => self.with_generics(generics, |r| {
-                r.with_self(item.id, |r| {
-                    for item in items {
-                        ast::walk_assoc_item(r, item);
-                    }
+            ItemKind::Trait { generics, items } => self.with_ty_scope(|r| {
+                // the implicit `Self` type parameter of a trait always has index 0
+                r.scopes[NS::Type].def_ty_param();
+                r.with_generics(generics, |r| {
+                    r.with_self(item.id, |r| {
+                        for item in items {
+                            r.resolve_assoc_item(item);
+                        }
+                    })
                 })
             }),
             ItemKind::Impl { generics, trait_path, self_ty, items } =>
@@ -182,6 +192,13 @@ impl<'a, 'ast> ast::Visitor<'ast> for LateResolver<'a, '_, 'ast> {
         self.with_val_scope(|this| ast::walk_closure(this, name, sig, body));
     }
 
+    /// the expression is evaluated at compile time so local variables are not in scope
+    fn visit_anon_const(&mut self, anon_const: &'ast AnonConst) {
+        let scopes = std::mem::take(&mut self.scopes[NS::Value]);
+        ast::walk_anon_const(self, anon_const);
+        self.scopes[NS::Value] = scopes;
+    }
+
     fn visit_item(&mut self, item: &'ast Item) {
         self.resolve_item(item)
     }
@@ -217,7 +234,7 @@ impl<'a, 'ast> ast::Visitor<'ast> for LateResolver<'a, '_, 'ast> {
 
     fn visit_ty(&mut self, ty: &'ast Ty) {
         match &ty.kind {
-            TyKind::Path(path) => self.resolve_path(path, NS::Type),
+            TyKind::Path(path) | TyKind::Dyn(path) => self.resolve_path(path, NS::Type),
             TyKind::ImplicitSelf => {
                 let res = self.scopes[NS::Type]
                     .lookup(Ident::unspanned(kw::USelf))
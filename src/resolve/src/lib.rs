@@ -57,6 +57,9 @@ pub struct Resolver<'a> {
     partial_resolutions: FxHashMap<NodeId, PartialRes>,
     node_id_to_def_id: FxHashMap<NodeId, DefId>,
     ty_param_id_to_idx: FxHashMap<NodeId, ParamIdx>,
+    /// traits declared with `#[lang]`, by name
+    /// these are referred to by compiler generated paths of the form `{{lang}}::<Trait>`
+    lang_items: FxHashMap<Symbol, Res<NodeId>>,
 }
 
 impl<'a> Resolver<'a> {
@@ -75,6 +78,7 @@ impl<'a> Resolver<'a> {
             node_id_to_def_id: Default::default(),
             primitive_types: Default::default(),
             ty_param_id_to_idx: Default::default(),
+            lang_items: Default::default(),
         }
     }
 
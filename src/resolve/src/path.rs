@@ -3,6 +3,7 @@
 use crate::*;
 use ast::*;
 use ir::{PartialRes, Res};
+use span::kw;
 
 impl<'a, 'r, 'ast> LateResolver<'a, 'r, 'ast> {
     /// `id` belongs to the `Ty` or `Expr`
@@ -34,6 +35,15 @@ impl<'a, 'r, 'ast> LateResolver<'a, 'r, 'ast> {
         match &segments {
             [segment] =>
                 self.resolve_path_segment(path, segment, NS::Value).map(PartialRes::resolved),
+            [root, remaining @ ..] if root.ident.symbol == kw::PathRoot =>
+                self.with_module_id(ROOT_MODULE, |this| {
+                    this.resolve_val_path_segments(path, remaining)
+                }),
+            // the remaining segments are resolved relative to the trait
+            [lang, segment, remaining @ ..] if lang.ident.symbol == kw::LangItem => {
+                let res = self.resolve_lang_item(path, segment)?;
+                Ok(PartialRes::new(res, remaining.len()))
+            }
             [segment, remaining @ ..] =>
                 match self.resolve_module(segment.ident).and_then(|module_id| {
                     self.with_module_id(module_id, |this| {
@@ -89,6 +99,8 @@ impl<'a, 'r, 'ast> LateResolver<'a, 'r, 'ast> {
                 | DefKind::AssocFn
                 | DefKind::Enum
                 | DefKind::Trait
+                | DefKind::Const
+                | DefKind::Static
                 | DefKind::Struct => {}
             }
         };
@@ -100,10 +112,24 @@ impl<'a, 'r, 'ast> LateResolver<'a, 'r, 'ast> {
         match path.segments.as_slice() {
             [] => panic!("empty ty path"),
             [segment] => self.resolve_path_segment(path, segment, NS::Type),
+            [lang, segment] if lang.ident.symbol == kw::LangItem =>
+                self.resolve_lang_item(path, segment),
             [_xs @ .., _segment] => todo!(),
         }
     }
 
+    /// `{{lang}}::<Trait>` refers to the trait declared with `#[lang]` under that name
+    /// these paths are only generated by the compiler (i.e. by derive expansion)
+    fn resolve_lang_item(
+        &mut self,
+        path: &'ast Path,
+        segment: &'ast PathSegment,
+    ) -> ResResult<'a, Res<NodeId>> {
+        self.resolver.lang_items.get(&segment.ident.symbol).copied().ok_or_else(|| {
+            self.build_error(path.span, ResolutionError::MissingLangItem(segment.ident))
+        })
+    }
+
     fn resolve_path_segment(
         &mut self,
         path: &'ast Path,
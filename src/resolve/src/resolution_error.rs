@@ -25,4 +25,6 @@ pub enum ResolutionError {
     DuplicatePatternIdentifier(Ident),
     #[error("self parameter is only allowed in functions declared within an impl block")]
     SelfParameterInFreeFunction,
+    #[error("no trait named `{0}` is declared with `#[lang]`")]
+    MissingLangItem(Ident),
 }
@@ -28,3 +28,20 @@ fn resolve_redeclaration() {
 fn self_in_free_function() {
     expect_resolution_error!("fn f(self) {}");
 }
+
+#[test]
+fn resolve_derive_without_lang_trait() {
+    expect_resolution_error!(
+        "trait Eq { fn eq(self, other: Self) -> bool; } #[derive(Eq)] struct S;"
+    );
+}
+
+#[test]
+fn resolve_derive_with_lang_trait_out_of_scope() {
+    // the generated impl refers to the `#[lang]` trait directly, so it needn't be in scope
+    resolve!(
+        "mod m { #[lang] trait Eq { fn eq(self, other: Self) -> bool; } }
+        mod n { #[derive(Eq)] struct S; }
+        fn main() -> int { 0 }"
+    );
+}
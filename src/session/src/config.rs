@@ -1,7 +1,10 @@
 use clap::Clap;
 use error::ErrorFormat;
 use serde::Deserialize;
+use std::convert::{TryFrom, TryInto};
+use std::fmt::{self, Display, Formatter};
 use std::path::PathBuf;
+use std::str::FromStr;
 
 #[derive(Clone, Debug, Default, Clap, Deserialize)]
 pub struct CompilerOptions {
@@ -11,11 +14,154 @@ pub struct CompilerOptions {
     pub input_path: PathBuf,
     #[clap(long("error-format"), default_value = "text")]
     pub error_format: ErrorFormat,
-    // TODO take optimization level as parameter (or debug/release)
+    /// optimization level (0-3), takes precedence over the `opt-level` of the profile
+    #[clap(short('O'), long("opt-level"))]
+    pub opt_level: Option<OptLevel>,
+    /// build using the release profile
+    #[clap(long)]
+    pub release: bool,
+    /// disables the runtime checks for integer overflow and division by zero
+    #[clap(long("no-debug-assertions"))]
+    pub no_debug_assertions: bool,
+    /// check for undefined behaviour such as dangling pointers when interpreting (`l interp`)
+    #[clap(long("check-ub"))]
+    pub check_ub: bool,
+    /// emit dwarf debug info
+    #[clap(short('g'))]
+    pub debuginfo: bool,
+    /// comma separated list of outputs to emit (`llvm-ir`, `llvm-bc`, `asm`, `obj`, `link`)
+    #[clap(long, use_delimiter = true)]
+    #[serde(default)]
+    pub emit: Vec<EmitKind>,
+    /// the directory to write the outputs to, defaults to the root of the package
+    #[clap(long("out-dir"))]
+    pub out_dir: Option<PathBuf>,
+    /// the target triple to generate code for, defaults to the host
+    /// linking for a foreign target requires `cc` to be a linker for that target
+    #[clap(long)]
+    pub target: Option<String>,
+    /// the settings of the selected profile in `L.toml`
+    #[clap(skip)]
+    #[serde(skip)]
+    pub profile: Profile,
 }
 
 impl CompilerOptions {
     pub fn with_input_path(input_path: PathBuf) -> Self {
         Self { input_path, ..Self::default() }
     }
+
+    pub fn opt_level(&self) -> OptLevel {
+        let default = if self.release { OptLevel::O3 } else { OptLevel::O0 };
+        self.opt_level.or(self.profile.opt_level).unwrap_or(default)
+    }
+
+    /// whether generated code checks for integer overflow and division by zero
+    pub fn debug_assertions(&self) -> bool {
+        !self.no_debug_assertions && self.profile.debug_assertions.unwrap_or(!self.release)
+    }
+
+    pub fn debuginfo(&self) -> bool {
+        self.debuginfo || self.profile.debug.unwrap_or(false)
+    }
+
+    /// the requested outputs, just the executable if none were specified
+    pub fn emit(&self) -> &[EmitKind] {
+        if self.emit.is_empty() { &[EmitKind::Link] } else { &self.emit }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EmitKind {
+    /// textual llvm ir
+    LlvmIr,
+    /// llvm bitcode
+    LlvmBc,
+    /// native assembly
+    Asm,
+    /// native object file
+    Obj,
+    /// executable
+    Link,
+}
+
+impl EmitKind {
+    pub fn extension(self) -> &'static str {
+        match self {
+            EmitKind::LlvmIr => "ll",
+            EmitKind::LlvmBc => "bc",
+            EmitKind::Asm => "s",
+            EmitKind::Obj => "o",
+            EmitKind::Link => "out",
+        }
+    }
+}
+
+impl FromStr for EmitKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "llvm-ir" => Ok(Self::LlvmIr),
+            "llvm-bc" => Ok(Self::LlvmBc),
+            "asm" => Ok(Self::Asm),
+            "obj" => Ok(Self::Obj),
+            "link" => Ok(Self::Link),
+            _ => Err(format!(
+                "invalid emit kind `{}` (expected llvm-ir, llvm-bc, asm, obj or link)",
+                s
+            )),
+        }
+    }
+}
+
+/// a `[profile.<name>]` section of `L.toml`
+/// unspecified settings fall back to the defaults of the profile
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Profile {
+    pub opt_level: Option<OptLevel>,
+    pub debug_assertions: Option<bool>,
+    /// whether to emit debug info
+    pub debug: Option<bool>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(try_from = "u8")]
+pub enum OptLevel {
+    O0,
+    O1,
+    O2,
+    O3,
+}
+
+impl Display for OptLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", *self as u8)
+    }
+}
+
+impl TryFrom<u8> for OptLevel {
+    type Error = String;
+
+    fn try_from(level: u8) -> Result<Self, Self::Error> {
+        match level {
+            0 => Ok(Self::O0),
+            1 => Ok(Self::O1),
+            2 => Ok(Self::O2),
+            3 => Ok(Self::O3),
+            _ => Err(format!("invalid optimization level `{}` (expected 0-3)", level)),
+        }
+    }
+}
+
+impl FromStr for OptLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<u8>()
+            .map_err(|_| format!("invalid optimization level `{}` (expected 0-3)", s))?
+            .try_into()
+    }
 }
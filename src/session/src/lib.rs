@@ -4,7 +4,7 @@ extern crate log;
 mod config;
 mod profiling;
 
-pub use config::CompilerOptions;
+pub use config::{CompilerOptions, EmitKind, OptLevel, Profile};
 
 use error::Diagnostics;
 use profiling::Profiler;
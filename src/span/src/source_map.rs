@@ -172,6 +172,20 @@ impl SourceMap {
     pub fn span_to_string(&self, span: Span) -> String {
         self.span_to_slice(span).to_owned()
     }
+
+    /// `file:line:col` of the start of `span` (in the same format as diagnostics)
+    pub fn span_to_location(&self, span: Span) -> String {
+        let (line, col) = self.span_to_line_col(span);
+        format!("{}:{}:{}", self.modules[span.file].name, line, col)
+    }
+
+    /// the (1-indexed) line and column of the start of `span`
+    pub fn span_to_line_col(&self, span: Span) -> (usize, usize) {
+        let byte_index = span.start().to_usize();
+        let line_index = self.line_index(span.file, byte_index).unwrap();
+        let line_start = self.line_range(span.file, line_index).unwrap().start;
+        (1 + line_index, 1 + byte_index - line_start)
+    }
 }
 
 impl<'a> Index<Span> for &'a SourceFile {
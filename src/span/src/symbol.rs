@@ -8,18 +8,48 @@ symbols! {
         Empty: "",
         USelf: "Self",
         LSelf: "self",
+        // the following can't be written in source and are only used in generated paths
+        PathRoot: "{{root}}",
+        LangItem: "{{lang}}",
+        Intrinsics: "{{intrinsics}}",
     }
     // the following must be in alphabetical order
     Symbols {
+        Clone,
+        Debug,
+        Eq,
+        Hash,
         addr,
         bool,
+        catch_unwind,
         char,
+        char_to_int,
+        checked_add,
+        checked_div,
+        checked_mul,
+        checked_sub,
+        clone,
+        derive,
+        eq,
         float,
+        float_to_bits,
+        fmt,
+        hash,
+        inline,
         int,
         intrinsics,
+        lang,
         main,
+        other,
+        panic,
         print,
+        ptr_cast,
         rc,
+        wrapping_add,
+        wrapping_mul,
+        wrapping_sub,
+        x,
+        y,
     }
 }
 
@@ -1,8 +1,8 @@
 use crate as tir;
 use fmt::Display;
 use ir::{DefId, FieldIdx, VariantIdx};
-use lcore::ty::{AdtTy, Const, SubstsRef, Ty};
-use span::Span;
+use lcore::ty::{AdtTy, Const, PointerCast, SubstsRef, Ty, UpvarCapture};
+use span::{Span, Symbol};
 use std::fmt::{self, Formatter};
 
 #[derive(Debug)]
@@ -44,9 +44,15 @@ pub enum ExprKind<'tcx> {
     Deref(Box<tir::Expr<'tcx>>),
     /// box x
     Box(Box<tir::Expr<'tcx>>),
+    /// pointer casts inserted by coercions (e.g. `&T` to `&dyn Trait`)
+    Cast(PointerCast, Box<tir::Expr<'tcx>>),
     Closure {
+        id: ir::Id,
         body: Box<tir::Body<'tcx>>,
+        /// the expressions that initialize the environment of the closure
+        /// each one corresponds to the capture of the same index
         upvars: Vec<tir::Expr<'tcx>>,
+        captures: &'tcx [UpvarCapture],
     },
     Adt {
         adt: &'tcx AdtTy,
@@ -54,6 +60,8 @@ pub enum ExprKind<'tcx> {
         substs: SubstsRef<'tcx>,
         fields: Vec<tir::Field<'tcx>>,
     },
+    /// panic!("msg")
+    Panic(Symbol),
     Break,
     Continue,
 }
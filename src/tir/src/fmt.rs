@@ -98,12 +98,13 @@ where
             tir::ExprKind::Tuple(xs) => indent!(self, "({})", lutil::join2(xs.iter(), ",")),
             tir::ExprKind::Ref(expr) => indent!(self, "(&{})", expr),
             tir::ExprKind::Deref(expr) => indent!(self, "(*{})", expr),
+            tir::ExprKind::Cast(_, inner) => indent!(self, "({} as {})", inner, expr.ty),
             tir::ExprKind::Ret(expr) => match expr {
                 Some(expr) => indent!(self, "return {}", expr),
                 None => indent!(self, "return"),
             },
             tir::ExprKind::Match(expr, arms) => self.fmt_match(expr, arms),
-            tir::ExprKind::Closure { upvars: _, body } =>
+            tir::ExprKind::Closure { body, .. } =>
                 indent!(self, "(λ({}) {})", lutil::join2(body.params.iter(), ","), body),
             tir::ExprKind::Call(f, args) => self.fmt_call(f, args),
             tir::ExprKind::Assign(l, r) => indent!(self, "({} = {})", l, r),
@@ -119,6 +120,7 @@ where
                 })?;
                 indent!(self, "}}")
             }
+            tir::ExprKind::Panic(msg) => indent!(self, "panic!({:?})", msg.as_str()),
             tir::ExprKind::Break => indent!(self, "break"),
             tir::ExprKind::Continue => indent!(self, "continue"),
         }?;
@@ -35,7 +35,8 @@ impl<'tcx> Pattern<'tcx> {
             PatternKind::Box(pat) => pat.is_refutable(),
             PatternKind::Field(fs) => fs.iter().any(|f| f.pat.is_refutable()),
             PatternKind::Lit(..) => true,
-            PatternKind::Variant(.., pats) => pats.iter().any(|p| p.is_refutable()),
+            PatternKind::Variant(adt, .., pats) =>
+                adt.variants.len() > 1 || pats.iter().any(|p| p.is_refutable()),
             PatternKind::Wildcard | PatternKind::Binding(..) => false,
         }
     }
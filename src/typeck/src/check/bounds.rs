@@ -0,0 +1,98 @@
+use crate::FnCtx;
+use index::Idx;
+use ir::DefId;
+use lcore::ty::{self, *};
+use span::Span;
+
+/// a requirement that `ty` satisfies `bound`
+/// these are registered when generic items are instantiated and are checked once `ty` is known
+#[derive(Debug, Clone, Copy)]
+crate struct Obligation<'tcx> {
+    span: Span,
+    ty: Ty<'tcx>,
+    bound: Bound<'tcx>,
+}
+
+impl<'a, 'tcx> FnCtx<'a, 'tcx> {
+    /// the bounds on the generic parameters of `def_id` must hold for the given substitutions
+    crate fn register_bound_obligations(&self, span: Span, def_id: DefId, substs: SubstsRef<'tcx>) {
+        for param in self.generics_of(def_id).params {
+            let ty = substs[param.index.index()];
+            for &bound in param.bounds {
+                let sig = bound.sig.map(|sig| sig.subst(self.tcx, substs));
+                let bound = Bound { sig, ..bound };
+                self.obligations.borrow_mut().push(Obligation { span, ty, bound });
+            }
+        }
+    }
+
+    /// checks the obligations whose types are known and leaves the rest for later
+    crate fn select_obligations(&self) {
+        let obligations = std::mem::take(&mut *self.obligations.borrow_mut());
+        for obligation in obligations {
+            if !self.select_obligation(obligation) {
+                self.obligations.borrow_mut().push(obligation);
+            }
+        }
+    }
+
+    /// returns whether the obligation could be checked
+    fn select_obligation(&self, obligation: Obligation<'tcx>) -> bool {
+        let Obligation { span, ty, bound } = obligation;
+        let ty = self.shallow_resolve_ty(ty);
+        let lang_items = self.lang_items(());
+        let fn_trait_kind = lang_items.fn_trait_kind(bound.trait_def_id);
+        let found_sig = match ty.kind {
+            ty::Infer(TyVar(..)) => return false,
+            ty::Error => return true,
+            // function pointers implement all the `Fn*` traits
+            ty::FnPtr(sig) if fn_trait_kind.is_some() => Some(sig),
+            // closures only implement the `Fn*` traits that their kind extends to
+            ty::Opaque(closure_id, sig) => match fn_trait_kind {
+                Some(kind) => {
+                    let closure_kind = self.tables.borrow().closure_kind(closure_id);
+                    if !closure_kind.extends(kind) {
+                        let err = TypeError::ClosureKindMismatch(kind, closure_kind);
+                        self.emit_ty_err(span, err);
+                        return true;
+                    }
+                    Some(sig)
+                }
+                None => return self.report_unsatisfied_bound(span, ty, bound),
+            },
+            ty::Param(param) => {
+                // a parameter bounded by an `Fn*` trait satisfies the `Fn*` traits it extends to
+                let satisfies = |b: &Bound<'tcx>| {
+                    match (lang_items.fn_trait_kind(b.trait_def_id), fn_trait_kind) {
+                        (Some(found), Some(expected)) => found.extends(expected),
+                        _ => b.trait_def_id == bound.trait_def_id,
+                    }
+                };
+                match self.param_bounds(param).iter().find(|&b| satisfies(b)) {
+                    Some(param_bound) => param_bound.sig,
+                    None => return self.report_unsatisfied_bound(span, ty, bound),
+                }
+            }
+            _ if self.implements_trait(ty, bound.trait_def_id) => None,
+            _ => return self.report_unsatisfied_bound(span, ty, bound),
+        };
+
+        if let (Some(expected), Some(found)) = (bound.sig, found_sig) {
+            self.unify(span, self.mk_fn_ptr(expected), self.mk_fn_ptr(found));
+        }
+        true
+    }
+
+    fn report_unsatisfied_bound(&self, span: Span, ty: Ty<'tcx>, bound: Bound<'tcx>) -> bool {
+        let trait_ident = self.defs().ident(bound.trait_def_id);
+        self.emit_ty_err(span, TypeError::TraitNotImplemented(ty, trait_ident));
+        true
+    }
+
+    /// the bounds declared on the type parameter `param` of the item being checked
+    crate fn param_bounds(&self, param: ParamTy) -> &'tcx [Bound<'tcx>] {
+        let def_id = self.tables.borrow().def_id();
+        let generics = self.generics_of(def_id);
+        generics.params.iter().find(|p| p.index == param.idx).map_or(&[], |p| p.bounds)
+    }
+}
@@ -1,4 +1,5 @@
 use crate::FnCtx;
+use ir::DefId;
 use lcore::ty::{self, *};
 use span::Span;
 use std::ops::Deref;
@@ -17,13 +18,22 @@ impl<'a, 'tcx> TypeRelation<'tcx> for Coercion<'a, 'tcx> {
     }
 
     fn relate_tys(&mut self, ty: Ty<'tcx>, target: Ty<'tcx>) -> TypeResult<'tcx, Ty<'tcx>> {
-        dbg!(ty);
-        dbg!(target);
         match (ty.kind, target.kind) {
             (ty::Never, _) => {
                 self.adjustments.push(Adjustment::new(target, AdjustmentKind::NeverToAny));
                 Ok(target)
             }
+            (ty::Box(pointee), ty::Box(target_pointee))
+            | (ty::Ptr(pointee), ty::Ptr(target_pointee)) =>
+                match self.shallow_resolve_ty(target_pointee).kind {
+                    ty::Dyn(trait_def_id) => {
+                        let pointee = self.shallow_resolve_ty(pointee);
+                        self.coerce_unsized(ty, pointee, target, trait_def_id)
+                    }
+                    _ => self.at(self.span).equate(ty, target),
+                },
+            (ty::Opaque(closure_id, sig), ty::FnPtr(_)) =>
+                self.coerce_closure_fn_ptr(closure_id, sig, target),
             // if it isn't one of the cases for coercion, fallback to `equate`
             _ => self.at(self.span).equate(ty, target),
         }
@@ -39,9 +49,50 @@ impl<'a, 'tcx> Coercion<'a, 'tcx> {
         if ty.contains_err() || target.contains_err() {
             return Ok(vec![]);
         }
-        let ty = self.partially_resolve_ty(self.span, ty);
+        let ty = self.shallow_resolve_ty(ty);
+        let target = self.shallow_resolve_ty(target);
         self.relate_tys(ty, target)?;
-        return Ok(vec![]);
+        Ok(std::mem::take(&mut self.adjustments))
+    }
+
+    /// unsizing coercion from `&T` to `&dyn Trait` (and similarly for raw pointers)
+    fn coerce_unsized(
+        &mut self,
+        ty: Ty<'tcx>,
+        pointee: Ty<'tcx>,
+        target: Ty<'tcx>,
+        trait_def_id: DefId,
+    ) -> TypeResult<'tcx, Ty<'tcx>> {
+        match pointee.kind {
+            // there is nothing to unsize if the pointee is already a trait object
+            // and if the pointee is unknown we assume it is the trait object itself
+            ty::Dyn(..) | ty::Infer(..) => return self.at(self.span).equate(ty, target),
+            _ => {}
+        };
+
+        if !self.implements_trait(pointee, trait_def_id) {
+            let trait_ident = self.defs().ident(trait_def_id);
+            return Err(TypeError::TraitNotImplemented(pointee, trait_ident));
+        }
+        self.adjustments.push(Adjustment::new(target, AdjustmentKind::Cast(PointerCast::Unsize)));
+        Ok(target)
+    }
+
+    /// coercion from a closure to a function pointer which is only allowed
+    /// if the closure has no environment
+    fn coerce_closure_fn_ptr(
+        &mut self,
+        closure_id: ir::Id,
+        sig: FnSig<'tcx>,
+        target: Ty<'tcx>,
+    ) -> TypeResult<'tcx, Ty<'tcx>> {
+        if !self.tables.borrow().upvar_captures_for_closure(closure_id).is_empty() {
+            return Err(TypeError::CapturingClosureToFnPtr);
+        }
+        let fn_ptr = self.at(self.span).equate(self.mk_fn_ptr(sig), target)?;
+        let kind = AdjustmentKind::Cast(PointerCast::ClosureFnPtr);
+        self.adjustments.push(Adjustment::new(fn_ptr, kind));
+        Ok(fn_ptr)
     }
 }
 
@@ -65,7 +116,9 @@ impl<'a, 'tcx> FnCtx<'a, 'tcx> {
         target: Ty<'tcx>,
     ) -> TypeResult<'tcx, Ty<'tcx>> {
         Coercion::new(self, expr.span).coerce(ty, target).and_then(|adjustments| {
-            self.record_adjustments(expr.id, adjustments);
+            if !adjustments.is_empty() {
+                self.record_adjustments(expr.id, adjustments);
+            }
             Ok(if ty.contains_err() { self.set_ty_err() } else { target })
         })
     }
@@ -1,17 +1,19 @@
 use super::FnCtx;
 use crate::{Autoderef, TyConv};
 use ast::{BinOp, Ident, Lit, Mutability, UnaryOp};
+use ir::Res;
 use itertools::Itertools;
 use lcore::ty::{self, *};
 use rustc_hash::FxHashMap;
+use span::{Span, Symbol};
 
 impl<'a, 'tcx> FnCtx<'a, 'tcx> {
     pub fn check_expr(&mut self, expr: &ir::Expr<'tcx>) -> Ty<'tcx> {
         let ty = match &expr.kind {
             ir::ExprKind::Box(expr) => self.check_expr_box(expr),
             ir::ExprKind::Loop(block) => self.check_expr_loop(expr, block),
-            ir::ExprKind::Lit(lit) => self.check_lit(lit),
-            ir::ExprKind::Bin(op, l, r) => self.check_expr_binop(*op, l, r),
+            ir::ExprKind::Lit(lit) => self.check_lit(expr.span, lit),
+            ir::ExprKind::Bin(op, l, r) => self.check_expr_binop(expr, *op, l, r),
             ir::ExprKind::Unary(op, operand) => self.check_expr_unary(expr, *op, operand),
             ir::ExprKind::Block(block) => self.check_block(block),
             ir::ExprKind::Path(qpath) => self.check_qpath(expr, qpath),
@@ -23,7 +25,8 @@ impl<'a, 'tcx> FnCtx<'a, 'tcx> {
             ir::ExprKind::Assign(l, r) => self.check_expr_assign(expr, l, r),
             ir::ExprKind::Ret(ret) => self.check_expr_ret(expr, ret.as_deref()),
             ir::ExprKind::Field(base, ident) => self.check_expr_field(expr, base, *ident),
-            ir::ExprKind::Break | ir::ExprKind::Continue => self.tcx.types.never,
+            ir::ExprKind::Panic(..) | ir::ExprKind::Break | ir::ExprKind::Continue =>
+                self.tcx.types.never,
             ir::ExprKind::Err => self.set_ty_err(),
         };
         self.record_ty(expr.id, ty)
@@ -43,11 +46,7 @@ impl<'a, 'tcx> FnCtx<'a, 'tcx> {
     ) -> Ty<'tcx> {
         let operand_ty = self.check_expr(operand);
         match op {
-            UnaryOp::Neg => todo!(),
-            UnaryOp::Not => {
-                self.unify(expr.span, self.types.bool, operand_ty);
-                self.types.bool
-            }
+            UnaryOp::Neg | UnaryOp::Not => self.check_expr_arith_unary(expr, op, operand_ty),
             // TODO how to handle mutability?
             UnaryOp::Deref => self.deref_ty(expr.span, operand_ty),
             UnaryOp::Ref => {
@@ -59,6 +58,32 @@ impl<'a, 'tcx> FnCtx<'a, 'tcx> {
         }
     }
 
+    /// `-` applies to integers and floats, `!` to booleans and (bitwise) to integers
+    fn check_expr_arith_unary(
+        &mut self,
+        expr: &ir::Expr<'tcx>,
+        op: UnaryOp,
+        operand_ty: Ty<'tcx>,
+    ) -> Ty<'tcx> {
+        let ty = self.shallow_resolve_ty(operand_ty);
+        match (op, ty.kind) {
+            (_, ty::Error) => ty,
+            (_, ty::Int) | (_, ty::Infer(IntVar(_))) => ty,
+            (UnaryOp::Neg, ty::Float) | (UnaryOp::Neg, ty::Infer(FloatVar(_))) => ty,
+            (UnaryOp::Not, ty::Bool) => ty,
+            // without any other information, assume the most common case
+            (UnaryOp::Neg, ty::Infer(TyVar(_))) => {
+                self.unify(expr.span, self.types.int, ty);
+                self.types.int
+            }
+            (UnaryOp::Not, ty::Infer(TyVar(_))) => {
+                self.unify(expr.span, self.types.bool, ty);
+                self.types.bool
+            }
+            _ => self.emit_ty_err(expr.span, TypeError::UnsupportedUnaryOp(op, ty)),
+        }
+    }
+
     fn check_expr_box(&mut self, expr: &ir::Expr<'tcx>) -> Ty<'tcx> {
         let ty = self.check_expr(expr);
         self.mk_box_ty(ty)
@@ -269,14 +294,41 @@ impl<'a, 'tcx> FnCtx<'a, 'tcx> {
         f: &ir::Expr<'tcx>,
         args: &[ir::Expr<'tcx>],
     ) -> Ty<'tcx> {
-        let ret = self.new_infer_var(expr.span);
         let f_ty = self.check_expr(f);
-        let params = self.check_expr_list(args);
-        let ty = self.tcx.mk_fn_ptr(FnSig { params, ret });
-        self.unify(expr.span, ty, f_ty);
+        let ret = match self.callee_sig(f_ty) {
+            // if the signature is already known, coerce each argument to its parameter type
+            Some(sig) if sig.params.len() == args.len() => {
+                let mut has_error = false;
+                for (arg, param_ty) in args.iter().zip(sig.params) {
+                    let arg_ty = self.check_expr(arg);
+                    has_error |= self.coerce(arg, arg_ty, param_ty).contains_err();
+                }
+                // the coercion has already reported the mismatch
+                if has_error { self.set_ty_err() } else { sig.ret }
+            }
+            _ => {
+                let ret = self.new_infer_var(expr.span);
+                let params = self.check_expr_list(args);
+                let ty = self.tcx.mk_fn_ptr(FnSig { params, ret });
+                self.unify(expr.span, ty, f_ty);
+                ret
+            }
+        };
+        // the arguments may have determined the types that some bounds are waiting on
+        self.select_obligations();
         ret
     }
 
+    /// the signature of a callee of type `ty` if it is known
+    fn callee_sig(&self, ty: Ty<'tcx>) -> Option<FnSig<'tcx>> {
+        match self.shallow_resolve_ty(ty).kind {
+            ty::FnPtr(sig) | ty::Opaque(_, sig) => Some(sig),
+            // a value of a type parameter can be called through its `Fn*` bound
+            ty::Param(param) => self.param_bounds(param).iter().find_map(|bound| bound.sig),
+            _ => None,
+        }
+    }
+
     fn check_closure_expr(
         &mut self,
         closure: &ir::Expr<'tcx>,
@@ -286,7 +338,7 @@ impl<'a, 'tcx> FnCtx<'a, 'tcx> {
         // the resolver resolved the closure name to the id of the entire closure expr
         // so we define an immutable local variable for it with the closure's type
         let sig = self.lower_fn_sig(sig);
-        let ty = self.mk_fn_ptr(sig);
+        let ty = self.mk_opaque_ty(closure.id, sig);
         self.record_upvars(closure, body);
         self.def_local(closure.id, Mutability::Imm, ty);
         let _fcx = self.check_fn(sig, body);
@@ -355,14 +407,23 @@ impl<'a, 'tcx> FnCtx<'a, 'tcx> {
 
     fn check_expr_binop(
         &mut self,
+        expr: &ir::Expr<'tcx>,
         op: ast::BinOp,
         l: &ir::Expr<'tcx>,
         r: &ir::Expr<'tcx>,
     ) -> Ty<'tcx> {
         let tl = self.check_expr(l);
         let tr = self.check_expr(r);
+        // operators on adts are overloaded through the corresponding lang item trait
+        if let ty::Adt(..) = self.shallow_resolve_ty(tl).kind {
+            return self.check_overloaded_binop(expr, op, tl, tr);
+        }
+
         match op {
-            BinOp::Eq => todo!(),
+            BinOp::Eq | BinOp::Neq => {
+                self.unify(r.span, tl, tr);
+                self.tcx.types.bool
+            }
             // TODO deal with floats
             BinOp::Mul | BinOp::Div | BinOp::Add | BinOp::Sub => {
                 self.unify(l.span, self.tcx.types.int, tl);
@@ -375,7 +436,6 @@ impl<'a, 'tcx> FnCtx<'a, 'tcx> {
                 self.unify(r.span, tl, tr);
                 self.tcx.types.bool
             }
-            BinOp::Neq => todo!(),
             BinOp::And | BinOp::Or => {
                 self.unify(l.span, self.tcx.types.int, tl);
                 self.unify(r.span, tl, tr);
@@ -384,11 +444,50 @@ impl<'a, 'tcx> FnCtx<'a, 'tcx> {
         }
     }
 
-    fn check_lit(&self, lit: &ast::Lit) -> Ty<'tcx> {
+    /// checks `l op r` as a call to the method of the lang item trait for `op`
+    /// e.g. `l + r` is checked as `Add::add(l, r)`
+    fn check_overloaded_binop(
+        &mut self,
+        expr: &ir::Expr<'tcx>,
+        op: ast::BinOp,
+        tl: Ty<'tcx>,
+        tr: Ty<'tcx>,
+    ) -> Ty<'tcx> {
+        let self_ty = self.shallow_resolve_ty(tl);
+        let (lang_item, method) = LangItem::binop(op);
+        let trait_def_id = match self.lang_items(()).get(lang_item) {
+            Some(trait_def_id) => trait_def_id,
+            None => {
+                let err = TypeError::UnsupportedBinOp(op, self_ty, lang_item.name());
+                return self.emit_ty_err(expr.span, err);
+            }
+        };
+
+        if !self.implements_trait(self_ty, trait_def_id) {
+            let err = TypeError::TraitNotImplemented(self_ty, self.defs().ident(trait_def_id));
+            return self.emit_ty_err(expr.span, err);
+        }
+
+        let ident = Ident::unspanned(Symbol::intern_str(method));
+        let (res, f_ty) = self.check_trait_relative_path(expr, trait_def_id, ident);
+        if let Res::Err = res {
+            return f_ty;
+        }
+
+        let substs = self.tables.borrow().node_substs_opt(expr.id).unwrap();
+        self.unify(expr.span, self_ty, substs[0]);
+        let ret = self.new_infer_var(expr.span);
+        let params = self.tcx.mk_substs([tl, tr].iter().copied());
+        let ty = self.tcx.mk_fn_ptr(FnSig { params, ret });
+        self.unify(expr.span, ty, f_ty);
+        ret
+    }
+
+    fn check_lit(&self, span: Span, lit: &ast::Lit) -> Ty<'tcx> {
         match lit {
             Lit::Bool(..) => self.tcx.types.bool,
-            Lit::Float(..) => self.tcx.types.float,
-            Lit::Int(..) => self.tcx.types.int,
+            Lit::Float(..) => self.new_float_var(span),
+            Lit::Int(..) => self.new_int_var(span),
         }
     }
 }
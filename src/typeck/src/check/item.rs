@@ -1,5 +1,7 @@
-use ir::DefId;
+use ir::{DefId, DefKind, Res};
+use itertools::Itertools;
 use lcore::queries::Queries;
+use lcore::ty::TypeError;
 use lcore::TyCtx;
 
 pub fn provide(queries: &mut Queries) {
@@ -9,13 +11,21 @@ pub fn provide(queries: &mut Queries) {
 fn validate_item_type<'tcx>(tcx: TyCtx<'tcx>, def_id: DefId) {
     match tcx.defs().get(def_id) {
         ir::DefNode::Item(item) => match item.kind {
-            ir::ItemKind::Fn(..) => {}
+            // lowering the generics checks the bounds on the type parameters
+            ir::ItemKind::Fn(..) => {
+                tcx.generics_of(def_id);
+            }
             ir::ItemKind::Enum(..) | ir::ItemKind::Struct(..) => self::validate_adt(tcx, def_id),
+            ir::ItemKind::Const(..) | ir::ItemKind::Static(..) => {
+                tcx.type_of(def_id);
+            }
             ir::ItemKind::Use(..) => {}
             ir::ItemKind::Extern(..) => {}
             ir::ItemKind::TypeAlias(..) => {}
             ir::ItemKind::Mod(..) => {}
             ir::ItemKind::Trait { .. } => {}
+            ir::ItemKind::Impl { trait_path: Some(path), impl_item_refs, .. } =>
+                self::validate_trait_impl(tcx, path, impl_item_refs),
             ir::ItemKind::Impl { .. } => {}
         },
         ir::DefNode::ImplItem(..) => {}
@@ -25,6 +35,7 @@ fn validate_item_type<'tcx>(tcx: TyCtx<'tcx>, def_id: DefId) {
         ir::DefNode::Field(..) => {}
         ir::DefNode::Variant(..) => {}
         ir::DefNode::TyParam(..) => {}
+        ir::DefNode::AnonConst(..) => {}
     }
 }
 
@@ -44,3 +55,40 @@ fn validate_adt<'tcx>(tcx: TyCtx<'tcx>, def_id: DefId) {
         }
     }
 }
+
+/// checks that a trait impl provides all items of the trait that have no default
+/// and that it does not define any items that are not part of the trait
+fn validate_trait_impl<'tcx>(
+    tcx: TyCtx<'tcx>,
+    trait_path: &ir::Path<'tcx>,
+    impl_item_refs: &[ir::ImplItemRef],
+) {
+    let trait_def_id = match trait_path.res {
+        Res::Def(def_id, DefKind::Trait) => def_id,
+        _ => return,
+    };
+
+    let trait_item = tcx.ir.items[&trait_def_id];
+    let trait_items = match trait_item.kind {
+        ir::ItemKind::Trait { trait_item_refs, .. } =>
+            trait_item_refs.iter().map(|item_ref| tcx.ir.trait_items[&item_ref.id]).collect_vec(),
+        _ => unreachable!(),
+    };
+    let impl_items = impl_item_refs.iter().map(|item_ref| tcx.impl_item(item_ref.id)).collect_vec();
+
+    for impl_item in &impl_items {
+        if !trait_items.iter().any(|item| item.ident == impl_item.ident) {
+            tcx.sess.emit_error(
+                impl_item.span,
+                TypeError::NotTraitMember(impl_item.ident, trait_item.ident),
+            );
+        }
+    }
+
+    for item in &trait_items {
+        if !item.kind.has_default() && !impl_items.iter().any(|i| i.ident == item.ident) {
+            let err = TypeError::MissingTraitItem(item.ident, trait_item.ident);
+            tcx.sess.emit_error(trait_path.span, err);
+        }
+    }
+}
@@ -63,6 +63,7 @@ crate struct MethodResolutionCtx<'a, 'tcx> {
     self_ty: Ty<'tcx>,
     ident: Ident,
     inherent_candidates: Vec<Candidate<'tcx>>,
+    trait_candidates: Vec<Candidate<'tcx>>,
 }
 
 trait InherentCandidates<'tcx> {
@@ -76,7 +77,14 @@ impl<'a, 'tcx> MethodResolutionCtx<'a, 'tcx> {
         self_ty: Ty<'tcx>,
         ident: Ident,
     ) -> Self {
-        Self { fcx, self_ty, xpat, ident, inherent_candidates: Default::default() }
+        Self {
+            fcx,
+            self_ty,
+            xpat,
+            ident,
+            inherent_candidates: Default::default(),
+            trait_candidates: Default::default(),
+        }
     }
 
     fn collect_inherent_candidates(&mut self) {
@@ -84,17 +92,90 @@ impl<'a, 'tcx> MethodResolutionCtx<'a, 'tcx> {
         ty.inherent_candidates(self)
     }
 
+    fn collect_trait_candidates(&mut self) {
+        match self.self_ty.kind {
+            ty::Adt(adt, _) =>
+                for &impl_def_id in self.trait_impls_of(adt.def_id) {
+                    self.trait_impl_candidates(impl_def_id);
+                },
+            // the `Self` type within a trait has access to all the items of the trait
+            ty::Param(param) => match self.defs().get(param.def_id) {
+                ir::DefNode::Item(item) => match item.kind {
+                    ir::ItemKind::Trait { .. } => self.trait_item_candidates(item.id.def, false),
+                    _ => {}
+                },
+                _ => {}
+            },
+            // all the items of a trait are callable on its trait object
+            ty::Dyn(trait_def_id) => self.trait_item_candidates(trait_def_id, false),
+            _ => {}
+        }
+    }
+
+    /// collects the item with the given name from a trait impl
+    /// if the impl does not define the item, we fallback to the default provided by the trait
+    fn trait_impl_candidates(&mut self, impl_def_id: DefId) {
+        let impl_block = self.ir.items[&impl_def_id];
+        let (trait_path, impl_item_refs) = match impl_block.kind {
+            ir::ItemKind::Impl { trait_path: Some(trait_path), impl_item_refs, .. } =>
+                (trait_path, impl_item_refs),
+            _ => unreachable!(),
+        };
+
+        let impl_item = impl_item_refs
+            .iter()
+            .map(|item_ref| self.ir.impl_items[&item_ref.id])
+            .find(|impl_item| impl_item.ident == self.ident);
+
+        match impl_item {
+            Some(impl_item) => self
+                .trait_candidates
+                .push(Candidate::new(impl_item.id.def, impl_item.kind.def_kind())),
+            None => match trait_path.res {
+                Res::Def(trait_def_id, DefKind::Trait) =>
+                    self.trait_item_candidates(trait_def_id, true),
+                _ => {}
+            },
+        }
+    }
+
+    /// collects the items of the trait with the given name
+    /// `require_default` indicates whether only items with a default implementation are valid
+    fn trait_item_candidates(&mut self, trait_def_id: DefId, require_default: bool) {
+        let trait_item_refs = match self.ir.items[&trait_def_id].kind {
+            ir::ItemKind::Trait { trait_item_refs, .. } => trait_item_refs,
+            _ => unreachable!(),
+        };
+
+        for item_ref in trait_item_refs {
+            let trait_item = self.ir.trait_items[&item_ref.id];
+            if trait_item.ident != self.ident || (require_default && !trait_item.kind.has_default())
+            {
+                continue;
+            }
+            self.trait_candidates.push(Candidate::new(item_ref.id.0, trait_item.kind.def_kind()));
+        }
+    }
+
     fn resolve(mut self) -> MethodResult<'tcx, Res> {
         self.collect_inherent_candidates();
+        self.collect_trait_candidates();
         self.resolve_candidates()
     }
 
     /// chooses a single candidate from the possibilities and returns a resolution to it
+    /// inherent candidates take precedence over trait candidates
     fn resolve_candidates(mut self) -> MethodResult<'tcx, Res> {
-        if self.inherent_candidates.len() == 1 {
-            let selected = self.inherent_candidates.pop().unwrap();
+        let candidates = if self.inherent_candidates.is_empty() {
+            &mut self.trait_candidates
+        } else {
+            &mut self.inherent_candidates
+        };
+
+        if candidates.len() == 1 {
+            let selected = candidates.pop().unwrap();
             Ok(Res::Def(selected.def_id, selected.def_kind))
-        } else if self.inherent_candidates.len() < 1 {
+        } else if candidates.len() < 1 {
             Err(MethodError::None)
         } else {
             Err(MethodError::Ambiguous)
@@ -116,6 +197,8 @@ impl<'tcx> InherentCandidates<'tcx> for Ty<'tcx> {
     fn inherent_candidates(&self, rcx: &mut MethodResolutionCtx) {
         match self.kind {
             ty::Adt(adt, _) => adt.def_id.inherent_candidates(rcx),
+            // type parameters and trait objects have no inherent impls
+            ty::Param(..) | ty::Dyn(..) => {}
             _ => todo!(),
         }
     }
@@ -1,3 +1,4 @@
+mod bounds;
 mod coerce;
 mod expr;
 mod item;
@@ -8,8 +9,9 @@ mod stmt;
 
 use crate::TyConv;
 use ast::Mutability;
+use bounds::Obligation;
 use infer::{InferCtx, InferCtxBuilder, TyCtxtInferExt};
-use ir::{self, DefId};
+use ir::{self, DefId, DefKind, Res};
 use lcore::queries::Queries;
 use lcore::ty::*;
 use rustc_hash::FxHashMap;
@@ -71,6 +73,22 @@ impl<'a, 'tcx> FnCtx<'a, 'tcx> {
     pub fn lower_tys(&self, ir_tys: &[ir::Ty<'tcx>]) -> &'tcx [Ty<'tcx>] {
         self.tcx.mk_substs(ir_tys.iter().map(|ty| self.ir_ty_to_ty(ty)))
     }
+
+    /// whether there is an impl of the given trait for `ty`
+    crate fn implements_trait(&self, ty: Ty<'tcx>, trait_def_id: DefId) -> bool {
+        let adt = match ty.kind {
+            TyKind::Adt(adt, _) => adt,
+            // trait impls are currently only supported for adts
+            _ => return false,
+        };
+        self.trait_impls_of(adt.def_id).iter().any(|impl_def_id| {
+            match self.ir.items[impl_def_id].kind {
+                ir::ItemKind::Impl { trait_path: Some(path), .. } =>
+                    path.res == Res::Def(trait_def_id, DefKind::Trait),
+                _ => false,
+            }
+        })
+    }
 }
 
 impl<'a, 'tcx> TyConv<'tcx> for InferCtx<'a, 'tcx> {
@@ -101,6 +119,7 @@ impl<'a, 'tcx> Deref for InheritedCtx<'a, 'tcx> {
 pub struct InheritedCtx<'a, 'tcx> {
     crate infcx: &'a InferCtx<'a, 'tcx>,
     locals: RefCell<FxHashMap<ir::Id, LocalTy<'tcx>>>,
+    obligations: RefCell<Vec<Obligation<'tcx>>>,
 }
 
 pub struct InheritedCtxBuilder<'tcx> {
@@ -127,7 +146,7 @@ impl<'tcx> InheritedCtxBuilder<'tcx> {
 
 impl<'a, 'tcx> InheritedCtx<'a, 'tcx> {
     pub fn new(infcx: &'a InferCtx<'a, 'tcx>) -> Self {
-        Self { infcx, locals: Default::default() }
+        Self { infcx, locals: Default::default(), obligations: Default::default() }
     }
 
     pub fn build(tcx: TyCtx<'tcx>, def_id: DefId) -> InheritedCtxBuilder<'tcx> {
@@ -138,7 +157,9 @@ impl<'a, 'tcx> InheritedCtx<'a, 'tcx> {
     pub fn check_fn_item(&'a self, def_id: DefId, body: &ir::Body<'tcx>) -> FnCtx<'a, 'tcx> {
         let sig = self.tcx.fn_sig(def_id);
         // don't instantiate anything and typeck the body using the param tys
-        self.check_fn(sig, body)
+        let fcx = self.check_fn(sig, body);
+        fcx.select_obligations();
+        fcx
     }
 
     // common logic between closures and function items
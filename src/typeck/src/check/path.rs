@@ -1,4 +1,5 @@
 use crate::{FnCtx, TyConv};
+use ast::Ident;
 use ir::{CtorKind, DefId, DefKind, QPath, Res};
 use lcore::ty::*;
 
@@ -50,11 +51,49 @@ impl<'a, 'tcx> FnCtx<'a, 'tcx> {
     ) -> (Res, Ty<'tcx>) {
         match qpath {
             QPath::Resolved(path) => (path.res, self.check_expr_path(xpat, path)),
-            QPath::TypeRelative(self_ty, segment) =>
-                self.check_type_relative_path(xpat, self.ir_ty_to_ty(self_ty), segment),
+            QPath::TypeRelative(self_ty, segment) => match self_ty.kind {
+                ir::TyKind::Path(QPath::Resolved(ir::Path {
+                    res: Res::Def(trait_def_id, DefKind::Trait),
+                    ..
+                })) => self.check_trait_relative_path(xpat, *trait_def_id, segment.ident),
+                _ => self.check_type_relative_path(xpat, self.ir_ty_to_ty(self_ty), segment),
+            },
         }
     }
 
+    /// `Trait::item` refers to the item of some (yet to be inferred) implementor of the trait
+    /// the `Self` type is left as an inference variable to be determined by its usage
+    /// e.g. `Trait::f(x)` where `x: &dyn Trait` dispatches through the vtable of `x`
+    crate fn check_trait_relative_path(
+        &mut self,
+        xpat: &dyn ir::ExprOrPat<'tcx>,
+        trait_def_id: DefId,
+        ident: Ident,
+    ) -> (Res, Ty<'tcx>) {
+        let trait_item_refs = match self.ir.items[&trait_def_id].kind {
+            ir::ItemKind::Trait { trait_item_refs, .. } => trait_item_refs,
+            _ => unreachable!(),
+        };
+
+        let trait_item = trait_item_refs
+            .iter()
+            .map(|item_ref| self.ir.trait_items[&item_ref.id])
+            .find(|trait_item| trait_item.ident == ident);
+
+        let trait_item = match trait_item {
+            Some(trait_item) => trait_item,
+            None => {
+                let trait_ident = self.defs().ident(trait_def_id);
+                let err = TypeError::NotTraitMember(ident, trait_ident);
+                return (Res::Err, self.emit_ty_err(xpat.span(), err));
+            }
+        };
+
+        let res = Res::Def(trait_item.id.def, trait_item.kind.def_kind());
+        self.record_type_relative_res(xpat.id(), res);
+        (res, self.instantiate(xpat, trait_item.id.def))
+    }
+
     crate fn check_type_relative_path(
         &mut self,
         xpat: &dyn ir::ExprOrPat<'tcx>,
@@ -68,6 +107,11 @@ impl<'a, 'tcx> FnCtx<'a, 'tcx> {
         self.record_type_relative_res(xpat.id(), res);
         let (def_id, def_kind) = res.expect_def();
         let ty = self.check_res_def(xpat, def_id, def_kind);
+        // trait items are generic over `Self` which is the type the path is relative to
+        if let ir::DefNode::TraitItem(..) = self.defs().get(def_id) {
+            let substs = self.tables.borrow().node_substs_opt(xpat.id()).unwrap();
+            self.unify(xpat.span(), self_ty, substs[0]);
+        }
         (res, ty)
     }
 
@@ -103,7 +147,14 @@ impl<'a, 'tcx> FnCtx<'a, 'tcx> {
             | DefKind::AssocFn
             | DefKind::Enum
             | DefKind::TypeAlias
-            | DefKind::Struct => self.instantiate(xpat, def_id),
+            | DefKind::Struct => {
+                let ty = self.instantiate(xpat, def_id);
+                let substs = self.tables.borrow().node_substs_opt(xpat.id()).unwrap();
+                self.register_bound_obligations(xpat.span(), def_id, substs);
+                ty
+            }
+            // constants are never generic
+            DefKind::Const | DefKind::Static => self.type_of(def_id),
             DefKind::Trait => todo!(),
             DefKind::TyParam(..)
             | DefKind::Impl
@@ -1,32 +1,44 @@
 use crate::TyConv;
-use ir::{DefId, DefNode};
+use ast::Ident;
+use index::Idx;
+use ir::{DefId, DefNode, ParamIdx};
 use lcore::queries::Queries;
 use lcore::ty::{self, TyCtx, TyParam};
+use span::kw;
 
 crate fn provide(queries: &mut Queries) {
     *queries = Queries { generics_of, ..*queries }
 }
 
 pub fn generics_of<'tcx>(tcx: TyCtx<'tcx>, def_id: DefId) -> &'tcx ty::Generics<'tcx> {
+    if tcx.defs().is_const(def_id) {
+        return tcx.alloc(ty::Generics { params: &[] });
+    }
+
     let generics = tcx.defs().generics(def_id);
 
-    // impl item is the only kind of defnode that may possibly have outer generics
-    let impl_generic_params = match tcx.defs().get(def_id) {
+    // impl items and trait items inherit the generics of their parent
+    // traits additionally have an implicit `Self` parameter
+    let parent_generic_params = match tcx.defs().get(def_id) {
         DefNode::ImplItem(item) => generics_of(tcx, item.impl_def_id).params,
+        DefNode::TraitItem(item) => generics_of(tcx, item.trait_def_id).params,
+        DefNode::Item(item) => match item.kind {
+            ir::ItemKind::Trait { .. } => tcx.alloc_iter(std::iter::once(TyParam {
+                id: item.id,
+                span: item.span,
+                ident: Ident::new(item.span, kw::USelf),
+                index: ParamIdx::new(0),
+                default: None,
+                bounds: &[],
+            })),
+            _ => &[],
+        },
         _ => &[],
     };
 
-    let generic_params = tcx.alloc_iter(generics.params.iter().map(
-        |&ir::TyParam { id, index, ident, span, default }| TyParam {
-            id,
-            span,
-            ident,
-            index,
-            default: default.map(|ty| tcx.ir_ty_to_ty(ty)),
-        },
-    ));
+    let generic_params = generics.params.iter().map(|param| tcx.lower_ty_param(param));
 
-    let mut params = impl_generic_params.to_vec();
+    let mut params = parent_generic_params.to_vec();
     params.extend(generic_params);
     let params = tcx.alloc_iter(params);
 
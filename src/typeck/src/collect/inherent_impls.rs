@@ -3,7 +3,7 @@
 use crate::TyConv;
 use ir::{DefId, Visitor};
 use lcore::queries::Queries;
-use lcore::ty::{self, InherentImpls, TyCtx};
+use lcore::ty::{self, InherentImpls, TyCtx, TypeError};
 use rustc_hash::FxHashMap;
 
 crate fn provide(queries: &mut Queries) {
@@ -49,6 +49,10 @@ impl<'tcx> ir::Visitor<'tcx> for InherentCollector<'tcx> {
             ty::Ptr(..) => todo!(),
             ty::Param(..) => todo!(),
             ty::Opaque(..) => todo!(),
+            // trait objects are only usable through the items of their trait
+            ty::Dyn(..) => {
+                tcx.sess.emit_error(item.span, TypeError::TraitObjectImpl(self_ty));
+            }
             ty::Adt(adt, _) => self.visit_def(adt.def_id, item.id.def),
             ty::Bool | ty::Discr | ty::Char | ty::Float | ty::Int => todo!(),
             ty::Never => todo!(),
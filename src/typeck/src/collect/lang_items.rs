@@ -0,0 +1,45 @@
+//! collect lang items
+
+use ast::AttrKind;
+use lcore::queries::Queries;
+use lcore::ty::{LangItem, LangItems, TyCtx, TypeError};
+use rustc_hash::FxHashMap;
+use span::Span;
+
+crate fn provide(queries: &mut Queries) {
+    *queries = Queries { lang_items: |tcx, ()| lang_items(tcx), ..*queries }
+}
+
+/// lang items are the traits marked with `#[lang]`
+/// the name of the trait must match one of the well known names in `LangItem`
+fn lang_items<'tcx>(tcx: TyCtx<'tcx>) -> &'tcx LangItems {
+    let mut lang_items = LangItems::default();
+    let mut spans = FxHashMap::<LangItem, Span>::default();
+    for item in tcx.ir.items.values() {
+        let is_lang = item.attrs.iter().any(|attr| attr.kind == AttrKind::Lang);
+        if !is_lang {
+            continue;
+        }
+
+        let name = item.ident.as_str();
+        let lang_item = match LangItem::ALL.iter().find(|lang_item| lang_item.name() == name) {
+            Some(&lang_item) => lang_item,
+            None => {
+                tcx.sess.emit_error(item.span, TypeError::UnknownLangItem(item.ident));
+                continue;
+            }
+        };
+
+        match spans.get(&lang_item) {
+            Some(&span) => {
+                let err = TypeError::DuplicateLangItem(item.ident);
+                tcx.sess.emit_error(vec![span, item.span], err);
+            }
+            None => {
+                spans.insert(lang_item, item.span);
+                lang_items.items.insert(lang_item, item.id.def);
+            }
+        }
+    }
+    tcx.alloc(lang_items)
+}
@@ -2,12 +2,14 @@ use lcore::queries::Queries;
 
 mod generics;
 mod inherent_impls;
+mod lang_items;
 mod trait_impls;
 mod tys;
 
 pub fn provide(queries: &mut Queries) {
     inherent_impls::provide(queries);
     trait_impls::provide(queries);
+    lang_items::provide(queries);
     generics::provide(queries);
     tys::provide(queries);
 }
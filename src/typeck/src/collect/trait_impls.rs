@@ -4,13 +4,22 @@
 // the current purpose of this is to validate trait impls (by running ir_ty_to_ty)
 
 use crate::TyConv;
-use ir::{DefId, Visitor};
+use ir::{DefId, DefKind, Res, Visitor};
 use lcore::queries::Queries;
-use lcore::ty::{self, TraitImpls, TyCtx};
+use lcore::ty::{self, TraitImpls, TyCtx, TypeError};
 use rustc_hash::FxHashMap;
 
 crate fn provide(queries: &mut Queries) {
-    *queries = Queries { trait_impls: |tcx, ()| trait_impls(tcx), trait_impls_of, ..*queries }
+    *queries = Queries {
+        trait_impls: |tcx, ()| trait_impls(tcx),
+        trait_impls_of,
+        impls_of_trait,
+        ..*queries
+    }
+}
+
+fn impls_of_trait<'tcx>(tcx: TyCtx<'tcx>, trait_def_id: DefId) -> &'tcx [DefId] {
+    tcx.trait_impls(()).impls_of_trait.get(&trait_def_id).map_or(&[], |xs| &xs)
 }
 
 fn trait_impls_of<'tcx>(tcx: TyCtx<'tcx>, def_id: DefId) -> &'tcx [DefId] {
@@ -28,16 +37,22 @@ fn trait_impls<'tcx>(tcx: TyCtx<'tcx>) -> &'tcx TraitImpls {
 struct TraitImplCollector<'tcx> {
     tcx: TyCtx<'tcx>,
     trait_impls: FxHashMap<DefId, Vec<DefId>>,
+    impls_of_trait: FxHashMap<DefId, Vec<DefId>>,
 }
 
 impl<'tcx> ir::Visitor<'tcx> for TraitImplCollector<'tcx> {
     fn visit_item(&mut self, item: &'tcx ir::Item<'tcx>) {
         let tcx = self.tcx;
-        let self_ty = match item.kind {
-            ir::ItemKind::Impl { self_ty, trait_path: Some(_), .. } => tcx.ir_ty_to_ty(self_ty),
+        let (self_ty, trait_path) = match item.kind {
+            ir::ItemKind::Impl { self_ty, trait_path: Some(path), .. } =>
+                (tcx.ir_ty_to_ty(self_ty), path),
             _ => return,
         };
 
+        if let Res::Def(trait_def_id, DefKind::Trait) = trait_path.res {
+            self.impls_of_trait.entry(trait_def_id).or_default().push(item.id.def);
+        }
+
         // sanity check that these types are consistent
         debug_assert_eq!(self_ty, tcx.type_of(item.id.def));
 
@@ -50,6 +65,10 @@ impl<'tcx> ir::Visitor<'tcx> for TraitImplCollector<'tcx> {
             ty::Ptr(..) => todo!(),
             ty::Param(..) => todo!(),
             ty::Opaque(..) => todo!(),
+            // trait objects are only usable through the items of their trait
+            ty::Dyn(..) => {
+                tcx.sess.emit_error(item.span, TypeError::TraitObjectImpl(self_ty));
+            }
             ty::Adt(adt, _) => self.visit_def(adt.def_id, item.id.def),
             ty::Bool | ty::Discr | ty::Char | ty::Float | ty::Int => todo!(),
             ty::Never => todo!(),
@@ -60,12 +79,12 @@ impl<'tcx> ir::Visitor<'tcx> for TraitImplCollector<'tcx> {
 
 impl<'tcx> TraitImplCollector<'tcx> {
     fn new(tcx: TyCtx<'tcx>) -> Self {
-        Self { tcx, trait_impls: Default::default() }
+        Self { tcx, trait_impls: Default::default(), impls_of_trait: Default::default() }
     }
 
     fn collect(mut self) -> TraitImpls {
         self.visit_ir(self.tcx.ir);
-        TraitImpls { trait_impls: self.trait_impls }
+        TraitImpls { trait_impls: self.trait_impls, impls_of_trait: self.impls_of_trait }
     }
 
     fn visit_def(&mut self, type_def_id: DefId, impl_def_id: DefId) {
@@ -3,8 +3,9 @@
 use index::Idx;
 use ir::{CtorKind, DefId, VariantIdx};
 use lcore::queries::Queries;
-use lcore::ty::{AdtKind, AdtTy, FieldTy, TyCtx, TypeError, VariantTy};
+use lcore::ty::{AdtKind, AdtTy, ConstKind, FieldTy, TyCtx, TypeError, VariantTy};
 use rustc_hash::FxHashMap;
+use std::convert::TryFrom;
 
 crate fn provide(queries: &mut Queries) {
     *queries = Queries { adt_ty, ..*queries }
@@ -18,15 +19,27 @@ fn adt_ty<'tcx>(tcx: TyCtx<'tcx>, def_id: DefId) -> &'tcx AdtTy {
 
     let (kind, variants) = match item.kind {
         ir::ItemKind::Enum(_, variants) => {
-            let variants = variants.iter().map(|variant| self::variant_ty(tcx, variant)).collect();
+            let discrs = self::enum_discrs(tcx, variants);
+            let variants = variants
+                .iter()
+                .zip(discrs)
+                .map(|(variant, discr)| self::variant_ty(tcx, variant, discr))
+                .collect();
             (AdtKind::Enum, variants)
         }
         ir::ItemKind::Struct(_, kind) => {
             // little bit hacky, turning the variant kind into a variant...
             let &ir::Item { id, span, ident, .. } = item;
-            let variant =
-                ir::Variant { id, ident, span, adt_def_id: id.def, kind, idx: VariantIdx::new(0) };
-            let variant = std::iter::once(&variant).map(|v| self::variant_ty(tcx, v)).collect();
+            let variant = ir::Variant {
+                id,
+                ident,
+                span,
+                adt_def_id: id.def,
+                kind,
+                idx: VariantIdx::new(0),
+                discr: None,
+            };
+            let variant = std::iter::once(&variant).map(|v| self::variant_ty(tcx, v, 0)).collect();
 
             (AdtKind::Struct, variant)
         }
@@ -36,7 +49,46 @@ fn adt_ty<'tcx>(tcx: TyCtx<'tcx>, def_id: DefId) -> &'tcx AdtTy {
     tcx.mk_adt(def_id, kind, item.ident, variants)
 }
 
-fn variant_ty<'tcx>(tcx: TyCtx<'tcx>, variant: &ir::Variant<'tcx>) -> VariantTy {
+/// each discriminant is either explicitly specified or is one more than the previous discriminant
+/// (the first defaults to zero)
+fn enum_discrs<'tcx>(tcx: TyCtx<'tcx>, variants: &[ir::Variant<'tcx>]) -> Vec<i16> {
+    let mut seen = FxHashMap::default();
+    let mut next = 0;
+    let mut discrs = Vec::with_capacity(variants.len());
+    for variant in variants {
+        let (span, discr) = match variant.discr {
+            Some(anon_const) => match tcx.eval_const(anon_const.id.def) {
+                Ok(c) => match c.kind {
+                    ConstKind::Int(i) => (anon_const.span, i),
+                    _ => unreachable!("discriminants are typechecked as integers"),
+                },
+                // the error has already been reported
+                Err(..) => (anon_const.span, next),
+            },
+            None => (variant.span, next),
+        };
+
+        let discr = match i16::try_from(discr) {
+            Ok(discr) if discr >= 0 => discr,
+            _ => {
+                tcx.sess.emit_error(span, TypeError::DiscriminantOutOfRange(discr));
+                0
+            }
+        };
+
+        if let Some(prev_span) = seen.insert(discr, span) {
+            tcx.sess.emit_error(
+                vec![span, prev_span],
+                TypeError::DuplicateDiscriminant(discr as i64),
+            );
+        }
+        next = discr as i64 + 1;
+        discrs.push(discr);
+    }
+    discrs
+}
+
+fn variant_ty<'tcx>(tcx: TyCtx<'tcx>, variant: &ir::Variant<'tcx>, discr: i16) -> VariantTy {
     let &ir::Variant { id, ident, kind, .. } = variant;
 
     let mut seen = FxHashMap::default();
@@ -54,5 +106,5 @@ fn variant_ty<'tcx>(tcx: TyCtx<'tcx>, variant: &ir::Variant<'tcx>) -> VariantTy
         })
         .collect();
 
-    VariantTy { def_id: id.def, ident, fields, ctor_kind: CtorKind::from(&kind) }
+    VariantTy { def_id: id.def, ident, fields, ctor_kind: CtorKind::from(&kind), discr }
 }
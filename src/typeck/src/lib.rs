@@ -14,6 +14,7 @@ mod autoderef;
 mod check;
 mod coherence;
 pub mod collect;
+mod object_safety;
 mod tyconv;
 mod type_of;
 mod upvars;
@@ -27,5 +28,6 @@ pub use tyconv::TyConv;
 pub fn provide(queries: &mut Queries) {
     collect::provide(queries);
     check::provide(queries);
+    object_safety::provide(queries);
     type_of::provide(queries);
 }
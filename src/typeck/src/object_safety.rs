@@ -0,0 +1,80 @@
+use ir::{DefId, Res};
+use lcore::queries::Queries;
+use lcore::ty::{ObjectSafetyViolation, TypeError};
+use lcore::TyCtx;
+
+pub fn provide(queries: &mut Queries) {
+    *queries = Queries { is_object_safe, ..*queries }
+}
+
+/// a trait is object safe if all of its methods can be called through a vtable
+/// that is, each method must take `&self` as its first parameter,
+/// must not refer to `Self` anywhere else in its signature,
+/// and neither the trait nor the method may be generic
+/// each violation is reported as an error (only once per trait as this is a query)
+fn is_object_safe<'tcx>(tcx: TyCtx<'tcx>, trait_def_id: DefId) -> bool {
+    let item = tcx.ir.items[&trait_def_id];
+    let trait_item_refs = match item.kind {
+        ir::ItemKind::Trait { trait_item_refs, .. } => trait_item_refs,
+        _ => unreachable!(),
+    };
+
+    let mut violations = vec![];
+    if !tcx.defs().generics(trait_def_id).params.is_empty() {
+        violations.push((item.span, ObjectSafetyViolation::GenericTrait));
+    }
+
+    for item_ref in trait_item_refs {
+        let trait_item = tcx.ir.trait_items[&item_ref.id];
+        let ident = trait_item.ident;
+        let sig = match trait_item.kind {
+            ir::TraitItemKind::Fn(sig, _) => sig,
+        };
+
+        if !tcx.defs().generics(trait_item.id.def).params.is_empty() {
+            violations.push((trait_item.span, ObjectSafetyViolation::GenericMethod(ident)));
+        }
+
+        match sig.inputs {
+            [receiver, params @ ..] if is_self_ref(receiver) =>
+                if params.iter().chain(sig.output).any(mentions_self) {
+                    violations.push((trait_item.span, ObjectSafetyViolation::SelfReference(ident)))
+                },
+            _ => violations.push((trait_item.span, ObjectSafetyViolation::NoReceiver(ident))),
+        }
+    }
+
+    let is_object_safe = violations.is_empty();
+    for (span, violation) in violations {
+        tcx.sess.emit_error(span, TypeError::ObjectSafety(item.ident, violation));
+    }
+    is_object_safe
+}
+
+/// whether `ty` is `&Self`
+fn is_self_ref(ty: &ir::Ty) -> bool {
+    match ty.kind {
+        ir::TyKind::Box(ty) => match ty.kind {
+            ir::TyKind::Path(ir::QPath::Resolved(path)) => matches!(path.res, Res::SelfTy { .. }),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+fn mentions_self<'tcx>(ty: &'tcx ir::Ty<'tcx>) -> bool {
+    let mut visitor = SelfTyVisitor { found: false };
+    ir::Visitor::visit_ty(&mut visitor, ty);
+    visitor.found
+}
+
+struct SelfTyVisitor {
+    found: bool,
+}
+
+impl<'ir> ir::Visitor<'ir> for SelfTyVisitor {
+    fn visit_path(&mut self, path: &'ir ir::Path<'ir>) {
+        self.found |= matches!(path.res, Res::SelfTy { .. });
+        ir::walk_path(self, path);
+    }
+}
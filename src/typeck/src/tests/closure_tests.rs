@@ -32,22 +32,25 @@ fn check_recursive_named_closure() {
 fn check_simple_lambda_no_capture() {
     let tir = typeck_expr!("fn () => 5; 5");
     let lines = lines!(&tir);
-    assert_eq!(lines[0], "(λ() 5:int):fn()->int;");
+    assert_eq!(lines[0], "(λ() 5:int):[closure fn()->int];");
 }
 
 #[test]
 fn check_simple_lambda_with_parameter_no_capture() {
     let tir = typeck_expr!("fn (x) => 5 + x; 5");
     let lines = lines!(&tir);
-    assert_eq!(lines[0], "(λ(x:int) (+ 5:int x:int):int):fn(int)->int;");
+    assert_eq!(lines[0], "(λ(x:int) (+ 5:int x:int):int):[closure fn(int)->int];");
 }
 
 #[test]
 fn check_fn_call() {
     let tir = typeck_expr!("let f = fn(x) => x; f(3)");
     let lines = lines!(&tir);
-    assert_eq!(lines[0], "let f:fn(int)->int = (λ(x:int) x:int):fn(int)->int;");
-    assert_eq!(lines[1], "(f:fn(int)->int 3:int):int");
+    assert_eq!(
+        lines[0],
+        "let f:[closure fn(int)->int] = (λ(x:int) x:int):[closure fn(int)->int];"
+    );
+    assert_eq!(lines[1], "(f:[closure fn(int)->int] 3:int):int");
 }
 
 #[test]
@@ -55,7 +58,7 @@ fn check_lambda_with_capture() {
     let tir = typeck_expr!("let num = 55; fn(y) => num + y; num");
     let lines = lines!(&tir);
     assert_eq!(lines[0], "let num:int = 55:int;");
-    assert_eq!(lines[1], "(λ(y:int) (+ num:int y:int):int):fn(int)->int;");
+    assert_eq!(lines[1], "(λ(y:int) (+ num:int y:int):int):[closure fn(int)->int];");
 }
 
 #[test]
@@ -63,10 +66,181 @@ fn check_higher_order_lambda() {
     let tir = typeck_expr!("let f = fn(x) => false; let g = fn(p) => p(3); g(f); 5");
     let lines = lines!(&tir);
     // note `false` is represented as `0`
-    assert_eq!(lines[0], "let f:fn(int)->bool = (λ(x:int) false:bool):fn(int)->bool;");
+    assert_eq!(
+        lines[0],
+        "let f:[closure fn(int)->bool] = (λ(x:int) false:bool):[closure fn(int)->bool];"
+    );
     assert_eq!(
         lines[1],
-        "let g:fn(fn(int)->bool)->bool = (λ(p:fn(int)->bool) (p:fn(int)->bool 3:int):bool):fn(fn(int)->bool)->bool;"
+        "let g:[closure fn(fn(int)->bool)->bool] = (λ(p:fn(int)->bool) (p:fn(int)->bool 3:int):bool):[closure fn(fn(int)->bool)->bool];"
+    );
+    // `f` captures nothing so it is coerced to a function pointer
+    assert_eq!(
+        lines[2],
+        "(g:[closure fn(fn(int)->bool)->bool] (f:[closure fn(int)->bool] as fn(int)->bool):fn(int)->bool):bool;"
     );
-    assert_eq!(lines[2], "(g:fn(fn(int)->bool)->bool f:fn(int)->bool):bool;");
+}
+
+#[test]
+fn check_capturing_closure_to_fn_ptr() {
+    let src = r#"
+    fn apply(f: fn(int) -> int) -> int { f(1) }
+    fn main() -> int {
+        let k = 2;
+        apply(fn(x) => x + k)
+    }"#;
+    expect_type_error!(src);
+}
+
+#[test]
+fn check_closures_have_distinct_types() {
+    expect_type_error_expr!("let mut f = fn(x) => x + 1; f = fn(x) => x + 2; 5");
+}
+
+#[test]
+fn check_closure_with_fn_bound() {
+    let src = r#"
+    #[lang]
+    trait Fn {}
+    fn map<F: Fn(int) -> int>(f: F, x: int) -> int { f(x) }
+    fn main() -> int {
+        let k = 2;
+        map(fn(x) => x + k, 1)
+    }"#;
+    typeck!(src);
+}
+
+#[test]
+fn check_fn_bound_infers_closure_params() {
+    let src = r#"
+    #[lang]
+    trait Fn {}
+    fn map<F: Fn(int) -> bool>(f: F) -> bool { f(1) }
+    fn main() -> int {
+        let b = map(fn(x) => true);
+        0
+    }"#;
+    typeck!(src);
+}
+
+#[test]
+fn check_fn_bound_signature_mismatch() {
+    let src = r#"
+    #[lang]
+    trait Fn {}
+    fn map<F: Fn(int) -> int>(f: F, x: int) -> int { f(x) }
+    fn main() -> int {
+        map(fn(x) => false, 1)
+    }"#;
+    expect_type_error!(src);
+}
+
+#[test]
+fn check_fn_bound_not_satisfied() {
+    let src = r#"
+    #[lang]
+    trait Fn {}
+    fn map<F: Fn(int) -> int>(f: F, x: int) -> int { f(x) }
+    fn main() -> int {
+        map(5, 1)
+    }"#;
+    expect_type_error!(src);
+}
+
+#[test]
+fn check_fn_bound_forwarded_through_param() {
+    let src = r#"
+    #[lang]
+    trait Fn {}
+    fn map<F: Fn(int) -> int>(f: F, x: int) -> int { f(x) }
+    fn twice<G: Fn(int) -> int>(g: G, x: int) -> int { map(g, map(g, x)) }
+    fn main() -> int {
+        twice(fn(x) => x * 2, 1)
+    }"#;
+    typeck!(src);
+}
+
+#[test]
+fn check_mutating_closure_does_not_satisfy_fn_bound() {
+    let src = r#"
+    #[lang]
+    trait Fn {}
+    fn call<F: Fn() -> int>(f: F) -> int { f() }
+    fn main() -> int {
+        let mut n = 0;
+        call(fn() => { n = n + 1; n })
+    }"#;
+    expect_type_error!(src);
+}
+
+#[test]
+fn check_mutating_closure_satisfies_fn_mut_bound() {
+    let src = r#"
+    #[lang]
+    trait FnMut {}
+    #[lang]
+    trait FnOnce {}
+    fn call<F: FnMut() -> int>(f: F) -> int { f() }
+    fn call_once<F: FnOnce() -> int>(f: F) -> int { f() }
+    fn main() -> int {
+        let mut n = 0;
+        call(fn() => { n = n + 1; n }) + call_once(fn() => { n = n + 1; n })
+    }"#;
+    typeck!(src);
+}
+
+#[test]
+fn check_nested_closure_mutating_upvar_is_fn_mut() {
+    let src = r#"
+    #[lang]
+    trait Fn {}
+    fn call<F: Fn() -> int>(f: F) -> int { f() }
+    fn main() -> int {
+        let mut n = 0;
+        call(fn() => {
+            let inc = fn() => { n = n + 1; };
+            inc();
+            n
+        })
+    }"#;
+    expect_type_error!(src);
+}
+
+#[test]
+fn check_fn_bound_extends_to_fn_mut_bound() {
+    let src = r#"
+    #[lang]
+    trait Fn {}
+    #[lang]
+    trait FnMut {}
+    fn call<F: FnMut() -> int>(f: F) -> int { f() }
+    fn forward<G: Fn() -> int>(g: G) -> int { call(g) }
+    fn main() -> int {
+        forward(fn() => 1)
+    }"#;
+    typeck!(src);
+}
+
+#[test]
+fn check_fn_mut_bound_does_not_extend_to_fn_bound() {
+    let src = r#"
+    #[lang]
+    trait Fn {}
+    #[lang]
+    trait FnMut {}
+    fn call<F: Fn() -> int>(f: F) -> int { f() }
+    fn forward<G: FnMut() -> int>(g: G) -> int { call(g) }
+    fn main() -> int {
+        forward(fn() => 1)
+    }"#;
+    expect_type_error!(src);
+}
+
+#[test]
+fn check_parenthesized_args_on_non_fn_trait() {
+    let src = r#"
+    trait Foo {}
+    fn map<F: Foo(int) -> int>(f: F) {}
+    fn main() -> int { 0 }"#;
+    expect_type_error!(src);
 }
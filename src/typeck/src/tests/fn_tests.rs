@@ -14,3 +14,31 @@ fn typeck_return_stmt_expr() {
     // statement into a return expression
     let _tir = typeck!("fn main() -> int { return 5; }");
 }
+
+#[test]
+fn typeck_mismatched_fn_arg() {
+    let src = r#"
+    fn f(x: int) -> int { x }
+
+    fn main() -> int {
+        f(false)
+    }"#;
+    expect_type_error!(src);
+}
+
+#[test]
+fn typeck_mismatched_fn_arg_not_coercible_to_trait_object() {
+    let src = r#"
+    trait T {
+        fn t(&self) -> int;
+    }
+
+    struct S;
+
+    fn f(t: &dyn T) -> int { 0 }
+
+    fn main() -> int {
+        f(box S)
+    }"#;
+    expect_type_error!(src);
+}
@@ -10,3 +10,70 @@ fn check_box_expr() {
     let _tir = typeck!("fn main() -> int { let x = 5; let boxed = box x; 5 }");
     // dbg!(_tir);
 }
+
+#[test]
+fn int_literal_defaults_to_int() {
+    let tir = typeck_expr!("let x = 5; 0");
+    let lines = lines!(&tir);
+    assert_eq!(lines[0], "let x:int = 5:int;");
+}
+
+#[test]
+fn float_literal_defaults_to_float() {
+    let tir = typeck_expr!("let x = 1.5; 0");
+    let lines = lines!(&tir);
+    assert!(lines[0].starts_with("let x:float ="));
+}
+
+#[test]
+fn int_literal_inferred_through_generic_fn() {
+    typeck!("fn id<T>(t: T) -> T { t } fn main() -> int { id(5) }");
+}
+
+#[test]
+fn int_literal_with_non_numeric_type() {
+    expect_type_error_expr!("let x: bool = 5; 0");
+}
+
+#[test]
+fn float_literal_with_int_type() {
+    expect_type_error_expr!("let x: int = 1.5; 0");
+}
+
+#[test]
+fn unify_int_and_float_literals() {
+    expect_type_error_expr!("if true { 1 } else { 1.5 }; 0");
+}
+
+#[test]
+fn check_neg_int_and_float() {
+    let tir = typeck_expr!("let x = -5; let y = -1.5; 0");
+    let lines = lines!(&tir);
+    assert_eq!(lines[0], "let x:int = (-5:int):int;");
+    assert!(lines[1].starts_with("let y:float ="));
+}
+
+#[test]
+fn check_not_bool_and_int() {
+    typeck_expr!("let b = !true; let i = !5; i");
+}
+
+#[test]
+fn check_neg_bool() {
+    expect_type_error_expr!("let b = -true; 0");
+}
+
+#[test]
+fn check_not_float() {
+    expect_type_error_expr!("let f = !1.5; 0");
+}
+
+#[test]
+fn check_panic_is_never() {
+    typeck!("fn f() -> int { panic!(\"unimplemented\") } fn main() -> int { f() }");
+}
+
+#[test]
+fn check_const_used_as_type() {
+    expect_type_error!("const N: int = 1; fn f(x: N) -> int { 0 } fn main() -> int { 0 }");
+}
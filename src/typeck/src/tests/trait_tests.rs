@@ -0,0 +1,282 @@
+use super::*;
+
+#[test]
+fn typeck_default_trait_method() {
+    let src = r#"
+    trait Default {
+        fn default() -> Self;
+        fn twice() -> (Self, Self) { (Self::default(), Self::default()) }
+    }
+
+    fn main() -> int { 0 }"#;
+    typeck!(src);
+}
+
+#[test]
+fn ill_typed_default_trait_method() {
+    let src = r#"
+    trait Default {
+        fn default() -> Self;
+        fn broken() -> int { Self::default() }
+    }
+
+    fn main() -> int { 0 }"#;
+    expect_type_error!(src);
+}
+
+#[test]
+fn missing_required_trait_item() {
+    let src = r#"
+    struct S;
+
+    trait Default {
+        fn default() -> Self;
+    }
+
+    impl Default for S {}
+
+    fn main() -> int { 0 }"#;
+    expect_type_error!(src);
+}
+
+#[test]
+fn impl_item_not_member_of_trait() {
+    let src = r#"
+    struct S;
+
+    trait Empty {}
+
+    impl Empty for S {
+        fn f() {}
+    }
+
+    fn main() -> int { 0 }"#;
+    expect_type_error!(src);
+}
+
+#[test]
+fn typeck_trait_object() {
+    let src = r#"
+    struct S;
+
+    trait Get {
+        fn get(&self) -> int;
+    }
+
+    impl Get for S {
+        fn get(&self) -> int { 5 }
+    }
+
+    fn call(x: &dyn Get) -> int { Get::get(x) }
+
+    fn main() -> int { call(box S) }"#;
+    typeck!(src);
+}
+
+#[test]
+fn unsize_to_unimplemented_trait_object() {
+    let src = r#"
+    struct S;
+
+    trait Get {
+        fn get(&self) -> int;
+    }
+
+    fn call(x: &dyn Get) -> int { Get::get(x) }
+
+    fn main() -> int { call(box S) }"#;
+    expect_type_error!(src);
+}
+
+#[test]
+fn trait_object_of_non_object_safe_trait() {
+    let src = r#"
+    trait Duplicate {
+        fn duplicate(&self) -> Self;
+    }
+
+    fn f(x: &dyn Duplicate) {}
+
+    fn main() -> int { 0 }"#;
+    expect_type_error!(src);
+}
+
+#[test]
+fn trait_object_of_trait_without_receiver() {
+    let src = r#"
+    trait Five {
+        fn five() -> int;
+    }
+
+    fn f(x: &dyn Five) {}
+
+    fn main() -> int { 0 }"#;
+    expect_type_error!(src);
+}
+
+#[test]
+fn dyn_non_trait() {
+    let src = r#"
+    struct S;
+
+    fn f(x: &dyn S) {}
+
+    fn main() -> int { 0 }"#;
+    expect_type_error!(src);
+}
+
+#[test]
+fn binop_on_adt_without_lang_item_trait() {
+    let src = r#"
+    struct S;
+
+    fn main() -> int {
+        let s = S + S;
+        0
+    }"#;
+    expect_type_error!(src);
+}
+
+#[test]
+fn binop_on_adt_without_impl() {
+    let src = r#"
+    struct S;
+
+    #[lang]
+    trait Add {
+        fn add(self, other: Self) -> Self;
+    }
+
+    fn main() -> int {
+        let s = S + S;
+        0
+    }"#;
+    expect_type_error!(src);
+}
+
+#[test]
+fn typeck_overloaded_binop() {
+    let src = r#"
+    struct S;
+
+    #[lang]
+    trait Eq {
+        fn eq(self, other: Self) -> bool;
+    }
+
+    impl Eq for S {
+        fn eq(self, other: Self) -> bool { true }
+    }
+
+    fn main() -> int {
+        let b: bool = S == S;
+        0
+    }"#;
+    typeck!(src);
+}
+
+#[test]
+fn binop_requires_lang_trait() {
+    let src = r#"
+    struct S;
+
+    trait Eq {
+        fn eq(self, other: Self) -> bool;
+    }
+
+    impl Eq for S {
+        fn eq(self, other: Self) -> bool { true }
+    }
+
+    fn main() -> int {
+        let b: bool = S == S;
+        0
+    }"#;
+    expect_type_error!(src);
+}
+
+#[test]
+fn duplicate_lang_item() {
+    let src = r#"
+    #[lang]
+    trait Eq {
+        fn eq(self, other: Self) -> bool;
+    }
+
+    mod m {
+        #[lang]
+        trait Eq {
+            fn eq(self, other: Self) -> bool;
+        }
+    }
+
+    fn main() -> int { 0 }"#;
+    expect_type_error!(src);
+}
+
+#[test]
+fn unknown_lang_item() {
+    let src = r#"
+    #[lang]
+    trait Sum {}
+
+    fn main() -> int { 0 }"#;
+    expect_type_error!(src);
+}
+
+#[test]
+fn typeck_derived_impls() {
+    let src = r#"
+    #[lang]
+    trait Eq {
+        fn eq(self, other: Self) -> bool;
+    }
+
+    #[lang]
+    trait Clone {
+        fn clone(self) -> Self;
+    }
+
+    #[derive(Eq, Clone)]
+    struct P { x: int, y: bool }
+
+    #[derive(Eq, Clone)]
+    enum E { A(int, P), B }
+
+    fn main() -> int {
+        let e = E::A(1, P { x: 2, y: true });
+        let b: bool = e == Clone::clone(e);
+        0
+    }"#;
+    typeck!(src);
+}
+
+#[test]
+fn derive_clone_with_field_not_implementing_clone() {
+    let src = r#"
+    #[lang]
+    trait Clone {
+        fn clone(self) -> Self;
+    }
+
+    struct T;
+
+    #[derive(Clone)]
+    struct S(T);
+
+    fn main() -> int { 0 }"#;
+    expect_type_error!(src);
+}
+
+#[test]
+fn trait_impl_on_trait_object() {
+    let src = r#"
+    trait T {}
+
+    trait U {}
+
+    impl U for dyn T {}
+
+    fn main() -> int { 0 }"#;
+    expect_type_error!(src);
+}
@@ -3,7 +3,9 @@
 //! `InferCtx` allows inference variables, one does not
 
 use ir::{DefId, DefKind, QPath, Res};
-use lcore::ty::{FnSig, Generics, Subst, Substs, Ty, TyCtx, TyParam, TypeError};
+use lcore::ty::{
+    Bound, ConstKind, FnSig, Generics, Subst, Substs, Ty, TyCtx, TyParam, TypeError,
+};
 use span::Span;
 
 /// refer to module comments
@@ -27,11 +29,23 @@ pub trait TyConv<'tcx> {
                 ret: ret.map(|ty| self.ir_ty_to_ty(ty)).unwrap_or(tcx.types.unit),
             }),
             ir::TyKind::Path(qpath) => self.qpath_to_ty(qpath),
+            ir::TyKind::Dyn(path) => self.dyn_path_to_ty(path),
             ir::TyKind::Tuple(tys) => tcx.mk_tup_iter(tys.iter().map(|ty| self.ir_ty_to_ty(ty))),
             ir::TyKind::Ptr(ty) => tcx.mk_ptr_ty(self.ir_ty_to_ty(ty)),
-            ir::TyKind::Array(_ty) => {
-                // tcx.mk_array_ty(self.ir_ty_to_ty(ty), todo!()),
-                todo!();
+            ir::TyKind::Array(ty, len) => {
+                let ty = self.ir_ty_to_ty(ty);
+                let len = match tcx.eval_const(len.id.def) {
+                    Ok(c) => match c.kind {
+                        ConstKind::Int(n) if n < 0 => {
+                            tcx.sess.emit_error(len.span, TypeError::NegativeArrayLen(n));
+                            return tcx.mk_ty_err();
+                        }
+                        ConstKind::Int(n) => n as usize,
+                        _ => unreachable!("array lengths are typechecked as integers"),
+                    },
+                    Err(..) => return tcx.mk_ty_err(),
+                };
+                tcx.mk_array_ty(ty, len)
             }
             ir::TyKind::Infer => self.infer_ty(ir_ty.span),
             ir::TyKind::Err => tcx.mk_ty_err(),
@@ -103,6 +117,11 @@ pub trait TyConv<'tcx> {
                 let ty = tcx.type_of(def_id);
                 ty.subst(tcx, substs)
             }
+            DefKind::Const | DefKind::Static => {
+                let ident = tcx.defs().ident(def_id);
+                tcx.sess.emit_error(path.span, TypeError::ExpectedType(def_kind, ident));
+                tcx.mk_ty_err()
+            }
             DefKind::Ctor(..) | DefKind::Trait | DefKind::Fn | DefKind::AssocFn | DefKind::Impl =>
                 todo!(),
             DefKind::Mod | DefKind::Extern | DefKind::Use =>
@@ -121,19 +140,65 @@ pub trait TyConv<'tcx> {
         }
     }
 
+    fn dyn_path_to_ty(&self, path: &ir::Path<'tcx>) -> Ty<'tcx> {
+        let tcx = self.tcx();
+        match path.res {
+            Res::Def(def_id, DefKind::Trait) if tcx.is_object_safe(def_id) => tcx.mk_dyn_ty(def_id),
+            // object safety violations are reported by `is_object_safe`
+            Res::Def(_, DefKind::Trait) | Res::Err => tcx.mk_ty_err(),
+            _ => {
+                tcx.sess.emit_error(path.span, TypeError::ExpectedTrait(path.to_string()));
+                tcx.mk_ty_err()
+            }
+        }
+    }
+
     fn ensure_no_generic_args(&self, segments: &[ir::PathSegment<'tcx>]) {
         segments.iter().for_each(|segment| assert!(segment.args.is_none()))
     }
 
     fn lower_generics(&self, generics: &ir::Generics<'tcx>) -> &'tcx Generics<'tcx> {
         let tcx = self.tcx();
-        let params =
-            generics.params.iter().map(|&ir::TyParam { id, index, ident, span, default }| {
-                TyParam { id, span, ident, index, default: default.map(|ty| self.ir_ty_to_ty(ty)) }
-            });
+        let params = generics.params.iter().map(|param| self.lower_ty_param(param));
         tcx.alloc(Generics { params: tcx.alloc_iter(params) })
     }
 
+    fn lower_ty_param(&self, param: &ir::TyParam<'tcx>) -> TyParam<'tcx> {
+        let &ir::TyParam { id, index, ident, span, default, bounds } = param;
+        let default = default.map(|ty| self.ir_ty_to_ty(ty));
+        let bounds = self.tcx().alloc_iter(bounds.iter().filter_map(|b| self.lower_bound(b)));
+        TyParam { id, span, ident, index, default, bounds }
+    }
+
+    /// returns `None` if the bound is erroneous
+    fn lower_bound(&self, bound: &ir::GenericBound<'tcx>) -> Option<Bound<'tcx>> {
+        let tcx = self.tcx();
+        let path = bound.path;
+        let trait_def_id = match path.res {
+            Res::Def(def_id, DefKind::Trait) => def_id,
+            Res::Err => return None,
+            _ => {
+                tcx.sess.emit_error(path.span, TypeError::ExpectedTrait(path.to_string()));
+                return None;
+            }
+        };
+
+        let is_fn_trait = tcx.lang_items(()).is_fn_trait(trait_def_id);
+        let sig = bound.sig.map(|ty| self.ir_ty_to_ty(ty).expect_fn_ptr());
+        match sig {
+            Some(_) if !is_fn_trait => {
+                let err = TypeError::ParenthesizedArgsOnNonFnTrait(path.to_string());
+                tcx.sess.emit_error(bound.span, err);
+                None
+            }
+            None if is_fn_trait => {
+                tcx.sess.emit_error(bound.span, TypeError::MissingFnBoundSig(path.to_string()));
+                None
+            }
+            _ => Some(Bound { span: bound.span, trait_def_id, sig }),
+        }
+    }
+
     fn ir_fn_sig_to_ty(&self, sig: &ir::FnSig<'tcx>) -> Ty<'tcx> {
         self.tcx().mk_fn_ptr(self.lower_fn_sig(sig))
     }
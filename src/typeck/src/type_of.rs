@@ -1,7 +1,10 @@
 use crate::TyConv;
-use ir::{DefId, DefNode};
+use ast::Ident;
+use index::Idx;
+use ir::{DefId, DefNode, ParamIdx};
 use lcore::queries::Queries;
 use lcore::ty::*;
+use span::kw;
 
 pub fn provide(queries: &mut Queries) {
     *queries = Queries { type_of, fn_sig, ..*queries }
@@ -13,9 +16,13 @@ fn type_of<'tcx>(tcx: TyCtx<'tcx>, def_id: DefId) -> Ty<'tcx> {
         ir::DefNode::Item(item) => match item.kind {
             ir::ItemKind::Fn(..) => tcx.mk_fn_ptr(tcx.fn_sig(def_id)),
             ir::ItemKind::Enum(..) | ir::ItemKind::Struct(..) => self::type_of_adt(tcx, def_id),
-            ir::ItemKind::TypeAlias(_, ty) => tcx.ir_ty_to_ty(ty),
+            ir::ItemKind::TypeAlias(_, ty)
+            | ir::ItemKind::Const(ty, _)
+            | ir::ItemKind::Static(ty, _) => tcx.ir_ty_to_ty(ty),
             ir::ItemKind::Mod(..) | ir::ItemKind::Use(..) | ir::ItemKind::Extern(..) => panic!(),
-            ir::ItemKind::Trait { generics: _, trait_item_refs: _ } => todo!(),
+            // within a trait, `Self` is treated as the type parameter with index 0
+            ir::ItemKind::Trait { .. } =>
+                tcx.mk_ty_param(def_id, ParamIdx::new(0), Ident::new(item.span, kw::USelf)),
             ir::ItemKind::Impl { generics: _, trait_path: _, self_ty, impl_item_refs: _ } =>
                 tcx.ir_ty_to_ty(self_ty),
         },
@@ -31,6 +38,8 @@ fn type_of<'tcx>(tcx: TyCtx<'tcx>, def_id: DefId) -> Ty<'tcx> {
             ir::ForeignItemKind::Fn(..) => tcx.mk_fn_ptr(tcx.fn_sig(def_id)),
         },
         ir::DefNode::Field(f) => tcx.ir_ty_to_ty(f.ty),
+        // array lengths and discriminants are both integers
+        ir::DefNode::AnonConst(..) => tcx.types.int,
         ir::DefNode::TyParam(_) => panic!(),
     }
 }
@@ -40,8 +49,12 @@ fn fn_sig<'tcx>(tcx: TyCtx<'tcx>, def_id: DefId) -> FnSig<'tcx> {
     match tcx.defs().get(def_id) {
         DefNode::Item(item) => match item.kind {
             ir::ItemKind::Fn(sig, ..) => tcx.lower_fn_sig(sig),
+            // the initializer of a constant is checked as the body of a nullary function
+            ir::ItemKind::Const(..) | ir::ItemKind::Static(..) =>
+                tcx.mk_fn_sig(Substs::empty(), tcx.type_of(def_id)),
             _ => panic!(),
         },
+        DefNode::AnonConst(..) => tcx.mk_fn_sig(Substs::empty(), tcx.type_of(def_id)),
         DefNode::ImplItem(impl_item) => match impl_item.kind {
             ir::ImplItemKind::Fn(sig, ..) => tcx.lower_fn_sig(sig),
         },
@@ -1,15 +1,43 @@
 use super::FnCtx;
+use ast::Mutability;
 use ir::{self, Visitor};
-use lcore::ty::{TyCtx, UpvarId};
+use itertools::Itertools;
+use lcore::ty::{CaptureMode, ClosureKind, TyCtx, UpvarCapture, UpvarId};
 use rustc_hash::FxHashSet;
 
 impl<'a, 'tcx> FnCtx<'a, 'tcx> {
     /// write the upvars mentioned by `closure` to `TypeckOutputs`
+    /// mutable variables are captured by reference and everything else is captured by value
+    /// (the variables captured by reference are boxed so escaping closures do not dangle)
     crate fn record_upvars(&mut self, closure: &ir::Expr, body: &ir::Body) {
         let locals = LocalsVisitor::find_locals(body);
         let upvar_visitor = UpvarVisitor::new(self.tcx, closure.id, &locals);
         let upvars = upvar_visitor.resolve_upvars(body);
-        self.tables.borrow_mut().record_upvar_capture_for_closure(closure.id, upvars);
+        // sort the upvars to give the environment of the closure a deterministic layout
+        let captures = upvars
+            .into_iter()
+            .sorted_by_key(|upvar| upvar.var_id)
+            .map(|upvar_id| {
+                let mode = match self.local_ty(upvar_id.var_id).mtbl {
+                    Mutability::Mut => CaptureMode::ByRef,
+                    Mutability::Imm => CaptureMode::ByValue,
+                };
+                UpvarCapture { upvar_id, mode }
+            })
+            .collect::<Vec<_>>();
+
+        // a closure that assigns to any of its upvars (possibly within a nested closure)
+        // only implements `FnMut`
+        let assigned = AssignedVarsVisitor::find_assigned_vars(body);
+        let kind = if captures.iter().any(|capture| assigned.contains(&capture.upvar_id.var_id)) {
+            ClosureKind::FnMut
+        } else {
+            ClosureKind::Fn
+        };
+
+        let mut tables = self.tables.borrow_mut();
+        tables.record_upvar_captures_for_closure(closure.id, captures);
+        tables.record_closure_kind(closure.id, kind);
     }
 }
 
@@ -36,7 +64,9 @@ impl<'a, 'tcx> Visitor<'_> for UpvarVisitor<'a, 'tcx> {
     /// the only way to syntactically reference a variable is a path expression
     fn visit_path(&mut self, path: &ir::Path) {
         if let ir::Res::Local(var_id) = path.res {
-            if !self.locals.contains(&var_id) {
+            // a named closure referring to itself does not capture anything
+            // the closure is rebuilt from its own environment instead
+            if var_id != self.closure_id && !self.locals.contains(&var_id) {
                 self.upvars.insert(UpvarId { closure_id: self.closure_id, var_id });
             }
         }
@@ -66,4 +96,48 @@ impl ir::Visitor<'_> for LocalsVisitor {
         }
         ir::walk_pat(self, pat);
     }
+
+    /// named closures introduce a name for themselves
+    fn visit_expr(&mut self, expr: &ir::Expr) {
+        if let ir::ExprKind::Closure(..) = expr.kind {
+            self.locals.insert(expr.id);
+        }
+        ir::walk_expr(self, expr);
+    }
+}
+
+/// collects the ids of the variables that are assigned to (including through their fields)
+#[derive(Default)]
+struct AssignedVarsVisitor {
+    assigned: FxHashSet<ir::Id>,
+}
+
+impl AssignedVarsVisitor {
+    fn find_assigned_vars(body: &ir::Body) -> FxHashSet<ir::Id> {
+        let mut v = Self::default();
+        v.visit_body(body);
+        v.assigned
+    }
+
+    /// the variable that an assignment to `expr` writes to
+    /// writing through a pointer does not write to the variable holding the pointer
+    fn assigned_var(expr: &ir::Expr) -> Option<ir::Id> {
+        match expr.kind {
+            ir::ExprKind::Path(ir::QPath::Resolved(path)) => match path.res {
+                ir::Res::Local(var_id) => Some(var_id),
+                _ => None,
+            },
+            ir::ExprKind::Field(base, _) => Self::assigned_var(base),
+            _ => None,
+        }
+    }
+}
+
+impl ir::Visitor<'_> for AssignedVarsVisitor {
+    fn visit_expr(&mut self, expr: &ir::Expr) {
+        if let ir::ExprKind::Assign(lhs, _) = expr.kind {
+            self.assigned.extend(Self::assigned_var(lhs));
+        }
+        ir::walk_expr(self, expr);
+    }
 }
@@ -22,6 +22,8 @@ struct WritebackCtx<'a, 'tcx> {
 
 impl<'a, 'tcx> WritebackCtx<'a, 'tcx> {
     fn new(fcx: &'a FnCtx<'a, 'tcx>) -> Self {
+        // unconstrained numeric literals default to `int` and `float`
+        fcx.default_numeric_infer_vars();
         let substs = fcx.inference_substs();
         let mut subst_folder = InferVarSubstsFolder::new(fcx.tcx, substs);
 
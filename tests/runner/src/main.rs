@@ -122,12 +122,16 @@ impl TestCtx {
         }
     }
 
+    /// output tests are run through both the llvm backend and the mir interpreter
     fn run_output_test(&mut self, path: &Path) -> io::Result<()> {
-        let output = self.run(path, ErrorFormat::Text)?;
         let mut stdout_path = path.to_path_buf();
         assert!(stdout_path.set_extension("stdout"));
         let expected_stdout = fs::read_to_string(stdout_path).ok().unwrap();
+        let output = self.run(path, ErrorFormat::Text)?;
         self.compare(&output.stdout, &expected_stdout);
+        let interp_output = self.run_with("interp", path, ErrorFormat::Text)?;
+        self.compare(&interp_output.stdout, &expected_stdout);
+        self.compare(interp_output.status.code(), output.status.code());
         Ok(())
     }
 
@@ -166,8 +170,18 @@ impl TestCtx {
     }
 
     fn run(&self, path: &Path, error_format: ErrorFormat) -> io::Result<Output> {
+        self.run_with("run", path, error_format)
+    }
+
+    /// runs the compiler with the given subcommand on the file at `path`
+    fn run_with(
+        &self,
+        subcommand: &str,
+        path: &Path,
+        error_format: ErrorFormat,
+    ) -> io::Result<Output> {
         let mut cmd = Command::new("l");
-        cmd.arg("run").arg(path);
+        cmd.arg(subcommand).arg(path);
         cmd.arg(format!("--error-format={}", error_format));
         let output = cmd.output()?;
         let status = output.status;